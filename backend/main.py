@@ -12,6 +12,8 @@ import platform
 import argparse
 import tempfile
 import shutil
+import threading
+import time
 from pathlib import Path
 
 # Ensure FFmpeg is in PATH
@@ -71,6 +73,9 @@ def test_ffmpeg():
 # Test FFmpeg on startup
 test_ffmpeg()
 
+# The desktop app points this at its per-user data directory
+OUTPUT_DIR = Path(os.environ.get("WEB_WHISPER_OUTPUT_DIR", "outputs"))
+
 import gradio as gr
 import numpy as np
 from typing import Optional, Tuple
@@ -122,6 +127,8 @@ def transcribe_audio(
     else:
         return "No audio input provided", "", ""
     
+    # Markers read by the desktop app so it does not stop the server mid-transcription
+    print("[web-whisper] busy", flush=True)
     try:
         # Get the appropriate engine for the selected model
         current_engine = get_transcription_engine(model_choice)
@@ -181,8 +188,8 @@ def transcribe_audio(
         
         # Save output if requested
         if save_output:
-            output_dir = Path("outputs")
-            output_dir.mkdir(exist_ok=True)
+            output_dir = OUTPUT_DIR
+            output_dir.mkdir(parents=True, exist_ok=True)
             
             # Get base filename from input file
             if audio_file is not None:
@@ -219,6 +226,7 @@ def transcribe_audio(
         return error_msg, "", error_msg
     
     finally:
+        print("[web-whisper] idle", flush=True)
         # Clean up temp file if created
         if 'temp_file' in locals():
             try:
@@ -400,6 +408,160 @@ def create_interface():
     
     return demo
 
+TOKEN_COOKIE = "web_whisper_token"
+
+# Media sent by the desktop app over /api/upload; owned by the server, so the
+# transcription never depends on reading a file from another process's temp folder
+UPLOAD_DIR = Path(tempfile.gettempdir()) / "web_whisper_uploads"
+
+class JobCancelled(Exception):
+    pass
+
+# Transcription jobs submitted over /api/jobs by the desktop app, by id. Finished jobs
+# stay until their result is fetched.
+API_JOBS = {}
+# One job decodes at a time; the app queues its own jobs, so this only guards overlap
+API_ENGINE_LOCK = threading.Lock()
+# The engine of the last job, kept loaded so the next one with the same model starts at once
+_api_engine = {"key": None, "engine": None}
+
+def _engine_for_job(model, compute_type, threads):
+    """Loaded engine for the job's model; returns it and how long loading took."""
+    key = (model, compute_type, threads)
+    if _api_engine["key"] == key:
+        return _api_engine["engine"], 0.0
+    _api_engine["key"], _api_engine["engine"] = None, None
+    started = time.perf_counter()
+    engine = auto_engine_detailed(model_name=model, compute_type=compute_type, cpu_threads=threads)
+    _api_engine["key"], _api_engine["engine"] = key, engine
+    return engine, time.perf_counter() - started
+
+def run_api_job(job_id, request):
+    """Transcribe one /api/jobs submission, filling in its segments as they are decoded."""
+    from transcribe_simple import _normalize_result, _normalize_segment, _peak_rss_bytes
+
+    job = API_JOBS[job_id]
+    word_timestamps = bool(request.get("word_timestamps"))
+    language = request.get("language") or "auto"
+
+    def on_segment(segment):
+        if job["cancelled"]:
+            raise JobCancelled()
+        job["segments"].append(_normalize_segment(segment, word_timestamps))
+
+    with API_ENGINE_LOCK:
+        if job["cancelled"]:
+            job["status"] = "cancelled"
+            return
+        job["status"] = "running"
+        print("[web-whisper] busy", flush=True)
+        try:
+            engine, load_seconds = _engine_for_job(request.get("model"), request.get("compute_type"),
+                                                   request.get("threads"))
+            result = engine(request["path"], language=None if language == "auto" else language,
+                            on_segment=on_segment, word_timestamps=word_timestamps,
+                            initial_prompt=request.get("prompt"))
+            job["result"] = _normalize_result(result, word_timestamps)
+            job["stats"] = {"model": getattr(engine, "model_name", request.get("model")),
+                            "model_load_seconds": load_seconds, "peak_rss_bytes": _peak_rss_bytes()}
+            job["status"] = "completed"
+        except JobCancelled:
+            job["status"] = "cancelled"
+        except Exception as e:
+            job["error"] = str(e)
+            job["status"] = "failed"
+        finally:
+            print("[web-whisper] idle", flush=True)
+            if request.get("delete_after"):
+                Path(request["path"]).unlink(missing_ok=True)
+
+def serve_with_token(demo, server_name, server_port, token):
+    """
+    Serve the interface behind a check for the session token, given once as ?token=
+    (the desktop app's URL) and remembered in a cookie for the page's own requests.
+    Other local programs and web pages cannot use the server without it.
+    """
+    import hmac
+    import uvicorn
+    import uuid
+    from fastapi import FastAPI, Request, UploadFile
+    from fastapi.responses import JSONResponse, PlainTextResponse
+
+    app = FastAPI()
+
+    @app.middleware("http")
+    async def require_token(request: Request, call_next):
+        supplied = request.query_params.get("token") or request.cookies.get(TOKEN_COOKIE) or ""
+        if not hmac.compare_digest(supplied.encode(), token.encode()):
+            return PlainTextResponse("Forbidden", status_code=403)
+        response = await call_next(request)
+        response.set_cookie(TOKEN_COOKIE, token, httponly=True, samesite="strict")
+        return response
+
+    @app.post("/api/upload")
+    async def upload(file: UploadFile):
+        """Store an uploaded media file and return the path to transcribe it from."""
+        name = Path(file.filename or "upload").name or "upload"
+        UPLOAD_DIR.mkdir(parents=True, exist_ok=True)
+        target = UPLOAD_DIR / f"{uuid.uuid4().hex}_{name}"
+        size = 0
+        try:
+            with open(target, "wb") as out:
+                while chunk := await file.read(1024 * 1024):
+                    out.write(chunk)
+                    size += len(chunk)
+        except OSError as e:
+            target.unlink(missing_ok=True)
+            return JSONResponse({"error": f"Failed to store upload: {e}"}, status_code=500)
+        return {"path": str(target.resolve()), "size": size}
+
+    @app.post("/api/jobs")
+    async def submit_job(request: Request):
+        """Start transcribing a file on the server; returns the job id to poll."""
+        body = await request.json()
+        path = body.get("path")
+        if not path or not Path(path).is_file():
+            return JSONResponse({"error": f"File not found: {path}"}, status_code=404)
+        job_id = uuid.uuid4().hex
+        API_JOBS[job_id] = {"status": "queued", "segments": [], "result": None, "stats": None, "error": None,
+                            "cancelled": False}
+        threading.Thread(target=run_api_job, args=(job_id, body), daemon=True).start()
+        return {"job_id": job_id}
+
+    @app.get("/api/jobs/{job_id}")
+    def poll_job(job_id: str, since: int = 0):
+        """Status and the segments decoded after the first `since`."""
+        job = API_JOBS.get(job_id)
+        if job is None:
+            return JSONResponse({"error": f"Unknown job: {job_id}"}, status_code=404)
+        return {"status": job["status"], "segments": job["segments"][since:], "error": job["error"]}
+
+    @app.get("/api/jobs/{job_id}/result")
+    def job_result(job_id: str):
+        """The finished transcript; the job is forgotten once it has been fetched."""
+        job = API_JOBS.get(job_id)
+        if job is None:
+            return JSONResponse({"error": f"Unknown job: {job_id}"}, status_code=404)
+        if job["status"] != "completed":
+            return JSONResponse({"error": f"Job is {job['status']}"}, status_code=409)
+        del API_JOBS[job_id]
+        return {"result": job["result"], "stats": job["stats"]}
+
+    @app.delete("/api/jobs/{job_id}")
+    def cancel_job(job_id: str):
+        """Stop a job at its next segment; a finished job is simply forgotten."""
+        job = API_JOBS.get(job_id)
+        if job is None:
+            return JSONResponse({"error": f"Unknown job: {job_id}"}, status_code=404)
+        job["cancelled"] = True
+        if job["status"] in ("completed", "failed", "cancelled"):
+            del API_JOBS[job_id]
+        return {"status": job["status"]}
+
+    # Registered before Gradio is mounted at "/", which would otherwise take the route
+    app = gr.mount_gradio_app(app, demo, path="/")
+    uvicorn.run(app, host=server_name, port=server_port)
+
 def main():
     parser = argparse.ArgumentParser(description="Web Whisper - Cross-platform Speech to Text")
     
@@ -435,7 +597,7 @@ def main():
     args = parser.parse_args()
     
     # Create output directory
-    Path("outputs").mkdir(exist_ok=True)
+    OUTPUT_DIR.mkdir(parents=True, exist_ok=True)
     
     # Check if running in Docker
     is_docker = os.path.exists('/.dockerenv')
@@ -447,6 +609,12 @@ def main():
     # Create and launch interface
     demo = create_interface()
     
+    # Set by the desktop app: every request must carry this session token
+    token = os.environ.get("WEB_WHISPER_TOKEN")
+    if token:
+        serve_with_token(demo, server_name, args.server_port, token)
+        return
+    
     demo.launch(
         server_name=server_name,
         server_port=args.server_port,
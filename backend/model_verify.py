@@ -0,0 +1,125 @@
+# -*- coding: utf-8 -*-
+"""
+Checksum verification for downloaded Whisper models.
+
+configs/model_manifest.json pins each Hugging Face repo to a revision and lists the
+SHA-256 of its files. A model is only handed to the engine once every listed file
+matches; files that do not are deleted and fetched again. A verified snapshot gets a
+marker file so large weights are not re-hashed on every start.
+
+If configs/model_manifest.json.sig and configs/model_manifest.pub exist, the manifest
+itself must carry a valid Ed25519 signature (raw 64-byte signature, PEM public key).
+Regenerate the manifest with scripts/update_model_manifest.py.
+"""
+import hashlib
+import json
+import os
+import sys
+
+CONFIG_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "configs")
+MANIFEST_PATH = os.path.join(CONFIG_DIR, "model_manifest.json")
+SIGNATURE_PATH = MANIFEST_PATH + ".sig"
+PUBLIC_KEY_PATH = os.path.join(CONFIG_DIR, "model_manifest.pub")
+
+VERIFIED_MARKER = ".web-whisper-verified"
+# Downloads tried per model before giving up, counting the first
+MAX_ATTEMPTS = 3
+
+
+def model_repo(model_name):
+    """Hugging Face repo for a faster-whisper size ("medium") or a full repo id."""
+    return model_name if "/" in model_name else f"Systran/faster-whisper-{model_name}"
+
+
+def _verify_signature(contents):
+    if not os.path.exists(SIGNATURE_PATH):
+        return
+    if not os.path.exists(PUBLIC_KEY_PATH):
+        raise RuntimeError(f"Model manifest is signed but {PUBLIC_KEY_PATH} is missing")
+    from cryptography.exceptions import InvalidSignature
+    from cryptography.hazmat.primitives.serialization import load_pem_public_key
+
+    with open(PUBLIC_KEY_PATH, "rb") as f:
+        public_key = load_pem_public_key(f.read())
+    with open(SIGNATURE_PATH, "rb") as f:
+        signature = f.read()
+    try:
+        public_key.verify(signature, contents)
+    except InvalidSignature:
+        raise RuntimeError("Model manifest signature is invalid; refusing to use it")
+
+
+def load_manifest():
+    if not os.path.exists(MANIFEST_PATH):
+        return {}
+    with open(MANIFEST_PATH, "rb") as f:
+        contents = f.read()
+    _verify_signature(contents)
+    return json.loads(contents).get("models", {})
+
+
+def _sha256(path):
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for block in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(block)
+    return digest.hexdigest()
+
+
+def _entry_digest(entry):
+    return hashlib.sha256(json.dumps(entry, sort_keys=True).encode("utf-8")).hexdigest()
+
+
+def _remove(path):
+    # Hub snapshots are symlinks into the blob store; drop the blob so it is fetched again
+    target = os.path.realpath(path)
+    for candidate in {path, target}:
+        try:
+            os.remove(candidate)
+        except FileNotFoundError:
+            pass
+
+
+def verified_model_path(model_name):
+    """
+    Download `model_name` at the pinned revision and return the local snapshot path
+    once its files match the manifest. Models not in the manifest are returned as
+    given, with a warning, unless WEB_WHISPER_REQUIRE_VERIFIED_MODELS=1.
+    """
+    repo = model_repo(model_name)
+    entry = load_manifest().get(repo)
+    if entry is None:
+        if os.environ.get("WEB_WHISPER_REQUIRE_VERIFIED_MODELS") == "1":
+            raise RuntimeError(f"Model {repo} is not in the model manifest; refusing to download it")
+        print(f"Warning: {repo} is not in the model manifest; it will not be verified", file=sys.stderr)
+        return model_name
+
+    from huggingface_hub import hf_hub_download, snapshot_download
+
+    revision = entry["revision"]
+    files = entry["files"]
+    path = snapshot_download(repo, revision=revision)
+    marker = os.path.join(path, VERIFIED_MARKER)
+    digest = _entry_digest(entry)
+    if os.path.exists(marker):
+        with open(marker, encoding="utf-8") as f:
+            if f.read().strip() == digest:
+                return path
+
+    for attempt in range(1, MAX_ATTEMPTS + 1):
+        bad = [
+            name for name, expected in files.items()
+            if not os.path.exists(os.path.join(path, name)) or _sha256(os.path.join(path, name)) != expected
+        ]
+        if not bad:
+            with open(marker, "w", encoding="utf-8") as f:
+                f.write(digest)
+            return path
+        if attempt == MAX_ATTEMPTS:
+            break
+        print(f"Checksum mismatch in {repo} ({', '.join(bad)}); downloading again", file=sys.stderr)
+        for name in bad:
+            _remove(os.path.join(path, name))
+            hf_hub_download(repo, name, revision=revision, force_download=True)
+
+    raise RuntimeError(f"Model {repo} failed checksum verification after {MAX_ATTEMPTS} downloads: {', '.join(bad)}")
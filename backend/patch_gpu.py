@@ -2,6 +2,8 @@
 import platform
 import importlib
 
+from model_verify import model_repo, verified_model_path
+
 def get_available_models():
     """Get available Whisper models for the current platform."""
     system = platform.system()
@@ -24,6 +26,46 @@ def get_available_models():
             "⚡ Fastest": "base"
         }
 
+# Approximate download sizes; unknown models assume the largest
+MODEL_DOWNLOAD_BYTES = {
+    "base": 150 * 1024**2,
+    "small": 500 * 1024**2,
+    "medium": 1600 * 1024**2,
+    "large-v2": 3200 * 1024**2,
+    "large-v3": 3200 * 1024**2,
+}
+DISK_RESERVE_BYTES = 200 * 1024**2
+
+def ensure_model_space(model_name):
+    """
+    Fail early with a clear error when a model that still has to be downloaded
+    will not fit in the Hugging Face cache, instead of dying halfway through the download.
+    """
+    import os
+    import shutil
+
+    cache_dir = os.environ.get("HF_HUB_CACHE") or os.path.join(
+        os.environ.get("HF_HOME") or os.path.join(os.path.expanduser("~"), ".cache", "huggingface"), "hub"
+    )
+    repo = model_repo(model_name)
+    if os.path.isdir(os.path.join(cache_dir, "models--" + repo.replace("/", "--"))):
+        return
+
+    path = cache_dir
+    while not os.path.exists(path) and os.path.dirname(path) != path:
+        path = os.path.dirname(path)
+    try:
+        available = shutil.disk_usage(path).free
+    except OSError:
+        return
+    short_name = model_name.split("/")[-1].replace("whisper-", "").replace("-mlx", "")
+    required = MODEL_DOWNLOAD_BYTES.get(short_name, max(MODEL_DOWNLOAD_BYTES.values())) + DISK_RESERVE_BYTES
+    if available < required:
+        raise RuntimeError(
+            f"Not enough disk space in {cache_dir}: {-(-required // 1024**2)} MB needed, "
+            f"{available // 1024**2} MB free (downloading {model_name})"
+        )
+
 def auto_engine(model_choice="🎯 High Accuracy"):
     """
     GPU auto-detection for cross-platform Whisper inference with model selection.
@@ -34,6 +76,8 @@ def auto_engine(model_choice="🎯 High Accuracy"):
     machine = platform.machine()
     available_models = get_available_models()
     model_name = available_models.get(model_choice, list(available_models.values())[0])
+    ensure_model_space(model_name)
+    model_path = verified_model_path(model_name)
     
     if system == "Darwin" and machine.startswith("arm"):
         # Apple Silicon macOS - Use MLX backend
@@ -41,7 +85,7 @@ def auto_engine(model_choice="🎯 High Accuracy"):
             mlx = importlib.import_module("mlx_whisper")
             
             def transcribe_mlx(audio_file):
-                result = mlx.transcribe(audio_file, path_or_hf_repo=model_name)
+                result = mlx.transcribe(audio_file, path_or_hf_repo=model_path)
                 return result["text"]
             
             print(f"Using MLX backend with model: {model_name}")
@@ -56,7 +100,7 @@ def auto_engine(model_choice="🎯 High Accuracy"):
             from faster_whisper import WhisperModel
             
             # Initialize model with CUDA acceleration using selected model
-            model = WhisperModel(model_name, device="cuda", compute_type="float16")
+            model = WhisperModel(model_path, device="cuda", compute_type="float16")
             
             def transcribe_faster_whisper(audio_file):
                 segments, info = model.transcribe(audio_file)
@@ -69,7 +113,7 @@ def auto_engine(model_choice="🎯 High Accuracy"):
             raise RuntimeError("faster-whisper not available. Install with: pip install faster-whisper")
         except Exception as e:
             # Fallback to CPU if CUDA not available
-            model = WhisperModel(model_name, device="cpu", compute_type="int8")
+            model = WhisperModel(model_path, device="cpu", compute_type="int8")
             
             def transcribe_cpu_fallback(audio_file):
                 segments, info = model.transcribe(audio_file)
@@ -82,7 +126,7 @@ def auto_engine(model_choice="🎯 High Accuracy"):
         # Intel macOS - Use faster-whisper as fallback
         try:
             from faster_whisper import WhisperModel
-            model = WhisperModel(model_name, device="cpu", compute_type="int8")
+            model = WhisperModel(model_path, device="cpu", compute_type="int8")
             
             def transcribe_intel_mac(audio_file):
                 segments, info = model.transcribe(audio_file)
@@ -100,10 +144,10 @@ def auto_engine(model_choice="🎯 High Accuracy"):
             from faster_whisper import WhisperModel
             # Try CUDA first, fallback to CPU
             try:
-                model = WhisperModel(model_name, device="cuda", compute_type="float16")
+                model = WhisperModel(model_path, device="cuda", compute_type="float16")
                 device_info = "CUDA"
             except:
-                model = WhisperModel(model_name, device="cpu", compute_type="int8")
+                model = WhisperModel(model_path, device="cpu", compute_type="int8")
                 device_info = "CPU"
             
             def transcribe_linux(audio_file):
@@ -213,14 +257,22 @@ def _detect_generic_gpu():
         return "💻 CPU処理 (PyTorch未インストール)"
 
 # Enhanced version with more detailed transcription options
-def auto_engine_detailed(model_choice="🎯 High Accuracy"):
+def auto_engine_detailed(model_choice="🎯 High Accuracy", model_name=None, compute_type=None, cpu_threads=None):
     """
     Enhanced GPU auto-detection with detailed transcription options including timestamps.
+    `model_name` (a faster-whisper size such as "medium") and `compute_type` override the
+    menu choice; the app passes them after checking the model fits in GPU memory.
+    `cpu_threads` caps faster-whisper's CPU threads (battery saver); None lets it decide.
     """
     system = platform.system()
     machine = platform.machine()
-    available_models = get_available_models()
-    model_name = available_models.get(model_choice, list(available_models.values())[0])
+    if model_name is None:
+        available_models = get_available_models()
+        model_name = available_models.get(model_choice, list(available_models.values())[0])
+    elif system == "Darwin" and machine.startswith("arm") and "/" not in model_name:
+        model_name = f"mlx-community/whisper-{model_name}-mlx"
+    ensure_model_space(model_name)
+    model_path = verified_model_path(model_name)
     
     if system == "Darwin" and machine.startswith("arm"):
         # Apple Silicon macOS - Use MLX backend
@@ -230,13 +282,16 @@ def auto_engine_detailed(model_choice="🎯 High Accuracy"):
             def transcribe_mlx_detailed(audio_file, **kwargs):
                 result = mlx.transcribe(
                     audio_file, 
-                    path_or_hf_repo=model_name,
+                    path_or_hf_repo=model_path,
                     word_timestamps=kwargs.get('word_timestamps', True),
-                    language=kwargs.get('language', None)
+                    language=kwargs.get('language', None),
+                    initial_prompt=kwargs.get('initial_prompt', None),
+                    clip_timestamps=kwargs.get('clip_timestamps', "0")
                 )
                 return result
             
             print(f"Using MLX backend for Apple Silicon with model: {model_name}")
+            transcribe_mlx_detailed.model_name = model_name
             return transcribe_mlx_detailed
             
         except ImportError:
@@ -249,21 +304,33 @@ def auto_engine_detailed(model_choice="🎯 High Accuracy"):
             
             # Auto-detect best device
             device = "cuda" if system == "Windows" else "cpu"
-            compute_type = "float16" if device == "cuda" else "int8"
+            # Half-precision types need CUDA; the CPU path always runs int8
+            compute_type = (compute_type or "float16") if device == "cuda" else "int8"
             
+            threads = cpu_threads or 0
             try:
-                model = WhisperModel(model_name, device=device, compute_type=compute_type)
+                model = WhisperModel(model_path, device=device, compute_type=compute_type, cpu_threads=threads)
             except:
                 # Fallback to CPU
-                model = WhisperModel(model_name, device="cpu", compute_type="int8")
+                model = WhisperModel(model_path, device="cpu", compute_type="int8", cpu_threads=threads)
                 device = "cpu"
             
             def transcribe_faster_whisper_detailed(audio_file, **kwargs):
                 segments, info = model.transcribe(
                     audio_file,
                     language=kwargs.get('language', None),
-                    word_timestamps=kwargs.get('word_timestamps', True)
+                    word_timestamps=kwargs.get('word_timestamps', True),
+                    initial_prompt=kwargs.get('initial_prompt', None),
+                    clip_timestamps=kwargs.get('clip_timestamps', "0")
                 )
+                on_segment = kwargs.get('on_segment')
+                decoded = []
+                for segment in segments:
+                    decoded.append(segment)
+                    if on_segment:
+                        on_segment({"start": segment.start, "end": segment.end, "text": segment.text,
+                                    "words": getattr(segment, 'words', None) or []})
+                segments = decoded
                 
                 result = {
                     "text": " ".join(segment.text for segment in segments),
@@ -282,6 +349,7 @@ def auto_engine_detailed(model_choice="🎯 High Accuracy"):
                 return result
             
             print(f"Using faster-whisper backend on {system} with {device.upper()} and model: {model_name}")
+            transcribe_faster_whisper_detailed.model_name = model_name
             return transcribe_faster_whisper_detailed
             
         except ImportError:
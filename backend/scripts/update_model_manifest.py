@@ -0,0 +1,84 @@
+"""
+Regenerate configs/model_manifest.json from the Hugging Face Hub.
+
+Pins every model the app can select to the repo's current revision and records the
+SHA-256 of each file (LFS files from the Hub's metadata, small files by downloading
+them). Review the diff before committing. With --sign, also writes a detached Ed25519
+signature next to the manifest using the given PEM private key.
+
+    python scripts/update_model_manifest.py [--sign private_key.pem]
+"""
+import argparse
+import hashlib
+import json
+import os
+import sys
+
+BACKEND_DIR = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+sys.path.insert(0, BACKEND_DIR)
+
+from model_verify import MANIFEST_PATH, SIGNATURE_PATH, model_repo  # noqa: E402
+
+# Every size the app or the menu can ask for, on both backends
+FASTER_WHISPER_SIZES = ["base", "small", "medium", "large-v2", "large-v3"]
+MLX_REPOS = [
+    "mlx-community/whisper-large-v3-mlx",
+    "mlx-community/whisper-large-v3-turbo",
+    "mlx-community/whisper-medium-mlx",
+    "mlx-community/whisper-base-mlx",
+    "mlx-community/whisper-small-mlx",
+]
+
+
+def file_sha256(repo, revision, sibling):
+    lfs = getattr(sibling, "lfs", None)
+    if lfs:
+        return lfs["sha256"] if isinstance(lfs, dict) else lfs.sha256
+    from huggingface_hub import hf_hub_download
+
+    path = hf_hub_download(repo, sibling.rfilename, revision=revision)
+    with open(path, "rb") as f:
+        return hashlib.sha256(f.read()).hexdigest()
+
+
+def manifest_entry(repo):
+    from huggingface_hub import HfApi
+
+    info = HfApi().model_info(repo, files_metadata=True)
+    files = {
+        sibling.rfilename: file_sha256(repo, info.sha, sibling)
+        for sibling in info.siblings
+        if sibling.rfilename not in (".gitattributes", "README.md")
+    }
+    return {"revision": info.sha, "files": files}
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Regenerate the model checksum manifest")
+    parser.add_argument("--sign", metavar="PRIVATE_KEY", help="PEM Ed25519 private key for a detached signature")
+    args = parser.parse_args()
+
+    models = {}
+    for repo in [model_repo(size) for size in FASTER_WHISPER_SIZES] + MLX_REPOS:
+        print(f"Pinning {repo}...")
+        models[repo] = manifest_entry(repo)
+
+    with open(MANIFEST_PATH, encoding="utf-8") as f:
+        manifest = json.load(f)
+    manifest["models"] = models
+    contents = (json.dumps(manifest, indent=2, sort_keys=True) + "\n").encode("utf-8")
+    with open(MANIFEST_PATH, "wb") as f:
+        f.write(contents)
+
+    if args.sign:
+        from cryptography.hazmat.primitives.serialization import load_pem_private_key
+
+        with open(args.sign, "rb") as f:
+            key = load_pem_private_key(f.read(), password=None)
+        with open(SIGNATURE_PATH, "wb") as f:
+            f.write(key.sign(contents))
+    print(f"Wrote {MANIFEST_PATH}")
+
+
+if __name__ == "__main__":
+    main()
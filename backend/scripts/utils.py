@@ -42,6 +42,37 @@ if LANG not in LANG_DICT:
 	print(f"WARNING! Language {LANG} not supported for the interface. Using English instead")
 MSG: dict[str, str] = LANG_DICT[LANG]
 
+# Always leave this much free so the OS and other apps are not starved (matches the app's check)
+DISK_RESERVE_BYTES = 200 * 1024 * 1024
+
+class InsufficientDiskSpaceError(OSError):
+	"""
+	Raised before a write that would not fit, instead of failing halfway through it.
+	"""
+	def __init__(self, path: str, required_bytes: int, available_bytes: int):
+		self.path = path
+		self.required_bytes = required_bytes
+		self.available_bytes = available_bytes
+		super().__init__(MSG["insufficient_disk_space"].format(
+			path, -(-required_bytes // (1024 * 1024)), available_bytes // (1024 * 1024)
+		))
+
+def ensure_free_space(directory: str, required_bytes: int = 0) -> None:
+	"""
+	Raise InsufficientDiskSpaceError when the volume holding `directory` cannot take `required_bytes` more.
+	The directory may not exist yet; its nearest existing parent is measured instead.
+	"""
+	path = os.path.abspath(directory)
+	while not os.path.exists(path) and os.path.dirname(path) != path:
+		path = os.path.dirname(path)
+	try:
+		available = shutil.disk_usage(path).free
+	except OSError:
+		return
+	required = required_bytes + DISK_RESERVE_BYTES
+	if available < required:
+		raise InsufficientDiskSpaceError(directory, required, available)
+
 def list_models() -> list[str]:
 	"""
 	Return a list of all models available in the `models/custom` directory.
@@ -163,6 +194,7 @@ def extract_audio_from_video(
 	"""
 	if not os.path.exists(temp_dir):
 		os.makedirs(temp_dir, exist_ok=True)
+	ensure_free_space(temp_dir)
 	
 	# Generate temporary audio file path
 	temp_audio_path = os.path.join(temp_dir, "temp_audio.mp3")
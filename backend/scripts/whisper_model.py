@@ -67,6 +67,9 @@ if LANG not in LANG_DICT:
 	print(f"WARNING! Language {LANG} not supported for the interface. Using English instead")
 MSG: dict[str, str] = LANG_DICT[LANG]
 
+# Upper bound for a Whisper checkpoint download (large-v3 in float32 is about 3.1 GB)
+MODEL_DOWNLOAD_BYTES = 4 * 1024 * 1024 * 1024
+
 class CustomWhisper():
 	def __init__(
 			self,
@@ -261,6 +264,7 @@ def load_custom_model(
 	# Check if model is already downloaded
 	is_local = _check_is_local(model_id, download_root)
 	if not is_local:
+		ensure_free_space(download_root, MODEL_DOWNLOAD_BYTES)
 		print(MSG["downloading_model"])
 
 	# Load Whisper model and processor
@@ -4,10 +4,53 @@ Simple command-line transcription script for Web Whisper
 """
 
 import sys
+import json
 import argparse
+import io
+import time
+from contextlib import redirect_stdout, redirect_stderr
 from pathlib import Path
 from patch_gpu import auto_engine_detailed
 
+def _normalize_words(words):
+    """Word timings from MLX (dicts) or faster-whisper (Word tuples), leading spaces kept."""
+    normalized = []
+    for word in words or []:
+        if isinstance(word, dict):
+            start, end, text = word.get("start"), word.get("end"), word.get("word", "")
+        else:
+            start, end, text = getattr(word, "start", None), getattr(word, "end", None), getattr(word, "word", "")
+        if start is None or end is None:
+            continue
+        normalized.append({"start": float(start), "end": float(end), "text": str(text)})
+    return normalized
+
+def _normalize_segment(segment, word_timestamps=False):
+    normalized = {
+        "start": float(segment.get("start", 0.0)),
+        "end": float(segment.get("end", 0.0)),
+        "text": str(segment.get("text", "")).strip(),
+    }
+    if word_timestamps:
+        normalized["words"] = _normalize_words(segment.get("words"))
+    return normalized
+
+def _normalize_result(result, word_timestamps=False):
+    """Convert engine output into a dict with text, language and segments."""
+    if isinstance(result, dict):
+        segments = [
+            _normalize_segment(segment, word_timestamps)
+            for segment in result.get("segments", [])
+            if isinstance(segment, dict)
+        ]
+        return {
+            "text": str(result.get("text", "")).strip(),
+            "language": result.get("language"),
+            "duration": segments[-1]["end"] if segments else None,
+            "segments": segments,
+        }
+    return {"text": str(result).strip(), "language": None, "duration": None, "segments": []}
+
 def transcribe_file(file_path: str, language: str = "auto", output_format: str = "text"):
     """Transcribe an audio file and return the result."""
     try:
@@ -61,18 +104,206 @@ def transcribe_file(file_path: str, language: str = "auto", output_format: str =
         print(f"Error during transcription: {e}", file=sys.stderr)
         return f"Error: {e}"
 
+def transcribe_file_detailed(file_path: str, language: str = "auto", model=None, compute_type=None, threads=None,
+                             word_timestamps=False):
+    """Transcribe an audio file and return text, language and timed segments."""
+    gpu_output = io.StringIO()
+    with redirect_stdout(gpu_output), redirect_stderr(gpu_output):
+        engine = auto_engine_detailed(model_name=model, compute_type=compute_type, cpu_threads=threads)
+    gpu_messages = gpu_output.getvalue()
+    if gpu_messages:
+        print(gpu_messages.strip(), file=sys.stderr)
+
+    print(f"Transcribing (detailed): {file_path}", file=sys.stderr)
+    result = engine(file_path, language=language if language != "auto" else None, word_timestamps=word_timestamps)
+    return _normalize_result(result, word_timestamps)
+
+def _peak_rss_bytes():
+    """Peak resident memory of this process, or None where it cannot be read."""
+    try:
+        import resource
+        peak = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+        # Linux reports kilobytes, macOS bytes
+        return peak if sys.platform == "darwin" else peak * 1024
+    except ImportError:
+        pass
+    try:
+        import psutil
+        return psutil.Process().memory_info().peak_wset
+    except (ImportError, AttributeError):
+        return None
+
+def detect_language(file_path: str, model=None, compute_type=None, threads=None):
+    """Print the language spoken in the first 30 seconds as one JSON line."""
+    gpu_output = io.StringIO()
+    with redirect_stdout(gpu_output), redirect_stderr(gpu_output):
+        engine = auto_engine_detailed(model_name=model, compute_type=compute_type, cpu_threads=threads)
+    gpu_messages = gpu_output.getvalue()
+    if gpu_messages:
+        print(gpu_messages.strip(), file=sys.stderr)
+
+    print(f"Detecting language: {file_path}", file=sys.stderr)
+    result = engine(file_path, language=None, word_timestamps=False, clip_timestamps="0,30")
+    language = result.get("language") if isinstance(result, dict) else None
+    print(json.dumps({"type": "language", "language": language}), flush=True)
+
+def detect_languages(model=None, compute_type=None, threads=None):
+    """Read file paths from stdin, one per line, and print one JSON line per file with its language.
+
+    The model is loaded once, so this is much faster than one --detect-language run per file.
+    """
+    gpu_output = io.StringIO()
+    with redirect_stdout(gpu_output), redirect_stderr(gpu_output):
+        engine = auto_engine_detailed(model_name=model, compute_type=compute_type, cpu_threads=threads)
+    gpu_messages = gpu_output.getvalue()
+    if gpu_messages:
+        print(gpu_messages.strip(), file=sys.stderr)
+
+    for line in sys.stdin:
+        file_path = line.rstrip("\r\n")
+        if not file_path:
+            continue
+        try:
+            result = engine(file_path, language=None, word_timestamps=False, clip_timestamps="0,30")
+        except Exception as e:
+            print(json.dumps({"type": "error", "file_path": file_path, "error": str(e)}, ensure_ascii=False), flush=True)
+            continue
+        language = result.get("language") if isinstance(result, dict) else None
+        probability = result.get("language_probability") if isinstance(result, dict) else None
+        line = {"type": "language", "file_path": file_path, "language": language, "probability": probability}
+        print(json.dumps(line, ensure_ascii=False), flush=True)
+
+def transcribe_file_streaming(file_path: str, language: str = "auto", model=None, compute_type=None, threads=None,
+                              word_timestamps=False, prompt=None):
+    """Print one JSON line per decoded segment, then a final result line and run stats."""
+    load_started = time.perf_counter()
+    gpu_output = io.StringIO()
+    with redirect_stdout(gpu_output), redirect_stderr(gpu_output):
+        engine = auto_engine_detailed(model_name=model, compute_type=compute_type, cpu_threads=threads)
+    model_load_seconds = time.perf_counter() - load_started
+    gpu_messages = gpu_output.getvalue()
+    if gpu_messages:
+        print(gpu_messages.strip(), file=sys.stderr)
+
+    emitted = 0
+
+    def emit(segment):
+        nonlocal emitted
+        emitted += 1
+        line = {"type": "segment", **_normalize_segment(segment, word_timestamps)}
+        print(json.dumps(line, ensure_ascii=False), flush=True)
+
+    print(f"Transcribing (streaming): {file_path}", file=sys.stderr)
+    result = _normalize_result(
+        engine(file_path, language=language if language != "auto" else None, on_segment=emit,
+               word_timestamps=word_timestamps, initial_prompt=prompt),
+        word_timestamps,
+    )
+    # Engines without incremental output (MLX) report all segments at the end
+    for segment in result["segments"][emitted:]:
+        emit(segment)
+    print(json.dumps({"type": "result", **result}, ensure_ascii=False), flush=True)
+    stats = {"type": "stats", "model": getattr(engine, "model_name", model),
+             "model_load_seconds": model_load_seconds, "peak_rss_bytes": _peak_rss_bytes()}
+    print(json.dumps(stats), flush=True)
+
+# Raw PCM the desktop app writes to stdin in --live mode: 16 kHz mono signed 16-bit little-endian
+LIVE_SAMPLE_RATE = 16000
+
+def transcribe_live(language: str = "auto", model=None, compute_type=None, threads=None, chunk_seconds=5.0,
+                    prompt=None):
+    """Transcribe PCM from stdin in fixed windows, printing one JSON line per segment as it is decoded."""
+    import numpy as np
+
+    gpu_output = io.StringIO()
+    with redirect_stdout(gpu_output), redirect_stderr(gpu_output):
+        engine = auto_engine_detailed(model_name=model, compute_type=compute_type, cpu_threads=threads)
+    gpu_messages = gpu_output.getvalue()
+    if gpu_messages:
+        print(gpu_messages.strip(), file=sys.stderr)
+    print(json.dumps({"type": "ready"}), flush=True)
+
+    window_bytes = max(int(chunk_seconds * LIVE_SAMPLE_RATE), LIVE_SAMPLE_RATE) * 2
+    offset = 0.0
+    stdin = sys.stdin.buffer
+    while True:
+        data = stdin.read(window_bytes)
+        # An odd trailing byte is half a sample
+        data = data[:len(data) - len(data) % 2]
+        if not data:
+            break
+        audio = np.frombuffer(data, dtype=np.int16).astype(np.float32) / 32768.0
+        result = _normalize_result(
+            engine(audio, language=language if language != "auto" else None, word_timestamps=False,
+                   initial_prompt=prompt),
+        )
+        for segment in result["segments"]:
+            if not segment["text"]:
+                continue
+            line = {"type": "segment", "start": segment["start"] + offset, "end": segment["end"] + offset,
+                    "text": segment["text"]}
+            print(json.dumps(line, ensure_ascii=False), flush=True)
+        offset += len(audio) / LIVE_SAMPLE_RATE
+
 def main():
     parser = argparse.ArgumentParser(description="Transcribe audio file")
-    parser.add_argument("file_path", help="Path to audio file")
+    parser.add_argument("file_path", help="Path to audio file, or - with --live or --detect-language")
     parser.add_argument("--language", default="auto", help="Language code (default: auto)")
-    parser.add_argument("--format", default="text", help="Output format (default: text)")
+    parser.add_argument("--format", default="text", choices=["text", "json", "jsonl"], help="Output format (default: text)")
+    parser.add_argument("--model", default=None, help="Model size, e.g. large-v3 or medium (default: High Accuracy preset)")
+    parser.add_argument("--compute-type", default=None, help="faster-whisper compute type on CUDA (default: float16)")
+    parser.add_argument("--threads", type=int, default=None, help="CPU threads for faster-whisper (default: automatic)")
+    parser.add_argument("--word-timestamps", action="store_true", help="Include per-word timings in json/jsonl output")
+    parser.add_argument("--prompt", default=None, help="Initial prompt with vocabulary or style for jsonl output")
+    parser.add_argument("--detect-language", action="store_true",
+                        help="Only print the detected language as JSON; with -, for each file path read from stdin")
+    parser.add_argument("--live", action="store_true", help="Read 16 kHz mono s16le PCM from stdin until it closes")
+    parser.add_argument("--chunk-seconds", type=float, default=5.0, help="Audio per decode in --live mode (default: 5)")
     
     args = parser.parse_args()
+
+    if args.live:
+        try:
+            transcribe_live(args.language, args.model, args.compute_type, args.threads, args.chunk_seconds, args.prompt)
+        except Exception as e:
+            print(f"Error during live transcription: {e}", file=sys.stderr)
+            sys.exit(1)
+        return
+
+    if args.detect_language and args.file_path == "-":
+        try:
+            detect_languages(args.model, args.compute_type, args.threads)
+        except Exception as e:
+            print(f"Error during language detection: {e}", file=sys.stderr)
+            sys.exit(1)
+        return
     
     if not Path(args.file_path).exists():
         print(f"Error: File not found: {args.file_path}", file=sys.stderr)
         sys.exit(1)
     
+    if args.detect_language:
+        try:
+            detect_language(args.file_path, args.model, args.compute_type, args.threads)
+        except Exception as e:
+            print(f"Error during language detection: {e}", file=sys.stderr)
+            sys.exit(1)
+        return
+
+    if args.format in ("json", "jsonl"):
+        try:
+            if args.format == "jsonl":
+                transcribe_file_streaming(args.file_path, args.language, args.model, args.compute_type, args.threads,
+                                          args.word_timestamps, args.prompt)
+            else:
+                detailed = transcribe_file_detailed(args.file_path, args.language, args.model, args.compute_type,
+                                                    args.threads, args.word_timestamps)
+                print(json.dumps(detailed, ensure_ascii=False))
+        except Exception as e:
+            print(f"Error during transcription: {e}", file=sys.stderr)
+            sys.exit(1)
+        return
+
     result = transcribe_file(args.file_path, args.language, args.format)
     print(result)
 
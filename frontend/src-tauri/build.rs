@@ -1,3 +1,15 @@
 fn main() {
+    // protox compiles the schema in pure Rust, so no protoc install is needed
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/transcription.proto");
+        let file_descriptors = protox::compile(["proto/transcription.proto"], ["proto"])
+            .expect("failed to parse proto/transcription.proto");
+        tonic_build::configure()
+            .build_client(false)
+            .compile_fds(file_descriptors)
+            .expect("failed to generate gRPC service code");
+    }
+
     tauri_build::build()
-}
\ No newline at end of file
+}
@@ -0,0 +1,405 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        DefaultBodyLimit, Multipart, Path, Query, Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Deserialize;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::info;
+
+use crate::engine::{self, TranscribeOptions, Transcript};
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::stream::StreamHub;
+use crate::{captions, config, history, secrets, subtitle};
+
+#[derive(Clone)]
+struct ApiState {
+    hub: StreamHub,
+    jobs: JobRegistry,
+    key: Arc<String>,
+    port: u16,
+}
+
+pub const DEFAULT_API_PORT: u16 = 8765;
+
+// Keychain entry of the key clients send as `Authorization: Bearer <key>`
+const API_KEY: &str = "api_key";
+
+// The API key, generated and stored in the keychain on first use
+pub fn api_key() -> Result<String, AppError> {
+    if let Some(key) = secrets::get(API_KEY)? {
+        return Ok(key);
+    }
+    let key: String = rand::thread_rng().sample_iter(&Alphanumeric).take(40).map(char::from).collect();
+    secrets::set(API_KEY, &key)?;
+    Ok(key)
+}
+
+// WEB_WHISPER_API_PORT overrides the port; "0" or "off" disables the API server, as does
+// the rest_api feature flag
+pub fn configured_port() -> Option<u16> {
+    if !crate::flags::enabled(crate::flags::Flag::RestApi) {
+        return None;
+    }
+    match std::env::var("WEB_WHISPER_API_PORT") {
+        Ok(value) if value == "off" => None,
+        Ok(value) => value.parse::<u16>().ok().filter(|port| *port != 0),
+        Err(_) => Some(DEFAULT_API_PORT),
+    }
+}
+
+pub async fn serve(port: u16, hub: StreamHub, jobs: JobRegistry) -> Result<(), AppError> {
+    let key = tokio::task::spawn_blocking(api_key).await.map_err(|e| format!("API key task failed: {}", e))??;
+    let state = ApiState { hub, jobs, key: Arc::new(key), port };
+    let app = Router::new()
+        .route("/v1/models", get(list_models))
+        .route("/v1/audio/transcriptions", post(create_transcription))
+        .route("/v1/stream", get(stream_segments))
+        .route("/v1/jobs/:job_id", get(get_job))
+        // Multipart overhead on top of the media itself
+        .layer(DefaultBodyLimit::max(config::max_upload_bytes().saturating_add(1024 * 1024) as usize))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("Failed to bind API server on port {}: {}", port, e))?;
+    info!("API server listening on http://127.0.0.1:{}/v1", port);
+
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| format!("API server error: {}", e).into())
+}
+
+// OpenAI-style error body: {"error": {"message", "type", "param", "code"}}
+fn api_error(status: StatusCode, message: &str, param: Option<&str>) -> Response {
+    let error_type = if status.is_client_error() { "invalid_request_error" } else { "server_error" };
+    let body = serde_json::json!({
+        "error": {
+            "message": message,
+            "type": error_type,
+            "param": param,
+            "code": null,
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+// Browsers attach Origin to cross-site requests; only pages served from the API itself may
+// call it, so a web page cannot drive the API even when it learns the key
+fn is_own_origin(origin: &str, port: u16) -> bool {
+    origin == format!("http://127.0.0.1:{}", port) || origin == format!("http://localhost:{}", port)
+}
+
+// Browsers cannot set headers on a WebSocket handshake, so /v1/stream also takes ?token=
+fn stream_token(request: &Request) -> Option<String> {
+    if request.uri().path() != "/v1/stream" {
+        return None;
+    }
+    Query::<StreamQuery>::try_from_uri(request.uri()).ok()?.0.token
+}
+
+// Every route needs the API key. Requests from other origins are refused, except a stream
+// opened with ?token=: caption overlays are file:// pages, and the key was handed to them.
+async fn authorize(State(api): State<ApiState>, request: Request, next: Next) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let token = stream_token(&request);
+    if bearer.as_deref().or(token.as_deref()) != Some(api.key.as_str()) {
+        return api_error(StatusCode::UNAUTHORIZED, "Invalid API key: send it as 'Authorization: Bearer <key>'", None);
+    }
+    if let Some(origin) = request.headers().get(header::ORIGIN) {
+        let own = origin.to_str().is_ok_and(|origin| is_own_origin(origin, api.port));
+        if !own && token.is_none() {
+            return api_error(StatusCode::FORBIDDEN, "Cross-origin requests are not allowed", None);
+        }
+    }
+    next.run(request).await
+}
+
+async fn list_models() -> Response {
+    Json(serde_json::json!({
+        "object": "list",
+        "data": [
+            {"id": engine::MODEL_ID, "object": "model", "created": 0, "owned_by": "web-whisper"}
+        ]
+    }))
+    .into_response()
+}
+
+// Copy the uploaded file field to a temp file chunk by chunk, enforcing the size limit
+async fn stage_upload(mut field: axum::extract::multipart::Field<'_>) -> Result<std::path::PathBuf, AppError> {
+    use tokio::io::AsyncWriteExt;
+
+    // Keep only the extension of the client-supplied name so ffmpeg can sniff the container
+    let extension = std::path::Path::new(field.file_name().unwrap_or("audio"))
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "bin".to_string());
+    let path = engine::temp_file_path(&format!("api_upload.{}", extension))?;
+    if let Some(dir) = path.parent() {
+        crate::disk::ensure_space(dir, 0)?;
+    }
+    let file = tokio::fs::File::create(&path).await.map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut writer = tokio::io::BufWriter::new(file);
+
+    let limit = config::max_upload_bytes();
+    let mut written = 0u64;
+    let result: Result<(), AppError> = async {
+        while let Some(chunk) = field.chunk().await.map_err(|e| format!("Failed to read file: {}", e))? {
+            written += chunk.len() as u64;
+            config::check_upload_size(written, limit)?;
+            writer.write_all(&chunk).await.map_err(|e| format!("Failed to write temp file: {}", e))?;
+        }
+        writer.flush().await.map_err(|e| format!("Failed to write temp file: {}", e).into())
+    }
+    .await;
+
+    match result {
+        Ok(()) => Ok(path),
+        Err(e) => {
+            drop(writer);
+            let _ = tokio::fs::remove_file(&path).await;
+            Err(e)
+        }
+    }
+}
+
+// Client-chosen ids end up in the registry, history and temp file names
+fn valid_job_id(job_id: &str) -> bool {
+    (1..=64).contains(&job_id.len()) && job_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn discard(staged: Option<std::path::PathBuf>) {
+    if let Some(path) = staged {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+async fn create_transcription(State(api): State<ApiState>, mut multipart: Multipart) -> Response {
+    let mut temp_path: Option<std::path::PathBuf> = None;
+    let mut language = "auto".to_string();
+    let mut response_format = "json".to_string();
+    let mut job_id = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                discard(temp_path);
+                return api_error(StatusCode::BAD_REQUEST, &format!("Invalid multipart body: {}", e), None);
+            }
+        };
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => match stage_upload(field).await {
+                Ok(path) => discard(temp_path.replace(path)),
+                Err(e) => {
+                    discard(temp_path);
+                    let status = match e {
+                        AppError::LimitExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+                        _ => StatusCode::BAD_REQUEST,
+                    };
+                    return api_error(status, &e.to_string(), Some("file"));
+                }
+            },
+            // model, prompt, temperature and timestamp_granularities are accepted for
+            // compatibility; the local engine always runs its configured model
+            _ => {
+                let value = match field.text().await {
+                    Ok(value) => value,
+                    Err(e) => {
+                        discard(temp_path);
+                        return api_error(StatusCode::BAD_REQUEST, &format!("Failed to read field '{}': {}", name, e), Some(&name));
+                    }
+                };
+                match name.as_str() {
+                    "language" if !value.trim().is_empty() => language = value.trim().to_string(),
+                    "response_format" => response_format = value.trim().to_string(),
+                    // Extension: lets a client subscribe to /v1/stream?job_id= before uploading
+                    "job_id" if !value.trim().is_empty() => job_id = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let temp_path = match temp_path {
+        Some(path) => path,
+        None => return api_error(StatusCode::BAD_REQUEST, "Missing required parameter: 'file'", Some("file")),
+    };
+    if !["json", "text", "srt", "verbose_json", "vtt"].contains(&response_format.as_str()) {
+        let _ = std::fs::remove_file(&temp_path);
+        return api_error(
+            StatusCode::BAD_REQUEST,
+            &format!("Unsupported response_format '{}'", response_format),
+            Some("response_format"),
+        );
+    }
+
+    let path_for_engine = temp_path.to_string_lossy().to_string();
+    let job_id = match job_id {
+        Some(job_id) if !valid_job_id(&job_id) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return api_error(StatusCode::BAD_REQUEST, "Invalid job_id: use 1 to 64 letters, digits, '-' or '_'", Some("job_id"));
+        }
+        Some(job_id) => {
+            let id = job_id.clone();
+            let in_history = tokio::task::spawn_blocking(move || history::get(&id).is_ok()).await.unwrap_or(true);
+            if in_history || !jobs::reserve(&api.jobs, &job_id, &path_for_engine) {
+                let _ = std::fs::remove_file(&temp_path);
+                return api_error(StatusCode::CONFLICT, &format!("Job {} already exists: choose another job_id", job_id), Some("job_id"));
+            }
+            job_id
+        }
+        None => engine::new_job_id(),
+    };
+    let options = TranscribeOptions { language, ..Default::default() };
+    let result = tokio::task::spawn_blocking(move || {
+        jobs::run_job(&api.jobs, &api.hub, &job_id, &path_for_engine, &options)
+    })
+    .await;
+    let _ = std::fs::remove_file(&temp_path);
+
+    let transcript = match result {
+        Ok(Ok(transcript)) => transcript,
+        Ok(Err(e)) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string(), None),
+        Err(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, &format!("Transcription task failed: {}", e), None),
+    };
+
+    format_response(&transcript, &response_format)
+}
+
+async fn get_job(State(api): State<ApiState>, Path(job_id): Path<String>) -> Response {
+    match jobs::get(&api.jobs, &job_id) {
+        Some(record) => Json(record).into_response(),
+        None => api_error(StatusCode::NOT_FOUND, &format!("Unknown job: {}", job_id), Some("job_id")),
+    }
+}
+
+#[derive(Deserialize)]
+struct StreamQuery {
+    job_id: Option<String>,
+    // The API key, checked by `authorize`
+    token: Option<String>,
+}
+
+// WebSocket feed of segment events; `?job_id=` restricts it to a single job. Segments are
+// sent once final: the engine reports no interim hypotheses to stream.
+async fn stream_segments(
+    State(api): State<ApiState>,
+    Query(query): Query<StreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| forward_events(socket, api.hub, query.job_id))
+}
+
+async fn forward_events(mut socket: WebSocket, hub: StreamHub, job_id: Option<String>) {
+    let mut events = hub.subscribe();
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    // Slow consumers skip missed events instead of disconnecting
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if job_id.as_deref().is_some_and(|id| id != event.job_id()) {
+                    continue;
+                }
+                let payload = match serde_json::to_string(&event) {
+                    Ok(payload) => payload,
+                    Err(_) => continue,
+                };
+                if socket.send(Message::Text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn format_response(transcript: &Transcript, response_format: &str) -> Response {
+    match response_format {
+        "text" => plain(format!("{}\n", transcript.text), "text/plain; charset=utf-8"),
+        "srt" => plain(subtitle::to_srt(&captions::cues(transcript)), "text/plain; charset=utf-8"),
+        "vtt" => plain(subtitle::to_vtt(&captions::cues(transcript)), "text/vtt; charset=utf-8"),
+        "verbose_json" => {
+            let segments: Vec<serde_json::Value> = transcript
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(id, segment)| {
+                    serde_json::json!({
+                        "id": id,
+                        "seek": 0,
+                        "start": segment.start,
+                        "end": segment.end,
+                        "text": segment.text,
+                        "tokens": [],
+                        "temperature": 0.0,
+                    })
+                })
+                .collect();
+            Json(serde_json::json!({
+                "task": "transcribe",
+                "language": transcript.language.clone().unwrap_or_default(),
+                "duration": transcript.duration.unwrap_or_default(),
+                "text": transcript.text,
+                "segments": segments,
+            }))
+            .into_response()
+        }
+        _ => Json(serde_json::json!({ "text": transcript.text })).into_response(),
+    }
+}
+
+fn plain(body: String, content_type: &'static str) -> Response {
+    ([(header::CONTENT_TYPE, content_type)], body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_job_ids_are_plain_and_short() {
+        for job_id in ["job-1", "my_upload_42", "A"] {
+            assert!(valid_job_id(job_id), "{}", job_id);
+        }
+        for job_id in ["", "../etc", "job 1", "job/1", "ジョブ", &"a".repeat(65)] {
+            assert!(!valid_job_id(job_id), "{}", job_id);
+        }
+    }
+
+    #[test]
+    fn reserved_ids_cannot_be_taken_twice() {
+        let registry = jobs::new_registry();
+        assert!(jobs::reserve(&registry, "job-1", "a.mp3"));
+        assert!(!jobs::reserve(&registry, "job-1", "b.mp3"));
+        assert_eq!(jobs::get(&registry, "job-1").unwrap().file_path, "a.mp3");
+    }
+}
@@ -0,0 +1,299 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::audit::{self, AuditAction};
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::jobs::JobRegistry;
+use crate::stream::StreamHub;
+use crate::{config, dedupe, fingerprint, history, journal, projects, temp, watch};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveFile {
+    // Path inside the archive
+    pub name: String,
+    pub job_id: Option<String>,
+    // Why the file is not queued
+    pub skipped: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveImport {
+    pub project_id: String,
+    pub queued: usize,
+    pub files: Vec<ArchiveFile>,
+    // Entries that are not supported media, such as notes or folders
+    pub ignored: usize,
+}
+
+fn is_media(name: &Path) -> bool {
+    let extension = name.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    watch::MEDIA_EXTENSIONS.contains(&extension.as_str())
+}
+
+// Hidden files and macOS resource forks (__MACOSX/._talk.m4a) are not recordings
+fn is_hidden(name: &Path) -> bool {
+    name.components().any(|part| {
+        let part = part.as_os_str().to_string_lossy();
+        part.starts_with('.') || part == "__MACOSX"
+    })
+}
+
+// Extract the media in the archive under `dir`. Entries whose path would land outside `dir`
+// ("../x", absolute paths) and symlinks are skipped, and the extracted media together may
+// not exceed `limit` bytes. `archive_path` only names the archive in messages.
+fn extract(
+    reader: impl Read + Seek,
+    archive_path: &str,
+    dir: &Path,
+    limit: u64,
+) -> Result<(Vec<(ArchiveFile, PathBuf)>, usize), AppError> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| AppError::invalid(format!("Invalid ZIP archive {}: {}", archive_path, e)))?;
+    let mut total: u64 = 0;
+    let (mut files, mut ignored) = (Vec::new(), 0);
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| AppError::invalid(format!("Invalid ZIP archive {}: {}", archive_path, e)))?;
+        let raw_name = entry.name().to_string();
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            warn!("Skipping unsafe path {:?} in {}", raw_name, archive_path);
+            let skipped = Some("Unsafe path outside the archive".to_string());
+            files.push((ArchiveFile { name: raw_name, job_id: None, skipped }, PathBuf::new()));
+            continue;
+        };
+        if is_hidden(&name) || !is_media(&name) {
+            ignored += 1;
+            continue;
+        }
+        if entry.is_symlink() {
+            let skipped = Some("Symbolic links are not extracted".to_string());
+            files.push((ArchiveFile { name: raw_name, job_id: None, skipped }, PathBuf::new()));
+            continue;
+        }
+
+        let target = dir.join(&name);
+        // enclosed_name already rules this out; checked again because a miss writes anywhere
+        if !target.starts_with(dir) {
+            return Err(AppError::invalid(format!("Invalid ZIP archive {}: unsafe path {}", archive_path, raw_name)));
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut out = std::fs::File::create(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+        // The sizes in the archive's header can lie, so the copy itself is capped
+        let remaining = limit.saturating_sub(total);
+        let copied = std::io::copy(&mut (&mut entry).take(remaining + 1), &mut out)
+            .map_err(|e| format!("Failed to extract {} from {}: {}", raw_name, archive_path, e))?;
+        total += copied;
+        config::check_upload_size(total, limit)?;
+        files.push((ArchiveFile { name: raw_name, job_id: None, skipped: None }, target));
+    }
+    Ok((files, ignored))
+}
+
+// The project named after the archive, created on first use
+fn archive_project(archive_path: &Path) -> Result<String, AppError> {
+    let name = archive_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    if let Some(project) = projects::list()?.into_iter().find(|project| project.name == name.trim()) {
+        return Ok(project.id);
+    }
+    Ok(projects::create(&name)?.id)
+}
+
+// Extract the recordings in a .zip into the temp folder and queue them under a project
+// named after the archive (or `project_id`). Extracted files are deleted as their jobs
+// finish, and the folder once the last one is done. Blocks while extracting.
+pub fn import_zip(
+    registry: &JobRegistry,
+    hub: &StreamHub,
+    archive_path: &str,
+    options: Option<TranscribeOptions>,
+    project_id: Option<String>,
+) -> Result<ArchiveImport, AppError> {
+    let path = Path::new(archive_path);
+    if !path.is_file() {
+        return Err(AppError::unknown(format!("File not found: {}", archive_path)));
+    }
+    if let Some(project_id) = &project_id {
+        projects::get(project_id)?;
+    }
+    audit::record(None, AuditAction::Read, "file", archive_path);
+
+    let dir = temp::dir().join(format!("zip-{}", engine::new_job_id().trim_start_matches("job-")));
+    let file = std::fs::File::open(archive_path).map_err(|e| format!("Failed to open {}: {}", archive_path, e))?;
+    let (extracted, ignored) = match extract(file, archive_path, &dir, config::max_upload_bytes()) {
+        Ok((extracted, _)) if extracted.is_empty() => {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(AppError::invalid(format!("Invalid ZIP archive {}: it has no supported media files", archive_path)));
+        }
+        Ok(extracted) => extracted,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+    let project_id = match project_id {
+        Some(project_id) => project_id,
+        None => archive_project(path)?,
+    };
+    let options = projects::options_for(Some(&project_id), options)?;
+
+    let archive_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let mut files = Vec::new();
+    let mut queue = Vec::new();
+    for (mut file, target) in extracted {
+        if file.skipped.is_none() {
+            let job_id = engine::new_job_id();
+            let target_path = target.to_string_lossy().to_string();
+            match dedupe::hash_file(&target_path) {
+                Ok(hash) => {
+                    let existing = dedupe::existing(registry, &hash);
+                    file.skipped = existing.map(|(existing, _)| format!("Duplicate of {}", existing));
+                    if file.skipped.is_none() {
+                        file.skipped = fingerprint::screen(&target_path, Some(&job_id), false).map(|found| found.describe());
+                    }
+                    if file.skipped.is_none() {
+                        let existing = dedupe::claim(registry, &hash, &job_id, false);
+                        file.skipped = existing.map(|(existing, _)| format!("Duplicate of {}", existing));
+                    }
+                }
+                Err(e) => file.skipped = Some(e.to_string()),
+            }
+            if file.skipped.is_none() {
+                projects::assign(&job_id, Some(&project_id))?;
+                file.job_id = Some(job_id.clone());
+                queue.push((job_id, target));
+            } else {
+                let _ = std::fs::remove_file(&target);
+            }
+        }
+        files.push(file);
+    }
+
+    let queued = queue.len();
+    if queued == 0 {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    // The last job to finish removes the extraction folder
+    let remaining = Arc::new(AtomicUsize::new(queued));
+    for (job_id, target) in queue {
+        let (registry, hub, options, remaining, dir) =
+            (registry.clone(), hub.clone(), options.clone(), remaining.clone(), dir.clone());
+        let archive_name = archive_name.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let file_path = target.to_string_lossy().to_string();
+            // Temporary: the extracted file is deleted when the job finishes
+            match journal::run(&registry, &hub, &job_id, &file_path, &options, true) {
+                Ok(transcript) => {
+                    let title = target.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| job_id.clone());
+                    let mut tags = BTreeMap::new();
+                    tags.insert("archive".to_string(), archive_name);
+                    if let Err(e) = history::add(&job_id, &title, &file_path, &transcript, tags) {
+                        warn!("Failed to save transcript of {}: {}", file_path, e);
+                    }
+                }
+                Err(e) => warn!("Transcription of {} failed: {}", file_path, e),
+            }
+            if remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+                let _ = std::fs::remove_dir_all(&dir);
+            }
+        });
+    }
+    info!("Queued {} file(s) from {} ({} other entries ignored)", queued, archive_path, ignored);
+    Ok(ArchiveImport { project_id, queued, files, ignored })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    fn archive(entries: &[(&str, &[u8])], method: CompressionMethod) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer.start_file(*name, SimpleFileOptions::default().compression_method(method)).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("web-whisper-test-{}-{}", name, engine::new_job_id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn skipped(files: &[(ArchiveFile, PathBuf)], name: &str) -> bool {
+        files.iter().any(|(file, _)| file.name == name && file.skipped.is_some())
+    }
+
+    #[test]
+    fn parent_and_absolute_paths_are_skipped() {
+        let bytes = archive(&[("../evil.mp3", b"x"), ("/tmp/evil.mp3", b"x"), ("talk.mp3", b"audio")], CompressionMethod::Stored);
+        let dir = scratch_dir("paths");
+        let (files, _) = extract(Cursor::new(bytes), "test.zip", &dir.join("out"), 1024).unwrap();
+        assert!(skipped(&files, "../evil.mp3"));
+        assert!(skipped(&files, "/tmp/evil.mp3"));
+        assert!(!dir.join("evil.mp3").exists());
+        let extracted: Vec<&PathBuf> = files.iter().filter(|(file, _)| file.skipped.is_none()).map(|(_, path)| path).collect();
+        assert_eq!(extracted, [&dir.join("out").join("talk.mp3")]);
+        assert_eq!(std::fs::read(extracted[0]).unwrap(), b"audio");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn symlinks_are_not_extracted() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer.add_symlink("link.mp3", "/etc/passwd", SimpleFileOptions::default()).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+        let dir = scratch_dir("symlink");
+        let (files, _) = extract(Cursor::new(bytes), "test.zip", &dir, 1024).unwrap();
+        assert!(skipped(&files, "link.mp3"));
+        assert!(std::fs::symlink_metadata(dir.join("link.mp3")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn notes_and_hidden_files_are_ignored() {
+        let bytes = archive(&[("notes.txt", b"x"), ("__MACOSX/._talk.mp3", b"x"), ("talk.mp3", b"x")], CompressionMethod::Stored);
+        let dir = scratch_dir("ignored");
+        let (files, ignored) = extract(Cursor::new(bytes), "test.zip", &dir, 1024).unwrap();
+        assert_eq!((files.len(), ignored), (1, 2));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn media_over_the_limit_is_rejected() {
+        let bytes = archive(&[("a.mp3", &[0; 600]), ("b.mp3", &[0; 600])], CompressionMethod::Stored);
+        let dir = scratch_dir("limit");
+        let result = extract(Cursor::new(bytes), "test.zip", &dir, 1000);
+        assert!(matches!(result, Err(AppError::LimitExceeded { .. })));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn understated_size_in_the_header_does_not_get_past_the_limit() {
+        let mut bytes = archive(&[("big.mp3", &[0; 100_000])], CompressionMethod::Deflated);
+        // Claim 10 bytes uncompressed in the local and central headers
+        let local = bytes.windows(4).position(|window| window == b"PK\x03\x04").unwrap();
+        bytes[local + 22..local + 26].copy_from_slice(&10u32.to_le_bytes());
+        let central = bytes.windows(4).position(|window| window == b"PK\x01\x02").unwrap();
+        bytes[central + 24..central + 28].copy_from_slice(&10u32.to_le_bytes());
+
+        let dir = scratch_dir("understated");
+        // The copy is capped by the bytes actually read, not by what the header claims
+        let result = extract(Cursor::new(bytes), "test.zip", &dir, 1000);
+        assert!(matches!(result, Err(AppError::LimitExceeded { .. })));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::audit::{self, AuditAction};
+use crate::engine::{self, Transcript};
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::{config, history, subtitle};
+
+// Containers ffmpeg can write from any input without extra flags
+const CLIP_FORMATS: &[&str] = &["mp3", "wav", "m4a", "ogg", "flac"];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SegmentClip {
+    pub index: usize,
+    pub start: f64,
+    pub end: f64,
+    pub path: String,
+}
+
+// Cut [start, end) seconds out of `input` into `output`; the container follows the output extension
+pub fn extract_clip(input: &str, start: f64, end: f64, output: &Path) -> Result<(), AppError> {
+    if end <= start {
+        return Err(AppError::invalid(format!("Invalid clip range {:.3}-{:.3}", start, end)));
+    }
+    if let Some(dir) = output.parent() {
+        crate::disk::ensure_space(dir, 0)?;
+    }
+    let result = Command::new("ffmpeg")
+        .args(["-y", "-v", "error", "-ss", &format!("{:.3}", start), "-t", &format!("{:.3}", end - start), "-i", input, "-vn"])
+        .arg(output)
+        .env("PATH", engine::path_with_ffmpeg())
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !result.status.success() {
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&result.stderr).trim()).into());
+    }
+    Ok(())
+}
+
+// The job's source file and current transcript, from history (with any edits) or memory
+pub fn job_source(registry: &JobRegistry, job_id: &str) -> Result<(String, String, Transcript), AppError> {
+    if let Ok(entry) = history::get(job_id) {
+        return Ok((entry.source, entry.title, entry.transcript));
+    }
+    let record = jobs::get(registry, job_id).ok_or_else(|| AppError::unknown(format!("Unknown job: {}", job_id)))?;
+    let transcript = record.transcript.ok_or("Job has no transcript")?;
+    let title = record.title.unwrap_or_else(|| {
+        Path::new(&record.file_path).file_stem().map_or_else(|| job_id.to_string(), |stem| stem.to_string_lossy().to_string())
+    });
+    Ok((record.file_path, title, transcript))
+}
+
+// Write one clip per selected segment into "<title>_clips" in the output folder.
+// Blocking: one ffmpeg run per clip.
+pub fn export_segment_clips(
+    registry: &JobRegistry,
+    job_id: &str,
+    indices: &[usize],
+    format: &str,
+) -> Result<Vec<SegmentClip>, AppError> {
+    if !CLIP_FORMATS.contains(&format) {
+        return Err(AppError::invalid(format!("Invalid clip format '{}': use one of {}", format, CLIP_FORMATS.join(", "))));
+    }
+    if indices.is_empty() {
+        return Err(AppError::invalid("Invalid segment selection: choose at least one segment"));
+    }
+    let (source, title, transcript) = job_source(registry, job_id)?;
+    let is_url = source.starts_with("http://") || source.starts_with("https://");
+    if !is_url && !Path::new(&source).exists() {
+        return Err(AppError::unknown(format!("Source audio not found: {}", source)));
+    }
+    let segments = indices
+        .iter()
+        .map(|&index| {
+            transcript.segments.get(index).map(|segment| (index, segment)).ok_or_else(|| {
+                AppError::invalid(format!("Invalid segment index {}: the transcript has {} segments", index, transcript.segments.len()))
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    let name: String = title.chars().map(|c| if "\\/:*?\"<>|".contains(c) { '-' } else { c }).collect();
+    let dir: PathBuf = config::output_dir()?.join(format!("{}_clips", name));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create clips folder: {}", e))?;
+    let mut clips = Vec::new();
+    for (index, segment) in segments {
+        // 00-01-02 so the clips sort by time and the name is valid on Windows
+        let timestamp = subtitle::format_timestamp(segment.start, '.')[..8].replace(':', "-");
+        let path = dir.join(format!("{}_{:04}_{}.{}", name, index + 1, timestamp, format));
+        extract_clip(&source, segment.start, segment.end, &path)?;
+        let path = path.to_string_lossy().to_string();
+        audit::record(Some(job_id), AuditAction::Write, "file", &path);
+        clips.push(SegmentClip { index, start: segment.start, end: segment.end, path });
+    }
+    Ok(clips)
+}
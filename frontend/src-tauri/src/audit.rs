@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::config;
+use crate::error::AppError;
+
+// Serializes appends so concurrent jobs never interleave within a line
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    // An input file handed to an engine
+    Read,
+    // A transcript written to a local file
+    Write,
+    // A transcript sent to an integration or webhook
+    Export,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AuditEntry {
+    // RFC 3339
+    pub timestamp: String,
+    // None for saves that are not tied to a job, e.g. text saved from the UI
+    pub job_id: Option<String>,
+    pub action: AuditAction,
+    // Where it went: "engine", "file", "S3", "Webhook", ...
+    pub destination: String,
+    // File path, URL or recipient
+    pub target: String,
+}
+
+fn audit_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("audit.jsonl"))
+}
+
+fn append(entry: &AuditEntry) -> Result<(), AppError> {
+    let path = audit_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    line.push('\n');
+    let _guard = AUDIT_LOCK.lock().unwrap();
+    // Opened in append mode only; entries are never rewritten or removed by the app
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write audit log: {}", e).into())
+}
+
+// Failing to audit never fails the operation itself; it is logged instead
+pub fn record(job_id: Option<&str>, action: AuditAction, destination: &str, target: &str) {
+    let entry = AuditEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        job_id: job_id.map(str::to_string),
+        action,
+        destination: destination.to_string(),
+        target: target.to_string(),
+    };
+    if let Err(e) = append(&entry) {
+        warn!("{}", e);
+    }
+}
+
+pub fn exported(job_id: &str, destination: &str, target: &str) {
+    record(Some(job_id), AuditAction::Export, destination, target);
+}
+
+// Only for a full data reset; nothing else removes audit entries
+pub fn clear() -> Result<(), AppError> {
+    let _guard = AUDIT_LOCK.lock().unwrap();
+    match std::fs::remove_file(audit_path()?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete audit log: {}", e).into()),
+    }
+}
+
+// Entries oldest first, filtered by job and start time (RFC 3339); `limit` keeps the newest
+pub fn query(job_id: Option<&str>, since: Option<&str>, limit: Option<usize>) -> Result<Vec<AuditEntry>, AppError> {
+    let since = since
+        .map(|since| {
+            chrono::DateTime::parse_from_rfc3339(since).map_err(|e| AppError::invalid(format!("Invalid timestamp '{}': {}", since, e)))
+        })
+        .transpose()?;
+    let path = audit_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = {
+        let _guard = AUDIT_LOCK.lock().unwrap();
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read audit log: {}", e))?
+    };
+
+    let mut entries = Vec::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        // A line cut short by a crash should not hide the rest of the log
+        let entry: AuditEntry = match serde_json::from_str(line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Skipping unreadable audit log line: {}", e);
+                continue;
+            }
+        };
+        if job_id.is_some_and(|job_id| entry.job_id.as_deref() != Some(job_id)) {
+            continue;
+        }
+        if let Some(since) = since {
+            match chrono::DateTime::parse_from_rfc3339(&entry.timestamp) {
+                Ok(timestamp) if timestamp >= since => {}
+                _ => continue,
+            }
+        }
+        entries.push(entry);
+    }
+    if let Some(limit) = limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+    Ok(entries)
+}
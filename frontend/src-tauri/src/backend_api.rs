@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tracing::{info, warn};
+
+use crate::engine::{EngineStats, Segment, TranscribeOptions, Transcript};
+use crate::error::AppError;
+use crate::gpu::ModelPlan;
+use crate::{config, events, idle, session};
+
+// How often a running job is polled for new segments
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// A file stored by the backend server (backend/main.py /api/upload)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BackendUpload {
+    // Where the server saved it; only meaningful to the server
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct UploadProgress<'a> {
+    file_path: &'a str,
+    sent_bytes: u64,
+    total_bytes: u64,
+    percent: f64,
+}
+
+// Body of POST /api/jobs
+#[derive(Debug, Serialize)]
+struct JobRequest<'a> {
+    path: &'a str,
+    language: &'a str,
+    model: &'a str,
+    compute_type: &'a str,
+    threads: Option<usize>,
+    word_timestamps: bool,
+    prompt: Option<&'a str>,
+    // The server removes its copy of the upload once the job is over
+    delete_after: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Submitted {
+    job_id: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobState {
+    status: JobStatus,
+    // Only the segments after the `since` the poll asked for
+    segments: Vec<Segment>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobResult {
+    result: Transcript,
+    stats: Option<EngineStats>,
+}
+
+// Server jobs by app job id, so a cancel can reach them (they have no PID to kill)
+static RUNNING: Mutex<Option<HashMap<String, (u16, String)>>> = Mutex::new(None);
+
+// Port of the backend server while it is up; None when stopped, starting or asleep
+pub fn running_port() -> Option<u16> {
+    if idle::status() != idle::BackendStatus::Running {
+        return None;
+    }
+    let app = events::app_handle()?;
+    let server = app.state::<crate::ServerState>().lock().unwrap().clone();
+    server.map(|server| server.port)
+}
+
+// Address of an API route on the backend, token included
+fn api_url(port: u16, route: &str) -> String {
+    format!("http://127.0.0.1:{}/api/{}?token={}", port, route, session::token())
+}
+
+fn error_text(response: reqwest::blocking::Response) -> String {
+    let status = response.status();
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        error: String,
+    }
+    match response.json::<ErrorBody>() {
+        Ok(body) => body.error,
+        Err(_) => status.to_string(),
+    }
+}
+
+// Counts bytes as reqwest reads the file, for "backend-upload-progress" events
+struct ProgressReader {
+    file: std::fs::File,
+    file_path: String,
+    sent: u64,
+    total: u64,
+}
+
+impl Read for ProgressReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.file.read(buf)?;
+        if read > 0 {
+            self.sent += read as u64;
+            let percent = if self.total == 0 { 100.0 } else { self.sent as f64 * 100.0 / self.total as f64 };
+            events::emit_named(
+                "backend-upload-progress",
+                &UploadProgress { file_path: &self.file_path, sent_bytes: self.sent, total_bytes: self.total, percent },
+            );
+        }
+        Ok(read)
+    }
+}
+
+// Stream a local file to the running backend as a multipart upload, without reading it
+// into memory. Sends "backend-upload-progress" events as it goes.
+pub fn upload(port: u16, file_path: &str) -> Result<BackendUpload, AppError> {
+    let file = std::fs::File::open(file_path).map_err(|e| format!("Failed to open {}: {}", file_path, e))?;
+    let total = file.metadata().map_err(|e| format!("Failed to read {}: {}", file_path, e))?.len();
+    config::check_upload_size(total, config::max_upload_bytes())?;
+    let file_name = Path::new(file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "upload".to_string());
+
+    let reader = ProgressReader { file, file_path: file_path.to_string(), sent: 0, total };
+    let part = reqwest::blocking::multipart::Part::reader_with_length(reader, total).file_name(file_name);
+    let form = reqwest::blocking::multipart::Form::new().part("file", part);
+
+    let response = crate::proxy::local_blocking_client()
+        .post(api_url(port, "upload"))
+        .multipart(form)
+        // Large media on a slow disk can take a while
+        .timeout(Duration::from_secs(3600))
+        .send()
+        .map_err(|e| format!("Failed to upload to the backend: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backend rejected upload: {}", error_text(response)).into());
+    }
+    let uploaded: BackendUpload = response.json().map_err(|e| AppError::invalid(format!("Invalid upload response: {}", e)))?;
+    info!("Uploaded {} to the backend ({} bytes)", file_path, uploaded.size);
+    Ok(uploaded)
+}
+
+// Typed client for the backend's job routes: submit, poll, fetch the result
+pub struct BackendClient {
+    port: u16,
+    client: reqwest::blocking::Client,
+}
+
+impl BackendClient {
+    pub fn new(port: u16) -> Self {
+        BackendClient { port, client: crate::proxy::local_blocking_client() }
+    }
+
+    fn submit(&self, request: &JobRequest) -> Result<String, AppError> {
+        let response = self
+            .client
+            .post(api_url(self.port, "jobs"))
+            .json(request)
+            .send()
+            .map_err(|e| format!("Failed to submit job to the backend: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Backend rejected job: {}", error_text(response)).into());
+        }
+        let submitted: Submitted = response.json().map_err(|e| AppError::invalid(format!("Invalid job response: {}", e)))?;
+        Ok(submitted.job_id)
+    }
+
+    fn poll(&self, job_id: &str, since: usize) -> Result<JobState, AppError> {
+        let url = format!("{}&since={}", api_url(self.port, &format!("jobs/{}", job_id)), since);
+        let response = self.client.get(url).send().map_err(|e| format!("Failed to poll backend job: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Backend job {} failed: {}", job_id, error_text(response)).into());
+        }
+        response.json().map_err(|e| AppError::invalid(format!("Invalid job status: {}", e)))
+    }
+
+    fn result(&self, job_id: &str) -> Result<JobResult, AppError> {
+        let url = api_url(self.port, &format!("jobs/{}/result", job_id));
+        let response = self.client.get(url).send().map_err(|e| format!("Failed to fetch backend result: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Backend job {} has no result: {}", job_id, error_text(response)).into());
+        }
+        response.json().map_err(|e| AppError::invalid(format!("Invalid job result: {}", e)))
+    }
+
+    fn cancel(&self, job_id: &str) -> Result<(), AppError> {
+        let response = self
+            .client
+            .delete(api_url(self.port, &format!("jobs/{}", job_id)))
+            .send()
+            .map_err(|e| format!("Failed to cancel backend job: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to cancel backend job {}: {}", job_id, error_text(response)).into());
+        }
+        Ok(())
+    }
+
+    // Upload the file, run it on the server's loaded model and wait for the transcript,
+    // reporting segments as they are decoded
+    pub fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        model: &ModelPlan,
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError> {
+        let uploaded = upload(self.port, file_path)?;
+        let request = JobRequest {
+            path: &uploaded.path,
+            language: &options.language,
+            model: &model.model,
+            compute_type: &model.compute_type,
+            threads: crate::power::thread_limit(),
+            word_timestamps: options.word_timestamps,
+            prompt: options.prompt.as_deref().filter(|prompt| !prompt.trim().is_empty()),
+            delete_after: true,
+        };
+        let backend_job = self.submit(&request)?;
+        info!("Transcribing {} as backend job {}", file_path, backend_job);
+        if let Some(job_id) = &options.job_id {
+            RUNNING.lock().unwrap().get_or_insert_with(HashMap::new).insert(job_id.clone(), (self.port, backend_job.clone()));
+        }
+        let outcome = self.wait(&backend_job, on_segment);
+        if let Some(job_id) = &options.job_id {
+            if let Some(running) = RUNNING.lock().unwrap().as_mut() {
+                running.remove(job_id);
+            }
+        }
+        outcome
+    }
+
+    fn wait(&self, backend_job: &str, on_segment: &mut dyn FnMut(&Segment)) -> Result<Transcript, AppError> {
+        let mut reported = 0;
+        loop {
+            let state = self.poll(backend_job, reported)?;
+            reported += state.segments.len();
+            state.segments.iter().for_each(&mut *on_segment);
+            match state.status {
+                JobStatus::Queued | JobStatus::Running => std::thread::sleep(POLL_INTERVAL),
+                JobStatus::Completed => break,
+                JobStatus::Cancelled => return Err(AppError::cancelled("Job cancelled")),
+                JobStatus::Failed => {
+                    return Err(AppError::engine(format!("Transcription failed: {}", state.error.unwrap_or_default())));
+                }
+            }
+        }
+        let JobResult { mut result, stats } = self.result(backend_job)?;
+        // Engines without incremental output (MLX) report all segments at the end
+        result.segments.iter().skip(reported).for_each(&mut *on_segment);
+        result.stats = stats;
+        Ok(result)
+    }
+}
+
+// Stop the server job running for an app job, if there is one
+pub fn cancel(job_id: &str) -> bool {
+    let Some((port, backend_job)) = RUNNING.lock().unwrap().as_ref().and_then(|running| running.get(job_id).cloned()) else {
+        return false;
+    };
+    info!("Cancelling job {} (backend job {})", job_id, backend_job);
+    if let Err(e) = BackendClient::new(port).cancel(&backend_job) {
+        warn!("{}", e);
+    }
+    true
+}
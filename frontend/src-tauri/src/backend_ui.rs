@@ -0,0 +1,77 @@
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::info;
+use crate::error::AppError;
+
+const WINDOW_LABEL: &str = "backend-ui";
+
+// Back, forward, reload and home buttons over the Gradio page. The page is a remote
+// origin without access to Tauri's IPC, so the buttons only use the page's own history.
+const TOOLBAR_SCRIPT: &str = r#"
+(function () {
+  if (window.top !== window) return;
+  const home = window.location.href;
+  function addToolbar() {
+    if (document.getElementById('web-whisper-nav')) return;
+    const bar = document.createElement('div');
+    bar.id = 'web-whisper-nav';
+    bar.style.cssText = 'position:fixed;top:8px;right:8px;z-index:2147483647;display:flex;gap:4px;' +
+      'background:rgba(32,32,32,0.75);border-radius:6px;padding:4px;';
+    const buttons = [
+      ['◀', 'Back', () => history.back()],
+      ['▶', 'Forward', () => history.forward()],
+      ['↻', 'Reload', () => location.reload()],
+      ['⌂', 'Home', () => { location.href = home; }],
+    ];
+    for (const [label, title, action] of buttons) {
+      const button = document.createElement('button');
+      button.textContent = label;
+      button.title = title;
+      button.style.cssText = 'border:none;background:transparent;color:#fff;font-size:14px;cursor:pointer;padding:2px 6px;';
+      button.addEventListener('click', action);
+      bar.appendChild(button);
+    }
+    document.body.appendChild(bar);
+  }
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', addToolbar);
+  } else {
+    addToolbar();
+  }
+})();
+"#;
+
+// Show the backend UI in its own app window, reusing the window when it is already open
+pub fn open(app: &AppHandle, url: &str) -> Result<(), AppError> {
+    let url: tauri::Url = url.parse().map_err(|e| AppError::invalid(format!("Invalid backend URL '{}': {}", url, e)))?;
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        window.navigate(url).map_err(|e| format!("Failed to open the backend UI: {}", e))?;
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+    info!("Opening the backend UI in an app window");
+    WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::External(url))
+        .title("Web Whisper - Whisper UI")
+        .inner_size(1200.0, 800.0)
+        .min_inner_size(800.0, 600.0)
+        .initialization_script(TOOLBAR_SCRIPT)
+        .build()
+        .map_err(|e| format!("Failed to open the backend UI: {}", e))?;
+    Ok(())
+}
+
+// Move through the window's history from the app's own menus: back, forward, reload or home
+pub fn navigate(app: &AppHandle, action: &str, home: &str) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(WINDOW_LABEL)
+        .ok_or(AppError::unknown("Backend UI window not found; open it with open_whisper_gui first"))?;
+    let script = match action {
+        "back" => "history.back()",
+        "forward" => "history.forward()",
+        "reload" => "location.reload()",
+        "home" => return open(app, home),
+        other => return Err(AppError::invalid(format!("Invalid navigation '{}': use back, forward, reload or home", other))),
+    };
+    window.eval(script).map_err(|e| format!("Failed to navigate the backend UI: {}", e).into())
+}
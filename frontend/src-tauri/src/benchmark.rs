@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::queue::{self, Priority, WorkerClass};
+use crate::{config, fixtures, gpu};
+
+// Serializes read-modify-write cycles on benchmarks.json
+static BENCHMARK_LOCK: Mutex<()> = Mutex::new(());
+
+// How often GPU memory is sampled while the engine runs
+const GPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BenchmarkResult {
+    pub id: String,
+    // RFC 3339
+    pub created_at: String,
+    pub engine: String,
+    pub sample: String,
+    pub model: Option<String>,
+    pub gpu: Option<String>,
+    pub audio_seconds: f64,
+    // Engine start to result, including model load
+    pub wall_seconds: f64,
+    pub model_load_seconds: Option<f64>,
+    // Processing time over audio duration, excluding model load; below 1.0 is faster than real time
+    pub real_time_factor: f64,
+    pub peak_rss_bytes: Option<u64>,
+    // Growth of GPU memory in use over the run, sampled
+    pub peak_gpu_bytes: Option<u64>,
+}
+
+fn benchmarks_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("benchmarks.json"))
+}
+
+fn read_results() -> Result<Vec<BenchmarkResult>, AppError> {
+    let path = benchmarks_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read benchmarks: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse benchmarks: {}", e).into())
+}
+
+fn append_result(result: &BenchmarkResult) -> Result<(), AppError> {
+    let _guard = BENCHMARK_LOCK.lock().unwrap();
+    let mut results = read_results()?;
+    results.push(result.clone());
+    let path = benchmarks_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(&results).map_err(|e| format!("Failed to serialize benchmarks: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write benchmarks: {}", e).into())
+}
+
+pub fn list() -> Result<Vec<BenchmarkResult>, AppError> {
+    let _guard = BENCHMARK_LOCK.lock().unwrap();
+    read_results()
+}
+
+fn gpu_used() -> Option<u64> {
+    gpu::query().map(|memory| memory.total_bytes.saturating_sub(memory.free_bytes))
+}
+
+// Transcribe the sample with the current engine settings and record the timings.
+// Blocks for the whole run; call from spawn_blocking.
+pub fn run(engine_name: &str, file_path: Option<String>) -> Result<BenchmarkResult, AppError> {
+    let sample = match file_path {
+        Some(path) => PathBuf::from(path),
+        None => fixtures::sample_clip()?,
+    };
+    let sample = sample.to_string_lossy().to_string();
+    let audio_seconds = engine::probe_duration(&sample).ok_or("Could not determine the sample duration (is ffprobe installed?)")?;
+
+    let engine = engine::engine_for(engine_name)?;
+    let id = format!("benchmark-{}", engine::new_job_id().trim_start_matches("job-"));
+    // Wait for running jobs so they do not skew the numbers
+    let _permit = queue::acquire(engine.worker_class(), &id, Priority::High, || false)?;
+
+    let sample_gpu = engine.worker_class() == WorkerClass::Gpu;
+    let baseline_gpu = if sample_gpu { gpu_used() } else { None };
+    let peak_gpu = Arc::new(Mutex::new(baseline_gpu));
+    let done = Arc::new(AtomicBool::new(false));
+    let sampler = baseline_gpu.map(|_| {
+        let (peak_gpu, done) = (peak_gpu.clone(), done.clone());
+        std::thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                if let Some(used) = gpu_used() {
+                    let mut peak = peak_gpu.lock().unwrap();
+                    *peak = Some(peak.map_or(used, |peak| peak.max(used)));
+                }
+                std::thread::sleep(GPU_SAMPLE_INTERVAL);
+            }
+        })
+    });
+
+    info!("Benchmarking the {} engine on {} ({:.1}s of audio)", engine.name(), sample, audio_seconds);
+    let options = TranscribeOptions::default();
+    let started = Instant::now();
+    let outcome = engine.transcribe(&sample, &options, &mut |_| {}, &mut |_| {});
+    let wall_seconds = started.elapsed().as_secs_f64();
+    done.store(true, Ordering::Relaxed);
+    if let Some(sampler) = sampler {
+        let _ = sampler.join();
+    }
+    let transcript = outcome?;
+
+    let stats = transcript.stats.unwrap_or_default();
+    let processing_seconds = wall_seconds - stats.model_load_seconds.unwrap_or(0.0);
+    let peak_gpu = *peak_gpu.lock().unwrap();
+    let result = BenchmarkResult {
+        id,
+        created_at: chrono::Local::now().to_rfc3339(),
+        engine: engine.name().to_string(),
+        sample,
+        model: stats.model,
+        gpu: if sample_gpu { gpu::query().map(|memory| memory.name) } else { None },
+        audio_seconds,
+        wall_seconds,
+        model_load_seconds: stats.model_load_seconds,
+        real_time_factor: processing_seconds.max(0.0) / audio_seconds.max(f64::EPSILON),
+        peak_rss_bytes: stats.peak_rss_bytes,
+        peak_gpu_bytes: peak_gpu.zip(baseline_gpu).map(|(peak, baseline)| peak.saturating_sub(baseline)),
+    };
+    append_result(&result)?;
+    Ok(result)
+}
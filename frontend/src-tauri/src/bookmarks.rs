@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::editing;
+use crate::engine::{self, Transcript};
+use crate::error::AppError;
+use crate::history;
+use crate::jobs::{self, JobRegistry};
+
+// A reviewer's marker at a moment in the recording
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Bookmark {
+    pub id: String,
+    // Seconds from the start
+    pub time: f64,
+    pub label: String,
+    #[serde(default)]
+    pub note: String,
+    // RFC 3339
+    pub created_at: String,
+}
+
+fn validate(time: f64, label: &str) -> Result<String, AppError> {
+    if !time.is_finite() || time < 0.0 {
+        return Err(AppError::invalid(format!("Invalid bookmark time {}: use seconds from the start", time)));
+    }
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(AppError::invalid("Invalid bookmark label: it must not be empty"));
+    }
+    Ok(label.to_string())
+}
+
+fn find<'a>(transcript: &'a mut Transcript, bookmark_id: &str) -> Result<&'a mut Bookmark, AppError> {
+    transcript
+        .bookmarks
+        .iter_mut()
+        .find(|bookmark| bookmark.id == bookmark_id)
+        .ok_or_else(|| AppError::unknown(format!("Unknown bookmark: {}", bookmark_id)))
+}
+
+// Bookmarks are stored with the transcript in history, but changing them is not an edit:
+// no revision is kept and the transcript is not marked edited
+fn modify<T>(
+    registry: &JobRegistry,
+    job_id: &str,
+    apply: impl FnOnce(&mut Transcript) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    editing::ensure_in_history(registry, job_id)?;
+    let (transcript, result) = history::annotate(job_id, |transcript| {
+        let result = apply(transcript)?;
+        transcript.bookmarks.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(result)
+    })?;
+    jobs::set_transcript(registry, job_id, transcript);
+    Ok(result)
+}
+
+// Oldest moment first
+pub fn list(registry: &JobRegistry, job_id: &str) -> Result<Vec<Bookmark>, AppError> {
+    if let Ok(entry) = history::get(job_id) {
+        return Ok(entry.transcript.bookmarks);
+    }
+    jobs::get(registry, job_id)
+        .and_then(|record| record.transcript)
+        .map(|transcript| transcript.bookmarks)
+        .ok_or_else(|| AppError::unknown(format!("Unknown job: {}", job_id)))
+}
+
+pub fn add(registry: &JobRegistry, job_id: &str, time: f64, label: &str, note: &str) -> Result<Bookmark, AppError> {
+    let bookmark = Bookmark {
+        id: format!("bookmark-{}", engine::new_job_id().trim_start_matches("job-")),
+        time,
+        label: validate(time, label)?,
+        note: note.trim().to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    modify(registry, job_id, |transcript| {
+        transcript.bookmarks.push(bookmark.clone());
+        Ok(bookmark)
+    })
+}
+
+pub fn update(
+    registry: &JobRegistry,
+    job_id: &str,
+    bookmark_id: &str,
+    time: f64,
+    label: &str,
+    note: &str,
+) -> Result<Bookmark, AppError> {
+    let label = validate(time, label)?;
+    modify(registry, job_id, |transcript| {
+        let bookmark = find(transcript, bookmark_id)?;
+        bookmark.time = time;
+        bookmark.label = label;
+        bookmark.note = note.trim().to_string();
+        Ok(bookmark.clone())
+    })
+}
+
+pub fn delete(registry: &JobRegistry, job_id: &str, bookmark_id: &str) -> Result<(), AppError> {
+    modify(registry, job_id, |transcript| {
+        find(transcript, bookmark_id)?;
+        transcript.bookmarks.retain(|bookmark| bookmark.id != bookmark_id);
+        Ok(())
+    })
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::config::{self, AppConfig};
+use crate::error::AppError;
+use crate::glossary::{self, GlossaryConfig};
+use crate::languages::{self, LanguageConfig};
+use crate::postprocess::{self, ItnConfig, PunctuationConfig};
+
+const BUNDLE_VERSION: u32 = 1;
+
+// Transcript cleanup settings in one file, so a team can share them across machines.
+// A part left out of the bundle is not changed on import.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RuleBundle {
+    pub version: u32,
+    // RFC 3339
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    // Per-language find/replace rules, prompts and models
+    #[serde(default)]
+    pub languages: Option<LanguageConfig>,
+    #[serde(default)]
+    pub glossary: Option<GlossaryConfig>,
+    #[serde(default)]
+    pub punctuation: Option<PunctuationConfig>,
+    #[serde(default)]
+    pub itn: Option<ItnConfig>,
+}
+
+pub fn current() -> RuleBundle {
+    RuleBundle {
+        version: BUNDLE_VERSION,
+        exported_at: Some(chrono::Local::now().to_rfc3339()),
+        languages: Some(languages::language_config()),
+        glossary: Some(glossary::glossary_config()),
+        punctuation: Some(postprocess::punctuation_config()),
+        itn: Some(postprocess::itn_config()),
+    }
+}
+
+pub fn export(file_path: &str) -> Result<String, AppError> {
+    let contents = serde_json::to_string_pretty(&current()).map_err(|e| format!("Failed to serialize rule bundle: {}", e))?;
+    std::fs::write(file_path, contents).map_err(|e| format!("Failed to write rule bundle: {}", e))?;
+    crate::audit::record(None, crate::audit::AuditAction::Write, "file", file_path);
+    Ok(file_path.to_string())
+}
+
+fn validate(bundle: &RuleBundle) -> Result<(), AppError> {
+    if bundle.version > BUNDLE_VERSION {
+        return Err(AppError::invalid(format!("Invalid rule bundle: version {} is newer than this app supports ({})", bundle.version, BUNDLE_VERSION)));
+    }
+    if let Some(languages) = &bundle.languages {
+        languages::validate(languages)?;
+    }
+    if let Some(glossary) = &bundle.glossary {
+        glossary::validate(glossary)?;
+    }
+    if let Some(punctuation) = &bundle.punctuation {
+        postprocess::validate_punctuation(punctuation)?;
+    }
+    Ok(())
+}
+
+// Bundle profiles and terms are added to the current ones; on a clash the bundle's win
+fn merge(mut bundle: RuleBundle, current: &AppConfig) -> RuleBundle {
+    if let Some(incoming) = bundle.languages.take() {
+        let mut merged = current.languages.clone().unwrap_or_default();
+        merged.detect_model = incoming.detect_model;
+        merged.profiles.extend(incoming.profiles);
+        bundle.languages = Some(merged);
+    }
+    if let Some(incoming) = bundle.glossary.take() {
+        let mut merged = current.glossary.clone().unwrap_or_default();
+        merged.fuzzy = incoming.fuzzy;
+        for term in incoming.terms {
+            merged.terms.retain(|existing| existing.term != term.term);
+            merged.terms.push(term);
+        }
+        bundle.glossary = Some(merged);
+    }
+    bundle
+}
+
+// Apply a bundle file. With `merge`, language profiles and glossary terms are added to
+// the current ones; otherwise each part in the bundle replaces the current settings.
+pub fn import(file_path: &str, merge_rules: bool) -> Result<RuleBundle, AppError> {
+    let contents = std::fs::read_to_string(file_path).map_err(|e| format!("Failed to read rule bundle: {}", e))?;
+    let bundle: RuleBundle = serde_json::from_str(&contents).map_err(|e| AppError::invalid(format!("Invalid rule bundle: {}", e)))?;
+    validate(&bundle)?;
+
+    let bundle = config::update(|app_config| {
+        let bundle = if merge_rules { merge(bundle, app_config) } else { bundle };
+        if let Some(languages) = &bundle.languages {
+            app_config.languages = Some(languages.clone());
+        }
+        if let Some(glossary) = &bundle.glossary {
+            app_config.glossary = Some(glossary.clone());
+        }
+        if let Some(punctuation) = &bundle.punctuation {
+            app_config.punctuation = Some(punctuation.clone());
+        }
+        if let Some(itn) = &bundle.itn {
+            app_config.itn = Some(itn.clone());
+        }
+        Ok(bundle)
+    })?;
+    info!("Imported rule bundle from {}", file_path);
+    Ok(bundle)
+}
@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Segment, Transcript};
+use crate::error::AppError;
+
+// Caption layout for SRT and WebVTT exports. With neither limit set, segments are
+// written as the engine produced them.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CaptionConfig {
+    // 42 is common for broadcast and streaming; around 16 for Japanese and Chinese
+    #[serde(default)]
+    pub max_chars_per_line: Option<usize>,
+    #[serde(default = "default_max_lines")]
+    pub max_lines: usize,
+    // Reading speed limit in characters per second, e.g. 17 for adults or 13 for children
+    #[serde(default)]
+    pub max_cps: Option<f64>,
+}
+
+fn default_max_lines() -> usize {
+    2
+}
+
+impl Default for CaptionConfig {
+    fn default() -> Self {
+        CaptionConfig { max_chars_per_line: None, max_lines: default_max_lines(), max_cps: None }
+    }
+}
+
+pub fn caption_config() -> CaptionConfig {
+    crate::config::load().captions.unwrap_or_default()
+}
+
+pub fn validate(config: &CaptionConfig) -> Result<(), AppError> {
+    if config.max_chars_per_line.is_some_and(|max| max < 4) {
+        return Err(AppError::invalid("Invalid max_chars_per_line: use at least 4"));
+    }
+    if config.max_lines == 0 {
+        return Err(AppError::invalid("Invalid max_lines: use at least 1"));
+    }
+    if config.max_cps.is_some_and(|max| !max.is_finite() || max < 1.0) {
+        return Err(AppError::invalid("Invalid max_cps: use at least 1 character per second"));
+    }
+    Ok(())
+}
+
+fn is_cjk_char(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF // kana
+        | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF // ideographs
+        | 0x3000..=0x303F | 0xFF00..=0xFFEF) // CJK punctuation, full-width forms
+}
+
+// Japanese and Chinese are written without spaces, so lines break between characters
+fn is_cjk(language: Option<&str>, text: &str) -> bool {
+    match language.map(|language| language.to_lowercase()) {
+        Some(language) if language.starts_with("ja") || language.starts_with("zh") => true,
+        Some(_) => false,
+        None => text.chars().any(is_cjk_char),
+    }
+}
+
+// Closing marks that must not start a line (kinsoku)
+fn no_line_start(c: char) -> bool {
+    "、。，．・：；？！ー）」』】〕〉》”,.!?)".contains(c)
+}
+
+// Break after punctuation in the last third of a full line, else at the limit
+fn wrap_cjk(text: &str, max: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace() || *c == ' ').collect();
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while chars.len() - start > max {
+        let mut end = start + max;
+        if let Some(break_at) = (start + max * 2 / 3..end).rev().find(|&i| "、。，．！？ ".contains(chars[i])) {
+            end = break_at + 1;
+        }
+        while end < chars.len() && no_line_start(chars[end]) {
+            end += 1;
+        }
+        lines.push(chars[start..end].iter().collect::<String>().trim().to_string());
+        start = end;
+    }
+    lines.push(chars[start..].iter().collect::<String>().trim().to_string());
+    lines.retain(|line| !line.is_empty());
+    lines
+}
+
+// Greedy word wrap; a word longer than a line is cut
+fn wrap_words(text: &str, max: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > max {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..max).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if line.is_empty() {
+            line = word;
+        } else if line.chars().count() + 1 + word.chars().count() <= max {
+            line.push(' ');
+            line.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut line, word));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn wrap(text: &str, max: usize, cjk: bool) -> Vec<String> {
+    if cjk { wrap_cjk(text, max) } else { wrap_words(text, max) }
+}
+
+// Re-wrap each segment to the line limit; a segment that needs more than max_lines
+// becomes several cues, with its time shared out by character count
+fn layout_segment(segment: &Segment, max: usize, max_lines: usize, cjk: bool) -> Vec<Segment> {
+    let lines = wrap(segment.text.trim(), max, cjk);
+    if lines.is_empty() {
+        return vec![Segment { words: Vec::new(), ..segment.clone() }];
+    }
+    let cues: Vec<Vec<String>> = lines.chunks(max_lines).map(|chunk| chunk.to_vec()).collect();
+    let total: usize = lines.iter().map(|line| line.chars().count()).sum::<usize>().max(1);
+    let duration = (segment.end - segment.start).max(0.0);
+    let mut start = segment.start;
+    let mut done = 0;
+    cues.into_iter()
+        .map(|cue| {
+            done += cue.iter().map(|line| line.chars().count()).sum::<usize>();
+            let end = segment.start + duration * done as f64 / total as f64;
+            let segment = Segment { start, end, text: cue.join("\n"), words: Vec::new() };
+            start = end;
+            segment
+        })
+        .collect()
+}
+
+// Characters a viewer has to read, line breaks not counted
+pub fn reading_length(text: &str) -> usize {
+    text.chars().filter(|c| *c != '\n').count()
+}
+
+// Characters per second, or None for a cue without length
+pub fn cps(segment: &Segment) -> Option<f64> {
+    let duration = segment.end - segment.start;
+    (duration > 0.0).then(|| reading_length(segment.text.trim()) as f64 / duration)
+}
+
+// Shortest pause kept between cues, about two frames, so players show them as separate
+const MIN_GAP: f64 = 0.08;
+
+// Give cues that read too fast more time: first by ending later, up to the next cue,
+// then by starting earlier, back to the previous one. Cues packed too tightly for either
+// stay as they are; lint reports them.
+fn enforce_reading_speed(cues: &mut [Segment], max_cps: f64, media_end: Option<f64>) {
+    for index in 0..cues.len() {
+        let needed = reading_length(cues[index].text.trim()) as f64 / max_cps;
+        let shortfall = needed - (cues[index].end - cues[index].start);
+        if shortfall <= 0.0 {
+            continue;
+        }
+        let latest_end = match cues.get(index + 1) {
+            Some(next) => next.start - MIN_GAP,
+            None => media_end.unwrap_or(f64::INFINITY),
+        };
+        let cue = &mut cues[index];
+        cue.end = (cue.start + needed).min(latest_end).max(cue.end);
+        let remaining = needed - (cue.end - cue.start);
+        if remaining > 0.0 {
+            let earliest_start = if index == 0 { 0.0 } else { cues[index - 1].end + MIN_GAP };
+            let cue = &mut cues[index];
+            cue.start = (cue.start - remaining).max(earliest_start).min(cue.start);
+        }
+    }
+}
+
+// Cues for SRT and WebVTT exports, laid out with the caption settings
+pub fn cues(transcript: &Transcript) -> Vec<Segment> {
+    layout(transcript, &caption_config())
+}
+
+fn layout(transcript: &Transcript, config: &CaptionConfig) -> Vec<Segment> {
+    let mut cues: Vec<Segment> = match config.max_chars_per_line {
+        Some(max) => transcript
+            .segments
+            .iter()
+            .flat_map(|segment| {
+                let cjk = is_cjk(transcript.language.as_deref(), &segment.text);
+                layout_segment(segment, max, config.max_lines.max(1), cjk)
+            })
+            .collect(),
+        None => transcript.segments.clone(),
+    };
+    if let Some(max_cps) = config.max_cps {
+        enforce_reading_speed(&mut cues, max_cps, transcript.duration);
+    }
+    cues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment { start, end, text: text.to_string(), words: Vec::new() }
+    }
+
+    #[test]
+    fn words_wrap_at_the_line_limit() {
+        let cases: [(&str, usize, &[&str]); 5] = [
+            ("the quick brown fox jumps", 10, &["the quick", "brown fox", "jumps"]),
+            ("abcde fghi", 10, &["abcde fghi"]),
+            ("supercalifragilistic", 10, &["supercalif", "ragilistic"]),
+            ("a supercalifragilistic b", 10, &["a", "supercalif", "ragilistic", "b"]),
+            ("  spaced   out  ", 42, &["spaced out"]),
+        ];
+        for (text, max, lines) in cases {
+            assert_eq!(wrap(text, max, false), lines, "{}", text);
+        }
+    }
+
+    #[test]
+    fn cjk_wraps_between_characters() {
+        let cases: [(&str, usize, &[&str]); 4] = [
+            // A closing mark is never moved to the start of a line
+            ("一二三四五六七八九十。次", 10, &["一二三四五六七八九十。", "次"]),
+            // Punctuation in the last third of the line is the preferred break
+            ("あいうえおかき、くけこさしす", 10, &["あいうえおかき、", "くけこさしす"]),
+            ("短い", 10, &["短い"]),
+            ("十六文字を超えない字幕の行", 16, &["十六文字を超えない字幕の行"]),
+        ];
+        for (text, max, lines) in cases {
+            assert_eq!(wrap(text, max, true), lines, "{}", text);
+        }
+    }
+
+    #[test]
+    fn cjk_is_chosen_by_language_or_by_the_text() {
+        assert!(is_cjk(Some("ja"), "no kana here"));
+        assert!(is_cjk(Some("zh-TW"), ""));
+        assert!(!is_cjk(Some("en"), "日本語"));
+        assert!(is_cjk(None, "日本語のテキスト"));
+        assert!(!is_cjk(None, "plain text"));
+    }
+
+    #[test]
+    fn segments_over_max_lines_become_several_cues() {
+        let cues = layout_segment(&segment(0.0, 6.0, "the quick brown fox jumps"), 10, 2, false);
+        let texts: Vec<&str> = cues.iter().map(|cue| cue.text.as_str()).collect();
+        assert_eq!(texts, ["the quick\nbrown fox", "jumps"]);
+        // Time is shared by characters: 18 of 23
+        assert!((cues[0].end - 6.0 * 18.0 / 23.0).abs() < 1e-9);
+        assert_eq!((cues[0].start, cues[1].start, cues[1].end), (0.0, cues[0].end, 6.0));
+
+        let single = layout_segment(&segment(0.0, 6.0, "the quick brown fox jumps"), 10, 3, false);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].text, "the quick\nbrown fox\njumps");
+    }
+
+    #[test]
+    fn layout_wraps_cjk_by_the_transcript_language() {
+        let transcript = Transcript {
+            language: Some("ja".to_string()),
+            segments: vec![segment(0.0, 4.0, "あいうえおかきくけこさしすせそたちつてと")],
+            ..Default::default()
+        };
+        let config = CaptionConfig { max_chars_per_line: Some(10), max_lines: 1, max_cps: None };
+        let cues = layout(&transcript, &config);
+        let texts: Vec<&str> = cues.iter().map(|cue| cue.text.as_str()).collect();
+        assert_eq!(texts, ["あいうえおかきくけこ", "さしすせそたちつてと"]);
+        assert_eq!((cues[0].end, cues[1].end), (2.0, 4.0));
+    }
+
+    #[test]
+    fn no_limits_keep_the_segments() {
+        let transcript = Transcript { segments: vec![segment(0.0, 1.0, "a long line that is never wrapped at all")], ..Default::default() };
+        let cues = layout(&transcript, &CaptionConfig::default());
+        assert_eq!(cues[0].text, transcript.segments[0].text);
+    }
+
+    #[test]
+    fn fast_cues_get_more_time_without_overlapping() {
+        // 20 characters at 10 per second need 2 seconds
+        let mut cues = [segment(1.0, 2.0, "twenty characters!!!"), segment(5.0, 6.0, "ok")];
+        enforce_reading_speed(&mut cues, 10.0, None);
+        assert_eq!((cues[0].start, cues[0].end), (1.0, 3.0));
+
+        // Boxed in by the next cue, it starts earlier instead
+        let mut cues = [segment(1.0, 2.0, "twenty characters!!!"), segment(2.5, 3.0, "ok")];
+        enforce_reading_speed(&mut cues, 10.0, None);
+        assert!((cues[0].end - (2.5 - MIN_GAP)).abs() < 1e-9);
+        assert!((cues[0].start - (cues[0].end - 2.0)).abs() < 1e-9);
+    }
+}
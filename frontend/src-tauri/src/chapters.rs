@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Segment, Transcript};
+use crate::error::AppError;
+
+// YouTube ignores chapter lists with fewer than three entries or any chapter under 10 seconds
+const MIN_CHAPTERS: usize = 3;
+const MIN_CHAPTER_SECONDS: f64 = 10.0;
+
+// Heuristic split: a new chapter starts at a pause this long once the current one has run long enough
+const TARGET_CHAPTER_SECONDS: f64 = 120.0;
+const MIN_PAUSE_SECONDS: f64 = 1.5;
+
+const TITLE_WORDS: usize = 6;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Chapter {
+    pub start: f64,
+    pub title: String,
+}
+
+fn title_from(segment: &Segment) -> String {
+    let words: Vec<&str> = segment.text.split_whitespace().collect();
+    let mut title = words.iter().take(TITLE_WORDS).copied().collect::<Vec<_>>().join(" ");
+    title = title.trim_end_matches([',', '.', '?', '!', ';', ':']).to_string();
+    if words.len() > TITLE_WORDS {
+        title.push('…');
+    }
+    title
+}
+
+// Chapters stored on the transcript win; otherwise split at pauses roughly every two minutes
+pub fn chapters(transcript: &Transcript) -> Vec<Chapter> {
+    if !transcript.chapters.is_empty() {
+        return transcript.chapters.clone();
+    }
+    let mut chapters: Vec<Chapter> = Vec::new();
+    let mut previous_end: Option<f64> = None;
+    for segment in &transcript.segments {
+        let starts_chapter = match (chapters.last(), previous_end) {
+            (None, _) => true,
+            (Some(current), Some(end)) => {
+                segment.start - current.start >= TARGET_CHAPTER_SECONDS && segment.start - end >= MIN_PAUSE_SECONDS
+            }
+            (Some(_), None) => false,
+        };
+        if starts_chapter {
+            chapters.push(Chapter { start: segment.start, title: title_from(segment) });
+        }
+        previous_end = Some(segment.end);
+    }
+    chapters
+}
+
+// 0:00 style; hours only when needed
+fn youtube_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+// Description block YouTube turns into chapters: first entry at 00:00, ascending, each at least 10 s
+pub fn to_youtube(transcript: &Transcript) -> Result<String, AppError> {
+    let mut chapters = chapters(transcript);
+    if let Some(first) = chapters.first_mut() {
+        first.start = 0.0;
+    }
+    chapters.dedup_by(|next, previous| next.start - previous.start < MIN_CHAPTER_SECONDS);
+    if chapters.len() < MIN_CHAPTERS {
+        return Err(AppError::invalid(format!(
+            "YouTube needs at least {} chapters; this transcript has {}",
+            MIN_CHAPTERS,
+            chapters.len()
+        )));
+    }
+    Ok(chapters
+        .iter()
+        .map(|chapter| format!("{} {}\n", youtube_timestamp(chapter.start), chapter.title))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment { start, end, text: text.to_string(), words: Vec::new() }
+    }
+
+    fn chapter(start: f64, title: &str) -> Chapter {
+        Chapter { start, title: title.to_string() }
+    }
+
+    #[test]
+    fn new_chapter_starts_at_a_pause_after_two_minutes() {
+        let transcript = Transcript {
+            segments: vec![
+                segment(0.5, 60.0, "Welcome back to the show, everyone out there."),
+                segment(60.0, 119.0, "More intro"),
+                // Long enough but no pause before it
+                segment(119.0, 125.0, "Still the intro"),
+                segment(127.0, 130.0, "Main topic!"),
+            ],
+            ..Default::default()
+        };
+        let chapters = chapters(&transcript);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!((chapters[0].start, chapters[0].title.as_str()), (0.5, "Welcome back to the show, everyone…"));
+        assert_eq!((chapters[1].start, chapters[1].title.as_str()), (127.0, "Main topic"));
+    }
+
+    #[test]
+    fn stored_chapters_win() {
+        let transcript = Transcript {
+            segments: vec![segment(0.0, 1.0, "Spoken")],
+            chapters: vec![chapter(0.0, "Stored")],
+            ..Default::default()
+        };
+        assert_eq!(chapters(&transcript)[0].title, "Stored");
+    }
+
+    #[test]
+    fn youtube_list_starts_at_zero_and_drops_short_chapters() {
+        let transcript = Transcript {
+            chapters: vec![chapter(3.0, "Intro"), chapter(8.0, "Too soon"), chapter(65.0, "Main"), chapter(3725.0, "Outro")],
+            ..Default::default()
+        };
+        assert_eq!(to_youtube(&transcript).unwrap(), "00:00 Intro\n01:05 Main\n1:02:05 Outro\n");
+    }
+
+    #[test]
+    fn youtube_needs_three_chapters() {
+        let transcript = Transcript {
+            chapters: vec![chapter(0.0, "Intro"), chapter(5.0, "Too soon"), chapter(60.0, "Main")],
+            ..Default::default()
+        };
+        assert!(matches!(to_youtube(&transcript), Err(AppError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn timestamps_show_hours_only_when_needed() {
+        assert_eq!(youtube_timestamp(-1.0), "00:00");
+        assert_eq!(youtube_timestamp(59.9), "00:59");
+        assert_eq!(youtube_timestamp(600.0), "10:00");
+        assert_eq!(youtube_timestamp(3600.0), "1:00:00");
+        assert_eq!(youtube_timestamp(36061.0), "10:01:01");
+    }
+}
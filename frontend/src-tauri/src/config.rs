@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+use crate::crypto::EncryptionConfig;
+use crate::engine::azure::AzureSpeechConfig;
+use crate::engine::google::GoogleSpeechConfig;
+use crate::engine::openai::OpenAiConfig;
+use crate::error::AppError;
+use crate::gpu::LocalModelConfig;
+use crate::integrations::anki::AnkiConfig;
+use crate::integrations::calendar::CalendarConfig;
+use crate::integrations::chat::ChatChannel;
+use crate::integrations::deepl::DeepLConfig;
+use crate::integrations::dropbox::DropboxConfig;
+use crate::integrations::email::EmailConfig;
+use crate::integrations::gdrive::GoogleDriveConfig;
+use crate::integrations::notion::NotionConfig;
+use crate::integrations::obsidian::ObsidianConfig;
+use crate::integrations::s3::S3Config;
+use crate::integrations::webhooks::WebhookConfig;
+use crate::heartbeat::HeartbeatConfig;
+use crate::idle::IdleConfig;
+use crate::captions::CaptionConfig;
+use crate::glossary::GlossaryConfig;
+use crate::keywords::KeywordConfig;
+use crate::languages::LanguageConfig;
+use crate::postprocess::{ItnConfig, PunctuationConfig};
+use crate::meeting::MeetingConfig;
+use crate::power::PowerConfig;
+use crate::proxy::ProxyConfig;
+use crate::queue::WorkerConfig;
+use crate::redact::RedactionConfig;
+use crate::retention::RetentionConfig;
+use crate::schedule::ScheduleConfig;
+use crate::resources::ResourceConfig;
+use crate::telemetry::TelemetryConfig;
+use crate::temp::TempConfig;
+
+// User settings persisted as JSON in the per-user config directory
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct AppConfig {
+    pub webhooks: Vec<WebhookConfig>,
+    pub s3: Option<S3Config>,
+    pub google_drive: Option<GoogleDriveConfig>,
+    pub dropbox: Option<DropboxConfig>,
+    pub obsidian: Option<ObsidianConfig>,
+    pub notion: Option<NotionConfig>,
+    pub deepl: Option<DeepLConfig>,
+    pub openai: Option<OpenAiConfig>,
+    pub azure_speech: Option<AzureSpeechConfig>,
+    pub google_speech: Option<GoogleSpeechConfig>,
+    pub chat_channels: Vec<ChatChannel>,
+    pub email: Option<EmailConfig>,
+    pub calendar: Option<CalendarConfig>,
+    pub anki: Option<AnkiConfig>,
+    // Largest media file accepted from the webview or the local API, in MB
+    pub max_upload_mb: Option<u64>,
+    pub workers: Option<WorkerConfig>,
+    pub local_model: Option<LocalModelConfig>,
+    // tracing filter directives, e.g. "info,web_whisper::engine=debug"
+    pub log_level: Option<String>,
+    pub temp_files: Option<TempConfig>,
+    pub proxy: Option<ProxyConfig>,
+    pub power: Option<PowerConfig>,
+    pub idle_shutdown: Option<IdleConfig>,
+    pub resources: Option<ResourceConfig>,
+    pub heartbeat: Option<HeartbeatConfig>,
+    // Default folder for saved transcripts; the platform Downloads folder when unset
+    pub output_dir: Option<String>,
+    // Name for saved transcripts, e.g. "{stem}_{lang}_{date}.{ext}"; "{stem}.{ext}" when unset
+    pub filename_template: Option<String>,
+    pub encryption: Option<EncryptionConfig>,
+    pub redaction: Option<RedactionConfig>,
+    pub retention: Option<RetentionConfig>,
+    pub keywords: Option<KeywordConfig>,
+    pub meeting: Option<MeetingConfig>,
+    pub schedule: Option<ScheduleConfig>,
+    pub languages: Option<LanguageConfig>,
+    pub captions: Option<CaptionConfig>,
+    pub punctuation: Option<PunctuationConfig>,
+    pub glossary: Option<GlossaryConfig>,
+    pub itn: Option<ItnConfig>,
+    // Run "local" jobs on the mock engine (canned segments, no Python); the
+    // WEB_WHISPER_MOCK_ENGINE env var overrides it
+    pub mock_engine: Option<bool>,
+    // Per-user overrides of the feature flags by name (see flags.rs)
+    pub feature_flags: Option<HashMap<String, bool>>,
+    pub telemetry: Option<TelemetryConfig>,
+}
+
+const DEFAULT_MAX_UPLOAD_MB: u64 = 4096;
+
+pub fn max_upload_bytes() -> u64 {
+    load().max_upload_mb.unwrap_or(DEFAULT_MAX_UPLOAD_MB) * 1024 * 1024
+}
+
+// `limit` comes from max_upload_bytes(), read once per upload
+pub fn check_upload_size(bytes: u64, limit: u64) -> Result<(), AppError> {
+    if bytes > limit {
+        return Err(AppError::limit_exceeded(format!(
+            "File exceeds the {} MB size limit (max_upload_mb in config.json)",
+            limit / (1024 * 1024)
+        )));
+    }
+    Ok(())
+}
+
+// Marker next to the executable that turns on portable mode
+const PORTABLE_FLAG: &str = "portable.flag";
+
+static PORTABLE_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+// In portable mode (portable.flag beside the EXE, e.g. on a USB stick) settings, history,
+// logs, models and temp files all live in `data` next to the executable
+pub fn portable_root() -> Option<PathBuf> {
+    PORTABLE_ROOT
+        .get_or_init(|| {
+            let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+            exe_dir.join(PORTABLE_FLAG).exists().then(|| exe_dir.join("data"))
+        })
+        .clone()
+}
+
+pub fn config_path() -> Result<PathBuf, AppError> {
+    if let Some(root) = portable_root() {
+        return Ok(root.join("config.json"));
+    }
+    let config_dir = dirs::config_dir().ok_or("Failed to resolve config directory")?;
+    Ok(config_dir.join("web-whisper").join("config.json"))
+}
+
+// Per-user data directory for history, feeds and other app-managed state
+pub fn data_dir() -> Result<PathBuf, AppError> {
+    if let Some(root) = portable_root() {
+        return Ok(root);
+    }
+    let data_dir = dirs::data_dir().ok_or("Failed to resolve data directory")?;
+    Ok(data_dir.join("web-whisper"))
+}
+
+// Where transcripts go by default: the configured output_dir, else the platform's
+// Downloads folder (localized and redirected ones included)
+pub fn output_dir() -> Result<PathBuf, AppError> {
+    if let Some(dir) = load().output_dir.filter(|dir| !dir.trim().is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    dirs::download_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join("Downloads")))
+        .ok_or_else(|| "Failed to resolve the Downloads directory".into())
+}
+
+// Per-user cache directory for temp files and downloaded models; safe to delete
+pub fn cache_dir() -> Result<PathBuf, AppError> {
+    if let Some(root) = portable_root() {
+        return Ok(root.join("cache"));
+    }
+    let cache_dir = dirs::cache_dir().ok_or("Failed to resolve cache directory")?;
+    Ok(cache_dir.join("web-whisper"))
+}
+
+// Serializes read-modify-write cycles on config.json across threads
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+// The saved settings. A missing file is the defaults; one that cannot be read or parsed
+// is an error, so it is never mistaken for defaults and saved over.
+fn read() -> Result<AppConfig, AppError> {
+    let path = config_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| AppError::invalid(format!("Invalid config {:?}: {}; fix or delete it to change settings", path, e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(AppConfig::default()),
+        Err(e) => Err(format!("Failed to read config {:?}: {}", path, e).into()),
+    }
+}
+
+// Missing or unreadable config falls back to defaults so the app still starts.
+// Read-only: settings are changed with `update`.
+pub fn load() -> AppConfig {
+    read().unwrap_or_else(|e| {
+        warn!("Ignoring config: {}", e);
+        AppConfig::default()
+    })
+}
+
+// Write-then-rename so a reader or a crash never sees a half-written file
+fn write(config: &AppConfig) -> Result<(), AppError> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let staging = path.with_extension("json.tmp");
+    std::fs::write(&staging, contents).map_err(|e| format!("Failed to write config: {}", e))?;
+    std::fs::rename(&staging, &path)
+        .map_err(|e| format!("Failed to write config: {}", e).into())
+}
+
+// Change the saved settings under the config lock, so concurrent setters cannot undo
+// each other. Nothing is written when `change` fails or config.json cannot be read.
+pub fn update<T>(change: impl FnOnce(&mut AppConfig) -> Result<T, AppError>) -> Result<T, AppError> {
+    let _guard = CONFIG_LOCK.lock().unwrap();
+    let mut config = read()?;
+    let result = change(&mut config)?;
+    write(&config)?;
+    Ok(result)
+}
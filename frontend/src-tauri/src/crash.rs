@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::error;
+
+use crate::{config, logging};
+use crate::error::AppError;
+
+// Log lines copied into each report
+const LOG_TAIL_LINES: usize = 200;
+
+// Written by the native crash handler, which cannot safely build a full report;
+// turned into one on the next launch
+const NATIVE_MARKER: &str = "native-crash.txt";
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CrashReport {
+    pub id: String,
+    // RFC 3339
+    pub created_at: String,
+    // "panic" or "native"
+    pub kind: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: Option<String>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub log_tail: Vec<String>,
+    // config.json with URLs cut to their host and credential-like fields removed
+    pub settings: serde_json::Value,
+}
+
+fn reports_dir() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("crash_reports"))
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+fn new_report(kind: &str, message: String) -> CrashReport {
+    let now = chrono::Local::now();
+    CrashReport {
+        id: format!("crash-{}", now.format("%Y%m%d-%H%M%S%.3f")),
+        created_at: now.to_rfc3339(),
+        kind: kind.to_string(),
+        message,
+        location: None,
+        thread: None,
+        backtrace: None,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        log_tail: log_tail(),
+        settings: settings_snapshot(),
+    }
+}
+
+// Last lines of the most recently written log file
+fn log_tail() -> Vec<String> {
+    let Ok(dir) = logging::log_dir() else { return Vec::new() };
+    let newest = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "log"))
+        .max_by_key(|entry| entry.metadata().and_then(|metadata| metadata.modified()).ok());
+    let Some(contents) = newest.and_then(|entry| std::fs::read_to_string(entry.path()).ok()) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+    lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].iter().map(|line| line.to_string()).collect()
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["secret", "token", "password", "key", "webhook"].iter().any(|word| key.contains(word))
+}
+
+fn redact(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) && !value.is_null() {
+                    *value = serde_json::Value::String("[redacted]".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+        // Tokens often ride in URL paths and queries (Slack/Discord webhooks, signed links)
+        serde_json::Value::String(text) => {
+            if let Ok(url) = reqwest::Url::parse(text) {
+                if let Some(host) = url.host_str() {
+                    *text = format!("{}://{}/[redacted]", url.scheme(), host);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn settings_snapshot() -> serde_json::Value {
+    let mut settings = serde_json::to_value(config::load()).unwrap_or_default();
+    redact(&mut settings);
+    settings
+}
+
+fn write_report(report: &CrashReport) -> Result<PathBuf, AppError> {
+    let dir = reports_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create crash report directory: {}", e))?;
+    let path = report_path(&dir, &report.id);
+    let contents = serde_json::to_string_pretty(report).map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write crash report: {}", e))?;
+    Ok(path)
+}
+
+fn on_panic(info: &std::panic::PanicHookInfo) {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    let mut report = new_report("panic", message);
+    report.location = info.location().map(|location| location.to_string());
+    report.thread = std::thread::current().name().map(str::to_string);
+    report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+    match write_report(&report) {
+        Ok(path) => error!("Panic: {} at {:?}; crash report saved to {:?}", report.message, report.location, path),
+        Err(e) => error!("Panic: {} at {:?}; {}", report.message, report.location, e),
+    }
+}
+
+// Turn a marker left by the native handler into a report; the log tail is from the crashed session
+fn collect_native_crash(dir: &Path) {
+    let marker = dir.join(NATIVE_MARKER);
+    let Ok(details) = std::fs::read_to_string(&marker) else { return };
+    let report = new_report("native", format!("Process crashed ({})", details.trim()));
+    if write_report(&report).is_ok() {
+        let _ = std::fs::remove_file(&marker);
+    }
+}
+
+static MARKER_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+#[cfg(unix)]
+mod native {
+    use std::ffi::CString;
+    use std::sync::OnceLock;
+
+    static MARKER: OnceLock<CString> = OnceLock::new();
+
+    // Only async-signal-safe calls in here: open, write, close, raise
+    extern "C" fn on_signal(signal: libc::c_int) {
+        if let Some(path) = MARKER.get() {
+            unsafe {
+                let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC, 0o600);
+                if fd >= 0 {
+                    let mut line = *b"signal 000\n";
+                    line[7] = b'0' + (signal / 100 % 10) as u8;
+                    line[8] = b'0' + (signal / 10 % 10) as u8;
+                    line[9] = b'0' + (signal % 10) as u8;
+                    libc::write(fd, line.as_ptr().cast(), line.len());
+                    libc::close(fd);
+                }
+            }
+        }
+        // SA_RESETHAND restored the default action, so this terminates as usual
+        unsafe { libc::raise(signal) };
+    }
+
+    pub fn install(marker: &std::path::Path) {
+        use std::os::unix::ffi::OsStrExt;
+
+        let Ok(path) = CString::new(marker.as_os_str().as_bytes()) else { return };
+        let _ = MARKER.set(path);
+        for signal in [libc::SIGSEGV, libc::SIGBUS, libc::SIGILL, libc::SIGFPE, libc::SIGABRT] {
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                // The alternate stack lets this run after a stack overflow too
+                action.sa_flags = libc::SA_ONSTACK | libc::SA_RESETHAND;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(signal, &action, std::ptr::null_mut());
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod native {
+    use windows_sys::Win32::System::Diagnostics::Debug::{
+        SetUnhandledExceptionFilter, EXCEPTION_CONTINUE_SEARCH, EXCEPTION_POINTERS,
+    };
+
+    unsafe extern "system" fn on_exception(info: *const EXCEPTION_POINTERS) -> i32 {
+        if let Some(path) = super::MARKER_PATH.get() {
+            let code = unsafe {
+                if info.is_null() || (*info).ExceptionRecord.is_null() {
+                    0
+                } else {
+                    (*(*info).ExceptionRecord).ExceptionCode as u32
+                }
+            };
+            let _ = std::fs::write(path, format!("exception 0x{:08X}\n", code));
+        }
+        // Let Windows Error Reporting handle the crash as before
+        EXCEPTION_CONTINUE_SEARCH
+    }
+
+    pub fn install(_marker: &std::path::Path) {
+        unsafe { SetUnhandledExceptionFilter(Some(on_exception)) };
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod native {
+    pub fn install(_marker: &std::path::Path) {}
+}
+
+// Install the panic hook and native crash handler; call once after logging::init
+pub fn install() {
+    let Ok(dir) = reports_dir() else { return };
+    let _ = std::fs::create_dir_all(&dir);
+    collect_native_crash(&dir);
+
+    let marker = dir.join(NATIVE_MARKER);
+    native::install(&marker);
+    let _ = MARKER_PATH.set(marker);
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        on_panic(info);
+        default_hook(info);
+    }));
+}
+
+// Reports not yet saved or dismissed, newest first
+pub fn pending() -> Result<Vec<CrashReport>, AppError> {
+    let dir = reports_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(&dir).map_err(|e| format!("Failed to read crash reports: {}", e))?;
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "json"))
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|contents| serde_json::from_str(&contents).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(reports)
+}
+
+pub fn get(id: &str) -> Result<CrashReport, AppError> {
+    let path = report_path(&reports_dir()?, id);
+    let contents = std::fs::read_to_string(&path).map_err(|_| AppError::unknown(format!("Unknown crash report: {}", id)))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse crash report: {}", e).into())
+}
+
+pub fn dismiss(id: &str) -> Result<(), AppError> {
+    let path = report_path(&reports_dir()?, id);
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete crash report: {}", e))?;
+    }
+    Ok(())
+}
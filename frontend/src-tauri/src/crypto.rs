@@ -0,0 +1,87 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use hkdf::Hkdf;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::Path;
+
+use crate::{config, secrets};
+use crate::error::AppError;
+
+// Keychain entry for the random master secret; per-file keys are derived from it
+pub const MASTER_SECRET: &str = "storage-encryption-secret";
+
+// Prefix of encrypted files, followed by the 12-byte nonce and the AES-256-GCM ciphertext
+const MAGIC: &[u8] = b"WWENC1";
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct EncryptionConfig {
+    // Encrypt history (including transcripts) at rest
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+pub fn encryption_config() -> EncryptionConfig {
+    config::load().encryption.unwrap_or_default()
+}
+
+fn master_secret() -> Result<Vec<u8>, AppError> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    if let Some(encoded) = secrets::get(MASTER_SECRET)? {
+        return engine.decode(encoded).map_err(|e| AppError::invalid(format!("Invalid encryption secret in keychain: {}", e)));
+    }
+    let mut secret = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    secrets::set(MASTER_SECRET, &engine.encode(&secret))?;
+    Ok(secret)
+}
+
+// One key per purpose ("history", ...), so a key recovered from one file opens nothing else
+fn cipher(purpose: &str) -> Result<Aes256Gcm, AppError> {
+    let secret = master_secret()?;
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, &secret)
+        .expand(format!("web-whisper {}", purpose).as_bytes(), &mut key)
+        .map_err(|e| format!("Failed to derive encryption key: {}", e))?;
+    Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Failed to derive encryption key: {}", e).into())
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn seal(purpose: &str, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher(purpose)?
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| "Failed to encrypt data".to_string())?;
+    Ok([MAGIC, &nonce[..], &ciphertext[..]].concat())
+}
+
+pub fn open(purpose: &str, data: &[u8]) -> Result<Vec<u8>, AppError> {
+    let body = data.strip_prefix(MAGIC).ok_or("Data is not encrypted")?;
+    if body.len() < NONCE_LEN {
+        return Err("Encrypted data is truncated".into());
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    cipher(purpose)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt data: the keychain secret does not match or the file is damaged".into())
+}
+
+// Read a file that may be encrypted or still plaintext (written before encryption was enabled)
+pub fn read_file(path: &Path, purpose: &str) -> Result<String, AppError> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let plaintext = if is_encrypted(&data) { open(purpose, &data)? } else { data };
+    String::from_utf8(plaintext).map_err(|e| AppError::invalid(format!("Invalid text in {:?}: {}", path, e)))
+}
+
+// Write `contents`, encrypted when encryption is enabled in the config
+pub fn write_file(path: &Path, purpose: &str, contents: &str) -> Result<(), AppError> {
+    let data = if encryption_config().enabled { seal(purpose, contents.as_bytes())? } else { contents.as_bytes().to_vec() };
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {:?}: {}", path, e).into())
+}
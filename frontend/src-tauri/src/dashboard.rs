@@ -0,0 +1,119 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::idle::{self, BackendStatus};
+use crate::jobs::{JobRegistry, JobStatus};
+use crate::{config, disk, engine, history, temp};
+
+// Completed transcriptions shown on the home screen
+const RECENT_LIMIT: usize = 5;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ActiveJob {
+    pub job_id: String,
+    pub title: Option<String>,
+    pub file_path: String,
+    pub processed_seconds: f64,
+    // None when the media length is unknown
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecentJob {
+    pub job_id: String,
+    pub title: String,
+    // RFC 3339
+    pub created_at: String,
+    pub language: Option<String>,
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StorageUsage {
+    pub data_dir: Option<String>,
+    // Free space on the data folder's volume
+    pub available_bytes: Option<u64>,
+    pub temp_bytes: u64,
+    pub model_cache_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Dashboard {
+    pub backend_status: BackendStatus,
+    pub server_port: Option<u16>,
+    pub queued: usize,
+    pub running: usize,
+    pub active_jobs: Vec<ActiveJob>,
+    // Newest first
+    pub recent: Vec<RecentJob>,
+    pub storage: StorageUsage,
+}
+
+// Total size of the files under `dir`; 0 when it does not exist
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map_or(0, |metadata| metadata.len()),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn storage() -> StorageUsage {
+    let data_dir = config::data_dir().ok();
+    StorageUsage {
+        available_bytes: data_dir.as_deref().and_then(disk::available_space),
+        data_dir: data_dir.map(|dir| dir.to_string_lossy().to_string()),
+        temp_bytes: dir_size(&temp::dir()),
+        model_cache_bytes: engine::model_cache_dir().map_or(0, |dir| dir_size(&dir)),
+    }
+}
+
+// Everything the home screen shows, in one call. Walks the temp and model folders,
+// so call from spawn_blocking.
+pub fn snapshot(registry: &JobRegistry, server_port: Option<u16>) -> Dashboard {
+    let (queued, mut active_jobs) = {
+        let jobs = registry.lock().unwrap();
+        let queued = jobs.values().filter(|record| record.status == JobStatus::Queued).count();
+        let active: Vec<ActiveJob> = jobs
+            .values()
+            .filter(|record| record.status == JobStatus::Running)
+            .map(|record| ActiveJob {
+                job_id: record.id.clone(),
+                title: record.title.clone(),
+                file_path: record.file_path.clone(),
+                processed_seconds: record.processed_seconds,
+                percent: record.percent,
+            })
+            .collect();
+        (queued, active)
+    };
+    active_jobs.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+
+    let mut entries = history::list().unwrap_or_default();
+    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    let recent = entries
+        .into_iter()
+        .take(RECENT_LIMIT)
+        .map(|entry| RecentJob {
+            job_id: entry.job_id,
+            title: entry.title,
+            created_at: entry.created_at,
+            language: entry.transcript.language,
+            duration: entry.transcript.duration,
+        })
+        .collect();
+
+    Dashboard {
+        backend_status: idle::status(),
+        server_port,
+        queued,
+        running: active_jobs.len(),
+        active_jobs,
+        recent,
+        storage: storage(),
+    }
+}
@@ -0,0 +1,61 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry, JobStatus};
+
+// SHA-256 of an input -> the job submitted for it this session
+static BY_HASH: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+// Hex SHA-256 of the file contents, read in 1 MB blocks. Blocks; use from spawn_blocking.
+pub fn hash_file(path: &str) -> Result<String, AppError> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+// Record `job_id` as the job for this content, unless an earlier job for it is queued,
+// running or completed; that job's id and status are returned instead. Failed and
+// cancelled jobs do not count, so the same file can be retried. `force` always claims.
+pub fn claim(registry: &JobRegistry, hash: &str, job_id: &str, force: bool) -> Option<(String, JobStatus)> {
+    let mut by_hash = BY_HASH.lock().unwrap();
+    let by_hash = by_hash.get_or_insert_with(HashMap::new);
+    if !force {
+        if let Some(existing) = active(registry, by_hash, hash) {
+            return Some(existing);
+        }
+    }
+    by_hash.insert(hash.to_string(), job_id.to_string());
+    None
+}
+
+// Give up `job_id`'s claim on this content, e.g. when its job could not be set up
+pub fn release(hash: &str, job_id: &str) {
+    if let Some(by_hash) = BY_HASH.lock().unwrap().as_mut() {
+        if by_hash.get(hash).is_some_and(|claimed| claimed == job_id) {
+            by_hash.remove(hash);
+        }
+    }
+}
+
+// The job `claim` would reject this content for, without claiming it
+pub fn existing(registry: &JobRegistry, hash: &str) -> Option<(String, JobStatus)> {
+    BY_HASH.lock().unwrap().as_ref().and_then(|by_hash| active(registry, by_hash, hash))
+}
+
+fn active(registry: &JobRegistry, by_hash: &HashMap<String, String>, hash: &str) -> Option<(String, JobStatus)> {
+    let existing = by_hash.get(hash)?;
+    // Claimed but not yet registered by run_job: it is about to be queued
+    let status = jobs::get(registry, existing).map_or(JobStatus::Queued, |record| record.status);
+    (!matches!(status, JobStatus::Failed | JobStatus::Cancelled)).then(|| (existing.clone(), status))
+}
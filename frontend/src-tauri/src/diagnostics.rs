@@ -0,0 +1,125 @@
+use regex::Regex;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::info;
+use zip::write::SimpleFileOptions;
+
+use crate::{config, crash, engine, hardware, logging};
+use crate::error::AppError;
+
+// Most recent log files included; older days rarely help with a fresh report
+const LOG_FILES: usize = 2;
+
+#[derive(Debug, Serialize)]
+struct AppInfo {
+    app_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    portable: bool,
+    // RFC 3339
+    created_at: String,
+}
+
+// key=value credentials in URLs and command lines, e.g. the backend's ?token=
+fn credential_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)\b(token|key|secret|password|sig|signature)=[^&\s]+").unwrap())
+}
+
+fn email_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[\w.+-]+@[\w-]+\.[\w.-]+").unwrap())
+}
+
+// Strip what a public issue should not show: credentials, email addresses and the
+// user's home folder, which usually contains their name
+fn sanitize(line: &str) -> String {
+    let mut line = credential_pattern().replace_all(line, "$1=[redacted]").into_owned();
+    line = email_pattern().replace_all(&line, "[email]").into_owned();
+    if let Some(home) = dirs::home_dir().map(|home| home.to_string_lossy().to_string()).filter(|home| home.len() > 1) {
+        line = line.replace(&home, "~");
+    }
+    line
+}
+
+// Python and engine package versions as the backend sees them; an error message when
+// Python cannot be run, e.g. with only the bundled sidecar installed
+fn backend_versions() -> serde_json::Value {
+    let script = "import json, platform\n\
+from importlib import metadata\n\
+versions = {'python': platform.python_version()}\n\
+for name in ('faster-whisper', 'ctranslate2', 'mlx-whisper', 'gradio', 'numpy'):\n\
+    try:\n\
+        versions[name] = metadata.version(name)\n\
+    except metadata.PackageNotFoundError:\n\
+        pass\n\
+print(json.dumps(versions))";
+    let output = Command::new(engine::python_command()).args(["-c", script]).output();
+    match output {
+        Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout)
+            .unwrap_or_else(|e| serde_json::json!({ "error": format!("Unreadable version output: {}", e) })),
+        Ok(output) => serde_json::json!({ "error": sanitize(String::from_utf8_lossy(&output.stderr).trim()) }),
+        Err(e) => serde_json::json!({ "error": format!("Failed to run Python: {}", e) }),
+    }
+}
+
+fn default_path() -> Result<PathBuf, AppError> {
+    let name = format!("web-whisper-diagnostics-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    Ok(config::output_dir()?.join(name))
+}
+
+// Zip logs, settings, system profile, backend versions and the latest crash report into
+// one file to attach to an issue; returns its path. Blocking: runs Python and reads logs.
+pub fn export(file_path: Option<&str>) -> Result<String, AppError> {
+    let path = match file_path {
+        Some(path) => PathBuf::from(path),
+        None => default_path()?,
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create folder {:?}: {}", dir, e))?;
+        crate::disk::ensure_space(dir, 0)?;
+    }
+
+    let file = std::fs::File::create(&path).map_err(|e| format!("Failed to create diagnostics bundle: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut add = |name: &str, contents: &[u8]| -> Result<(), AppError> {
+        zip.start_file(name, options).map_err(|e| format!("Failed to write diagnostics bundle: {}", e))?;
+        zip.write_all(contents).map_err(|e| format!("Failed to write diagnostics bundle: {}", e).into())
+    };
+
+    let app = AppInfo {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        portable: config::portable_root().is_some(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    add("app.json", &to_json(&app)?)?;
+    add("settings.json", &to_json(&crash::settings_snapshot())?)?;
+    add("system.json", &to_json(&hardware::profile())?)?;
+    add("backend.json", &to_json(&backend_versions())?)?;
+    if let Some(mut report) = crash::pending()?.into_iter().next() {
+        report.log_tail = report.log_tail.iter().map(|line| sanitize(line)).collect();
+        add("crash_report.json", &to_json(&report)?)?;
+    }
+    for log in logging::log_files().into_iter().take(LOG_FILES) {
+        let Ok(contents) = std::fs::read(&log) else { continue };
+        let sanitized: Vec<String> = String::from_utf8_lossy(&contents).lines().map(sanitize).collect();
+        let name = log.file_name().map_or_else(|| "log".to_string(), |name| name.to_string_lossy().to_string());
+        add(&format!("logs/{}", name), sanitized.join("\n").as_bytes())?;
+    }
+    zip.finish().map_err(|e| format!("Failed to write diagnostics bundle: {}", e))?;
+
+    let path = path.to_string_lossy().to_string();
+    info!("Saved diagnostics bundle to {}", path);
+    crate::audit::record(None, crate::audit::AuditAction::Write, "file", &path);
+    Ok(path)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, AppError> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize diagnostics: {}", e).into())
+}
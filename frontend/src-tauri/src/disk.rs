@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use crate::error::AppError;
+
+// Always leave this much free so the OS and other apps are not starved
+const RESERVE_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiskSpace {
+    pub path: String,
+    pub available_bytes: u64,
+    pub required_bytes: u64,
+    pub sufficient: bool,
+}
+
+// The directory may not exist yet; measure the volume of its nearest existing ancestor
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|ancestor| ancestor.exists()).map(Path::to_path_buf)
+}
+
+#[cfg(unix)]
+fn free_bytes(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn free_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn free_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+// Free space available to this user on the volume holding `dir`
+pub fn available_space(dir: &Path) -> Option<u64> {
+    free_bytes(&existing_ancestor(dir)?)
+}
+
+pub fn check(dir: &Path, required_bytes: u64) -> DiskSpace {
+    let required_bytes = required_bytes.saturating_add(RESERVE_BYTES);
+    // When the platform cannot tell, let the write proceed rather than block it
+    let available_bytes = available_space(dir).unwrap_or(u64::MAX);
+    DiskSpace {
+        path: dir.to_string_lossy().to_string(),
+        available_bytes,
+        required_bytes,
+        sufficient: available_bytes >= required_bytes,
+    }
+}
+
+// Fail before writing `required_bytes` into `dir` when the volume cannot hold them.
+// Pass 0 when the size is unknown to still catch an almost-full disk.
+pub fn ensure_space(dir: &Path, required_bytes: u64) -> Result<(), AppError> {
+    let space = check(dir, required_bytes);
+    if space.sufficient {
+        return Ok(());
+    }
+    let message = format!(
+        "Not enough disk space in {}: {} MB needed, {} MB free",
+        space.path,
+        space.required_bytes.div_ceil(1024 * 1024),
+        space.available_bytes / (1024 * 1024)
+    );
+    Err(AppError::insufficient_disk_space(&space.path, message))
+}
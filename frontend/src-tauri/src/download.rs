@@ -0,0 +1,84 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::engine;
+use crate::error::AppError;
+
+// yt-dlp is looked up like the backend sidecar: bundled next to the app first, then PATH
+pub fn ytdlp_command() -> PathBuf {
+    let bundled = if cfg!(target_os = "windows") { "yt-dlp.exe" } else { "yt-dlp" };
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(bundled)))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from(bundled))
+}
+
+// "[download]  42.1% of 10.00MiB at ..." -> 42.1
+fn parse_progress(line: &str) -> Option<f64> {
+    let rest = line.strip_prefix("[download]")?.trim_start();
+    let percent = rest.split('%').next()?;
+    percent.trim().parse::<f64>().ok()
+}
+
+// Download the audio track of `url` into the temp dir and return the extracted file
+pub fn download_audio(url: &str, mut on_progress: impl FnMut(f64)) -> Result<PathBuf, AppError> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| AppError::invalid(format!("Invalid URL '{}': {}", url, e)))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(AppError::invalid(format!("Unsupported URL scheme: {}", parsed.scheme())));
+    }
+
+    let download_dir = crate::temp::dir().join("downloads");
+    std::fs::create_dir_all(&download_dir)
+        .map_err(|e| format!("Failed to create download directory: {}", e))?;
+    // The final size is unknown until yt-dlp starts; at least refuse on a full disk
+    crate::disk::ensure_space(&download_dir, 0)?;
+    let output_template = download_dir.join(format!("{}_%(title).80s.%(ext)s", engine::new_job_id()));
+
+    let mut command = Command::new(ytdlp_command());
+    command
+        .args([
+            "--no-playlist",
+            "--newline",
+            "--extract-audio",
+            "--audio-format", "m4a",
+            "--print", "after_move:filepath",
+            "--output", &output_template.to_string_lossy(),
+            url,
+        ])
+        .env("PATH", engine::path_with_ffmpeg())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    crate::proxy::configure_command(&mut command)?;
+    let mut child = command.spawn().map_err(|e| format!("Failed to start yt-dlp (is it installed?): {}", e))?;
+
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buffer = String::new();
+            let _ = stderr.read_to_string(&mut buffer);
+            buffer
+        })
+    });
+
+    // --print switches progress to stdout alongside the final path line
+    let mut downloaded = None;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(percent) = parse_progress(&line) {
+                on_progress(percent);
+            } else if !line.starts_with('[') && !line.trim().is_empty() {
+                downloaded = Some(PathBuf::from(line.trim()));
+            }
+        }
+    }
+
+    let status = child.wait().map_err(|e| format!("Failed to wait for yt-dlp: {}", e))?;
+    let stderr = stderr_reader.and_then(|handle| handle.join().ok()).unwrap_or_default();
+    if !status.success() {
+        return Err(format!("Download failed: {}", stderr.trim()).into());
+    }
+    downloaded
+        .filter(|path| path.exists())
+        .ok_or_else(|| "yt-dlp finished without producing an audio file".into())
+}
@@ -0,0 +1,284 @@
+use serde::Serialize;
+use std::path::Path;
+use tracing::info;
+
+use crate::engine::{self, Segment, TranscribeOptions, Transcript};
+use crate::error::AppError;
+use crate::history;
+use crate::jobs::{self, JobRegistry};
+use crate::queue;
+use crate::{audio, languages, postprocess, temp};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SegmentChange {
+    // First segment of the change in the `to` version
+    pub index: usize,
+    pub start: f64,
+    pub end: f64,
+    // None when that version has no text there
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+// Rebuild the full text from the segments. Engines that join with newlines get spaces after an edit.
+pub fn rejoin(transcript: &mut Transcript) {
+    transcript.text = transcript
+        .segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+}
+
+// Finished jobs are saved to history as they complete; this adds any whose save failed
+pub fn ensure_in_history(registry: &JobRegistry, job_id: &str) -> Result<(), AppError> {
+    let Some(record) = jobs::get(registry, job_id) else { return Ok(()) };
+    let Some(transcript) = &record.transcript else { return Ok(()) };
+    let title = record.title.clone().unwrap_or_else(|| {
+        Path::new(&record.file_path).file_stem().map_or_else(|| job_id.to_string(), |stem| stem.to_string_lossy().to_string())
+    });
+    history::add_if_missing(job_id, &title, &record.file_path, transcript)
+}
+
+// Apply `edit` to the job's transcript in history, keeping the previous version, mark it
+// edited and refresh the in-memory copy that exports read. Returns the edited transcript.
+pub fn edit(
+    registry: &JobRegistry,
+    job_id: &str,
+    edit: impl FnOnce(&mut Transcript) -> Result<(), AppError>,
+) -> Result<Transcript, AppError> {
+    ensure_in_history(registry, job_id)?;
+    let transcript = history::update_transcript(job_id, |transcript| {
+        edit(transcript)?;
+        transcript.edited = true;
+        Ok(())
+    })?;
+    jobs::set_transcript(registry, job_id, transcript.clone());
+    Ok(transcript)
+}
+
+pub fn revert(registry: &JobRegistry, job_id: &str, version: u32) -> Result<Transcript, AppError> {
+    let transcript = history::revert(job_id, version)?;
+    jobs::set_transcript(registry, job_id, transcript.clone());
+    Ok(transcript)
+}
+
+// Segments with the same timing are compared by text. Elsewhere, e.g. where a range was
+// re-transcribed into a different number of segments, each stretch of overlapping segments
+// is one change covering their time range. `index` is where it starts in the `to` version.
+pub fn diff(job_id: &str, from: u32, to: u32) -> Result<Vec<SegmentChange>, AppError> {
+    let entry = history::get(job_id)?;
+    Ok(changes(&entry.version(from)?.segments, &entry.version(to)?.segments))
+}
+
+fn changes(before: &[Segment], after: &[Segment]) -> Vec<SegmentChange> {
+    let same_timing = |old: &Segment, new: &Segment| (old.start - new.start).abs() < 1e-3 && (old.end - new.end).abs() < 1e-3;
+    let joined = |segments: &[Segment]| {
+        let texts: Vec<&str> = segments.iter().map(|segment| segment.text.trim()).filter(|text| !text.is_empty()).collect();
+        (!texts.is_empty()).then(|| texts.join(" "))
+    };
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if let (Some(old), Some(new)) = (before.get(i), after.get(j)) {
+            if same_timing(old, new) {
+                if old.text.trim() != new.text.trim() {
+                    changes.push(SegmentChange {
+                        index: j,
+                        start: new.start,
+                        end: new.end,
+                        before: Some(old.text.trim().to_string()),
+                        after: Some(new.text.trim().to_string()),
+                    });
+                }
+                i += 1;
+                j += 1;
+                continue;
+            }
+        }
+        // Grow the stretch from whichever side starts first until neither side overlaps it
+        let (first_old, first_new) = (i, j);
+        let starts_first = |old: Option<&Segment>, new: Option<&Segment>| match (old, new) {
+            (Some(old), Some(new)) => old.start <= new.start,
+            (old, _) => old.is_some(),
+        };
+        let (range_start, mut range_end) = if starts_first(before.get(i), after.get(j)) {
+            i += 1;
+            (before[i - 1].start, before[i - 1].end)
+        } else {
+            j += 1;
+            (after[j - 1].start, after[j - 1].end)
+        };
+        loop {
+            if let Some(old) = before.get(i).filter(|old| old.start < range_end) {
+                range_end = range_end.max(old.end);
+                i += 1;
+            } else if let Some(new) = after.get(j).filter(|new| new.start < range_end) {
+                range_end = range_end.max(new.end);
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        let (old_text, new_text) = (joined(&before[first_old..i]), joined(&after[first_new..j]));
+        if old_text != new_text {
+            changes.push(SegmentChange { index: first_new, start: range_start, end: range_end, before: old_text, after: new_text });
+        }
+    }
+    changes
+}
+
+pub fn update_segment(registry: &JobRegistry, job_id: &str, index: usize, text: &str) -> Result<Transcript, AppError> {
+    let text = text.trim();
+    edit(registry, job_id, |transcript| {
+        let count = transcript.segments.len();
+        let segment = transcript
+            .segments
+            .get_mut(index)
+            .ok_or_else(|| AppError::invalid(format!("Invalid segment index {}: the transcript has {} segments", index, count)))?;
+        segment.text = text.to_string();
+        // The old word timings no longer match the text
+        segment.words.clear();
+        rejoin(transcript);
+        Ok(())
+    })
+}
+
+// Run [start, end) of the job's audio through the engine again, e.g. with a larger `model`,
+// and splice the new segments over the ones they replace. The range grows to whole
+// segments so none is cut in half. The previous version is kept, so a revert undoes it.
+// Blocking: cuts the audio and waits for the engine.
+pub fn retranscribe_range(
+    registry: &JobRegistry,
+    job_id: &str,
+    start: f64,
+    end: f64,
+    options: &TranscribeOptions,
+) -> Result<Transcript, AppError> {
+    if !(start >= 0.0 && end > start) {
+        return Err(AppError::invalid(format!("Invalid range {:.3}-{:.3}: end must be after start", start, end)));
+    }
+    let (source, _, transcript) = audio::job_source(registry, job_id)?;
+    let is_url = source.starts_with("http://") || source.starts_with("https://");
+    if !is_url && !Path::new(&source).exists() {
+        return Err(AppError::unknown(format!("Source audio not found: {}", source)));
+    }
+    let overlaps = |segment: &Segment| segment.start < end && segment.end > start;
+    let covered = transcript.segments.iter().filter(|segment| overlaps(segment));
+    let (start, end) = covered.fold((start, end), |(start, end), segment| (start.min(segment.start), end.max(segment.end)));
+
+    let mut options = options.clone();
+    // Keep the language the rest of the transcript was decoded in
+    if options.language == "auto" {
+        if let Some(language) = &transcript.language {
+            options.language = language.clone();
+        }
+    }
+    let run_id = format!("{}-range-{}", job_id, engine::new_job_id().trim_start_matches("job-"));
+    let clip = temp::dir().join(format!("{}.wav", run_id));
+    if let Some(dir) = clip.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create temp folder: {}", e))?;
+    }
+    audio::extract_clip(&source, start, end, &clip)?;
+    info!("Re-transcribing {:.1}-{:.1}s of job {}", start, end, job_id);
+    let outcome = engine::engine_for(&options.engine).and_then(|engine| {
+        let _permit = queue::acquire(engine.worker_class(), &run_id, options.priority, || false)?;
+        engine.transcribe(&clip.to_string_lossy(), &options, &mut |_| {}, &mut |_| {})
+    });
+    let _ = std::fs::remove_file(&clip);
+    let mut redone = outcome?;
+
+    // Clip times start at 0; move them to the range and keep them inside it
+    redone.segments = redone
+        .segments
+        .into_iter()
+        .map(|mut segment| {
+            segment.start = (segment.start + start).min(end);
+            segment.end = (segment.end + start).min(end);
+            for word in &mut segment.words {
+                word.start = (word.start + start).min(end);
+                word.end = (word.end + start).min(end);
+            }
+            segment
+        })
+        .filter(|segment| !segment.text.trim().is_empty())
+        .collect();
+    // The same punctuation, ITN, glossary and language rules as a finished job; redaction
+    // is left to exports as for the rest of the transcript
+    redone.corrections.clear();
+    postprocess::apply(&mut redone, &options);
+    languages::post_process(&mut redone);
+
+    edit(registry, job_id, |transcript| {
+        let at = transcript.segments.iter().position(|segment| segment.end > start).unwrap_or(transcript.segments.len());
+        let removed: Vec<usize> = (0..transcript.segments.len()).filter(|&index| overlaps(&transcript.segments[index])).collect();
+        let added = redone.segments.len();
+        transcript.segments.retain(|segment| !overlaps(segment));
+        let at = at.min(transcript.segments.len());
+        transcript.segments.splice(at..at, redone.segments);
+        // Glossary fixes point at segments: drop the replaced ones' and renumber the rest
+        transcript.corrections.retain(|correction| !removed.contains(&correction.segment));
+        for correction in &mut transcript.corrections {
+            if correction.segment >= at {
+                correction.segment = correction.segment - removed.iter().filter(|&&index| index < correction.segment).count() + added;
+            }
+        }
+        transcript.corrections.extend(redone.corrections.into_iter().map(|mut correction| {
+            correction.segment += at;
+            correction
+        }));
+        rejoin(transcript);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment { start, end, text: text.to_string(), words: Vec::new() }
+    }
+
+    #[test]
+    fn edited_segment_is_one_change_at_its_index() {
+        let before = [segment(0.0, 2.0, "hello"), segment(2.0, 4.0, "wrold"), segment(4.0, 6.0, "bye")];
+        let mut after = before.clone();
+        after[1].text = "world".to_string();
+        let changes = changes(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].index, changes[0].start, changes[0].end), (1, 2.0, 4.0));
+        assert_eq!(changes[0].before.as_deref(), Some("wrold"));
+        assert_eq!(changes[0].after.as_deref(), Some("world"));
+    }
+
+    #[test]
+    fn spliced_range_is_one_change_and_later_segments_still_match() {
+        let before = [segment(0.0, 2.0, "a"), segment(2.0, 6.0, "mumble"), segment(6.0, 8.0, "z")];
+        let after = [segment(0.0, 2.0, "a"), segment(2.0, 3.5, "first"), segment(3.5, 6.0, "second"), segment(6.0, 8.0, "z")];
+        let changes = changes(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].index, changes[0].start, changes[0].end), (1, 2.0, 6.0));
+        assert_eq!(changes[0].before.as_deref(), Some("mumble"));
+        assert_eq!(changes[0].after.as_deref(), Some("first second"));
+    }
+
+    #[test]
+    fn range_that_came_back_empty_has_no_after_text() {
+        let before = [segment(0.0, 2.0, "a"), segment(2.0, 4.0, "noise")];
+        let after = [segment(0.0, 2.0, "a")];
+        let changes = changes(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].index, 1);
+        assert_eq!(changes[0].after, None);
+    }
+
+    #[test]
+    fn retimed_text_is_not_a_change() {
+        let before = [segment(0.0, 2.0, "a"), segment(2.0, 4.0, "b")];
+        let after = [segment(0.5, 2.5, "a"), segment(2.5, 4.5, "b")];
+        assert!(changes(&before, &after).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{Segment, TranscribeOptions, Transcript, TranscriptionEngine};
+use crate::error::AppError;
+use crate::secrets;
+
+pub const API_KEY: &str = "azure-speech-key";
+
+const API_VERSION: &str = "2024-11-15";
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AzureSpeechConfig {
+    // Speech resource region, e.g. "westeurope"
+    pub region: String,
+    // Candidate locales ("en-US", "ja-JP") used when the job language is "auto" or a bare code
+    #[serde(default)]
+    pub locales: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FastTranscriptionResponse {
+    #[serde(default)]
+    duration_milliseconds: Option<u64>,
+    #[serde(default)]
+    combined_phrases: Vec<CombinedPhrase>,
+    #[serde(default)]
+    phrases: Vec<Phrase>,
+}
+
+#[derive(Deserialize)]
+struct CombinedPhrase {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Phrase {
+    offset_milliseconds: u64,
+    duration_milliseconds: u64,
+    text: String,
+    #[serde(default)]
+    locale: Option<String>,
+}
+
+pub struct AzureEngine {
+    pub config: AzureSpeechConfig,
+}
+
+impl AzureEngine {
+    // Azure wants full locales; a job language that already is one wins over the configured list
+    fn locales(&self, options: &TranscribeOptions) -> Vec<String> {
+        if options.language.contains('-') {
+            return vec![options.language.clone()];
+        }
+        if options.language != "auto" {
+            let prefix = format!("{}-", options.language.to_lowercase());
+            let matching: Vec<String> = self
+                .config
+                .locales
+                .iter()
+                .filter(|locale| locale.to_lowercase().starts_with(&prefix))
+                .cloned()
+                .collect();
+            if !matching.is_empty() {
+                return matching;
+            }
+        }
+        self.config.locales.clone()
+    }
+}
+
+impl TranscriptionEngine for AzureEngine {
+    fn name(&self) -> &'static str {
+        "azure"
+    }
+
+    fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        _on_start: &mut dyn FnMut(u32),
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError> {
+        let api_key = secrets::get(API_KEY)?.ok_or(AppError::not_configured("Azure Speech key is not configured"))?;
+
+        let definition = serde_json::json!({ "locales": self.locales(options) });
+        let form = reqwest::blocking::multipart::Form::new()
+            .text("definition", definition.to_string())
+            .file("audio", file_path)
+            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+
+        info!("Uploading {} to Azure Speech ({})", file_path, self.config.region);
+        let response = crate::proxy::blocking_client()?
+            .post(format!(
+                "https://{}.api.cognitive.microsoft.com/speechtotext/transcriptions:transcribe?api-version={}",
+                self.config.region, API_VERSION
+            ))
+            .header("Ocp-Apim-Subscription-Key", api_key)
+            .multipart(form)
+            .send()
+            .map_err(|e| AppError::upstream(format!("Azure Speech request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().unwrap_or_default();
+            return Err(AppError::upstream(format!("Azure Speech request failed: HTTP {} {}", status, detail.trim())));
+        }
+        let body: FastTranscriptionResponse =
+            response.json().map_err(|e| AppError::invalid(format!("Invalid Azure Speech response: {}", e)))?;
+
+        let language = body.phrases.iter().find_map(|phrase| phrase.locale.clone());
+        let segments: Vec<Segment> = body
+            .phrases
+            .into_iter()
+            .map(|phrase| Segment {
+                start: phrase.offset_milliseconds as f64 / 1000.0,
+                end: (phrase.offset_milliseconds + phrase.duration_milliseconds) as f64 / 1000.0,
+                text: phrase.text,
+                words: Vec::new(),
+            })
+            .collect();
+        for segment in &segments {
+            on_segment(segment);
+        }
+        let text = body.combined_phrases.into_iter().map(|phrase| phrase.text).collect::<Vec<_>>().join("\n");
+
+        Ok(Transcript {
+            text,
+            language,
+            duration: body.duration_milliseconds.map(|ms| ms as f64 / 1000.0),
+            segments,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::{Segment, TranscribeOptions, Transcript, TranscriptionEngine, Word};
+use crate::error::AppError;
+use crate::secrets;
+
+pub const API_KEY: &str = "google-speech-api-key";
+
+const API_BASE: &str = "https://speech.googleapis.com/v1";
+
+// Synchronous recognition only accepts up to one minute of audio
+const SYNC_LIMIT_SECONDS: f64 = 60.0;
+
+// Inline audio content is capped at 10 MB for both request kinds
+const MAX_INLINE_BYTES: usize = 10 * 1024 * 1024;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GoogleSpeechConfig {
+    // BCP-47 code used when the job language is "auto", e.g. "en-US"
+    #[serde(default = "default_language_code")]
+    pub language_code: String,
+    // Recognition model, e.g. "latest_long" or "video"
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default = "default_punctuation")]
+    pub enable_automatic_punctuation: bool,
+}
+
+fn default_language_code() -> String {
+    "en-US".to_string()
+}
+
+fn default_punctuation() -> bool {
+    true
+}
+
+#[derive(Deserialize, Default)]
+struct RecognizeResponse {
+    #[serde(default)]
+    results: Vec<RecognitionResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecognitionResult {
+    #[serde(default)]
+    alternatives: Vec<Alternative>,
+    #[serde(default)]
+    result_end_time: Option<String>,
+    #[serde(default)]
+    language_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Alternative {
+    #[serde(default)]
+    transcript: String,
+    #[serde(default)]
+    words: Vec<WordInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WordInfo {
+    start_time: String,
+    #[serde(default)]
+    end_time: Option<String>,
+    #[serde(default)]
+    word: String,
+}
+
+#[derive(Deserialize)]
+struct Operation {
+    name: String,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    error: Option<OperationError>,
+    #[serde(default)]
+    response: Option<RecognizeResponse>,
+}
+
+#[derive(Deserialize)]
+struct OperationError {
+    message: String,
+}
+
+// Durations come back as protobuf JSON strings such as "12.340s"
+fn parse_duration(value: &str) -> f64 {
+    value.trim_end_matches('s').parse().unwrap_or(0.0)
+}
+
+// Re-encode to 16 kHz mono Ogg Opus, which the API accepts and keeps long recordings under the inline cap
+fn encode_opus(file_path: &str) -> Result<Vec<u8>, AppError> {
+    // Opus at speech bitrates is far smaller than the source, so the source size is a safe upper bound
+    let source_size = std::fs::metadata(file_path).map(|metadata| metadata.len()).unwrap_or(0);
+    let temp_dir = crate::temp::dir();
+    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    crate::disk::ensure_space(&temp_dir, source_size)?;
+    let output_path = temp_dir.join(format!("{}_google.ogg", super::new_job_id()));
+    let output = Command::new("ffmpeg")
+        .args(["-y", "-v", "error", "-i", file_path, "-ac", "1", "-ar", "16000", "-c:a", "libopus", "-b:a", "24k"])
+        .arg(&output_path)
+        .env("PATH", super::path_with_ffmpeg())
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    let bytes = std::fs::read(&output_path).map_err(|e| format!("Failed to read encoded audio: {}", e).into());
+    let _ = std::fs::remove_file(&output_path);
+    bytes
+}
+
+fn check(response: reqwest::blocking::Response) -> Result<reqwest::blocking::Response, AppError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let detail = response.text().unwrap_or_default();
+    Err(AppError::upstream(format!("Google Speech request failed: HTTP {} {}", status, detail.trim())))
+}
+
+pub struct GoogleEngine {
+    pub config: GoogleSpeechConfig,
+}
+
+impl GoogleEngine {
+    fn language_code(&self, options: &TranscribeOptions) -> String {
+        if options.language == "auto" {
+            self.config.language_code.clone()
+        } else {
+            options.language.clone()
+        }
+    }
+
+    // Files over a minute go through longrunningrecognize and are polled until done
+    fn recognize(&self, api_key: &str, request: &serde_json::Value, long_running: bool) -> Result<RecognizeResponse, AppError> {
+        let http = crate::proxy::blocking_client()?;
+        if !long_running {
+            let response = http
+                .post(format!("{}/speech:recognize", API_BASE))
+                .query(&[("key", api_key)])
+                .json(request)
+                .send()
+                .map_err(|e| AppError::upstream(format!("Google Speech request failed: {}", e)))?;
+            return check(response)?.json().map_err(|e| AppError::invalid(format!("Invalid Google Speech response: {}", e)));
+        }
+
+        let response = http
+            .post(format!("{}/speech:longrunningrecognize", API_BASE))
+            .query(&[("key", api_key)])
+            .json(request)
+            .send()
+            .map_err(|e| AppError::upstream(format!("Google Speech request failed: {}", e)))?;
+        let mut operation: Operation = check(response)?.json().map_err(|e| AppError::invalid(format!("Invalid Google Speech response: {}", e)))?;
+        debug!("Google Speech operation {} started", operation.name);
+
+        while !operation.done {
+            std::thread::sleep(POLL_INTERVAL);
+            let response = http
+                .get(format!("{}/operations/{}", API_BASE, operation.name))
+                .query(&[("key", api_key)])
+                .send()
+                .map_err(|e| AppError::upstream(format!("Google Speech request failed: {}", e)))?;
+            operation = check(response)?.json().map_err(|e| AppError::invalid(format!("Invalid Google Speech response: {}", e)))?;
+        }
+        if let Some(error) = operation.error {
+            return Err(format!("Google Speech recognition failed: {}", error.message).into());
+        }
+        Ok(operation.response.unwrap_or_default())
+    }
+}
+
+impl TranscriptionEngine for GoogleEngine {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        _on_start: &mut dyn FnMut(u32),
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError> {
+        let api_key = secrets::get(API_KEY)?.ok_or(AppError::not_configured("Google Speech API key is not configured"))?;
+
+        let duration = super::probe_duration(file_path);
+        let audio = encode_opus(file_path)?;
+        if audio.len() > MAX_INLINE_BYTES {
+            return Err(AppError::limit_exceeded(format!(
+                "Encoded audio is {:.1} MB; Google Speech accepts at most 10 MB inline",
+                audio.len() as f64 / (1024.0 * 1024.0)
+            )));
+        }
+
+        let mut config = serde_json::json!({
+            "encoding": "OGG_OPUS",
+            "sampleRateHertz": 16000,
+            "languageCode": self.language_code(options),
+            "enableAutomaticPunctuation": self.config.enable_automatic_punctuation,
+            "enableWordTimeOffsets": true,
+        });
+        if let Some(model) = &self.config.model {
+            config["model"] = serde_json::json!(model);
+        }
+        let request = serde_json::json!({
+            "config": config,
+            "audio": { "content": base64::engine::general_purpose::STANDARD.encode(&audio) },
+        });
+
+        let long_running = duration.is_none_or(|seconds| seconds > SYNC_LIMIT_SECONDS);
+        info!("Sending {} to Google Speech ({})", file_path, if long_running { "long-running" } else { "sync" });
+        let body = self.recognize(&api_key, &request, long_running)?;
+
+        // Each result covers the audio since the previous one ended
+        let mut segments = Vec::new();
+        let mut language = None;
+        let mut previous_end = 0.0;
+        for result in body.results {
+            let end = result.result_end_time.as_deref().map(parse_duration).unwrap_or(previous_end);
+            if language.is_none() {
+                language = result.language_code;
+            }
+            if let Some(alternative) = result.alternatives.into_iter().next() {
+                let start = alternative.words.first().map(|word| parse_duration(&word.start_time)).unwrap_or(previous_end);
+                let words = if options.word_timestamps {
+                    // Google drops the spaces between words; add them back like Whisper's output,
+                    // except for languages written without spaces
+                    let language_code = language.as_deref().unwrap_or(&options.language).to_lowercase();
+                    let separator = if ["ja", "zh", "yue", "th"].iter().any(|code| language_code.starts_with(code)) { "" } else { " " };
+                    alternative
+                        .words
+                        .iter()
+                        .map(|word| {
+                            let word_start = parse_duration(&word.start_time);
+                            let word_end = word.end_time.as_deref().map(parse_duration).unwrap_or(word_start);
+                            Word { start: word_start, end: word_end, text: format!("{}{}", separator, word.word) }
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let segment = Segment { start, end, text: alternative.transcript.trim().to_string(), words };
+                on_segment(&segment);
+                segments.push(segment);
+            }
+            previous_end = end;
+        }
+        let text = segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" ");
+
+        Ok(Transcript { text, language, duration, segments, ..Default::default() })
+    }
+}
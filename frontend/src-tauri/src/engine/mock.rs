@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+use super::{EngineStats, Segment, TranscribeOptions, Transcript, TranscriptionEngine, Word};
+use crate::error::AppError;
+
+pub const ENV_VAR: &str = "WEB_WHISPER_MOCK_ENGINE";
+
+// Length assumed for files ffprobe cannot read, e.g. an empty fixture
+const FALLBACK_SECONDS: f64 = 30.0;
+// Audio seconds "transcribed" per wall-clock second
+const SPEED: f64 = 8.0;
+// Pretend model load before the first segment
+const LOAD_DELAY: Duration = Duration::from_millis(600);
+
+const LINES: &[&str] = &[
+    "Welcome everyone, thanks for joining today.",
+    "Let's start with a quick review of last week's numbers.",
+    "Sign-ups were up about twelve percent compared to March.",
+    "Most of that came from the new onboarding flow.",
+    "There are still a few rough edges on mobile, though.",
+    "Could you share the crash reports after the meeting?",
+    "Sure, I'll send them over this afternoon.",
+    "Next up is the release schedule for version two.",
+    "We're aiming for the fifteenth, if testing goes well.",
+    "Okay, let's take five minutes and then continue.",
+];
+
+// Running mock jobs by app job id, true once cancelled; they have no process to kill
+static RUNNING: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
+
+// Returns canned segments at a realistic pace, without Python or a model, for frontend work
+pub struct MockEngine;
+
+// Mock mode sends "local" jobs here: WEB_WHISPER_MOCK_ENGINE=1 or mock_engine in config.json
+pub fn enabled() -> bool {
+    match std::env::var(ENV_VAR) {
+        Ok(value) => matches!(value.trim(), "1" | "true" | "yes"),
+        Err(_) => crate::config::load().mock_engine.unwrap_or(false),
+    }
+}
+
+// Stop a running mock job at its next segment; false when no mock job has that id
+pub fn cancel(job_id: &str) -> bool {
+    match RUNNING.lock().unwrap().as_mut().and_then(|running| running.get_mut(job_id)) {
+        Some(cancelled) => {
+            *cancelled = true;
+            true
+        }
+        None => false,
+    }
+}
+
+fn is_cancelled(job_id: &str) -> bool {
+    RUNNING.lock().unwrap().as_ref().and_then(|running| running.get(job_id).copied()).unwrap_or(false)
+}
+
+// Words spread evenly over the segment
+fn words(segment: &Segment) -> Vec<Word> {
+    let parts: Vec<&str> = segment.text.split_whitespace().collect();
+    let step = (segment.end - segment.start) / parts.len().max(1) as f64;
+    parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| Word {
+            start: segment.start + step * i as f64,
+            end: segment.start + step * (i + 1) as f64,
+            text: format!(" {}", part),
+        })
+        .collect()
+}
+
+impl TranscriptionEngine for MockEngine {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        _on_start: &mut dyn FnMut(u32),
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError> {
+        let duration = super::probe_duration(file_path).filter(|duration| *duration > 0.0).unwrap_or(FALLBACK_SECONDS);
+        info!("Mock transcription of {} ({:.1}s)", file_path, duration);
+        let job_id = options.job_id.clone().unwrap_or_default();
+        RUNNING.lock().unwrap().get_or_insert_with(HashMap::new).insert(job_id.clone(), false);
+        let outcome = self.segments(&job_id, duration, options, on_segment);
+        if let Some(running) = RUNNING.lock().unwrap().as_mut() {
+            running.remove(&job_id);
+        }
+        let segments = outcome?;
+
+        let language = if options.language == "auto" { "en".to_string() } else { options.language.clone() };
+        let stats = EngineStats {
+            model: Some("mock".to_string()),
+            model_load_seconds: Some(LOAD_DELAY.as_secs_f64()),
+            peak_rss_bytes: None,
+        };
+        Ok(Transcript {
+            text: segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" "),
+            language: Some(language),
+            duration: Some(duration),
+            segments,
+            stats: Some(stats),
+            ..Default::default()
+        })
+    }
+}
+
+impl MockEngine {
+    fn segments(
+        &self,
+        job_id: &str,
+        duration: f64,
+        options: &TranscribeOptions,
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Vec<Segment>, AppError> {
+        std::thread::sleep(LOAD_DELAY);
+
+        let mut segments = Vec::new();
+        let mut start = 0.0;
+        while start < duration {
+            if is_cancelled(job_id) {
+                return Err(AppError::cancelled("Job cancelled"));
+            }
+            let text = LINES[segments.len() % LINES.len()];
+            // About 2.5 words a second, like normal speech
+            let length = (text.split_whitespace().count() as f64 / 2.5).min(duration - start);
+            let mut segment = Segment { start, end: start + length, text: text.to_string(), words: Vec::new() };
+            if options.word_timestamps {
+                segment.words = words(&segment);
+            }
+            std::thread::sleep(Duration::from_secs_f64(length / SPEED));
+            on_segment(&segment);
+            segments.push(segment);
+            // A short pause between sentences
+            start += length + 0.4;
+        }
+        Ok(segments)
+    }
+}
@@ -0,0 +1,681 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{info, warn};
+
+use crate::bookmarks::Bookmark;
+use crate::chapters::Chapter;
+use crate::error::AppError;
+use crate::glossary::Correction;
+use crate::queue::{Priority, WorkerClass};
+
+pub mod azure;
+pub mod google;
+pub mod mock;
+pub mod openai;
+
+// Model id reported to API clients; the backend picks the actual Whisper weights
+pub const MODEL_ID: &str = "whisper-1";
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Segment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    // Only filled for jobs run with word_timestamps
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+}
+
+// `text` is the word as the model wrote it, leading space included
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Word {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Transcript {
+    pub text: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<Translation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    // Set once the text has been corrected by hand
+    #[serde(default)]
+    pub edited: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<Bookmark>,
+    // Glossary fixes made to the text, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub corrections: Vec<Correction>,
+    // Run metrics from the local backend; not part of the stored transcript
+    #[serde(skip)]
+    pub stats: Option<EngineStats>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct EngineStats {
+    // Checkpoint as the backend loaded it, e.g. an MLX repo on Apple Silicon
+    pub model: Option<String>,
+    pub model_load_seconds: Option<f64>,
+    pub peak_rss_bytes: Option<u64>,
+}
+
+// Machine translation stored alongside the original; segments mirror the original timing
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Translation {
+    pub language: String,
+    pub text: String,
+    pub segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TranscribeOptions {
+    pub language: String,
+    // "local" (the Python backend), a cloud engine ("openai", "azure", "google") or "mock"
+    pub engine: String,
+    // Explicit per-job opt-in to retry on the OpenAI API when the local engine cannot run
+    pub cloud_fallback: bool,
+    pub priority: Priority,
+    // Per-word timings for karaoke exports; local and Google engines only
+    pub word_timestamps: bool,
+    // Initial prompt for the local engine; the language profile's when unset
+    pub prompt: Option<String>,
+    // Whisper model for the local engine over the configured one and the language profile's,
+    // e.g. "large-v3" to redo a hard passage
+    pub model: Option<String>,
+    // Absolute folder for this job's saved files, over the project's and the global one
+    pub output_dir: Option<String>,
+    // Export formats (see subtitle::render) written to the output folder when the job completes
+    pub save_formats: Vec<String>,
+    // Text file the transcript is appended to on completion, for one running notes document
+    pub append_to: Option<String>,
+    // Restore punctuation and capitals; the punctuation config's default when unset
+    pub restore_punctuation: Option<bool>,
+    // Write spoken numbers, percentages and dates as digits and symbols (see itn.rs);
+    // the ITN config's default when unset
+    pub inverse_normalize: Option<bool>,
+    // Set by jobs::run_job so engines can tie server-side work to the job for cancelling
+    #[serde(skip)]
+    pub job_id: Option<String>,
+}
+
+impl Default for TranscribeOptions {
+    fn default() -> Self {
+        TranscribeOptions {
+            language: "auto".to_string(),
+            engine: "local".to_string(),
+            cloud_fallback: false,
+            priority: Priority::Normal,
+            word_timestamps: false,
+            prompt: None,
+            model: None,
+            output_dir: None,
+            save_formats: Vec::new(),
+            append_to: None,
+            restore_punctuation: None,
+            inverse_normalize: None,
+            job_id: None,
+        }
+    }
+}
+
+// Common interface of the local backend and cloud engines. Implementations block
+// until the transcript is ready; `on_start` receives a PID when there is a process to cancel.
+pub trait TranscriptionEngine {
+    fn name(&self) -> &'static str;
+    // Pool the job waits on before running; remote engines default to CPU
+    fn worker_class(&self) -> WorkerClass {
+        WorkerClass::Cpu
+    }
+    fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        on_start: &mut dyn FnMut(u32),
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError>;
+}
+
+pub struct LocalEngine;
+
+impl TranscriptionEngine for LocalEngine {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn worker_class(&self) -> WorkerClass {
+        if crate::queue::worker_config().local_on_cpu {
+            WorkerClass::Cpu
+        } else {
+            WorkerClass::Gpu
+        }
+    }
+
+    fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        on_start: &mut dyn FnMut(u32),
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError> {
+        let mut options = options.clone();
+        // Language profiles pick the model, so find out the language first
+        if options.language == "auto" && !crate::languages::language_config().profiles.is_empty() {
+            match detect_language(file_path, &mut *on_start) {
+                Ok(Some(language)) => {
+                    info!("Detected language {} for {}", language, file_path);
+                    options.language = language;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Language detection failed; using the default model: {}", e),
+            }
+        }
+        let profile = crate::languages::profile_for(&options.language).unwrap_or_default();
+        options.prompt = options.prompt.or(profile.prompt);
+
+        let mut config = crate::gpu::local_model_config();
+        if let Some(model) = options.model.clone().or(profile.model) {
+            config.model = model;
+        }
+        if let Some(compute_type) = profile.compute_type {
+            config.compute_type = compute_type;
+        }
+        // On the CPU pool the backend never touches the GPU, so its memory does not matter
+        let gpu = if self.worker_class() == WorkerClass::Gpu { crate::gpu::query() } else { None };
+        let plan = crate::gpu::plan_model(&config, gpu);
+        if let Some(warning) = &plan.warning {
+            warn!("GPU memory check: {}", warning);
+        }
+        // A running server already has the model loaded; the script loads it for every file
+        let server = crate::backend_api::running_port().filter(|_| crate::flags::enabled(crate::flags::Flag::ServerJobs));
+        if let Some(port) = server {
+            return crate::backend_api::BackendClient::new(port).transcribe(file_path, &options, &plan, on_segment);
+        }
+        transcribe_file_streaming(file_path, &options, &plan, on_start, on_segment)
+    }
+}
+
+pub fn engine_for(name: &str) -> Result<Box<dyn TranscriptionEngine>, AppError> {
+    match name {
+        "" | "local" if mock::enabled() => Ok(Box::new(mock::MockEngine)),
+        "" | "local" => Ok(Box::new(LocalEngine)),
+        "mock" => Ok(Box::new(mock::MockEngine)),
+        "openai" => Ok(Box::new(openai::OpenAiEngine { config: crate::config::load().openai.unwrap_or_default() })),
+        "azure" => {
+            let config = crate::config::load().azure_speech.ok_or(AppError::not_configured("Azure Speech is not configured"))?;
+            Ok(Box::new(azure::AzureEngine { config }))
+        }
+        "google" => {
+            let config = crate::config::load().google_speech.ok_or(AppError::not_configured("Google Speech is not configured"))?;
+            Ok(Box::new(google::GoogleEngine { config }))
+        }
+        other => Err(AppError::unknown(format!("Unknown transcription engine: {}", other))),
+    }
+}
+
+// Media duration in seconds via ffprobe, if available
+pub fn probe_duration(file_path: &str) -> Option<f64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1", file_path])
+        .env("PATH", path_with_ffmpeg())
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse::<f64>().ok()
+}
+
+// Locate the Python backend directory by looking for a marker file
+// (main.py, transcribe_simple.py, ...) in the usual dev and bundled locations
+pub fn find_backend_dir(marker: &str) -> Result<PathBuf, AppError> {
+    let current_exe = env::current_exe().map_err(|e| AppError::backend_unavailable(format!("Failed to get current exe: {}", e)))?;
+    let app_dir = current_exe.parent().ok_or("Failed to get app directory")?;
+    let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
+    let windows_fallback = PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile));
+
+    let grandparent = match app_dir.parent().and_then(|p| p.parent()) {
+        Some(grandparent) => grandparent,
+        None => return Ok(windows_fallback),
+    };
+
+    let candidates = [grandparent.join("backend"), grandparent.join("../backend")];
+    if let Some(found) = candidates.into_iter().find(|p| p.join(marker).exists()) {
+        return Ok(found);
+    }
+
+    if cfg!(target_os = "windows") {
+        // Windows: Try common development locations
+        let candidates = vec![
+            windows_fallback,
+            PathBuf::from("C:\\web-whisper\\backend"),
+            PathBuf::from("backend"), // Relative to current directory
+        ];
+        Ok(candidates.into_iter().find(|p| p.join(marker).exists())
+            .unwrap_or_else(|| PathBuf::from("backend")))
+    } else {
+        // macOS/Linux: Default to repo-relative 'backend'
+        Ok(PathBuf::from("backend"))
+    }
+}
+
+pub fn python_command() -> String {
+    "python".to_string()
+}
+
+// PATH with the usual ffmpeg install locations (Windows) prepended, including Lite cache path
+pub fn path_with_ffmpeg() -> String {
+    let current_path = env::var("PATH").unwrap_or_default();
+    let mut ffmpeg_paths: Vec<String> = vec![
+        "C:\\ffmpeg\\bin".to_string(),
+        "C:\\Program Files\\FFmpeg\\bin".to_string(),
+        "C:\\Program Files (x86)\\FFmpeg\\bin".to_string(),
+    ];
+    if let Ok(local_appdata) = env::var("LOCALAPPDATA") {
+        ffmpeg_paths.push(format!("{}\\\\WebWhisper\\\\bin", local_appdata));
+    }
+
+    let mut new_path = current_path;
+    for ffmpeg_path in ffmpeg_paths {
+        if !new_path.contains(&ffmpeg_path) {
+            new_path = format!("{};{}", ffmpeg_path, new_path);
+        }
+    }
+    new_path
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamLine {
+    Segment(Segment),
+    Result(Box<Transcript>),
+    Stats(EngineStats),
+}
+
+// Run transcribe_simple.py in jsonl mode, reporting each segment as soon as it is decoded.
+// `on_start` receives the engine PID so callers can cancel the job.
+pub fn transcribe_file_streaming(
+    file_path: &str,
+    options: &TranscribeOptions,
+    model: &crate::gpu::ModelPlan,
+    on_start: impl FnOnce(u32),
+    mut on_segment: impl FnMut(&Segment),
+) -> Result<Transcript, AppError> {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::Stdio;
+
+    let backend_dir = find_backend_dir("transcribe_simple.py")?;
+    let transcribe_script = backend_dir.join("transcribe_simple.py");
+    if !transcribe_script.exists() {
+        return Err(AppError::backend_unavailable(format!("Transcription script not found: {:?}", transcribe_script)));
+    }
+
+    info!("Transcribing file (streaming): {}", file_path);
+
+    let mut command = Command::new(python_command());
+    command
+        .args([
+            transcribe_script.to_str().unwrap(),
+            file_path,
+            "--language", &options.language,
+            "--format", "jsonl",
+            "--model", &model.model,
+            "--compute-type", &model.compute_type,
+        ])
+        .current_dir(&backend_dir)
+        .env("PATH", path_with_ffmpeg())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    use_app_dirs(&mut command);
+    if options.word_timestamps {
+        command.arg("--word-timestamps");
+    }
+    if let Some(prompt) = options.prompt.as_deref().filter(|prompt| !prompt.trim().is_empty()) {
+        command.args(["--prompt", prompt]);
+    }
+    if let Some(threads) = crate::power::thread_limit() {
+        command.args(["--threads", &threads.to_string()]).env("OMP_NUM_THREADS", threads.to_string());
+    }
+    // The first run of a model downloads it from Hugging Face
+    crate::proxy::configure_command(&mut command)?;
+    let mut child = command.spawn().map_err(|e| AppError::backend_unavailable(format!("Failed to execute transcription: {}", e)))?;
+    on_start(child.id());
+
+    // Drain stderr on a separate thread so a chatty engine cannot block on a full pipe
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buffer = String::new();
+            let _ = stderr.read_to_string(&mut buffer);
+            buffer
+        })
+    });
+
+    let mut transcript = None;
+    let mut stats = None;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            // Engine banners and other non-JSON output are ignored
+            match serde_json::from_str::<StreamLine>(&line) {
+                Ok(StreamLine::Segment(segment)) => on_segment(&segment),
+                Ok(StreamLine::Result(result)) => transcript = Some(*result),
+                Ok(StreamLine::Stats(reported)) => stats = Some(reported),
+                Err(_) => {}
+            }
+        }
+    }
+
+    let status = child.wait().map_err(|e| format!("Failed to wait for transcription: {}", e))?;
+    let stderr = stderr_reader.and_then(|handle| handle.join().ok()).unwrap_or_default();
+    if !status.success() {
+        return Err(AppError::engine(format!("Transcription failed: {}", stderr)));
+    }
+
+    let mut transcript = transcript.ok_or(AppError::engine("Transcription finished without a result"))?;
+    transcript.stats = stats;
+    Ok(transcript)
+}
+
+// Quick pass over the first 30 seconds with the small detection model; None when the
+// engine could not tell. `on_start` receives the PID so the job can still be cancelled.
+pub fn detect_language(file_path: &str, on_start: impl FnOnce(u32)) -> Result<Option<String>, AppError> {
+    let backend_dir = find_backend_dir("transcribe_simple.py")?;
+    let mut command = Command::new(python_command());
+    command
+        .args([
+            backend_dir.join("transcribe_simple.py").to_str().unwrap(),
+            file_path,
+            "--detect-language",
+            "--model",
+            &crate::languages::language_config().detect_model,
+        ])
+        .current_dir(&backend_dir)
+        .env("PATH", path_with_ffmpeg())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    use_app_dirs(&mut command);
+    crate::proxy::configure_command(&mut command)?;
+    let child = command.spawn().map_err(|e| AppError::backend_unavailable(format!("Failed to execute language detection: {}", e)))?;
+    on_start(child.id());
+    let output = child.wait_with_output().map_err(|e| format!("Failed to wait for language detection: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Language detection failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    #[derive(Deserialize)]
+    struct Detected {
+        language: Option<String>,
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Detected>(line).ok())
+        .next_back()
+        .and_then(|detected| detected.language))
+}
+
+// One file's result from detect_languages
+#[derive(Debug, Deserialize, Clone)]
+pub struct DetectedLanguage {
+    pub file_path: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    // Model confidence from 0 to 1, where the engine reports one
+    #[serde(default)]
+    pub probability: Option<f64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// Detect the language of many files in one backend run, loading the detection model once.
+// `on_result` gets each file as it is done; a file that fails does not stop the rest.
+pub fn detect_languages(file_paths: &[String], mut on_result: impl FnMut(DetectedLanguage)) -> Result<(), AppError> {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::process::Stdio;
+
+    let backend_dir = find_backend_dir("transcribe_simple.py")?;
+    let mut command = Command::new(python_command());
+    command
+        .args([
+            backend_dir.join("transcribe_simple.py").to_str().unwrap(),
+            "-",
+            "--detect-language",
+            "--model",
+            &crate::languages::language_config().detect_model,
+        ])
+        .current_dir(&backend_dir)
+        .env("PATH", path_with_ffmpeg())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    use_app_dirs(&mut command);
+    crate::proxy::configure_command(&mut command)?;
+    let mut child = command.spawn().map_err(|e| AppError::backend_unavailable(format!("Failed to execute language detection: {}", e)))?;
+
+    // Paths go in one per line from another thread, so a long list cannot fill both pipes
+    let paths: Vec<String> = file_paths.iter().filter(|path| !path.contains('\n')).cloned().collect();
+    let writer = child.stdin.take().map(|mut stdin| {
+        std::thread::spawn(move || {
+            for path in paths {
+                if writeln!(stdin, "{}", path).is_err() {
+                    break;
+                }
+            }
+        })
+    });
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buffer = String::new();
+            let _ = stderr.read_to_string(&mut buffer);
+            buffer
+        })
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Ok(detected) = serde_json::from_str::<DetectedLanguage>(&line) {
+                on_result(detected);
+            }
+        }
+    }
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+    let status = child.wait().map_err(|e| format!("Failed to wait for language detection: {}", e))?;
+    let stderr = stderr_reader.and_then(|handle| handle.join().ok()).unwrap_or_default();
+    if !status.success() {
+        return Err(format!("Language detection failed: {}", stderr.trim()).into());
+    }
+    Ok(())
+}
+
+// Where the server UI saves its transcripts
+pub fn backend_output_dir() -> Result<PathBuf, AppError> {
+    Ok(crate::config::data_dir()?.join("outputs"))
+}
+
+// Point the backend's scratch files, saved outputs and model downloads at this user's
+// app directories instead of the shared temp folder and install directory.
+// An HF_HOME set by the user still wins, except in portable mode where models must stay on the stick.
+pub fn use_app_dirs(command: &mut Command) {
+    let temp_dir = crate::temp::dir();
+    if let Err(e) = std::fs::create_dir_all(&temp_dir) {
+        warn!("Failed to create temp directory {:?}: {}", temp_dir, e);
+    }
+    for key in ["TMPDIR", "TEMP", "TMP"] {
+        command.env(key, &temp_dir);
+    }
+    if let Ok(output_dir) = backend_output_dir() {
+        command.env("WEB_WHISPER_OUTPUT_DIR", output_dir);
+    }
+    if env::var_os("HF_HOME").is_none() || crate::config::portable_root().is_some() {
+        if let Ok(cache_dir) = crate::config::cache_dir() {
+            command.env("HF_HOME", cache_dir.join("models"));
+        }
+    }
+}
+
+// Where the backend keeps downloaded models: HF_HOME when the user set it, else the app's cache
+pub fn model_cache_dir() -> Option<PathBuf> {
+    match env::var_os("HF_HOME") {
+        Some(home) if crate::config::portable_root().is_none() => Some(PathBuf::from(home)),
+        _ => crate::config::cache_dir().ok().map(|cache_dir| cache_dir.join("models")),
+    }
+}
+
+pub fn kill_process(pid: u32) -> Result<(), AppError> {
+    let output = if cfg!(target_os = "windows") {
+        Command::new("taskkill").args(["/F", "/PID", &pid.to_string()]).output()
+    } else {
+        Command::new("kill").args(["-9", &pid.to_string()]).output()
+    };
+    output.map(|_| ()).map_err(|e| format!("Failed to kill process: {}", e).into())
+}
+
+// Keeps ids made within one clock tick apart, e.g. a folder queued in a tight loop
+static JOB_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+pub fn new_job_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let sequence = JOB_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    format!("job-{}-{}", nanos, sequence)
+}
+
+// Longest name kept, in bytes; leaves room for the timestamp prefix within the usual 255-byte limit
+const MAX_FILE_NAME_BYTES: usize = 200;
+
+// Names Windows reserves for devices in any directory, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// Reduce an untrusted file name (from the webview or an API client) to a single safe path
+// component: directories are dropped, the name is NFC-normalized, characters Windows rejects
+// become "_", and long names are shortened keeping the extension.
+pub fn sanitize_file_name(file_name: &str) -> Result<String, AppError> {
+    use unicode_normalization::UnicodeNormalization;
+
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .nfc()
+        .map(|c| if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') { '_' } else { c })
+        .collect();
+    // Leading dots would hide the file or leave "." and ".."; Windows drops trailing dots and spaces
+    let mut name = cleaned.trim_start_matches(['.', ' ']).trim_end_matches(['.', ' ']).to_string();
+    if name.is_empty() {
+        return Err(AppError::invalid(format!("Invalid file name '{}'", file_name)));
+    }
+    let stem = name.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem.trim_end())) {
+        name.insert(0, '_');
+    }
+    if name.len() > MAX_FILE_NAME_BYTES {
+        let extension = name.rfind('.').map(|dot| name[dot..].to_string()).filter(|ext| ext.len() <= 16).unwrap_or_default();
+        let mut end = MAX_FILE_NAME_BYTES - extension.len();
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name = format!("{}{}", &name[..end], extension);
+    }
+    Ok(name)
+}
+
+// Unique path in the shared temp directory, creating the directory if needed
+pub fn temp_file_path(file_name: &str) -> Result<PathBuf, AppError> {
+    let file_name = sanitize_file_name(file_name)?;
+    let temp_dir = crate::temp::dir();
+    if !temp_dir.exists() {
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    }
+
+    // Generate unique filename to avoid conflicts
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    Ok(temp_dir.join(format!("{}_{}", timestamp, file_name)))
+}
+
+// Write uploaded bytes into the shared temp directory under a unique name
+pub fn write_temp_file(file_data: &[u8], file_name: &str) -> Result<PathBuf, AppError> {
+    use std::io::Write;
+
+    let temp_file_path = temp_file_path(file_name)?;
+    if let Some(dir) = temp_file_path.parent() {
+        crate::disk::ensure_space(dir, file_data.len() as u64)?;
+    }
+
+    // Write file data to temp location
+    let mut file = std::fs::File::create(&temp_file_path)
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    file.write_all(file_data)
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+    Ok(temp_file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parent_directories_are_dropped() {
+        assert_eq!(sanitize_file_name("../../etc/passwd").unwrap(), "passwd");
+        assert_eq!(sanitize_file_name("..\\..\\evil.mp3").unwrap(), "evil.mp3");
+        assert!(sanitize_file_name("..").is_err());
+        assert!(sanitize_file_name("a/..").is_err());
+    }
+
+    #[test]
+    fn absolute_paths_keep_only_the_file_name() {
+        assert_eq!(sanitize_file_name("/etc/passwd").unwrap(), "passwd");
+        assert_eq!(sanitize_file_name("C:\\Users\\me\\talk.mp3").unwrap(), "talk.mp3");
+        assert_eq!(sanitize_file_name("\\\\server\\share\\talk.mp3").unwrap(), "talk.mp3");
+        // A drive-relative name keeps no drive
+        assert_eq!(sanitize_file_name("C:talk.mp3").unwrap(), "C_talk.mp3");
+    }
+
+    #[test]
+    fn reserved_device_names_are_prefixed() {
+        assert_eq!(sanitize_file_name("CON").unwrap(), "_CON");
+        assert_eq!(sanitize_file_name("con.txt").unwrap(), "_con.txt");
+        assert_eq!(sanitize_file_name("lpt1 .wav").unwrap(), "_lpt1 .wav");
+        assert_eq!(sanitize_file_name("CONSOLE.txt").unwrap(), "CONSOLE.txt");
+    }
+
+    #[test]
+    fn empty_names_are_rejected() {
+        assert!(sanitize_file_name("").is_err());
+        assert!(sanitize_file_name("  . ").is_err());
+        assert!(sanitize_file_name("dir/").is_err());
+    }
+
+    #[test]
+    fn long_names_are_shortened_keeping_the_extension() {
+        let name = sanitize_file_name(&format!("{}.mp3", "a".repeat(300))).unwrap();
+        assert_eq!(name.len(), MAX_FILE_NAME_BYTES);
+        assert!(name.ends_with(".mp3"));
+
+        // Multi-byte characters are not cut in half
+        let name = sanitize_file_name(&format!("{}.wav", "録".repeat(100))).unwrap();
+        assert!(name.len() <= MAX_FILE_NAME_BYTES);
+        assert!(name.ends_with(".wav"));
+    }
+
+    #[test]
+    fn characters_windows_rejects_become_underscores() {
+        assert_eq!(sanitize_file_name("what?<now>|\"*.mp3").unwrap(), "what__now____.mp3");
+        assert_eq!(sanitize_file_name("tab\there.mp3").unwrap(), "tab_here.mp3");
+    }
+}
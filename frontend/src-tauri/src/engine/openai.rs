@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{Segment, TranscribeOptions, Transcript, TranscriptionEngine};
+use crate::error::AppError;
+use crate::secrets;
+
+pub const API_KEY: &str = "openai-api-key";
+
+// The transcription endpoint rejects uploads above 25 MB
+pub const MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OpenAiConfig {
+    // Any OpenAI-compatible server, e.g. a proxy or self-hosted gateway
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    #[serde(default = "default_model")]
+    pub model: String,
+    // USD per audio minute, used for the pre-submission estimate
+    #[serde(default = "default_price_per_minute")]
+    pub price_per_minute: f64,
+    // Jobs estimated above this amount are refused instead of uploaded
+    #[serde(default)]
+    pub max_cost_per_job: Option<f64>,
+}
+
+fn default_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_price_per_minute() -> f64 {
+    0.006
+}
+
+impl Default for OpenAiConfig {
+    fn default() -> Self {
+        OpenAiConfig {
+            base_url: default_base_url(),
+            model: default_model(),
+            price_per_minute: default_price_per_minute(),
+            max_cost_per_job: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CostEstimate {
+    pub size_bytes: u64,
+    pub duration_seconds: Option<f64>,
+    pub within_size_limit: bool,
+    // None when ffprobe could not determine the duration
+    pub estimated_cost_usd: Option<f64>,
+}
+
+pub fn estimate(config: &OpenAiConfig, file_path: &str) -> Result<CostEstimate, AppError> {
+    let size_bytes = std::fs::metadata(file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?
+        .len();
+    let duration_seconds = super::probe_duration(file_path);
+    Ok(CostEstimate {
+        size_bytes,
+        duration_seconds,
+        within_size_limit: size_bytes <= MAX_UPLOAD_BYTES,
+        estimated_cost_usd: duration_seconds.map(|seconds| seconds / 60.0 * config.price_per_minute),
+    })
+}
+
+#[derive(Deserialize)]
+struct VerboseResponse {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    segments: Vec<Segment>,
+}
+
+pub struct OpenAiEngine {
+    pub config: OpenAiConfig,
+}
+
+impl TranscriptionEngine for OpenAiEngine {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn transcribe(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+        _on_start: &mut dyn FnMut(u32),
+        on_segment: &mut dyn FnMut(&Segment),
+    ) -> Result<Transcript, AppError> {
+        let api_key = secrets::get(API_KEY)?.ok_or(AppError::not_configured("OpenAI API key is not configured"))?;
+
+        // Check limits before uploading anything
+        let estimate = estimate(&self.config, file_path)?;
+        if !estimate.within_size_limit {
+            return Err(AppError::limit_exceeded(format!(
+                "File is {:.1} MB; the OpenAI API accepts at most {} MB",
+                estimate.size_bytes as f64 / (1024.0 * 1024.0),
+                MAX_UPLOAD_BYTES / (1024 * 1024)
+            )));
+        }
+        if let Some(limit) = self.config.max_cost_per_job {
+            match estimate.estimated_cost_usd {
+                Some(cost) if cost > limit => {
+                    return Err(AppError::limit_exceeded(format!("Estimated cost ${:.3} exceeds the per-job limit of ${:.3}", cost, limit)));
+                }
+                None => return Err("Could not determine the audio duration to check the cost limit".into()),
+                _ => {}
+            }
+        }
+
+        let mut form = reqwest::blocking::multipart::Form::new()
+            .text("model", self.config.model.clone())
+            .text("response_format", "verbose_json")
+            .file("file", file_path)
+            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+        if options.language != "auto" {
+            form = form.text("language", options.language.clone());
+        }
+
+        info!("Uploading {} to {} ({} bytes)", file_path, self.config.base_url, estimate.size_bytes);
+        let response = crate::proxy::blocking_client()?
+            .post(format!("{}/audio/transcriptions", self.config.base_url.trim_end_matches('/')))
+            .bearer_auth(api_key)
+            .multipart(form)
+            .send()
+            .map_err(|e| AppError::upstream(format!("OpenAI request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().unwrap_or_default();
+            return Err(AppError::upstream(format!("OpenAI request failed: HTTP {} {}", status, detail.trim())));
+        }
+        let body: VerboseResponse = response.json().map_err(|e| AppError::invalid(format!("Invalid OpenAI response: {}", e)))?;
+
+        for segment in &body.segments {
+            on_segment(segment);
+        }
+        Ok(Transcript {
+            text: body.text.trim().to_string(),
+            language: body.language,
+            duration: body.duration.or(estimate.duration_seconds),
+            segments: body.segments,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,167 @@
+use serde::Serialize;
+
+use crate::fingerprint::HistoryMatch;
+use crate::jobs::JobStatus;
+
+// Error returned by every Tauri command. Serialized as {"code": "...", "message": "...", ...context}
+// so the frontend can branch on `code` and still show `message` as-is.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum AppError {
+    // The Python backend or its server is not running or could not be launched
+    BackendUnavailable { message: String },
+    NotFound {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
+    InvalidInput {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        field: Option<String>,
+    },
+    // A setting, credential or integration needs to be set up first
+    NotConfigured { message: String },
+    // An experimental subsystem whose feature flag (flags.rs) is off
+    FeatureDisabled { message: String, flag: String },
+    Cancelled { message: String },
+    // The same content was already submitted; `job_id` holds its result once `status` is completed.
+    // `similarity` is set when the recording only sounds like a history entry (fingerprint.rs).
+    Duplicate {
+        message: String,
+        job_id: String,
+        status: JobStatus,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        similarity: Option<f64>,
+    },
+    // Size, cost or rate limits
+    LimitExceeded { message: String },
+    InsufficientDiskSpace {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
+    // A remote service (cloud engine, upload target, feed) failed or rejected the request
+    Upstream { message: String },
+    // The transcription engine ran but failed
+    Engine { message: String },
+    Internal { message: String },
+}
+
+impl AppError {
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::BackendUnavailable { message }
+            | AppError::NotFound { message, .. }
+            | AppError::InvalidInput { message, .. }
+            | AppError::NotConfigured { message }
+            | AppError::FeatureDisabled { message, .. }
+            | AppError::Cancelled { message }
+            | AppError::Duplicate { message, .. }
+            | AppError::LimitExceeded { message }
+            | AppError::InsufficientDiskSpace { message, .. }
+            | AppError::Upstream { message }
+            | AppError::Engine { message }
+            | AppError::Internal { message } => message,
+        }
+    }
+
+    pub fn not_found(path: &str) -> Self {
+        AppError::NotFound { message: format!("File not found: {}", path), path: Some(path.to_string()) }
+    }
+
+    // Not found without a file path, e.g. an unknown job or project id
+    pub fn unknown(message: impl Into<String>) -> Self {
+        AppError::NotFound { message: message.into(), path: None }
+    }
+
+    pub fn invalid_input(field: &str, message: impl Into<String>) -> Self {
+        AppError::InvalidInput { message: message.into(), field: Some(field.to_string()) }
+    }
+
+    // Invalid input not tied to one field, e.g. a malformed file
+    pub fn invalid(message: impl Into<String>) -> Self {
+        AppError::InvalidInput { message: message.into(), field: None }
+    }
+
+    pub fn backend_unavailable(message: impl Into<String>) -> Self {
+        AppError::BackendUnavailable { message: message.into() }
+    }
+
+    pub fn not_configured(message: impl Into<String>) -> Self {
+        AppError::NotConfigured { message: message.into() }
+    }
+
+    pub fn feature_disabled(flag: &str, message: impl Into<String>) -> Self {
+        AppError::FeatureDisabled { message: message.into(), flag: flag.to_string() }
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        AppError::Cancelled { message: message.into() }
+    }
+
+    pub fn limit_exceeded(message: impl Into<String>) -> Self {
+        AppError::LimitExceeded { message: message.into() }
+    }
+
+    pub fn insufficient_disk_space(path: &str, message: impl Into<String>) -> Self {
+        AppError::InsufficientDiskSpace { message: message.into(), path: Some(path.to_string()) }
+    }
+
+    pub fn upstream(message: impl Into<String>) -> Self {
+        AppError::Upstream { message: message.into() }
+    }
+
+    pub fn engine(message: impl Into<String>) -> Self {
+        AppError::Engine { message: message.into() }
+    }
+
+    pub fn duplicate(job_id: &str, status: JobStatus) -> Self {
+        let message = if status == JobStatus::Completed {
+            format!("This file was already transcribed as job {}", job_id)
+        } else {
+            format!("This file is already queued as job {}", job_id)
+        };
+        AppError::Duplicate { message, job_id: job_id.to_string(), status, similarity: None }
+    }
+
+    pub fn similar_recording(found: &HistoryMatch) -> Self {
+        let message = format!(
+            "This recording matches \"{}\" in history ({:.0}% similar), already transcribed as job {}",
+            found.title,
+            found.similarity * 100.0,
+            found.job_id
+        );
+        AppError::Duplicate {
+            message,
+            job_id: found.job_id.clone(),
+            status: JobStatus::Completed,
+            similarity: Some(found.similarity),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        AppError::Internal { message: message.into() }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+// Errors from std, serde and other crates arrive as formatted strings. They carry no
+// code of their own, so they are internal; anything the frontend branches on is built
+// with one of the constructors above where it happens.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
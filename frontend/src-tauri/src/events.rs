@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use tracing::warn;
+
+use crate::engine::Segment;
+use crate::queue::{Priority, WorkerClass};
+
+// Set once in setup; jobs run before that (none do today) would emit nothing
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+// For code outside commands that needs managed state, e.g. the backend server's port
+pub fn app_handle() -> Option<&'static AppHandle> {
+    APP.get()
+}
+
+// Job lifecycle for the frontend's jobs panel. Each variant is its own Tauri event
+// (see `name`), with the fields as the payload.
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum JobEvent {
+    // Registered and waiting for a worker slot
+    Added {
+        job_id: String,
+        file_path: String,
+        title: Option<String>,
+        engine: String,
+        priority: Priority,
+    },
+    // Left the queue and started on an engine
+    Started {
+        job_id: String,
+        engine: &'static str,
+        class: WorkerClass,
+    },
+    // Sent with each segment; `percent` is None when the media duration is unknown
+    Progress {
+        job_id: String,
+        processed_seconds: f64,
+        duration_seconds: Option<f64>,
+        percent: Option<f64>,
+    },
+    Segment {
+        job_id: String,
+        segment: Segment,
+    },
+    Completed {
+        job_id: String,
+        text: String,
+        language: Option<String>,
+    },
+    Failed {
+        job_id: String,
+        error: String,
+        cancelled: bool,
+    },
+    // A watched keyword (see keywords.rs) appeared in a finished segment
+    KeywordHit {
+        job_id: String,
+        keyword: String,
+        start: f64,
+        end: f64,
+        text: String,
+    },
+    // The last queued or running job finished
+    QueueDrained {},
+}
+
+impl JobEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobEvent::Added { .. } => "job-added",
+            JobEvent::Started { .. } => "job-started",
+            JobEvent::Progress { .. } => "job-progress",
+            JobEvent::Segment { .. } => "job-segment",
+            JobEvent::Completed { .. } => "job-completed",
+            JobEvent::Failed { .. } => "job-failed",
+            JobEvent::KeywordHit { .. } => "keyword-hit",
+            JobEvent::QueueDrained {} => "queue-drained",
+        }
+    }
+}
+
+pub fn emit(event: JobEvent) {
+    emit_named(event.name(), &event);
+}
+
+// Events outside the job lifecycle, e.g. from the meeting session manager
+pub fn emit_named<T: Serialize>(name: &str, payload: &T) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit(name, payload);
+    }
+}
+
+// System notification; skipped like events when there is no app handle
+pub fn notify(title: &str, body: &str) {
+    if let Some(app) = APP.get() {
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            warn!("Failed to show notification: {}", e);
+        }
+    }
+}
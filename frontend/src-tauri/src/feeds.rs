@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::stream::StreamHub;
+use crate::{config, history};
+
+// Guards feeds.json and keeps two polls from downloading the same episode
+static FEEDS_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FeedSubscription {
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    // Episode GUIDs (or enclosure URLs) already transcribed or skipped
+    #[serde(default)]
+    pub seen: BTreeSet<String>,
+}
+
+fn feeds_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("feeds.json"))
+}
+
+fn read_file() -> Result<Vec<FeedSubscription>, AppError> {
+    let _guard = STATE_LOCK.lock().unwrap();
+    let path = feeds_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read feeds: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse feeds: {}", e).into())
+}
+
+fn write_file(feeds: &[FeedSubscription]) -> Result<(), AppError> {
+    let _guard = STATE_LOCK.lock().unwrap();
+    let path = feeds_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(feeds).map_err(|e| format!("Failed to serialize feeds: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write feeds: {}", e).into())
+}
+
+// The file is read and written off the async runtime
+async fn load() -> Result<Vec<FeedSubscription>, AppError> {
+    tokio::task::spawn_blocking(read_file).await.map_err(|e| format!("Feeds task failed: {}", e))?
+}
+
+async fn save(feeds: Vec<FeedSubscription>) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || write_file(&feeds)).await.map_err(|e| format!("Feeds task failed: {}", e))?
+}
+
+pub fn list() -> Result<Vec<FeedSubscription>, AppError> {
+    read_file()
+}
+
+struct Episode {
+    key: String,
+    title: String,
+    audio_url: String,
+    published: Option<String>,
+}
+
+async fn fetch_channel(url: &str) -> Result<rss::Channel, AppError> {
+    let bytes = crate::proxy::client()?
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch feed {}: {}", url, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read feed {}: {}", url, e))?;
+    rss::Channel::read_from(&bytes[..]).map_err(|e| AppError::invalid(format!("Invalid RSS feed {}: {}", url, e)))
+}
+
+// Items are newest-first in practically every podcast feed
+fn episodes(channel: &rss::Channel) -> Vec<Episode> {
+    channel
+        .items()
+        .iter()
+        .filter_map(|item| {
+            let audio_url = item.enclosure()?.url().to_string();
+            let key = item.guid().map(|guid| guid.value().to_string()).unwrap_or_else(|| audio_url.clone());
+            Some(Episode {
+                key,
+                title: item.title().unwrap_or("Untitled episode").to_string(),
+                audio_url,
+                published: item.pub_date().map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+// Subscribe and mark the back catalog as seen, except the latest `backfill` episodes
+pub async fn subscribe(url: &str, language: Option<String>, backfill: usize) -> Result<FeedSubscription, AppError> {
+    let channel = fetch_channel(url).await?;
+    let _guard = FEEDS_LOCK.lock().await;
+    let mut feeds = load().await?;
+    if feeds.iter().any(|feed| feed.url == url) {
+        return Err(format!("Already subscribed to {}", url).into());
+    }
+    let subscription = FeedSubscription {
+        url: url.to_string(),
+        title: channel.title().to_string(),
+        language,
+        seen: episodes(&channel).into_iter().skip(backfill).map(|episode| episode.key).collect(),
+    };
+    feeds.push(subscription.clone());
+    save(feeds).await?;
+    Ok(subscription)
+}
+
+pub async fn unsubscribe(url: &str) -> Result<(), AppError> {
+    let _guard = FEEDS_LOCK.lock().await;
+    let mut feeds = load().await?;
+    feeds.retain(|feed| feed.url != url);
+    save(feeds).await
+}
+
+async fn download_episode(audio_url: &str) -> Result<PathBuf, AppError> {
+    let extension = reqwest::Url::parse(audio_url)
+        .ok()
+        .and_then(|url| {
+            std::path::Path::new(url.path())
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "mp3".to_string());
+    let mut response = crate::proxy::client()?
+        .get(audio_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download episode {}: {}", audio_url, e))?;
+
+    let download_dir = crate::temp::dir().join("downloads");
+    tokio::fs::create_dir_all(&download_dir)
+        .await
+        .map_err(|e| format!("Failed to create download directory: {}", e))?;
+    crate::disk::ensure_space(&download_dir, response.content_length().unwrap_or(0))?;
+    let path = download_dir.join(format!("{}_episode.{}", engine::new_job_id(), extension));
+
+    use tokio::io::AsyncWriteExt;
+    let mut file = tokio::io::BufWriter::new(
+        tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| format!("Failed to create episode file: {}", e))?,
+    );
+    let limit = config::max_upload_bytes();
+    let mut written = 0u64;
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Episode download interrupted: {}", e))? {
+        written += chunk.len() as u64;
+        if let Err(e) = config::check_upload_size(written, limit) {
+            drop(file);
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(e);
+        }
+        file.write_all(&chunk).await.map_err(|e| format!("Failed to write episode file: {}", e))?;
+    }
+    file.flush().await.map_err(|e| format!("Failed to write episode file: {}", e))?;
+    Ok(path)
+}
+
+async fn transcribe_episode(
+    hub: &StreamHub,
+    registry: &JobRegistry,
+    feed: &FeedSubscription,
+    episode: &Episode,
+) -> Result<(), AppError> {
+    let path = download_episode(&episode.audio_url).await?;
+    let job_id = engine::new_job_id();
+    let options = TranscribeOptions {
+        language: feed.language.clone().unwrap_or_else(|| "auto".to_string()),
+        ..Default::default()
+    };
+
+    let (hub, registry, id, file_path) = (hub.clone(), registry.clone(), job_id.clone(), path.to_string_lossy().to_string());
+    let result = tokio::task::spawn_blocking(move || jobs::run_job(&registry, &hub, &id, &file_path, &options))
+        .await
+        .map_err(|e| format!("Transcription task failed: {}", e))?;
+    let _ = std::fs::remove_file(&path);
+    let transcript = result?;
+
+    let mut tags = BTreeMap::new();
+    tags.insert("show".to_string(), feed.title.clone());
+    tags.insert("episode".to_string(), episode.title.clone());
+    tags.insert("feed_url".to_string(), feed.url.clone());
+    tags.insert("guid".to_string(), episode.key.clone());
+    if let Some(published) = &episode.published {
+        tags.insert("published".to_string(), published.clone());
+    }
+    let (title, audio_url) = (episode.title.clone(), episode.audio_url.clone());
+    tokio::task::spawn_blocking(move || history::add(&job_id, &title, &audio_url, &transcript, tags))
+        .await
+        .map_err(|e| format!("History task failed: {}", e))??;
+    Ok(())
+}
+
+// Check every subscription once and transcribe unseen episodes, oldest first.
+// Returns how many episodes were transcribed.
+pub async fn poll(hub: &StreamHub, registry: &JobRegistry) -> Result<usize, AppError> {
+    let _guard = FEEDS_LOCK.lock().await;
+    let mut transcribed = 0;
+
+    for feed in load().await? {
+        let channel = match fetch_channel(&feed.url).await {
+            Ok(channel) => channel,
+            Err(e) => {
+                warn!("{}", e);
+                continue;
+            }
+        };
+        let mut new_episodes: Vec<Episode> = episodes(&channel)
+            .into_iter()
+            .filter(|episode| !feed.seen.contains(&episode.key))
+            .collect();
+        new_episodes.reverse();
+
+        for episode in new_episodes {
+            info!("New episode of {}: {}", feed.title, episode.title);
+            match transcribe_episode(hub, registry, &feed, &episode).await {
+                Ok(()) => transcribed += 1,
+                // Leave it unseen so the next poll retries
+                Err(e) => {
+                    warn!("Failed to transcribe episode {}: {}", episode.title, e);
+                    continue;
+                }
+            }
+            // Persist progress after each episode so a crash does not redo finished work
+            let mut feeds = load().await?;
+            if let Some(stored) = feeds.iter_mut().find(|stored| stored.url == feed.url) {
+                stored.seen.insert(episode.key.clone());
+            }
+            save(feeds).await?;
+        }
+    }
+    Ok(transcribed)
+}
+
+pub fn start_polling(hub: StreamHub, registry: JobRegistry) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = poll(&hub, &registry).await {
+                warn!("Feed poll failed: {}", e);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
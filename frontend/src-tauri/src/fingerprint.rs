@@ -0,0 +1,337 @@
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::error::AppError;
+use crate::history::{self, HistoryEntry};
+use crate::{config, engine};
+
+// Serializes read-modify-write cycles on fingerprints.json
+static FINGERPRINTS_LOCK: Mutex<()> = Mutex::new(());
+
+const SAMPLE_RATE: usize = 8000;
+// Only the start of a recording is fingerprinted; long enough to tell takes apart
+const MAX_SECONDS: &str = "120";
+const FRAME: usize = 1024;
+// 64 ms between sub-fingerprints
+const HOP: usize = 512;
+// 33 bands give the 32 bits of a sub-fingerprint; speech energy sits in this range
+const BANDS: usize = 33;
+const LOW_HZ: f64 = 300.0;
+const HIGH_HZ: f64 = 2000.0;
+// Shorter fingerprints are too easy to match by chance (about 6 seconds)
+const MIN_FRAMES: usize = 96;
+// How far a copy may be shifted against the original, e.g. by a trimmed intro (about 5 s)
+const MAX_SHIFT: i64 = 78;
+// Share of matching bits; unrelated audio lands near 0.5
+const MIN_SIMILARITY: f64 = 0.65;
+// Fingerprints of jobs that never made it into history are dropped after this
+const STALE_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredFingerprint {
+    job_id: String,
+    #[serde(default)]
+    duration: Option<f64>,
+    // RFC 3339
+    created_at: String,
+    // Sub-fingerprints as little-endian u32s, base64
+    fingerprint: String,
+}
+
+// A history entry that sounds like the file being imported
+#[derive(Debug, Serialize, Clone)]
+pub struct HistoryMatch {
+    pub job_id: String,
+    pub title: String,
+    // Share of fingerprint bits that agree, from about 0.5 (unrelated) to 1
+    pub similarity: f64,
+}
+
+impl HistoryMatch {
+    // Why an import skips the file, e.g. "Sounds like "Weekly sync" in history (92% similar, job …)"
+    pub fn describe(&self) -> String {
+        format!("Sounds like \"{}\" in history ({:.0}% similar, job {})", self.title, self.similarity * 100.0, self.job_id)
+    }
+}
+
+fn fingerprints_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("fingerprints.json"))
+}
+
+fn read_stored() -> Vec<StoredFingerprint> {
+    let contents = fingerprints_path().ok().and_then(|path| std::fs::read_to_string(path).ok());
+    contents.and_then(|contents| serde_json::from_str(&contents).ok()).unwrap_or_default()
+}
+
+fn write_stored(stored: &[StoredFingerprint]) -> Result<(), AppError> {
+    let path = fingerprints_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string(stored).map_err(|e| format!("Failed to serialize fingerprints: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write fingerprints: {}", e).into())
+}
+
+fn encode(fingerprint: &[u32]) -> String {
+    let bytes: Vec<u8> = fingerprint.iter().flat_map(|value| value.to_le_bytes()).collect();
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(encoded: &str) -> Vec<u32> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap_or_default();
+    bytes.chunks_exact(4).map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])).collect()
+}
+
+// 8 kHz mono samples from the start of the file
+fn decode_pcm(file_path: &str) -> Result<Vec<f32>, AppError> {
+    let sample_rate = SAMPLE_RATE.to_string();
+    let mut child = Command::new("ffmpeg")
+        .args(["-v", "error", "-t", MAX_SECONDS, "-i", file_path, "-ac", "1", "-ar", &sample_rate, "-f", "s16le", "-"])
+        .env("PATH", engine::path_with_ffmpeg())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    let mut bytes = Vec::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_end(&mut bytes).map_err(|e| format!("Failed to read decoded audio: {}", e))?;
+    }
+    let status = child.wait().map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+    if !status.success() {
+        return Err(format!("ffmpeg could not decode {}", file_path).into());
+    }
+    Ok(bytes.chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0).collect())
+}
+
+// In-place radix-2 FFT; `re` and `im` have a power-of-two length
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut length = 2;
+    while length <= n {
+        let angle = -2.0 * std::f32::consts::PI / length as f32;
+        for start in (0..n).step_by(length) {
+            for k in 0..length / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + length / 2);
+                let (t_re, t_im) = (re[b] * cos - im[b] * sin, re[b] * sin + im[b] * cos);
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        length <<= 1;
+    }
+}
+
+// Energy per band for each frame
+fn band_energies(samples: &[f32]) -> Vec<[f32; BANDS]> {
+    let window: Vec<f32> =
+        (0..FRAME).map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME as f32).cos()).collect();
+    // Bin edges of log-spaced bands, so each band covers a similar musical interval
+    let hz_per_bin = SAMPLE_RATE as f64 / FRAME as f64;
+    let edges: Vec<usize> = (0..=BANDS)
+        .map(|band| (LOW_HZ * (HIGH_HZ / LOW_HZ).powf(band as f64 / BANDS as f64) / hz_per_bin).round() as usize)
+        .collect();
+
+    let mut frames = Vec::new();
+    let mut start = 0;
+    while start + FRAME <= samples.len() {
+        let mut re: Vec<f32> = samples[start..start + FRAME].iter().zip(&window).map(|(sample, w)| sample * w).collect();
+        let mut im = vec![0.0; FRAME];
+        fft(&mut re, &mut im);
+        let mut energies = [0.0; BANDS];
+        for (band, energy) in energies.iter_mut().enumerate() {
+            let bins = edges[band]..edges[band + 1].max(edges[band] + 1);
+            *energy = bins.map(|bin| re[bin] * re[bin] + im[bin] * im[bin]).sum();
+        }
+        frames.push(energies);
+        start += HOP;
+    }
+    frames
+}
+
+// One 32-bit sub-fingerprint per frame: whether the energy difference between neighbouring
+// bands grew or shrank since the previous frame. It survives re-encoding and volume changes.
+fn sub_fingerprints(frames: &[[f32; BANDS]]) -> Vec<u32> {
+    frames
+        .windows(2)
+        .map(|pair| {
+            (0..BANDS - 1).fold(0u32, |bits, band| {
+                let now = pair[1][band] - pair[1][band + 1];
+                let before = pair[0][band] - pair[0][band + 1];
+                bits | (((now - before) > 0.0) as u32) << band
+            })
+        })
+        .collect()
+}
+
+// Fingerprint of the first two minutes of a media file. Blocks while ffmpeg decodes.
+pub fn compute(file_path: &str) -> Result<Vec<u32>, AppError> {
+    let fingerprint = sub_fingerprints(&band_energies(&decode_pcm(file_path)?));
+    if fingerprint.len() < MIN_FRAMES {
+        return Err(AppError::invalid(format!("{} is too short to fingerprint", file_path)));
+    }
+    // Digital silence gives all-zero sub-fingerprints, which would match any other silence
+    if fingerprint.iter().filter(|value| **value == 0).count() * 2 > fingerprint.len() {
+        return Err(AppError::invalid(format!("{} is mostly silence", file_path)));
+    }
+    Ok(fingerprint)
+}
+
+// Best share of agreeing bits over small shifts of one fingerprint against the other
+fn similarity(a: &[u32], b: &[u32]) -> f64 {
+    let min_overlap = (a.len().min(b.len()) / 2).max(MIN_FRAMES);
+    let mut best: f64 = 0.0;
+    for shift in -MAX_SHIFT..=MAX_SHIFT {
+        let skip = shift.unsigned_abs() as usize;
+        if skip >= a.len() || skip >= b.len() {
+            continue;
+        }
+        let (a, b) = if shift >= 0 { (&a[skip..], b) } else { (a, &b[skip..]) };
+        let overlap = a.len().min(b.len());
+        if overlap < min_overlap {
+            continue;
+        }
+        let differing: u32 = a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum();
+        best = best.max(1.0 - differing as f64 / (overlap * 32) as f64);
+    }
+    best
+}
+
+// Copies of one recording differ in length by little more than a trimmed intro or outro
+fn similar_length(a: Option<f64>, b: Option<f64>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() <= (a.max(b) * 0.1).max(10.0),
+        _ => true,
+    }
+}
+
+// The history entry that sounds most like `fingerprint`, if any is close enough
+pub fn find_match(fingerprint: &[u32], duration: Option<f64>) -> Result<Option<HistoryMatch>, AppError> {
+    let stored = {
+        let _guard = FINGERPRINTS_LOCK.lock().unwrap();
+        read_stored()
+    };
+    if stored.is_empty() {
+        return Ok(None);
+    }
+    let entries: BTreeMap<String, HistoryEntry> =
+        history::list()?.into_iter().map(|entry| (entry.job_id.clone(), entry)).collect();
+    let best = stored
+        .iter()
+        .filter(|stored| similar_length(stored.duration, duration))
+        .filter_map(|stored| entries.get(&stored.job_id).map(|entry| (entry, similarity(fingerprint, &decode(&stored.fingerprint)))))
+        .filter(|(_, similarity)| *similarity >= MIN_SIMILARITY)
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    Ok(best.map(|(entry, similarity)| HistoryMatch { job_id: entry.job_id.clone(), title: entry.title.clone(), similarity }))
+}
+
+// Keep the fingerprint for `job_id` so later imports can match it once it is in history.
+// Fingerprints of jobs that never reached history are dropped after a week.
+pub fn remember(job_id: &str, fingerprint: &[u32], duration: Option<f64>) -> Result<(), AppError> {
+    let in_history: HashSet<String> = history::list()?.into_iter().map(|entry| entry.job_id).collect();
+    let now = chrono::Local::now();
+    let _guard = FINGERPRINTS_LOCK.lock().unwrap();
+    let mut stored = read_stored();
+    stored.retain(|stored| {
+        stored.job_id != job_id
+            && (in_history.contains(&stored.job_id)
+                || chrono::DateTime::parse_from_rfc3339(&stored.created_at)
+                    .is_ok_and(|created| now.signed_duration_since(created).num_days() < STALE_DAYS))
+    });
+    stored.push(StoredFingerprint {
+        job_id: job_id.to_string(),
+        duration,
+        created_at: now.to_rfc3339(),
+        fingerprint: encode(fingerprint),
+    });
+    write_stored(&stored)
+}
+
+// Fingerprint a file about to be transcribed as `job_id` and look for it in history.
+// Unless `force` is set, a match is returned and nothing is kept; without a `job_id`
+// (a dry run) nothing is kept either. Files that cannot be fingerprinted are let through.
+pub fn screen(file_path: &str, job_id: Option<&str>, force: bool) -> Option<HistoryMatch> {
+    let fingerprint = match compute(file_path) {
+        Ok(fingerprint) => fingerprint,
+        Err(e) => {
+            warn!("Skipping the duplicate check: {}", e);
+            return None;
+        }
+    };
+    let duration = engine::probe_duration(file_path);
+    if !force {
+        match find_match(&fingerprint, duration) {
+            Ok(Some(found)) => {
+                info!("{} matches job {} in history ({:.0}% similar)", file_path, found.job_id, found.similarity * 100.0);
+                return Some(found);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Skipping the duplicate check: {}", e),
+        }
+    }
+    if let Some(Err(e)) = job_id.map(|job_id| remember(job_id, &fingerprint, duration)) {
+        warn!("{}", e);
+    }
+    None
+}
+
+// Add `file_path` to history with the transcript of the matching entry instead of
+// transcribing it again
+pub fn reuse(job_id: &str, file_path: &str, title: Option<String>) -> Result<HistoryEntry, AppError> {
+    let original = history::get(job_id)?;
+    let new_job_id = engine::new_job_id();
+    let title = title.filter(|title| !title.trim().is_empty()).unwrap_or_else(|| {
+        let path = std::path::Path::new(file_path);
+        path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| original.title.clone())
+    });
+    let mut tags = BTreeMap::new();
+    tags.insert("reused_from".to_string(), job_id.to_string());
+    let entry = history::add(&new_job_id, &title, file_path, &original.transcript, tags)?;
+    info!("Reused the transcript of {} for {} as {}", job_id, file_path, new_job_id);
+    Ok(entry)
+}
+
+// Fingerprint history entries whose source file is still on disk and that have none yet,
+// e.g. those added before duplicate detection. Returns how many were added. Blocking.
+pub fn index_history() -> Result<usize, AppError> {
+    let known: HashSet<String> = {
+        let _guard = FINGERPRINTS_LOCK.lock().unwrap();
+        read_stored().into_iter().map(|stored| stored.job_id).collect()
+    };
+    let mut added = 0;
+    for entry in history::list()? {
+        if known.contains(&entry.job_id) || !std::path::Path::new(&entry.source).is_file() {
+            continue;
+        }
+        match compute(&entry.source) {
+            Ok(fingerprint) => {
+                remember(&entry.job_id, &fingerprint, entry.transcript.duration)?;
+                added += 1;
+            }
+            Err(e) => warn!("Not fingerprinting {}: {}", entry.job_id, e),
+        }
+    }
+    info!("Fingerprinted {} history entries", added);
+    Ok(added)
+}
@@ -0,0 +1,161 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::{config, engine};
+use crate::error::AppError;
+
+const SAMPLE_RATE: u32 = 16000;
+const MAX_SECONDS: f64 = 600.0;
+// Length of the clip generated when no sample ships with the backend
+const SAMPLE_CLIP_SECONDS: f64 = 8.0;
+const SAMPLE_EXTENSIONS: &[&str] = &["wav", "flac", "mp3", "m4a", "ogg"];
+
+// F1/F2 pairs of a few vowels (a, e, i, o, u), cycled through syllable by syllable
+const VOWEL_FORMANTS: &[(f64, f64)] = &[(730.0, 1090.0), (530.0, 1840.0), (270.0, 2290.0), (570.0, 840.0), (300.0, 870.0)];
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureKind {
+    // 440 Hz sine
+    Tone,
+    // White noise
+    Noise,
+    // Voiced syllables and pauses at a speaking rhythm; not words, but it exercises
+    // voice activity detection and segmenting like a real voice
+    Speech,
+}
+
+fn tone(count: usize) -> Vec<f64> {
+    (0..count).map(|i| 0.5 * (2.0 * PI * 440.0 * i as f64 / SAMPLE_RATE as f64).sin()).collect()
+}
+
+fn noise(count: usize, rng: &mut StdRng) -> Vec<f64> {
+    (0..count).map(|_| rng.gen_range(-0.3..0.3)).collect()
+}
+
+// Gain of a formant resonance at `frequency`
+fn resonance(frequency: f64, formant: f64, bandwidth: f64) -> f64 {
+    1.0 / (1.0 + ((frequency - formant) / bandwidth).powi(2))
+}
+
+fn speech(count: usize, rng: &mut StdRng) -> Vec<f64> {
+    let rate = SAMPLE_RATE as f64;
+    let mut samples = vec![0.0; count];
+    let mut start = 0;
+    let mut syllable = 0;
+    while start < count {
+        // Words of 2-5 syllables of about a quarter second, then a pause
+        let syllables = rng.gen_range(2..=5);
+        for _ in 0..syllables {
+            let length = (rate * rng.gen_range(0.18..0.32)) as usize;
+            let (f1, f2) = VOWEL_FORMANTS[syllable % VOWEL_FORMANTS.len()];
+            syllable += 1;
+            let pitch = rng.gen_range(105.0..145.0);
+            let harmonics: Vec<f64> = (1..=(3500.0 / pitch) as usize)
+                .map(|k| {
+                    let frequency = k as f64 * pitch;
+                    (resonance(frequency, f1, 90.0) + 0.6 * resonance(frequency, f2, 120.0)) / k as f64
+                })
+                .collect();
+            // A short burst of noise before the vowel, like a consonant
+            let onset = length / 6;
+            for i in 0..length.min(count.saturating_sub(start)) {
+                let t = i as f64 / rate;
+                let envelope = (PI * i as f64 / length as f64).sin().powi(2);
+                // Pitch falls a little across the syllable
+                let phase = 2.0 * PI * pitch * t * (1.0 - 0.05 * i as f64 / length as f64);
+                let voiced: f64 = harmonics.iter().enumerate().map(|(k, gain)| gain * ((k + 1) as f64 * phase).sin()).sum();
+                let fricative = if i < onset { rng.gen_range(-0.15..0.15) } else { 0.0 };
+                samples[start + i] = envelope * voiced * 0.6 + fricative;
+            }
+            start += length;
+        }
+        start += (rate * rng.gen_range(0.15..0.6)) as usize;
+    }
+    // Scale so the loudest sample sits just under full scale
+    let peak = samples.iter().fold(0.0f64, |peak, sample| peak.max(sample.abs()));
+    if peak > 0.0 {
+        samples.iter_mut().for_each(|sample| *sample *= 0.8 / peak);
+    }
+    samples
+}
+
+// 16-bit PCM mono WAV at 16 kHz
+fn write_wav(path: &Path, samples: &[f64]) -> Result<(), AppError> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::with_capacity(44 + samples.len() * 2);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    bytes.extend_from_slice(&2u16.to_le_bytes()); // block align
+    bytes.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&((sample.clamp(-1.0, 1.0) * i16::MAX as f64) as i16).to_le_bytes());
+    }
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write {:?}: {}", path, e).into())
+}
+
+// Write a synthetic WAV of `kind` to `path`. The same kind and length always give the same file.
+pub fn generate(path: &Path, kind: FixtureKind, seconds: f64) -> Result<(), AppError> {
+    if !(seconds > 0.0 && seconds <= MAX_SECONDS) {
+        return Err(AppError::invalid(format!("Invalid length {}: use between 0 and {} seconds", seconds, MAX_SECONDS)));
+    }
+    let count = (seconds * SAMPLE_RATE as f64) as usize;
+    let mut rng = StdRng::seed_from_u64(0x5745_4257);
+    let samples = match kind {
+        FixtureKind::Tone => tone(count),
+        FixtureKind::Noise => noise(count, &mut rng),
+        FixtureKind::Speech => speech(count, &mut rng),
+    };
+    write_wav(path, &samples)
+}
+
+// Generate a test file into `file_path` (default: the output folder) and return its path
+pub fn create(file_path: Option<&str>, kind: FixtureKind, seconds: f64) -> Result<String, AppError> {
+    let path = match file_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let name = format!("web-whisper-test-{:?}-{}.wav", kind, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            config::output_dir()?.join(name.to_lowercase())
+        }
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create folder {:?}: {}", dir, e))?;
+    }
+    generate(&path, kind, seconds)?;
+    let path = path.to_string_lossy().to_string();
+    info!("Generated {:?} test audio ({:.1}s) at {}", kind, seconds, path);
+    crate::audit::record(None, crate::audit::AuditAction::Write, "file", &path);
+    Ok(path)
+}
+
+// The clip for benchmarks and the self-test: backend/samples/benchmark.* when one is
+// shipped, otherwise a synthetic speech clip generated once into the data folder
+pub fn sample_clip() -> Result<PathBuf, AppError> {
+    if let Ok(backend) = engine::find_backend_dir("transcribe_simple.py") {
+        let samples = backend.join("samples");
+        let mut shipped = SAMPLE_EXTENSIONS.iter().map(|extension| samples.join(format!("benchmark.{}", extension)));
+        if let Some(path) = shipped.find(|path| path.exists()) {
+            return Ok(path);
+        }
+    }
+    let dir = config::data_dir()?.join("samples");
+    let path = dir.join("sample.wav");
+    if !path.exists() {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create folder {:?}: {}", dir, e))?;
+        generate(&path, FixtureKind::Speech, SAMPLE_CLIP_SECONDS)?;
+        info!("Generated the sample clip at {:?}", path);
+    }
+    Ok(path)
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::config;
+use crate::error::AppError;
+
+// Experimental subsystems that can ship switched off and be turned on per user
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Flag {
+    // Live transcription from a device or pushed PCM (live.rs)
+    LiveMode,
+    // The OpenAI-compatible HTTP API on 127.0.0.1 (api.rs); read at startup
+    RestApi,
+    // Local jobs run on the backend server's loaded model when it is up (backend_api.rs)
+    ServerJobs,
+}
+
+const ALL: &[Flag] = &[Flag::LiveMode, Flag::RestApi, Flag::ServerJobs];
+
+impl Flag {
+    pub fn name(self) -> &'static str {
+        match self {
+            Flag::LiveMode => "live_mode",
+            Flag::RestApi => "rest_api",
+            Flag::ServerJobs => "server_jobs",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Flag::LiveMode => "Live transcription from an input device or pushed audio",
+            Flag::RestApi => "OpenAI-compatible local HTTP API (takes effect after a restart)",
+            Flag::ServerJobs => "Send local jobs to the running backend server instead of starting a process",
+        }
+    }
+
+    fn default(self) -> bool {
+        match self {
+            Flag::LiveMode | Flag::RestApi => false,
+            Flag::ServerJobs => true,
+        }
+    }
+
+    // e.g. WEB_WHISPER_FLAG_LIVE_MODE=1
+    fn env_var(self) -> String {
+        format!("WEB_WHISPER_FLAG_{}", self.name().to_uppercase())
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagSource {
+    Default,
+    Config,
+    Env,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FeatureFlag {
+    pub flag: Flag,
+    pub description: &'static str,
+    pub enabled: bool,
+    pub default: bool,
+    // Where `enabled` came from; an env override cannot be changed from the app
+    pub source: FlagSource,
+}
+
+fn env_value(flag: Flag) -> Option<bool> {
+    let value = std::env::var(flag.env_var()).ok()?;
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn resolve(flag: Flag, saved: &HashMap<String, bool>) -> FeatureFlag {
+    let (enabled, source) = match (env_value(flag), saved.get(flag.name())) {
+        (Some(enabled), _) => (enabled, FlagSource::Env),
+        (None, Some(enabled)) => (*enabled, FlagSource::Config),
+        (None, None) => (flag.default(), FlagSource::Default),
+    };
+    FeatureFlag { flag, description: flag.description(), enabled, default: flag.default(), source }
+}
+
+pub fn list() -> Vec<FeatureFlag> {
+    let saved = config::load().feature_flags.unwrap_or_default();
+    ALL.iter().map(|flag| resolve(*flag, &saved)).collect()
+}
+
+pub fn enabled(flag: Flag) -> bool {
+    resolve(flag, &config::load().feature_flags.unwrap_or_default()).enabled
+}
+
+// Err when `flag` is off, for subsystems to refuse to start
+pub fn require(flag: Flag, what: &str) -> Result<(), AppError> {
+    if enabled(flag) {
+        return Ok(());
+    }
+    Err(AppError::feature_disabled(flag.name(), format!("{} is turned off: turn on the {} feature flag", what, flag.name())))
+}
+
+// Save a per-user override; None goes back to the default
+pub fn set(flag: Flag, enabled: Option<bool>) -> Result<FeatureFlag, AppError> {
+    let resolved = config::update(|app_config| {
+        let mut saved = app_config.feature_flags.take().unwrap_or_default();
+        match enabled {
+            Some(enabled) => saved.insert(flag.name().to_string(), enabled),
+            None => saved.remove(flag.name()),
+        };
+        let resolved = resolve(flag, &saved);
+        app_config.feature_flags = Some(saved).filter(|saved| !saved.is_empty());
+        Ok(resolved)
+    })?;
+    info!("Feature flag {} set to {:?}", flag.name(), enabled);
+    Ok(resolved)
+}
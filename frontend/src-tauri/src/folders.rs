@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::jobs::JobRegistry;
+use crate::stream::StreamHub;
+use crate::watch::MEDIA_EXTENSIONS;
+use crate::{dedupe, fingerprint, history, journal, projects};
+
+fn wanted(path: &Path, extensions: &[String]) -> bool {
+    let Some(extension) = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()) else {
+        return false;
+    };
+    if extensions.is_empty() {
+        MEDIA_EXTENSIONS.contains(&extension.as_str())
+    } else {
+        extensions.iter().any(|wanted| wanted.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+    }
+}
+
+fn walk(dir: &Path, recursive: bool, extensions: &[String], found: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Hidden files are OS and sync clutter (.DS_Store, ._clip.mp4)
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        // file_type does not follow symlinks, so a linked folder cannot loop the walk
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() && recursive => walk(&path, recursive, extensions, found),
+            Ok(_) if path.is_file() && wanted(&path, extensions) => found.push(path),
+            _ => {}
+        }
+    }
+}
+
+// Media files in `dir`, sorted by path. `extensions` narrows the supported media types
+// (case-insensitive, with or without the dot); empty takes them all.
+pub fn media_files(dir: &Path, recursive: bool, extensions: &[String]) -> Result<Vec<PathBuf>, AppError> {
+    if !dir.is_dir() {
+        return Err(AppError::unknown(format!("Not a folder: {}", dir.display())));
+    }
+    let mut found = Vec::new();
+    walk(dir, recursive, extensions, &mut found);
+    found.sort();
+    Ok(found)
+}
+
+// Files and the media inside folders, in the given order without repeats
+pub fn expand(paths: &[String], recursive: bool) -> Result<Vec<PathBuf>, AppError> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        let found = if path.is_dir() {
+            media_files(&path, recursive, &[])?
+        } else if path.is_file() {
+            vec![path]
+        } else {
+            return Err(AppError::unknown(format!("File not found: {}", path.display())));
+        };
+        for file in found {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    Ok(files)
+}
+
+// Which files in a folder enqueue_folder picks up
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FolderFilter {
+    // e.g. ["mp3", "m4a"]; empty takes every supported media type
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    // Seconds; shorter files, and files whose length cannot be read, are skipped
+    #[serde(default)]
+    pub min_duration: Option<f64>,
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FolderFile {
+    pub file_path: String,
+    pub duration: Option<f64>,
+    // Set when the file was queued; a dry run queues nothing
+    pub job_id: Option<String>,
+    // Why the file is not queued
+    pub skipped: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FolderImport {
+    pub dry_run: bool,
+    pub queued: usize,
+    pub skipped: usize,
+    pub files: Vec<FolderFile>,
+}
+
+// Why a file would not be queued, or None to queue it
+fn skip_reason(duration: Option<f64>, filter: &FolderFilter) -> Option<String> {
+    let min_duration = filter.min_duration?;
+    match duration {
+        None => Some("Could not read the duration".to_string()),
+        Some(duration) if duration < min_duration => Some(format!("Shorter than {}s ({:.1}s)", min_duration, duration)),
+        Some(_) => None,
+    }
+}
+
+// Queue every matching media file under `dir` in one go. Each file is probed, and files
+// already queued or transcribed are skipped like in transcribe_audio.
+// Finished transcripts are saved to history. With `dry_run`, only report what would be
+// queued. Blocks while probing; call from spawn_blocking.
+pub fn enqueue_folder(
+    registry: &JobRegistry,
+    hub: &StreamHub,
+    dir: &str,
+    filter: &FolderFilter,
+    options: &TranscribeOptions,
+    project_id: Option<&str>,
+    dry_run: bool,
+) -> Result<FolderImport, AppError> {
+    let candidates = media_files(Path::new(dir), filter.recursive, &filter.extensions)?;
+    // Copies of the same recording inside the folder count as duplicates too
+    let mut batch: HashMap<String, String> = HashMap::new();
+    let mut files = Vec::new();
+    for candidate in candidates {
+        let file_path = candidate.to_string_lossy().to_string();
+        let duration = engine::probe_duration(&file_path);
+        let mut file = FolderFile { file_path, duration, job_id: None, skipped: skip_reason(duration, filter) };
+        if file.skipped.is_none() {
+            let job_id = engine::new_job_id();
+            let hash = match dedupe::hash_file(&file.file_path) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    file.skipped = Some(e.to_string());
+                    files.push(file);
+                    continue;
+                }
+            };
+            let existing = batch.get(&hash).cloned().or_else(|| dedupe::existing(registry, &hash).map(|(existing, _)| existing));
+            file.skipped = existing.map(|existing| format!("Duplicate of {}", existing));
+            if file.skipped.is_none() {
+                let remember_as = (!dry_run).then_some(job_id.as_str());
+                file.skipped = fingerprint::screen(&file.file_path, remember_as, false).map(|found| found.describe());
+            }
+            if file.skipped.is_none() && !dry_run {
+                let existing = dedupe::claim(registry, &hash, &job_id, false);
+                file.skipped = existing.map(|(existing, _)| format!("Duplicate of {}", existing));
+            }
+            if file.skipped.is_none() {
+                batch.insert(hash, if dry_run { file.file_path.clone() } else { job_id.clone() });
+                if !dry_run {
+                    queue(registry, hub, dir, &job_id, &file.file_path, options, project_id)?;
+                    file.job_id = Some(job_id);
+                }
+            }
+        }
+        files.push(file);
+    }
+
+    let skipped = files.iter().filter(|file| file.skipped.is_some()).count();
+    let import = FolderImport { dry_run, queued: files.len() - skipped, skipped, files };
+    if !dry_run {
+        info!("Queued {} file(s) from {} ({} skipped)", import.queued, dir, import.skipped);
+    }
+    Ok(import)
+}
+
+fn queue(
+    registry: &JobRegistry,
+    hub: &StreamHub,
+    dir: &str,
+    job_id: &str,
+    file_path: &str,
+    options: &TranscribeOptions,
+    project_id: Option<&str>,
+) -> Result<(), AppError> {
+    if project_id.is_some() {
+        projects::assign(job_id, project_id)?;
+    }
+    let (registry, hub, options) = (registry.clone(), hub.clone(), options.clone());
+    let (id, file_path, dir) = (job_id.to_string(), file_path.to_string(), dir.to_string());
+    tauri::async_runtime::spawn_blocking(move || match journal::run(&registry, &hub, &id, &file_path, &options, false) {
+        Ok(transcript) => {
+            let path = Path::new(&file_path);
+            let title = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| id.clone());
+            let mut tags = BTreeMap::new();
+            tags.insert("folder_import".to_string(), dir);
+            if let Err(e) = history::add(&id, &title, &file_path, &transcript, tags) {
+                warn!("Failed to save transcript of {}: {}", file_path, e);
+            }
+        }
+        Err(e) => warn!("Transcription of {} failed: {}", file_path, e),
+    });
+    Ok(())
+}
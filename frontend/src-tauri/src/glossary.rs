@@ -0,0 +1,319 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::editing;
+use crate::engine::Transcript;
+use crate::error::AppError;
+use crate::postprocess::Processor;
+
+// A canonical spelling, e.g. "Kubernetes", and other ways the engine writes it
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GlossaryTerm {
+    pub term: String,
+    // Matched exactly, ignoring case and punctuation: "k8s", "cube cuddle"
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GlossaryConfig {
+    #[serde(default)]
+    pub terms: Vec<GlossaryTerm>,
+    // Also fix near misses ("Kubernetis", "Kuber netes") of terms with 6 or more letters
+    #[serde(default = "default_fuzzy")]
+    pub fuzzy: bool,
+}
+
+fn default_fuzzy() -> bool {
+    true
+}
+
+impl Default for GlossaryConfig {
+    fn default() -> Self {
+        GlossaryConfig { terms: Vec::new(), fuzzy: default_fuzzy() }
+    }
+}
+
+pub fn glossary_config() -> GlossaryConfig {
+    crate::config::load().glossary.unwrap_or_default()
+}
+
+pub fn validate(config: &GlossaryConfig) -> Result<(), AppError> {
+    for term in &config.terms {
+        if normalize(&term.term).is_empty() {
+            return Err(AppError::invalid(format!("Invalid glossary term '{}': it needs letters or digits", term.term)));
+        }
+        if let Some(alias) = term.aliases.iter().find(|alias| normalize(alias).is_empty()) {
+            return Err(AppError::invalid(format!("Invalid alias '{}' for '{}': it needs letters or digits", alias, term.term)));
+        }
+    }
+    Ok(())
+}
+
+// One fix, kept on the transcript so the corrections can be reviewed
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Correction {
+    pub segment: usize,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GlossaryReport {
+    pub corrections: Vec<Correction>,
+    // Corrections per canonical term
+    pub terms: BTreeMap<String, usize>,
+}
+
+impl GlossaryReport {
+    pub fn from_corrections(corrections: &[Correction]) -> Self {
+        let mut terms = BTreeMap::new();
+        for correction in corrections {
+            *terms.entry(correction.to.clone()).or_insert(0) += 1;
+        }
+        GlossaryReport { corrections: corrections.to_vec(), terms }
+    }
+}
+
+// Lowercase letters and digits only, so "Kuber-netes," and "kubernetes" compare equal
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            current.push((previous[j] + usize::from(ca != *cb)).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+struct Matcher {
+    term: String,
+    normalized: String,
+    aliases: Vec<String>,
+    // Words in the term; windows of this many words, give or take one, are compared
+    words: usize,
+}
+
+impl Matcher {
+    fn matches(&self, candidate: &str, fuzzy: bool, exact_size: bool) -> bool {
+        if candidate == self.normalized || self.aliases.iter().any(|alias| alias == candidate) {
+            return true;
+        }
+        // Windows of another size only match exactly, or "kuberne is" would swallow the "is"
+        let fuzzy = fuzzy && exact_size;
+        // One edit per six letters: "kubernetis" is fixed, short everyday words are not
+        let length = self.normalized.chars().count();
+        fuzzy && length >= 6 && edit_distance(candidate, &self.normalized) <= length / 6
+    }
+}
+
+fn matchers(config: &GlossaryConfig) -> Vec<Matcher> {
+    let mut matchers: Vec<Matcher> = config
+        .terms
+        .iter()
+        .map(|term| Matcher {
+            term: term.term.trim().to_string(),
+            normalized: normalize(&term.term),
+            aliases: term.aliases.iter().map(|alias| normalize(alias)).collect(),
+            words: term.term.split_whitespace().count().max(1),
+        })
+        .collect();
+    // Longer terms first, so "Google Cloud" wins over "Google"
+    matchers.sort_by(|a, b| b.words.cmp(&a.words).then(b.normalized.len().cmp(&a.normalized.len())));
+    matchers
+}
+
+// Punctuation before the first and after the last letter or digit of a word
+fn affixes(word: &str) -> (&str, &str) {
+    let start = word.find(char::is_alphanumeric).unwrap_or(word.len());
+    let end = word.char_indices().rev().find(|(_, c)| c.is_alphanumeric()).map_or(start, |(index, c)| index + c.len_utf8());
+    (&word[..start], &word[end..])
+}
+
+fn correct_spaced(text: &str, matchers: &[Matcher], fuzzy: bool, segment: usize, corrections: &mut Vec<Correction>) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut index = 0;
+    'words: while index < words.len() {
+        for matcher in matchers {
+            for size in [matcher.words, matcher.words + 1, matcher.words.saturating_sub(1)] {
+                if size == 0 || index + size > words.len() {
+                    continue;
+                }
+                let window = &words[index..index + size];
+                // Punctuation inside the window means it spans a sentence or clause break
+                if window[..size - 1].iter().any(|word| !affixes(word).1.is_empty()) {
+                    continue;
+                }
+                let candidate: String = window.iter().map(|word| normalize(word)).collect();
+                if candidate.is_empty() || !matcher.matches(&candidate, fuzzy, size == matcher.words) {
+                    continue;
+                }
+                let (leading, _) = affixes(window[0]);
+                let (_, trailing) = affixes(window[size - 1]);
+                let original = window.join(" ");
+                let fixed = format!("{}{}{}", leading, matcher.term, trailing);
+                if fixed != original {
+                    corrections.push(Correction {
+                        segment,
+                        from: original[leading.len()..original.len() - trailing.len()].to_string(),
+                        to: matcher.term.clone(),
+                    });
+                }
+                out.push(fixed);
+                index += size;
+                continue 'words;
+            }
+        }
+        out.push(words[index].to_string());
+        index += 1;
+    }
+    out.join(" ")
+}
+
+// Japanese and Chinese have no word breaks to compare, so only aliases are replaced
+fn correct_unspaced(text: &str, config: &GlossaryConfig, segment: usize, corrections: &mut Vec<Correction>) -> String {
+    let mut text = text.to_string();
+    for term in &config.terms {
+        // An alias inside the term itself would be "corrected" again on every run
+        for alias in term.aliases.iter().filter(|alias| !alias.is_empty() && !term.term.contains(alias.as_str())) {
+            let count = text.matches(alias.as_str()).count();
+            if count > 0 {
+                text = text.replace(alias.as_str(), term.term.trim());
+                corrections.extend((0..count).map(|_| Correction { segment, from: alias.clone(), to: term.term.trim().to_string() }));
+            }
+        }
+    }
+    text
+}
+
+// Bring every term in the transcript to its canonical spelling; returns the corrections
+pub fn apply(transcript: &mut Transcript, config: &GlossaryConfig) -> Vec<Correction> {
+    let matchers = matchers(config);
+    let language = transcript.language.clone().unwrap_or_default().to_lowercase();
+    let unspaced = language.starts_with("ja") || language.starts_with("zh");
+    let mut corrections = Vec::new();
+    for (index, segment) in transcript.segments.iter_mut().enumerate() {
+        let before = corrections.len();
+        let text = if unspaced {
+            correct_unspaced(segment.text.trim(), config, index, &mut corrections)
+        } else {
+            correct_spaced(segment.text.trim(), &matchers, config.fuzzy, index, &mut corrections)
+        };
+        if corrections.len() > before {
+            segment.text = text;
+            // Word timings no longer line up with the corrected text
+            segment.words.clear();
+        }
+    }
+    if !corrections.is_empty() {
+        editing::rejoin(transcript);
+        transcript.corrections.extend(corrections.iter().cloned());
+    }
+    corrections
+}
+
+// Post-processing step; runs on every job while the glossary has terms
+pub struct Glossary(pub GlossaryConfig);
+
+impl Processor for Glossary {
+    fn name(&self) -> &'static str {
+        "glossary"
+    }
+
+    fn process(&self, transcript: &mut Transcript) -> Result<(), AppError> {
+        apply(transcript, &self.0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Segment;
+
+    fn config(fuzzy: bool) -> GlossaryConfig {
+        let term = |term: &str, aliases: &[&str]| GlossaryTerm {
+            term: term.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        };
+        GlossaryConfig {
+            terms: vec![
+                term("Kubernetes", &["k8s", "cube cuddle", "クーバネティス"]),
+                term("Google", &[]),
+                term("Google Cloud", &[]),
+            ],
+            fuzzy,
+        }
+    }
+
+    // The corrected text and how many corrections were recorded
+    fn correct(text: &str, language: &str, fuzzy: bool) -> (String, usize) {
+        let mut transcript = Transcript {
+            language: Some(language.to_string()),
+            segments: vec![Segment { start: 0.0, end: 1.0, text: text.to_string(), words: Vec::new() }],
+            ..Default::default()
+        };
+        let corrections = apply(&mut transcript, &config(fuzzy));
+        (transcript.segments[0].text.clone(), corrections.len())
+    }
+
+    #[test]
+    fn aliases_and_near_misses_are_fixed() {
+        let cases = [
+            ("we run k8s in prod", "we run Kubernetes in prod"),
+            ("deploy on cube cuddle.", "deploy on Kubernetes."),
+            ("(K8S), then", "(Kubernetes), then"),
+            ("kubernetis is great", "Kubernetes is great"),
+            ("a kubernets cluster", "a Kubernetes cluster"),
+            ("kuber netes clusters", "Kubernetes clusters"),
+            ("google cloud functions", "Google Cloud functions"),
+            ("just google it", "just Google it"),
+        ];
+        for (heard, fixed) in cases {
+            assert_eq!(correct(heard, "en", true), (fixed.to_string(), 1), "{}", heard);
+        }
+    }
+
+    #[test]
+    fn near_misses_too_far_or_across_a_break_are_left_alone() {
+        let cases = [
+            "a kubernaut cluster",
+            "a cube of cheese",
+            "cube, cuddle",
+            "kuberne is here",
+            "a googol of them",
+            "Kubernetes is already right",
+        ];
+        for text in cases {
+            assert_eq!(correct(text, "en", true), (text.to_string(), 0), "{}", text);
+        }
+    }
+
+    #[test]
+    fn fuzzy_off_keeps_aliases_only() {
+        assert_eq!(correct("kubernetis and k8s", "en", false), ("kubernetis and Kubernetes".to_string(), 1));
+    }
+
+    #[test]
+    fn unspaced_languages_replace_aliases() {
+        assert_eq!(correct("クーバネティスを使う", "ja", true), ("Kubernetesを使う".to_string(), 1));
+        assert_eq!(correct("クバネテスを使う", "ja", true), ("クバネテスを使う".to_string(), 0));
+    }
+
+    #[test]
+    fn report_counts_corrections_per_term() {
+        let corrections = [("k8s", "Kubernetes"), ("kubernetis", "Kubernetes"), ("google", "Google")]
+            .map(|(from, to)| Correction { segment: 0, from: from.to_string(), to: to.to_string() });
+        let report = GlossaryReport::from_corrections(&corrections);
+        assert_eq!(report.terms.get("Kubernetes"), Some(&2));
+        assert_eq!(report.terms.get("Google"), Some(&1));
+    }
+}
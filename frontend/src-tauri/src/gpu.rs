@@ -0,0 +1,584 @@
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::hardware::SystemProfile;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GpuMemory {
+    pub name: String,
+    // "nvml" or "metal"
+    pub backend: &'static str,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VramPolicy {
+    // Load the selected model anyway and only report that it may not fit
+    Warn,
+    // Step down to the best smaller or quantized model that fits
+    #[default]
+    Downgrade,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LocalModelConfig {
+    // faster-whisper size name; mapped to the matching MLX checkpoint on Apple Silicon
+    #[serde(default = "default_model")]
+    pub model: String,
+    #[serde(default = "default_compute_type")]
+    pub compute_type: String,
+    #[serde(default)]
+    pub vram_policy: VramPolicy,
+}
+
+fn default_model() -> String {
+    "large-v3".to_string()
+}
+
+fn default_compute_type() -> String {
+    "float16".to_string()
+}
+
+impl Default for LocalModelConfig {
+    fn default() -> Self {
+        LocalModelConfig { model: default_model(), compute_type: default_compute_type(), vram_policy: VramPolicy::default() }
+    }
+}
+
+pub fn local_model_config() -> LocalModelConfig {
+    crate::config::load().local_model.unwrap_or_default()
+}
+
+const MB: u64 = 1024 * 1024;
+
+// Best quality first, with the peak VRAM each needs while transcribing
+const MODEL_LADDER: &[(&str, &str, u64)] = &[
+    ("large-v3", "float16", 4700 * MB),
+    ("large-v3", "int8_float16", 3100 * MB),
+    ("large-v2", "float16", 4700 * MB),
+    ("large-v2", "int8_float16", 3100 * MB),
+    ("medium", "float16", 2600 * MB),
+    ("medium", "int8_float16", 1700 * MB),
+    ("small", "float16", 1200 * MB),
+    ("small", "int8_float16", 800 * MB),
+    ("base", "float16", 600 * MB),
+    ("base", "int8_float16", 400 * MB),
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ModelPlan {
+    pub model: String,
+    pub compute_type: String,
+    // None when no GPU could be queried, e.g. CPU-only machines
+    pub gpu: Option<GpuMemory>,
+    pub required_bytes: Option<u64>,
+    // The configured model when it was swapped for a smaller one
+    pub downgraded_from: Option<String>,
+    pub warning: Option<String>,
+}
+
+fn required_bytes(model: &str, compute_type: &str) -> Option<u64> {
+    MODEL_LADDER
+        .iter()
+        .find(|(name, compute, _)| *name == model && *compute == compute_type)
+        .map(|(_, _, bytes)| *bytes)
+}
+
+// Decide which model the local engine loads given the VRAM free right now
+pub fn plan_model(config: &LocalModelConfig, gpu: Option<GpuMemory>) -> ModelPlan {
+    let mut plan = ModelPlan {
+        model: config.model.clone(),
+        compute_type: config.compute_type.clone(),
+        required_bytes: required_bytes(&config.model, &config.compute_type),
+        gpu,
+        downgraded_from: None,
+        warning: None,
+    };
+    let (Some(gpu), Some(required)) = (&plan.gpu, plan.required_bytes) else {
+        return plan;
+    };
+    if gpu.free_bytes >= required {
+        return plan;
+    }
+
+    let shortfall = format!(
+        "{} ({}) needs about {} MB of GPU memory but {} has {} MB free",
+        config.model,
+        config.compute_type,
+        required / MB,
+        gpu.name,
+        gpu.free_bytes / MB
+    );
+    if config.vram_policy == VramPolicy::Warn {
+        plan.warning = Some(shortfall);
+        return plan;
+    }
+
+    let start = MODEL_LADDER
+        .iter()
+        .position(|(name, compute, _)| *name == config.model && *compute == config.compute_type)
+        .unwrap_or(0);
+    match MODEL_LADDER[start..].iter().find(|(_, _, bytes)| *bytes <= gpu.free_bytes) {
+        Some((name, compute, bytes)) => {
+            plan.warning = Some(format!("{}; using {} ({}) instead", shortfall, name, compute));
+            plan.downgraded_from = Some(config.model.clone());
+            plan.model = name.to_string();
+            plan.compute_type = compute.to_string();
+            plan.required_bytes = Some(*bytes);
+        }
+        None => plan.warning = Some(format!("{}; no smaller model fits either", shortfall)),
+    }
+    plan
+}
+
+const GB: u64 = 1024 * MB;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ModelRecommendation {
+    pub model: String,
+    pub compute_type: String,
+    // False when no GPU the local engine can use was found
+    pub on_gpu: bool,
+    pub reason: String,
+}
+
+// The largest model that fits this machine comfortably: on a CUDA or Metal GPU, the first
+// ladder entry that leaves a quarter of its footprint free for the desktop and other apps;
+// otherwise a size the CPU can get through in reasonable time.
+pub fn recommend_model(profile: &SystemProfile) -> ModelRecommendation {
+    // AMD and Intel GPUs are not used by the local engine
+    let usable = profile
+        .gpus
+        .iter()
+        .filter(|gpu| matches!(gpu.vendor, "NVIDIA" | "Apple"))
+        .filter_map(|gpu| Some((gpu, gpu.vram_bytes?)))
+        .max_by_key(|(_, vram)| *vram);
+    if let Some((gpu, vram)) = usable {
+        if let Some((name, compute, bytes)) = MODEL_LADDER.iter().find(|(_, _, bytes)| bytes + bytes / 4 <= vram) {
+            return ModelRecommendation {
+                model: name.to_string(),
+                compute_type: compute.to_string(),
+                on_gpu: true,
+                reason: format!("{} has {} MB of GPU memory; {} ({}) needs about {} MB", gpu.name, vram / MB, name, compute, bytes / MB),
+            };
+        }
+    }
+
+    let cores = profile.cpu.physical_cores.unwrap_or(profile.cpu.logical_cores);
+    let memory = profile.total_memory_bytes;
+    let model = if cores >= 8 && memory >= 16 * GB {
+        "medium"
+    } else if cores >= 4 && memory >= 8 * GB {
+        "small"
+    } else {
+        "base"
+    };
+    ModelRecommendation {
+        model: model.to_string(),
+        // The backend always runs int8 on the CPU
+        compute_type: "int8".to_string(),
+        on_gpu: false,
+        reason: format!("No usable GPU; {} CPU cores and {} GB of memory suit {}", cores, memory / GB, model),
+    }
+}
+
+// Until a model has been chosen, replace the one-size-fits-all default with one suited to this machine
+pub fn apply_recommended_default() {
+    if crate::config::load().local_model.is_some() {
+        return;
+    }
+    let recommendation = recommend_model(&crate::hardware::profile());
+    let saved = crate::config::update(|app_config| {
+        // A model chosen while the hardware was being profiled wins
+        if app_config.local_model.is_some() {
+            return Ok(());
+        }
+        info!("Defaulting to {} ({}): {}", recommendation.model, recommendation.compute_type, recommendation.reason);
+        app_config.local_model = Some(LocalModelConfig {
+            model: recommendation.model,
+            compute_type: recommendation.compute_type,
+            vram_policy: VramPolicy::default(),
+        });
+        Ok(())
+    });
+    if let Err(e) = saved {
+        warn!("Failed to save the recommended model: {}", e);
+    }
+}
+
+// Adapter details for display, without going through the Python backend
+#[derive(Debug, Serialize, Clone)]
+pub struct GpuInfo {
+    // "NVIDIA", "AMD", "Intel", "Apple", ... or "Unknown"
+    pub vendor: &'static str,
+    pub name: String,
+    // Dedicated memory, or the recommended working set on unified-memory Macs
+    pub vram_bytes: Option<u64>,
+    pub driver: Option<String>,
+    // "nvml", "metal", "dxgi", "sysfs", or "python" for the fallback, whose text is in `name`
+    pub source: &'static str,
+}
+
+#[cfg_attr(not(any(windows, target_os = "linux")), allow(dead_code))]
+fn vendor_from_pci(id: u32) -> &'static str {
+    match id {
+        0x10de => "NVIDIA",
+        0x1002 | 0x1022 => "AMD",
+        0x8086 => "Intel",
+        0x106b => "Apple",
+        0x5143 => "Qualcomm",
+        _ => "Unknown",
+    }
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn vendor_from_name(name: &str) -> &'static str {
+    let name = name.to_lowercase();
+    if name.contains("apple") {
+        "Apple"
+    } else if name.contains("amd") || name.contains("radeon") {
+        "AMD"
+    } else if name.contains("intel") {
+        "Intel"
+    } else if name.contains("nvidia") || name.contains("geforce") {
+        "NVIDIA"
+    } else {
+        "Unknown"
+    }
+}
+
+// Free and total memory of the first GPU, if a supported API is present
+pub fn query() -> Option<GpuMemory> {
+    #[cfg(target_os = "macos")]
+    {
+        metal::query()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        nvml::query()
+    }
+}
+
+// Vendor, name, memory and driver of each GPU, primary first. Empty when no native API
+// reports one; get_gpu_info then falls back to the Python detection script.
+pub fn detect_all() -> Vec<GpuInfo> {
+    #[cfg(target_os = "macos")]
+    {
+        metal::info().into_iter().collect()
+    }
+    #[cfg(windows)]
+    {
+        // DXGI lists every adapter, but NVML knows NVIDIA driver versions and exact memory
+        let nvidia = nvml::info();
+        let has_nvml = !nvidia.is_empty();
+        let others = dxgi::info().into_iter().filter(move |gpu| gpu.vendor != "NVIDIA" || !has_nvml);
+        nvidia.into_iter().chain(others).collect()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let nvidia = nvml::info();
+        let has_nvml = !nvidia.is_empty();
+        let others = sysfs::info().into_iter().filter(move |gpu| gpu.vendor != "NVIDIA" || !has_nvml);
+        nvidia.into_iter().chain(others).collect()
+    }
+    #[cfg(not(any(target_os = "macos", windows, target_os = "linux")))]
+    {
+        nvml::info()
+    }
+}
+
+pub fn detect() -> Option<GpuInfo> {
+    detect_all().into_iter().next()
+}
+
+#[cfg(not(target_os = "macos"))]
+mod nvml {
+    use super::{GpuInfo, GpuMemory};
+    use std::os::raw::{c_char, c_int, c_uint};
+
+    #[repr(C)]
+    struct Memory {
+        total: u64,
+        free: u64,
+        used: u64,
+    }
+
+    type Device = *mut std::ffi::c_void;
+
+    // Loaded at runtime so machines without the NVIDIA driver still start
+    #[cfg(windows)]
+    const LIBRARY: &str = "nvml.dll";
+    #[cfg(not(windows))]
+    const LIBRARY: &str = "libnvidia-ml.so.1";
+
+    // Memory of every device and the driver version, when the driver reports one
+    fn probe() -> Option<(Vec<GpuMemory>, Option<String>)> {
+        unsafe {
+            let library = libloading::Library::new(LIBRARY).ok()?;
+            let init: libloading::Symbol<unsafe extern "C" fn() -> c_int> = library.get(b"nvmlInit_v2\0").ok()?;
+            let shutdown: libloading::Symbol<unsafe extern "C" fn() -> c_int> = library.get(b"nvmlShutdown\0").ok()?;
+            let device_count: libloading::Symbol<unsafe extern "C" fn(*mut c_uint) -> c_int> =
+                library.get(b"nvmlDeviceGetCount_v2\0").ok()?;
+            let device_by_index: libloading::Symbol<unsafe extern "C" fn(c_uint, *mut Device) -> c_int> =
+                library.get(b"nvmlDeviceGetHandleByIndex_v2\0").ok()?;
+            let memory_info: libloading::Symbol<unsafe extern "C" fn(Device, *mut Memory) -> c_int> =
+                library.get(b"nvmlDeviceGetMemoryInfo\0").ok()?;
+            let device_name: libloading::Symbol<unsafe extern "C" fn(Device, *mut c_char, c_uint) -> c_int> =
+                library.get(b"nvmlDeviceGetName\0").ok()?;
+            let driver_version: Option<libloading::Symbol<unsafe extern "C" fn(*mut c_char, c_uint) -> c_int>> =
+                library.get(b"nvmlSystemGetDriverVersion\0").ok();
+
+            if init() != 0 {
+                return None;
+            }
+            let mut count: c_uint = 0;
+            if device_count(&mut count) != 0 {
+                count = 0;
+            }
+            let mut devices = Vec::new();
+            for index in 0..count {
+                let mut device: Device = std::ptr::null_mut();
+                let mut memory = Memory { total: 0, free: 0, used: 0 };
+                let mut name = [0 as c_char; 96];
+                if device_by_index(index, &mut device) != 0 || memory_info(device, &mut memory) != 0 {
+                    continue;
+                }
+                let name = if device_name(device, name.as_mut_ptr(), name.len() as c_uint) == 0 {
+                    std::ffi::CStr::from_ptr(name.as_ptr()).to_string_lossy().to_string()
+                } else {
+                    "NVIDIA GPU".to_string()
+                };
+                devices.push(GpuMemory { name, backend: "nvml", total_bytes: memory.total, free_bytes: memory.free });
+            }
+            let mut driver = [0 as c_char; 80];
+            let driver = driver_version
+                .is_some_and(|get| get(driver.as_mut_ptr(), driver.len() as c_uint) == 0)
+                .then(|| std::ffi::CStr::from_ptr(driver.as_ptr()).to_string_lossy().to_string());
+            shutdown();
+            Some((devices, driver))
+        }
+    }
+
+    pub fn query() -> Option<GpuMemory> {
+        probe()?.0.into_iter().next()
+    }
+
+    pub fn info() -> Vec<GpuInfo> {
+        let Some((devices, driver)) = probe() else { return Vec::new() };
+        devices
+            .into_iter()
+            .map(|memory| GpuInfo {
+                vendor: "NVIDIA",
+                name: memory.name,
+                vram_bytes: Some(memory.total_bytes),
+                driver: driver.clone(),
+                source: "nvml",
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod metal {
+    use super::{GpuInfo, GpuMemory};
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use std::os::raw::c_char;
+
+    #[link(name = "Metal", kind = "framework")]
+    extern "C" {
+        fn MTLCreateSystemDefaultDevice() -> *mut AnyObject;
+    }
+
+    // Unified memory: the budget Metal recommends for one process, minus what is already allocated
+    pub fn query() -> Option<GpuMemory> {
+        unsafe {
+            let device: Retained<AnyObject> = Retained::from_raw(MTLCreateSystemDefaultDevice())?;
+            let total: u64 = msg_send![&*device, recommendedMaxWorkingSetSize];
+            let allocated: usize = msg_send![&*device, currentAllocatedSize];
+            let name: *mut AnyObject = msg_send![&*device, name];
+            let name = if name.is_null() {
+                "Apple GPU".to_string()
+            } else {
+                let utf8: *const c_char = msg_send![name, UTF8String];
+                std::ffi::CStr::from_ptr(utf8).to_string_lossy().to_string()
+            };
+            Some(GpuMemory { name, backend: "metal", total_bytes: total, free_bytes: total.saturating_sub(allocated as u64) })
+        }
+    }
+
+    pub fn info() -> Option<GpuInfo> {
+        let memory = query()?;
+        Some(GpuInfo {
+            vendor: super::vendor_from_name(&memory.name),
+            name: memory.name,
+            vram_bytes: Some(memory.total_bytes),
+            // Metal drivers ship with macOS and have no version of their own
+            driver: None,
+            source: "metal",
+        })
+    }
+}
+
+#[cfg(windows)]
+mod dxgi {
+    use super::GpuInfo;
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct Guid(u32, u16, u16, [u8; 8]);
+
+    const IID_IDXGI_FACTORY1: Guid = Guid(0x770aae78, 0xf26f, 0x4dba, [0xa8, 0x29, 0x25, 0x3c, 0x83, 0xd1, 0xb3, 0x87]);
+    // Asking an adapter whether it supports IDXGIDevice returns the user-mode driver version
+    const IID_IDXGI_DEVICE: Guid = Guid(0x54ec77fa, 0x1377, 0x44e6, [0x8c, 0x32, 0x88, 0xfd, 0x5f, 0x44, 0xc8, 0x4c]);
+    const DXGI_ADAPTER_FLAG_SOFTWARE: u32 = 2;
+
+    #[repr(C)]
+    struct AdapterDesc1 {
+        description: [u16; 128],
+        vendor_id: u32,
+        device_id: u32,
+        sub_sys_id: u32,
+        revision: u32,
+        dedicated_video_memory: usize,
+        dedicated_system_memory: usize,
+        shared_system_memory: usize,
+        adapter_luid: [u32; 2],
+        flags: u32,
+    }
+
+    // Only the vtable slots used here; COM interfaces are plain tables of function pointers
+    #[repr(C)]
+    struct FactoryVtbl {
+        _query_interface: usize,
+        _add_ref: usize,
+        release: unsafe extern "system" fn(*mut c_void) -> u32,
+        // IDXGIObject (4) and IDXGIFactory (5)
+        _inherited: [usize; 9],
+        enum_adapters1: unsafe extern "system" fn(*mut c_void, u32, *mut *mut c_void) -> i32,
+    }
+
+    #[repr(C)]
+    struct AdapterVtbl {
+        _query_interface: usize,
+        _add_ref: usize,
+        release: unsafe extern "system" fn(*mut c_void) -> u32,
+        // IDXGIObject (4) and IDXGIAdapter::EnumOutputs, GetDesc
+        _inherited: [usize; 6],
+        check_interface_support: unsafe extern "system" fn(*mut c_void, *const Guid, *mut i64) -> i32,
+        get_desc1: unsafe extern "system" fn(*mut c_void, *mut AdapterDesc1) -> i32,
+    }
+
+    #[link(name = "dxgi")]
+    extern "system" {
+        fn CreateDXGIFactory1(riid: *const Guid, factory: *mut *mut c_void) -> i32;
+    }
+
+    unsafe fn vtable<T>(object: *mut c_void) -> &'static T {
+        &**(object as *const *const T)
+    }
+
+    fn driver_version(version: i64) -> String {
+        let version = version as u64;
+        format!("{}.{}.{}.{}", version >> 48, (version >> 32) & 0xffff, (version >> 16) & 0xffff, version & 0xffff)
+    }
+
+    // Hardware adapters in DXGI's order, which puts the primary display GPU first
+    pub fn info() -> Vec<GpuInfo> {
+        unsafe {
+            let mut factory: *mut c_void = std::ptr::null_mut();
+            if CreateDXGIFactory1(&IID_IDXGI_FACTORY1, &mut factory) < 0 || factory.is_null() {
+                return Vec::new();
+            }
+            let factory_vtbl = vtable::<FactoryVtbl>(factory);
+            let mut found = Vec::new();
+            let mut index = 0;
+            loop {
+                let mut adapter: *mut c_void = std::ptr::null_mut();
+                // Fails with DXGI_ERROR_NOT_FOUND past the last adapter
+                if (factory_vtbl.enum_adapters1)(factory, index, &mut adapter) < 0 || adapter.is_null() {
+                    break;
+                }
+                index += 1;
+                let adapter_vtbl = vtable::<AdapterVtbl>(adapter);
+                let mut desc: AdapterDesc1 = std::mem::zeroed();
+                let described = (adapter_vtbl.get_desc1)(adapter, &mut desc) >= 0;
+                let mut version = 0i64;
+                let versioned = (adapter_vtbl.check_interface_support)(adapter, &IID_IDXGI_DEVICE, &mut version) >= 0;
+                (adapter_vtbl.release)(adapter);
+                // Skip the Microsoft Basic Render Driver
+                if !described || desc.flags & DXGI_ADAPTER_FLAG_SOFTWARE != 0 {
+                    continue;
+                }
+                let length = desc.description.iter().position(|&c| c == 0).unwrap_or(desc.description.len());
+                found.push(GpuInfo {
+                    vendor: super::vendor_from_pci(desc.vendor_id),
+                    name: String::from_utf16_lossy(&desc.description[..length]),
+                    vram_bytes: Some(desc.dedicated_video_memory as u64),
+                    driver: versioned.then(|| driver_version(version)),
+                    source: "dxgi",
+                });
+            }
+            (factory_vtbl.release)(factory);
+            found
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sysfs {
+    use super::GpuInfo;
+    use std::path::Path;
+
+    fn read(path: &Path) -> Option<String> {
+        std::fs::read_to_string(path).ok().map(|contents| contents.trim().to_string())
+    }
+
+    fn hex(path: &Path) -> Option<u32> {
+        u32::from_str_radix(read(path)?.trim_start_matches("0x"), 16).ok()
+    }
+
+    // DRM cards backed by a PCI display device. VRAM is only exposed by amdgpu;
+    // the name falls back to the PCI ids since marketing names need the pci.ids database.
+    pub fn info() -> Vec<GpuInfo> {
+        let Ok(entries) = std::fs::read_dir("/sys/class/drm") else { return Vec::new() };
+        let mut cards: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("card") && !name.contains('-'))
+            })
+            .collect();
+        cards.sort();
+        cards
+            .into_iter()
+            .filter_map(|card| {
+                let device = card.join("device");
+                let vendor_id = hex(&device.join("vendor"))?;
+                let device_id = hex(&device.join("device"))?;
+                let vendor = super::vendor_from_pci(vendor_id);
+                let driver = std::fs::read_link(device.join("driver"))
+                    .ok()
+                    .and_then(|link| link.file_name().map(|name| name.to_string_lossy().to_string()));
+                let driver_version = driver.as_ref().and_then(|driver| read(&Path::new("/sys/module").join(driver).join("version")));
+                Some(GpuInfo {
+                    vendor,
+                    name: read(&device.join("product_name"))
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or_else(|| format!("{} GPU [{:04x}:{:04x}]", vendor, vendor_id, device_id)),
+                    vram_bytes: read(&device.join("mem_info_vram_total")).and_then(|total| total.parse().ok()),
+                    driver: match (driver, driver_version) {
+                        (Some(driver), Some(version)) => Some(format!("{} {}", driver, version)),
+                        (driver, _) => driver,
+                    },
+                    source: "sysfs",
+                })
+            })
+            .collect()
+    }
+}
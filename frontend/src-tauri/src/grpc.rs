@@ -0,0 +1,156 @@
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::pin::Pin;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry, JobStatus};
+use crate::journal;
+use crate::stream::{StreamEvent, StreamHub};
+
+pub mod proto {
+    tonic::include_proto!("webwhisper.v1");
+}
+
+use proto::transcription_server::{Transcription, TranscriptionServer};
+use tracing::info;
+
+pub const DEFAULT_GRPC_PORT: u16 = 50051;
+
+// WEB_WHISPER_GRPC_PORT overrides the port; "0" or "off" disables the service
+pub fn configured_port() -> Option<u16> {
+    match std::env::var("WEB_WHISPER_GRPC_PORT") {
+        Ok(value) if value == "off" => None,
+        Ok(value) => value.parse::<u16>().ok().filter(|port| *port != 0),
+        Err(_) => Some(DEFAULT_GRPC_PORT),
+    }
+}
+
+pub async fn serve(port: u16, hub: StreamHub, jobs: JobRegistry) -> Result<(), AppError> {
+    let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port));
+    info!("gRPC service listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(TranscriptionServer::new(TranscriptionService { hub, jobs }))
+        .serve(addr)
+        .await
+        .map_err(|e| format!("gRPC service error: {}", e))
+}
+
+struct TranscriptionService {
+    hub: StreamHub,
+    jobs: JobRegistry,
+}
+
+type SegmentStream = Pin<Box<dyn Stream<Item = Result<proto::SegmentEvent, Status>> + Send>>;
+
+fn to_proto_event(event: StreamEvent) -> proto::SegmentEvent {
+    use proto::segment_event::Event;
+
+    let (job_id, event) = match event {
+        StreamEvent::Final { job_id, segment } => (
+            job_id,
+            Event::FinalSegment(proto::Segment { start: segment.start, end: segment.end, text: segment.text }),
+        ),
+        StreamEvent::Completed { job_id, text } => (job_id, Event::CompletedText(text)),
+        StreamEvent::Failed { job_id, error } => (job_id, Event::Error(error)),
+    };
+    proto::SegmentEvent { job_id, event: Some(event) }
+}
+
+#[tonic::async_trait]
+impl Transcription for TranscriptionService {
+    type StreamSegmentsStream = SegmentStream;
+
+    async fn submit_job(
+        &self,
+        request: Request<proto::SubmitJobRequest>,
+    ) -> Result<Response<proto::SubmitJobResponse>, Status> {
+        use proto::submit_job_request::Source;
+
+        let request = request.into_inner();
+        // Uploaded bytes are staged in the temp dir and removed once the job ends
+        let (file_path, staged) = match request.source {
+            Some(Source::FilePath(path)) if !path.is_empty() => (path, false),
+            Some(Source::Audio(bytes)) if !bytes.is_empty() => {
+                let extension = std::path::Path::new(&request.file_name)
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "bin".to_string());
+                let path = engine::write_temp_file(&bytes, &format!("grpc_upload.{}", extension))
+                    .map_err(Status::internal)?;
+                (path.to_string_lossy().to_string(), true)
+            }
+            _ => return Err(Status::invalid_argument("file_path or audio is required")),
+        };
+
+        let language = if request.language.is_empty() { "auto".to_string() } else { request.language };
+        let options = TranscribeOptions { language, ..Default::default() };
+        let job_id = engine::new_job_id();
+
+        let (hub, registry, id) = (self.hub.clone(), self.jobs.clone(), job_id.clone());
+        tokio::task::spawn_blocking(move || {
+            let _ = journal::run(&registry, &hub, &id, &file_path, &options, staged);
+        });
+
+        Ok(Response::new(proto::SubmitJobResponse { job_id }))
+    }
+
+    async fn stream_segments(
+        &self,
+        request: Request<proto::StreamSegmentsRequest>,
+    ) -> Result<Response<Self::StreamSegmentsStream>, Status> {
+        let job_id = request.into_inner().job_id;
+        // Subscribe before inspecting the record so no event slips in between
+        let mut events = self.hub.subscribe();
+        let record = jobs::get(&self.jobs, &job_id)
+            .ok_or_else(|| Status::not_found(format!("Unknown job: {}", job_id)))?;
+
+        if record.status.is_finished() {
+            let event = match record.status {
+                JobStatus::Completed => StreamEvent::Completed { job_id, text: record.transcript.map(|t| t.text).unwrap_or_default() },
+                _ => StreamEvent::Failed { job_id, error: record.error.unwrap_or_else(|| "Job cancelled".to_string()) },
+            };
+            let stream: SegmentStream = Box::pin(tokio_stream::once(Ok(to_proto_event(event))));
+            return Ok(Response::new(stream));
+        }
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(64);
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) if event.job_id() == job_id => {
+                        let finished = !matches!(event, StreamEvent::Final { .. });
+                        if sender.send(Ok(to_proto_event(event))).await.is_err() || finished {
+                            break;
+                        }
+                    }
+                    // Lagged receivers just miss events rather than failing the RPC
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+
+    async fn cancel_job(
+        &self,
+        request: Request<proto::CancelJobRequest>,
+    ) -> Result<Response<proto::CancelJobResponse>, Status> {
+        let job_id = request.into_inner().job_id;
+        jobs::cancel(&self.jobs, &job_id).map_err(Status::failed_precondition)?;
+        Ok(Response::new(proto::CancelJobResponse {}))
+    }
+
+    async fn list_models(
+        &self,
+        _request: Request<proto::ListModelsRequest>,
+    ) -> Result<Response<proto::ListModelsResponse>, Status> {
+        Ok(Response::new(proto::ListModelsResponse {
+            models: vec![proto::Model { id: engine::MODEL_ID.to_string(), owned_by: "web-whisper".to_string() }],
+        }))
+    }
+}
@@ -0,0 +1,115 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use sysinfo::System;
+
+use crate::error::AppError;
+use crate::gpu::{self, GpuInfo};
+use crate::{config, disk, temp};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CpuProfile {
+    pub brand: String,
+    pub vendor: String,
+    // "x86_64", "aarch64", ...
+    pub arch: &'static str,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    // SIMD extensions the engines can use, e.g. "avx2" or "neon"
+    pub features: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiskFree {
+    // "data", "cache", "temp" or "output"
+    pub purpose: &'static str,
+    pub path: String,
+    // None when the platform cannot tell
+    pub available_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SystemProfile {
+    pub cpu: CpuProfile,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+    pub gpus: Vec<GpuInfo>,
+    // e.g. "macOS 14.5 Sonoma", "Windows 11 Pro", "Linux Ubuntu 24.04"
+    pub os: String,
+    pub kernel_version: Option<String>,
+    pub disks: Vec<DiskFree>,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpu_features() -> Vec<&'static str> {
+    [
+        ("sse4.2", is_x86_feature_detected!("sse4.2")),
+        ("avx", is_x86_feature_detected!("avx")),
+        ("avx2", is_x86_feature_detected!("avx2")),
+        ("fma", is_x86_feature_detected!("fma")),
+        ("f16c", is_x86_feature_detected!("f16c")),
+        ("avx512f", is_x86_feature_detected!("avx512f")),
+    ]
+    .into_iter()
+    .filter(|(_, detected)| *detected)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+#[cfg(target_arch = "aarch64")]
+fn cpu_features() -> Vec<&'static str> {
+    [
+        ("neon", std::arch::is_aarch64_feature_detected!("neon")),
+        ("fp16", std::arch::is_aarch64_feature_detected!("fp16")),
+        ("dotprod", std::arch::is_aarch64_feature_detected!("dotprod")),
+    ]
+    .into_iter()
+    .filter(|(_, detected)| *detected)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn cpu_features() -> Vec<&'static str> {
+    Vec::new()
+}
+
+fn disk_free(purpose: &'static str, path: Result<PathBuf, AppError>) -> Option<DiskFree> {
+    let path = path.ok()?;
+    Some(DiskFree { purpose, available_bytes: disk::available_space(&path), path: path.to_string_lossy().to_string() })
+}
+
+// Survey the machine for support requests and model recommendations.
+// Blocking: GPU and disk queries touch drivers and the file system.
+pub fn profile() -> SystemProfile {
+    let mut system = System::new();
+    system.refresh_cpu_all();
+    system.refresh_memory();
+    let first_cpu = system.cpus().first();
+
+    let disks = [
+        disk_free("data", config::data_dir()),
+        disk_free("cache", config::cache_dir()),
+        disk_free("temp", Ok(temp::dir())),
+        disk_free("output", config::output_dir()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    SystemProfile {
+        cpu: CpuProfile {
+            brand: first_cpu.map(|cpu| cpu.brand().trim().to_string()).unwrap_or_default(),
+            vendor: first_cpu.map(|cpu| cpu.vendor_id().to_string()).unwrap_or_default(),
+            arch: std::env::consts::ARCH,
+            physical_cores: system.physical_core_count(),
+            logical_cores: system.cpus().len().max(1),
+            features: cpu_features(),
+        },
+        total_memory_bytes: system.total_memory(),
+        available_memory_bytes: system.available_memory(),
+        gpus: gpu::detect_all(),
+        os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        kernel_version: System::kernel_version(),
+        disks,
+    }
+}
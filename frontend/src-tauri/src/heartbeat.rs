@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::idle::{self, BackendStatus};
+use crate::{engine, proxy};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HeartbeatConfig {
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    // Missed pings in a row before the backend is marked degraded
+    #[serde(default = "default_max_failures")]
+    pub max_failures: u32,
+    // Relaunch the server when no running one can be found; otherwise only report it
+    #[serde(default = "default_auto_restart")]
+    pub auto_restart: bool,
+}
+
+fn default_interval_secs() -> u64 {
+    10
+}
+
+fn default_max_failures() -> u32 {
+    3
+}
+
+fn default_auto_restart() -> bool {
+    true
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        HeartbeatConfig {
+            interval_secs: default_interval_secs(),
+            max_failures: default_max_failures(),
+            auto_restart: default_auto_restart(),
+        }
+    }
+}
+
+pub fn heartbeat_config() -> HeartbeatConfig {
+    crate::config::load().heartbeat.unwrap_or_default()
+}
+
+// Relaunches back off up to this long, so a server that crashes on start is not respawned in a loop
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+const DEFAULT_PORT: u16 = 7860;
+
+async fn responds(client: &reqwest::Client, url: &str) -> bool {
+    let request = client.get(url).timeout(Duration::from_secs(5));
+    request.send().await.is_ok_and(|response| response.status().is_success())
+}
+
+// A server still answering on the last known port or the default one with this session's
+// token, e.g. after a transient hang. It must also pass the same checks as a fresh launch,
+// since a server that ignores tokens answers this one too.
+async fn readopt(app: &AppHandle, client: &reqwest::Client, known_port: Option<u16>) -> bool {
+    let mut ports = known_port.into_iter().collect::<Vec<_>>();
+    if !ports.contains(&DEFAULT_PORT) {
+        ports.push(DEFAULT_PORT);
+    }
+    for port in ports {
+        let url = crate::session::url(port);
+        if responds(client, &url).await && crate::session::verify(port).await.is_ok() {
+            info!("Re-adopted backend server on port {}", port);
+            *app.state::<crate::ServerState>().lock().unwrap() =
+                Some(crate::ServerInfo { url, port, status: "running".to_string() });
+            idle::set_status(app, BackendStatus::Running);
+            return true;
+        }
+    }
+    false
+}
+
+async fn relaunch(app: &AppHandle) -> bool {
+    // The old process may be hung rather than gone
+    if let Some(pid) = app.state::<crate::ProcessState>().lock().unwrap().take() {
+        if let Err(e) = engine::kill_process(pid) {
+            warn!("{}", e);
+        }
+    }
+    *app.state::<crate::ServerState>().lock().unwrap() = None;
+    info!("Relaunching the backend server");
+    match crate::start_gradio_server(app.clone(), app.state(), app.state()).await {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Failed to relaunch the backend server: {}", e);
+            // start_gradio_server reported it stopped; stay degraded so the next beat retries
+            idle::set_status(app, BackendStatus::Degraded);
+            false
+        }
+    }
+}
+
+// Ping the server on a schedule while it should be up. After `max_failures` missed pings
+// it is marked degraded, then re-adopted if something answers on the port, or relaunched.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let client = proxy::local_client();
+        let mut failures = 0;
+        let mut backoff = Duration::ZERO;
+        loop {
+            let config = heartbeat_config();
+            tokio::time::sleep(Duration::from_secs(config.interval_secs.max(1)) + backoff).await;
+
+            // Stopped, sleeping and starting servers are not expected to answer
+            if !matches!(idle::status(), BackendStatus::Running | BackendStatus::Degraded) {
+                failures = 0;
+                backoff = Duration::ZERO;
+                continue;
+            }
+            let server = app.state::<crate::ServerState>().lock().unwrap().clone();
+            if let Some(server) = &server {
+                if responds(&client, &server.url).await {
+                    if idle::status() == BackendStatus::Degraded {
+                        info!("Backend server on port {} is responding again", server.port);
+                        idle::set_status(&app, BackendStatus::Running);
+                    }
+                    failures = 0;
+                    backoff = Duration::ZERO;
+                    continue;
+                }
+            }
+
+            failures += 1;
+            if failures < config.max_failures.max(1) {
+                continue;
+            }
+            if idle::status() != BackendStatus::Degraded {
+                warn!("Backend server missed {} heartbeats; marking it degraded", failures);
+                idle::set_status(&app, BackendStatus::Degraded);
+            }
+            let port = server.map(|server| server.port);
+            if readopt(&app, &client, port).await || (config.auto_restart && relaunch(&app).await) {
+                failures = 0;
+                backoff = Duration::ZERO;
+            } else {
+                backoff = (backoff * 2).max(Duration::from_secs(config.interval_secs.max(1))).min(MAX_BACKOFF);
+            }
+        }
+    });
+}
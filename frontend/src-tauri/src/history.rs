@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::{config, crypto};
+use crate::engine::Transcript;
+use crate::error::AppError;
+
+// Serializes read-modify-write cycles on history.json across threads
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+// Older revisions beyond this are dropped, oldest first
+const MAX_REVISIONS: usize = 50;
+
+// A transcript as it was before an edit or revert replaced it
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Revision {
+    pub version: u32,
+    // RFC 3339, when this version was replaced
+    pub replaced_at: String,
+    pub transcript: Transcript,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VersionInfo {
+    pub version: u32,
+    // RFC 3339; None for the current version
+    pub replaced_at: Option<String>,
+    pub edited: bool,
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub job_id: String,
+    pub title: String,
+    // Original file path or URL
+    pub source: String,
+    // RFC 3339
+    pub created_at: String,
+    pub transcript: Transcript,
+    // Free-form metadata, e.g. show/episode for podcast feeds
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    // Earlier versions of `transcript`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
+}
+
+impl HistoryEntry {
+    // Version 1 is the engine's output; each edit or revert adds one
+    pub fn current_version(&self) -> u32 {
+        self.revisions.last().map_or(1, |revision| revision.version + 1)
+    }
+
+    fn replace_transcript(&mut self, transcript: Transcript) {
+        let previous = std::mem::replace(&mut self.transcript, transcript);
+        self.revisions.push(Revision {
+            version: self.current_version(),
+            replaced_at: chrono::Local::now().to_rfc3339(),
+            transcript: previous,
+        });
+        let excess = self.revisions.len().saturating_sub(MAX_REVISIONS);
+        self.revisions.drain(..excess);
+    }
+
+    pub fn version(&self, version: u32) -> Result<&Transcript, AppError> {
+        if version == self.current_version() {
+            return Ok(&self.transcript);
+        }
+        self.revisions
+            .iter()
+            .find(|revision| revision.version == version)
+            .map(|revision| &revision.transcript)
+            .ok_or_else(|| AppError::invalid(format!("Invalid version {}: it does not exist or was dropped", version)))
+    }
+}
+
+fn history_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("history.json"))
+}
+
+fn read_entries() -> Result<Vec<HistoryEntry>, AppError> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = crypto::read_file(&path, "history")?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse history: {}", e).into())
+}
+
+fn write_entries(entries: &[HistoryEntry]) -> Result<(), AppError> {
+    let path = history_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+    crypto::write_file(&path, "history", &contents)
+}
+
+// Rewrite history.json after encryption is turned on or off
+pub fn reencrypt() -> Result<(), AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let entries = read_entries()?;
+    write_entries(&entries)
+}
+
+// Drop entries created before `cutoff`; returns how many were removed
+pub fn prune(cutoff: std::time::SystemTime) -> Result<usize, AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut entries = read_entries()?;
+    let before = entries.len();
+    entries.retain(|entry| {
+        !chrono::DateTime::parse_from_rfc3339(&entry.created_at)
+            .is_ok_and(|created| std::time::SystemTime::from(created) < cutoff)
+    });
+    let removed = before - entries.len();
+    if removed > 0 {
+        write_entries(&entries)?;
+    }
+    Ok(removed)
+}
+
+pub fn clear() -> Result<(), AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    match std::fs::remove_file(history_path()?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete history: {}", e).into()),
+    }
+}
+
+pub fn list() -> Result<Vec<HistoryEntry>, AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    read_entries()
+}
+
+fn find<'a>(entries: &'a mut [HistoryEntry], job_id: &str) -> Result<&'a mut HistoryEntry, AppError> {
+    entries.iter_mut().find(|entry| entry.job_id == job_id).ok_or_else(|| AppError::unknown(format!("Unknown job: {}", job_id)))
+}
+
+pub fn get(job_id: &str) -> Result<HistoryEntry, AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    find(&mut read_entries()?, job_id).cloned()
+}
+
+// Apply `edit` to the transcript stored for `job_id`, keeping the old one as a revision
+pub fn update_transcript(
+    job_id: &str,
+    edit: impl FnOnce(&mut Transcript) -> Result<(), AppError>,
+) -> Result<Transcript, AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut entries = read_entries()?;
+    let entry = find(&mut entries, job_id)?;
+    let mut transcript = entry.transcript.clone();
+    edit(&mut transcript)?;
+    entry.replace_transcript(transcript.clone());
+    write_entries(&entries)?;
+    Ok(transcript)
+}
+
+// Change the stored transcript without keeping a revision, e.g. for bookmarks
+pub fn annotate<T>(
+    job_id: &str,
+    apply: impl FnOnce(&mut Transcript) -> Result<T, AppError>,
+) -> Result<(Transcript, T), AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut entries = read_entries()?;
+    let entry = find(&mut entries, job_id)?;
+    let result = apply(&mut entry.transcript)?;
+    let transcript = entry.transcript.clone();
+    write_entries(&entries)?;
+    Ok((transcript, result))
+}
+
+pub fn versions(job_id: &str) -> Result<Vec<VersionInfo>, AppError> {
+    let entry = get(job_id)?;
+    let mut versions: Vec<VersionInfo> = entry
+        .revisions
+        .iter()
+        .map(|revision| VersionInfo {
+            version: revision.version,
+            replaced_at: Some(revision.replaced_at.clone()),
+            edited: revision.transcript.edited,
+            current: false,
+        })
+        .collect();
+    versions.push(VersionInfo {
+        version: entry.current_version(),
+        replaced_at: None,
+        edited: entry.transcript.edited,
+        current: true,
+    });
+    Ok(versions)
+}
+
+// Make `version` current again. The version being replaced is kept, so a revert can be undone.
+pub fn revert(job_id: &str, version: u32) -> Result<Transcript, AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut entries = read_entries()?;
+    let entry = find(&mut entries, job_id)?;
+    if version == entry.current_version() {
+        return Ok(entry.transcript.clone());
+    }
+    let mut transcript = entry.version(version)?.clone();
+    // Bookmarks are not part of the text's history
+    transcript.bookmarks = entry.transcript.bookmarks.clone();
+    entry.replace_transcript(transcript.clone());
+    write_entries(&entries)?;
+    Ok(transcript)
+}
+
+pub fn add_if_missing(job_id: &str, title: &str, source: &str, transcript: &Transcript) -> Result<(), AppError> {
+    if list()?.iter().any(|entry| entry.job_id == job_id) {
+        return Ok(());
+    }
+    add(job_id, title, source, transcript, BTreeMap::new()).map(|_| ())
+}
+
+pub fn add(
+    job_id: &str,
+    title: &str,
+    source: &str,
+    transcript: &Transcript,
+    tags: BTreeMap<String, String>,
+) -> Result<HistoryEntry, AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut entries = read_entries()?;
+    // Finished jobs are saved as they complete; a later add, e.g. from a feed or watch
+    // folder, fills in its title, source and tags instead of adding a second entry
+    if let Ok(entry) = find(&mut entries, job_id) {
+        entry.title = title.to_string();
+        entry.source = source.to_string();
+        entry.transcript = transcript.clone();
+        entry.tags.extend(tags);
+        let entry = entry.clone();
+        write_entries(&entries)?;
+        return Ok(entry);
+    }
+    let entry = HistoryEntry {
+        id: format!("history-{}", job_id.trim_start_matches("job-")),
+        job_id: job_id.to_string(),
+        title: title.to_string(),
+        source: source.to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        transcript: transcript.clone(),
+        tags,
+        revisions: Vec::new(),
+    };
+    entries.push(entry.clone());
+    write_entries(&entries)?;
+    Ok(entry)
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::{config, engine};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IdleConfig {
+    // Stop the backend server after `idle_minutes` without jobs or requests
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_idle_minutes() -> u64 {
+    15
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        IdleConfig { enabled: default_enabled(), idle_minutes: default_idle_minutes() }
+    }
+}
+
+pub fn idle_config() -> IdleConfig {
+    config::load().idle_shutdown.unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendStatus {
+    Stopped,
+    Starting,
+    Running,
+    // Missed heartbeats; being re-adopted or relaunched (see heartbeat.rs)
+    Degraded,
+    // Stopped for being idle; the next request starts it again
+    Sleeping,
+}
+
+struct Activity {
+    status: BackendStatus,
+    last_active: Instant,
+    // Jobs running through run_job
+    jobs: usize,
+    // Transcriptions in progress on the server, from its "[web-whisper] busy/idle" log lines
+    requests: usize,
+}
+
+static ACTIVITY: Mutex<Option<Activity>> = Mutex::new(None);
+
+fn with_activity<T>(apply: impl FnOnce(&mut Activity) -> T) -> T {
+    let mut activity = ACTIVITY.lock().unwrap();
+    apply(activity.get_or_insert_with(|| Activity {
+        status: BackendStatus::Stopped,
+        last_active: Instant::now(),
+        jobs: 0,
+        requests: 0,
+    }))
+}
+
+// The idle timer only counts down once the backend is free
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub fn touch() {
+    with_activity(|activity| activity.last_active = Instant::now());
+}
+
+pub fn status() -> BackendStatus {
+    with_activity(|activity| activity.status)
+}
+
+// Record the new status and tell the UI with a "backend-status" event
+pub fn set_status(app: &AppHandle, status: BackendStatus) {
+    with_activity(|activity| {
+        activity.status = status;
+        activity.last_active = Instant::now();
+        if status != BackendStatus::Running {
+            activity.requests = 0;
+        }
+    });
+    let _ = app.emit("backend-status", serde_json::json!({ "status": status }));
+}
+
+// Held by run_job while a job runs; keeps the backend awake until dropped
+pub struct JobActivity;
+
+impl Drop for JobActivity {
+    fn drop(&mut self) {
+        with_activity(|activity| {
+            activity.jobs = activity.jobs.saturating_sub(1);
+            activity.last_active = Instant::now();
+        });
+    }
+}
+
+pub fn job_started() -> JobActivity {
+    with_activity(|activity| {
+        activity.jobs += 1;
+        activity.last_active = Instant::now();
+    });
+    JobActivity
+}
+
+// Fed every stdout line of the server; main.py marks each transcription's start and end
+pub fn observe_log(line: &str) {
+    match line.trim() {
+        "[web-whisper] busy" => with_activity(|activity| {
+            activity.requests += 1;
+            activity.last_active = Instant::now();
+        }),
+        "[web-whisper] idle" => with_activity(|activity| {
+            activity.requests = activity.requests.saturating_sub(1);
+            activity.last_active = Instant::now();
+        }),
+        _ => {}
+    }
+}
+
+fn idle_for(activity: &Activity) -> Option<Duration> {
+    (activity.status == BackendStatus::Running && activity.jobs == 0 && activity.requests == 0)
+        .then(|| activity.last_active.elapsed())
+}
+
+// Kill the server we started and mark it sleeping. A server found already running
+// on the port was not started by us and has no PID to stop.
+fn put_to_sleep(app: &AppHandle) {
+    let Some(pid) = app.state::<crate::ProcessState>().lock().unwrap().take() else {
+        return;
+    };
+    info!("Backend idle; stopping server process {}", pid);
+    if let Err(e) = engine::kill_process(pid) {
+        warn!("{}", e);
+        *app.state::<crate::ProcessState>().lock().unwrap() = Some(pid);
+        return;
+    }
+    *app.state::<crate::ServerState>().lock().unwrap() = None;
+    set_status(app, BackendStatus::Sleeping);
+}
+
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let config = idle_config();
+            if !config.enabled {
+                continue;
+            }
+            let limit = Duration::from_secs(config.idle_minutes.max(1) * 60);
+            if with_activity(|activity| idle_for(activity)).is_some_and(|idle| idle >= limit) {
+                put_to_sleep(&app);
+            }
+        }
+    });
+}
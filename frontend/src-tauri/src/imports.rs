@@ -0,0 +1,177 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::info;
+
+use crate::audit::{self, AuditAction};
+use crate::engine::{self, Segment, Transcript};
+use crate::error::AppError;
+use crate::history::{self, HistoryEntry};
+use crate::{subtitle, watch};
+
+// WebVTT markup inside cue text: voice and class spans, karaoke timestamps, <b>/<i>/<u>
+fn tag_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"</?[^>\s][^>]*>").unwrap())
+}
+
+fn clean_text(text: &str) -> String {
+    let text = tag_pattern().replace_all(text, "");
+    let text = text.replace("&amp;", "&").replace("&lt;", "<").replace("&gt;", ">").replace("&nbsp;", " ");
+    // Cue line breaks are layout; captions.rs wraps again on export
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Without a byte order mark and with Unix line endings, so blocks split on blank lines
+fn normalize(content: &str) -> String {
+    content.trim_start_matches('\u{feff}').replace("\r\n", "\n")
+}
+
+// Cues of an SRT or WebVTT file with markup removed and empty cues dropped
+fn parse(content: &str) -> Result<Vec<Segment>, AppError> {
+    // WebVTT style and region blocks carry no cues
+    let blocks: Vec<&str> = content
+        .split("\n\n")
+        .filter(|block| !block.trim_start().starts_with("STYLE") && !block.trim_start().starts_with("REGION"))
+        .collect();
+    let segments = subtitle::parse_srt(&blocks.join("\n\n"))?;
+    Ok(segments
+        .into_iter()
+        .map(|segment| Segment { text: clean_text(&segment.text), ..segment })
+        .filter(|segment| !segment.text.is_empty())
+        .collect())
+}
+
+// Media next to the subtitles with the same name, e.g. talk.mp4 for talk.srt or talk.en.vtt
+fn find_media(subtitle_path: &Path) -> Option<PathBuf> {
+    let dir = subtitle_path.parent()?;
+    let stem = subtitle_path.file_stem()?.to_string_lossy().to_string();
+    let base = stem.split('.').next().unwrap_or(&stem).to_string();
+    [stem, base].iter().find_map(|name| {
+        watch::MEDIA_EXTENSIONS
+            .iter()
+            .map(|extension| dir.join(format!("{}.{}", name, extension)))
+            .find(|path| path.is_file())
+    })
+}
+
+// "Language: en" in a WebVTT header
+fn language_from_header(content: &str) -> Option<String> {
+    let header = content.split("\n\n").next()?;
+    if !header.starts_with("WEBVTT") {
+        return None;
+    }
+    let code = header.lines().find_map(|line| line.strip_prefix("Language:"))?.trim();
+    (!code.is_empty()).then(|| code.to_string())
+}
+
+// The language code in names like "talk.en.srt" or "talk.pt-BR.vtt"
+fn language_from_name(subtitle_path: &Path) -> Option<String> {
+    let stem = subtitle_path.file_stem()?.to_string_lossy().to_string();
+    let (_, code) = stem.rsplit_once('.')?;
+    let primary = code.split(['-', '_']).next()?;
+    let valid = (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_lowercase());
+    valid.then(|| code.to_string())
+}
+
+// Add an existing SRT or WebVTT file to history so it can be edited, searched and exported
+// like a transcript. `media_path` ties it to its recording; without it, a media file with
+// the same name next to the subtitles is used when there is one.
+pub fn import_subtitles(
+    file_path: &str,
+    media_path: Option<&str>,
+    title: Option<String>,
+    language: Option<String>,
+) -> Result<HistoryEntry, AppError> {
+    let path = Path::new(file_path);
+    let format = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if format != "srt" && format != "vtt" {
+        return Err(AppError::invalid(format!("Unsupported subtitle file {}: use .srt or .vtt", file_path)));
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read subtitle file {}: {}", file_path, e))?;
+    let content = normalize(&content);
+    let mut segments = parse(&content)?;
+    if segments.is_empty() {
+        return Err(AppError::invalid(format!("Invalid subtitle file {}: it has no cues", file_path)));
+    }
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let media = match media_path {
+        Some(media) if !Path::new(media).is_file() => return Err(AppError::unknown(format!("Media file not found: {}", media))),
+        Some(media) => Some(PathBuf::from(media)),
+        None => find_media(path),
+    };
+    let last_end = segments.iter().map(|segment| segment.end).fold(0.0, f64::max);
+    let duration = media.as_ref().and_then(|media| engine::probe_duration(&media.to_string_lossy())).unwrap_or(last_end);
+    let transcript = Transcript {
+        text: segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" "),
+        language: language.or_else(|| language_from_header(&content)).or_else(|| language_from_name(path)),
+        duration: Some(duration),
+        segments,
+        ..Default::default()
+    };
+
+    let title = title.filter(|title| !title.trim().is_empty()).unwrap_or_else(|| {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        stem.split('.').next().unwrap_or(&stem).to_string()
+    });
+    let source = media.as_deref().unwrap_or(path).to_string_lossy().to_string();
+    let mut tags = BTreeMap::new();
+    tags.insert("imported_from".to_string(), file_path.to_string());
+    tags.insert("format".to_string(), format);
+
+    let job_id = engine::new_job_id();
+    audit::record(Some(&job_id), AuditAction::Read, "file", file_path);
+    let entry = history::add(&job_id, &title, &source, &transcript, tags)?;
+    info!("Imported {} into history as {} ({} cues)", file_path, job_id, entry.transcript.segments.len());
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cues(content: &str) -> Vec<(f64, f64, String)> {
+        parse(&normalize(content)).unwrap().into_iter().map(|segment| (segment.start, segment.end, segment.text)).collect()
+    }
+
+    #[test]
+    fn srt_with_crlf_and_bom() {
+        let content = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\nHello\r\nthere\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\nBye\r\n";
+        assert_eq!(cues(content), [(1.0, 2.5, "Hello there".to_string()), (3.0, 4.0, "Bye".to_string())]);
+    }
+
+    #[test]
+    fn vtt_note_style_and_region_blocks_are_skipped() {
+        let content = "WEBVTT\n\nSTYLE\n::cue { color: yellow }\n\nREGION\nid:left width:40%\n\nNOTE\nchecked by the editor\n\n00:01.000 --> 00:02.000\nOnly cue\n";
+        assert_eq!(cues(content), [(1.0, 2.0, "Only cue".to_string())]);
+    }
+
+    #[test]
+    fn vtt_cue_ids_settings_and_short_timestamps() {
+        let content = "WEBVTT\n\nintro\n01:02.500 --> 01:04.250 align:start position:10% line:0\nFirst\n\n1:00:00.000 --> 1:00:01.000\nSecond\n";
+        assert_eq!(cues(content), [(62.5, 64.25, "First".to_string()), (3600.0, 3601.0, "Second".to_string())]);
+    }
+
+    #[test]
+    fn markup_and_entities_are_stripped() {
+        let content = "WEBVTT\n\n00:00.000 --> 00:02.000\n<v Roger>Hello</v> <c.yellow>world</c> &amp; <00:00:01.500><i>more</i>&nbsp;text\n";
+        assert_eq!(cues(content)[0].2, "Hello world & more text");
+    }
+
+    #[test]
+    fn cues_left_empty_by_markup_are_dropped() {
+        let content = "1\n00:00:00,000 --> 00:00:01,000\n<i></i>\n\n2\n00:00:01,000 --> 00:00:02,000\nKept\n";
+        assert_eq!(cues(content).len(), 1);
+    }
+
+    #[test]
+    fn language_from_the_header_or_the_file_name() {
+        assert_eq!(language_from_header("WEBVTT\nLanguage: de\n\n00:00.000 --> 00:01.000\nHallo"), Some("de".to_string()));
+        assert_eq!(language_from_header("1\n00:00:00,000 --> 00:00:01,000\nLanguage: de"), None);
+        assert_eq!(language_from_name(Path::new("talk.pt-BR.vtt")), Some("pt-BR".to_string()));
+        assert_eq!(language_from_name(Path::new("talk.final.srt")), None);
+        assert_eq!(language_from_name(Path::new("talk.srt")), None);
+    }
+}
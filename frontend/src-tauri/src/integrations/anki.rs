@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::audio;
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AnkiConfig {
+    // Where the importable deck file is written
+    pub output_dir: String,
+    // Anki's collection.media folder; clips go to <output_dir>/<deck>_media when unset
+    #[serde(default)]
+    pub media_dir: Option<String>,
+    #[serde(default = "default_true")]
+    pub include_audio: bool,
+    // Padding around each clip so words at the edges are not cut off
+    #[serde(default = "default_padding")]
+    pub clip_padding: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_padding() -> f64 {
+    0.25
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnkiExport {
+    pub deck_path: String,
+    pub media_dir: Option<String>,
+    pub cards: usize,
+}
+
+// Fields are HTML; tabs and newlines would break the TSV layout
+fn field(text: &str) -> String {
+    text.trim()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(['\t', '\n', '\r'], " ")
+}
+
+// One card per segment: original text, translation (when present) and an audio clip,
+// as a tab-separated file Anki imports with File > Import
+pub fn export_deck(config: &AnkiConfig, record: &JobRecord) -> Result<AnkiExport, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript to export")?;
+    if transcript.segments.is_empty() {
+        return Err("Transcript has no segments to turn into cards".into());
+    }
+    let translations = transcript.translation.as_ref().map(|translation| &translation.segments);
+
+    let deck_name: String = super::render_template("{stem}", record, "txt")
+        .chars()
+        .map(|c| if "\\/:*?\"<>|".contains(c) { '-' } else { c })
+        .collect();
+    let output_dir = PathBuf::from(&config.output_dir);
+    std::fs::create_dir_all(&output_dir).map_err(|e| format!("Failed to create export folder: {}", e))?;
+
+    let with_audio = config.include_audio && std::path::Path::new(&record.file_path).exists();
+    if config.include_audio && !with_audio {
+        warn!("Source audio for {} is gone; exporting cards without clips", record.id);
+    }
+    let media_dir = match &config.media_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => output_dir.join(format!("{}_media", deck_name)),
+    };
+    if with_audio {
+        std::fs::create_dir_all(&media_dir).map_err(|e| format!("Failed to create media folder: {}", e))?;
+    }
+
+    let mut deck = String::from("#separator:tab\n#html:true\n#columns:Text\tTranslation\tAudio\n");
+    for (index, segment) in transcript.segments.iter().enumerate() {
+        let translation = translations.and_then(|segments| segments.get(index)).map(|s| field(&s.text)).unwrap_or_default();
+        let mut sound = String::new();
+        if with_audio {
+            // Job id keeps clip names unique across decks sharing collection.media
+            let clip_name = format!("{}_{:04}.mp3", record.id, index + 1);
+            let start = (segment.start - config.clip_padding).max(0.0);
+            audio::extract_clip(&record.file_path, start, segment.end + config.clip_padding, &media_dir.join(&clip_name))?;
+            sound = format!("[sound:{}]", clip_name);
+        }
+        deck.push_str(&format!("{}\t{}\t{}\n", field(&segment.text), translation, sound));
+    }
+
+    let deck_path = output_dir.join(format!("{}.txt", deck_name));
+    std::fs::write(&deck_path, deck).map_err(|e| format!("Failed to write deck: {}", e))?;
+    info!("Exported {} Anki cards for {} to {:?}", transcript.segments.len(), record.id, deck_path);
+    Ok(AnkiExport {
+        deck_path: deck_path.to_string_lossy().to_string(),
+        media_dir: with_audio.then(|| media_dir.to_string_lossy().to_string()),
+        cards: transcript.segments.len(),
+    })
+}
@@ -0,0 +1,177 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::warn;
+use crate::error::AppError;
+
+// Calendars are refetched at most this often
+const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+// Recordings often start a little before the meeting does
+const EARLY_START_GRACE_MINUTES: i64 = 10;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CalendarSource {
+    // Secret iCal address (Google Calendar, Outlook, iCloud ...)
+    IcsUrl { url: String },
+    // Program that prints iCalendar data to stdout, e.g. a script reading EventKit on macOS
+    Command { program: String, #[serde(default)] args: Vec<String> },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CalendarConfig {
+    pub source: CalendarSource,
+    // Append attendee names to the job title
+    #[serde(default)]
+    pub include_attendees: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Meeting {
+    pub title: String,
+    pub attendees: Vec<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct PartialEvent {
+    title: Option<String>,
+    attendees: Vec<String>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+}
+
+static CACHE: Mutex<Option<(CalendarSource, Instant, Vec<Meeting>)>> = Mutex::new(None);
+
+fn fetch(source: &CalendarSource) -> Result<String, AppError> {
+    match source {
+        CalendarSource::IcsUrl { url } => {
+            // webcal:// is plain HTTPS in practice
+            let url = url.replacen("webcal://", "https://", 1);
+            let response = crate::proxy::blocking_client()?
+                .get(&url)
+                .send()
+                .map_err(|e| AppError::upstream(format!("Calendar request failed: {}", e)))?;
+            if !response.status().is_success() {
+                return Err(AppError::upstream(format!("Calendar request failed: HTTP {}", response.status())));
+            }
+            response.text().map_err(|e| format!("Failed to read calendar: {}", e).into())
+        }
+        CalendarSource::Command { program, args } => {
+            let output = std::process::Command::new(program)
+                .args(args)
+                .output()
+                .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+            if !output.status.success() {
+                return Err(AppError::upstream(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim())));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+    }
+}
+
+// UTC ("...Z") or floating/TZID times; TZID values are read as local time, all-day dates are skipped
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive));
+    }
+    if NaiveDate::parse_from_str(value, "%Y%m%d").is_ok() {
+        return None;
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|time| time.with_timezone(&Utc))
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", " ").replace("\\N", " ").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+// Minimal RFC 5545 reader: VEVENT start, end, summary and attendees.
+// Recurring events only match their first occurrence.
+pub fn parse_ics(data: &str) -> Vec<Meeting> {
+    // Continuation lines start with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in data.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut meetings = Vec::new();
+    let mut current: Option<PartialEvent> = None;
+    for line in lines {
+        let Some((name_and_params, value)) = line.split_once(':') else { continue };
+        let mut parts = name_and_params.split(';');
+        let name = parts.next().unwrap_or_default().to_ascii_uppercase();
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value == "VEVENT" => current = Some(PartialEvent::default()),
+            ("END", Some(_)) if value == "VEVENT" => {
+                if let Some(PartialEvent { title: Some(title), attendees, start: Some(start), end: Some(end) }) = current.take() {
+                    meetings.push(Meeting { title, attendees, start, end });
+                }
+            }
+            ("SUMMARY", Some(event)) => event.title = Some(unescape(value)),
+            ("ATTENDEE", Some(event)) => {
+                // Prefer CN=Display Name, fall back to the mailto address
+                let display = parts
+                    .find_map(|param| param.strip_prefix("CN="))
+                    .map(|name| name.trim_matches('"').to_string())
+                    .unwrap_or_else(|| value.trim_start_matches("mailto:").to_string());
+                event.attendees.push(display);
+            }
+            ("DTSTART", Some(event)) => event.start = parse_time(value),
+            ("DTEND", Some(event)) => event.end = parse_time(value),
+            _ => {}
+        }
+    }
+    meetings
+}
+
+fn meetings(source: &CalendarSource) -> Result<Vec<Meeting>, AppError> {
+    let mut cache = CACHE.lock().unwrap();
+    if let Some((cached_source, fetched_at, meetings)) = cache.as_ref() {
+        if cached_source == source && fetched_at.elapsed() < CACHE_TTL {
+            return Ok(meetings.clone());
+        }
+    }
+    let meetings = parse_ics(&fetch(source)?);
+    *cache = Some((source.clone(), Instant::now(), meetings.clone()));
+    Ok(meetings)
+}
+
+pub fn meeting_at(config: &CalendarConfig, time: DateTime<Utc>) -> Result<Option<Meeting>, AppError> {
+    let grace = chrono::Duration::minutes(EARLY_START_GRACE_MINUTES);
+    Ok(meetings(&config.source)?
+        .into_iter()
+        .filter(|meeting| meeting.start - grace <= time && time < meeting.end)
+        // With overlapping events, the one that started most recently wins
+        .max_by_key(|meeting| meeting.start))
+}
+
+pub fn title_for(config: &CalendarConfig, meeting: &Meeting) -> String {
+    if config.include_attendees && !meeting.attendees.is_empty() {
+        format!("{} - {}", meeting.title, meeting.attendees.join(", "))
+    } else {
+        meeting.title.clone()
+    }
+}
+
+// Title for a recording from the meeting that was running when the file was created.
+// Blocking; errors are logged so a broken calendar never fails a job.
+pub fn title_for_recording(file_path: &str) -> Option<String> {
+    let config = crate::config::load().calendar?;
+    let metadata = std::fs::metadata(file_path).ok()?;
+    let started: SystemTime = metadata.created().or_else(|_| metadata.modified()).ok()?;
+    match meeting_at(&config, DateTime::<Utc>::from(started)) {
+        Ok(meeting) => meeting.map(|meeting| title_for(&config, &meeting)),
+        Err(e) => {
+            warn!("Calendar lookup failed: {}", e);
+            None
+        }
+    }
+}
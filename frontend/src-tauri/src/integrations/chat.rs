@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatKind {
+    Slack,
+    Discord,
+}
+
+impl ChatKind {
+    // Slack truncates section text around 4000 characters; Discord rejects content over 2000
+    fn max_message_chars(self) -> usize {
+        match self {
+            ChatKind::Slack => 3900,
+            ChatKind::Discord => 1900,
+        }
+    }
+}
+
+// One incoming webhook per Slack workspace channel or Discord server channel
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ChatChannel {
+    pub name: String,
+    pub kind: ChatKind,
+    pub webhook_url: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareContent {
+    // File name, duration, language and the opening of the transcript
+    Summary,
+    Transcript,
+}
+
+const SUMMARY_EXCERPT_CHARS: usize = 600;
+
+fn summary(record: &JobRecord) -> Result<String, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript")?;
+    let file_name = std::path::Path::new(&record.file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| record.id.clone());
+
+    let mut details = Vec::new();
+    if let Some(duration) = transcript.duration {
+        details.push(format!("{}m {:02}s", (duration / 60.0) as u64, (duration % 60.0) as u64));
+    }
+    if let Some(language) = &transcript.language {
+        details.push(language.clone());
+    }
+    let mut excerpt: String = transcript.text.chars().take(SUMMARY_EXCERPT_CHARS).collect();
+    if excerpt.len() < transcript.text.len() {
+        excerpt.push('…');
+    }
+    let details = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
+    Ok(format!("Transcript of {}{}\n\n{}", file_name, details, excerpt))
+}
+
+// Split on line breaks, then spaces, so no message exceeds `limit` characters
+fn chunk_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+
+    let mut push_piece = |piece: &str, separator: &str, current: &mut String, current_chars: &mut usize| {
+        let piece_chars = piece.chars().count();
+        let separator_chars = if current.is_empty() { 0 } else { separator.chars().count() };
+        if *current_chars + separator_chars + piece_chars > limit && !current.is_empty() {
+            chunks.push(std::mem::take(current));
+            *current_chars = 0;
+        }
+        if !current.is_empty() {
+            current.push_str(separator);
+            *current_chars += separator_chars;
+        }
+        current.push_str(piece);
+        *current_chars += piece_chars;
+    };
+
+    for line in text.lines() {
+        if line.chars().count() <= limit {
+            push_piece(line, "\n", &mut current, &mut current_chars);
+            continue;
+        }
+        for word in line.split(' ') {
+            // A single word longer than the limit is hard-split
+            let chars: Vec<char> = word.chars().collect();
+            for piece in chars.chunks(limit) {
+                push_piece(&piece.iter().collect::<String>(), " ", &mut current, &mut current_chars);
+            }
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+pub async fn post_message(channel: &ChatChannel, text: &str) -> Result<(), AppError> {
+    let body = match channel.kind {
+        ChatKind::Slack => serde_json::json!({ "text": text }),
+        // Never let transcript text ping @everyone or roles
+        ChatKind::Discord => serde_json::json!({ "content": text, "allowed_mentions": { "parse": [] } }),
+    };
+    let response = crate::proxy::apply(reqwest::Client::builder())?
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| AppError::upstream(format!("Failed to build HTTP client: {}", e)))?
+        .post(&channel.webhook_url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("{} request failed: {}", channel.name, e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::upstream(format!("{} request failed: HTTP {} {}", channel.name, status, detail.trim())));
+    }
+    Ok(())
+}
+
+// Post a job to a channel as one or more messages; returns the number of messages sent
+pub async fn share(channel: &ChatChannel, record: &JobRecord, content: ShareContent) -> Result<usize, AppError> {
+    let text = match content {
+        ShareContent::Summary => summary(record)?,
+        ShareContent::Transcript => record.transcript.as_ref().ok_or("Job has no transcript")?.text.clone(),
+    };
+    let chunks = chunk_message(&text, channel.kind.max_message_chars());
+    for chunk in &chunks {
+        post_message(channel, chunk).await?;
+        // Both services rate-limit incoming webhooks to about one message per second
+        if chunks.len() > 1 {
+            tokio::time::sleep(Duration::from_millis(1100)).await;
+        }
+    }
+    Ok(chunks.len())
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Segment, Transcript, Translation};
+use crate::error::AppError;
+use crate::secrets;
+
+pub const API_KEY: &str = "deepl-api-key";
+
+// DeepL accepts up to 50 texts per request
+const BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DeepLConfig {
+    // DeepL target code, e.g. "EN-US", "DE", "JA"
+    pub target_language: String,
+    // Free-plan keys (ending in ":fx") use api-free.deepl.com
+    #[serde(default)]
+    pub free_api: bool,
+    #[serde(default)]
+    pub auto_translate: bool,
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    translations: Vec<TranslatedText>,
+}
+
+#[derive(Deserialize)]
+struct TranslatedText {
+    text: String,
+}
+
+async fn translate_texts(config: &DeepLConfig, api_key: &str, texts: &[String]) -> Result<Vec<String>, AppError> {
+    let host = if config.free_api { "api-free.deepl.com" } else { "api.deepl.com" };
+    let http = crate::proxy::client()?;
+    let mut translated = Vec::with_capacity(texts.len());
+
+    for batch in texts.chunks(BATCH_SIZE) {
+        let response = http
+            .post(format!("https://{}/v2/translate", host))
+            .header("Authorization", format!("DeepL-Auth-Key {}", api_key))
+            .json(&serde_json::json!({ "text": batch, "target_lang": config.target_language }))
+            .send()
+            .await
+            .map_err(|e| AppError::upstream(format!("DeepL request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::upstream(format!("DeepL request failed: HTTP {} {}", status, detail.trim())));
+        }
+        let body: TranslateResponse = response.json().await.map_err(|e| AppError::invalid(format!("Invalid DeepL response: {}", e)))?;
+        if body.translations.len() != batch.len() {
+            return Err("DeepL returned a different number of translations".into());
+        }
+        translated.extend(body.translations.into_iter().map(|t| t.text));
+    }
+    Ok(translated)
+}
+
+// Translate segment by segment so the translation keeps the original timing
+pub async fn translate(config: &DeepLConfig, transcript: &Transcript) -> Result<Translation, AppError> {
+    let api_key = secrets::get(API_KEY)?.ok_or(AppError::not_configured("DeepL API key is not configured"))?;
+
+    if transcript.segments.is_empty() {
+        let text = translate_texts(config, &api_key, std::slice::from_ref(&transcript.text)).await?.remove(0);
+        return Ok(Translation { language: config.target_language.clone(), text, segments: Vec::new() });
+    }
+
+    let texts: Vec<String> = transcript.segments.iter().map(|segment| segment.text.trim().to_string()).collect();
+    let translated = translate_texts(config, &api_key, &texts).await?;
+    let segments: Vec<Segment> = transcript
+        .segments
+        .iter()
+        .zip(translated)
+        .map(|(segment, text)| Segment { start: segment.start, end: segment.end, text, words: Vec::new() })
+        .collect();
+    let text = segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" ");
+    Ok(Translation { language: config.target_language.clone(), text, segments })
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::oauth::{self, Provider};
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+use crate::{secrets, subtitle};
+
+pub const REFRESH_TOKEN: &str = "dropbox-refresh-token";
+
+const PROVIDER: Provider = Provider {
+    name: "Dropbox",
+    auth_url: "https://www.dropbox.com/oauth2/authorize",
+    token_url: "https://api.dropboxapi.com/oauth2/token",
+    scope: None,
+    extra_auth_params: &[("token_access_type", "offline")],
+};
+
+fn default_folder() -> String {
+    "/Web Whisper".to_string()
+}
+
+fn default_format() -> String {
+    "txt".to_string()
+}
+
+fn default_redirect_port() -> u16 {
+    53682
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DropboxConfig {
+    // App key of a Dropbox app with files.content.write
+    pub app_key: String,
+    #[serde(default = "default_folder")]
+    pub folder: String,
+    #[serde(default = "default_format")]
+    pub format: String,
+    // Dropbox only accepts registered redirect URIs, so the loopback port is fixed:
+    // register http://127.0.0.1:<redirect_port>/callback in the app console
+    #[serde(default = "default_redirect_port")]
+    pub redirect_port: u16,
+}
+
+fn client(config: &DropboxConfig) -> oauth::Client {
+    // PKCE flow: no app secret is shipped with the desktop app
+    oauth::Client { client_id: config.app_key.clone(), client_secret: None }
+}
+
+pub async fn connect(config: &DropboxConfig) -> Result<(), AppError> {
+    let tokens = oauth::authorize(&PROVIDER, &client(config), Some(config.redirect_port)).await?;
+    let refresh_token = tokens.refresh_token.ok_or("Dropbox did not return a refresh token")?;
+    secrets::set(REFRESH_TOKEN, &refresh_token)
+}
+
+pub fn disconnect() -> Result<(), AppError> {
+    secrets::delete(REFRESH_TOKEN)
+}
+
+pub async fn send_transcript(config: &DropboxConfig, record: &JobRecord) -> Result<String, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript to upload")?;
+    let refresh_token = secrets::get(REFRESH_TOKEN)?.ok_or(AppError::not_configured("Dropbox is not connected"))?;
+    let token = oauth::refresh(&PROVIDER, &client(config), &refresh_token).await?.access_token;
+
+    let name = super::render_template("{stem}.{ext}", record, &config.format);
+    let path = format!("{}/{}", config.folder.trim_end_matches('/'), name);
+    let arg = serde_json::json!({ "path": path, "mode": "add", "autorename": true });
+    let body = subtitle::render(transcript, &config.format)?;
+
+    let response = crate::proxy::client()?
+        .post("https://content.dropboxapi.com/2/files/upload")
+        .bearer_auth(token)
+        .header("Dropbox-API-Arg", arg.to_string())
+        .header("Content-Type", "application/octet-stream")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("Dropbox upload failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::upstream(format!("Dropbox upload failed: HTTP {} {}", status, detail.trim())));
+    }
+
+    let metadata: serde_json::Value = response.json().await.map_err(|e| AppError::invalid(format!("Invalid Dropbox response: {}", e)))?;
+    let uploaded_path = metadata["path_display"].as_str().unwrap_or(&path).to_string();
+    info!("Sent transcript for {} to Dropbox: {}", record.id, uploaded_path);
+    Ok(uploaded_path)
+}
@@ -0,0 +1,104 @@
+use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+use crate::{secrets, subtitle};
+
+pub const PASSWORD: &str = "smtp-password";
+
+fn default_port() -> u16 {
+    587
+}
+
+fn default_subject_template() -> String {
+    "Transcript: {stem}".to_string()
+}
+
+fn default_format() -> String {
+    "txt".to_string()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    // Implicit TLS, usually port 465
+    Tls,
+    // Plain connection upgraded with STARTTLS, usually port 587
+    StartTls,
+    // Unencrypted; only for local relays
+    None,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EmailConfig {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub security: SmtpSecurity,
+    // Login name; the password is kept in the keychain
+    #[serde(default)]
+    pub username: Option<String>,
+    // Sender address, e.g. "Web Whisper <notes@example.com>"
+    pub from: String,
+    // Placeholders: {job_id} {stem} {date} {ext}
+    #[serde(default = "default_subject_template")]
+    pub subject_template: String,
+    // Attach the transcript as a file instead of putting it in the body
+    #[serde(default)]
+    pub as_attachment: bool,
+    // Attachment format: txt, srt, vtt or json
+    #[serde(default = "default_format")]
+    pub format: String,
+}
+
+fn transport(config: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, AppError> {
+    let builder = match config.security {
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)),
+    }
+    .map_err(|e| AppError::invalid(format!("Invalid SMTP server {}: {}", config.host, e)))?
+    .port(config.port);
+
+    let builder = match &config.username {
+        Some(username) => {
+            let password = secrets::get(PASSWORD)?.ok_or(AppError::not_configured("SMTP password is not configured"))?;
+            builder.credentials(Credentials::new(username.clone(), password))
+        }
+        None => builder,
+    };
+    Ok(builder.build())
+}
+
+// Send a job's transcript to `to`; returns the subject line used
+pub async fn send_transcript(config: &EmailConfig, record: &JobRecord, to: &str) -> Result<String, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript")?;
+    let from: Mailbox = config.from.parse().map_err(|e| AppError::invalid(format!("Invalid sender address {}: {}", config.from, e)))?;
+    let to: Mailbox = to.parse().map_err(|e| AppError::invalid(format!("Invalid recipient address {}: {}", to, e)))?;
+    let subject = super::render_template(&config.subject_template, record, &config.format);
+
+    let builder = Message::builder().from(from).to(to).subject(subject.clone());
+    let message = if config.as_attachment {
+        let contents = subtitle::render(transcript, &config.format)?;
+        let file_name = super::render_template("{stem}.{ext}", record, &config.format);
+        let content_type = ContentType::parse(subtitle::content_type(&config.format))
+            .map_err(|e| AppError::invalid(format!("Invalid content type: {}", e)))?;
+        builder.multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::plain(format!("Transcript of {} attached.", file_name)))
+                .singlepart(Attachment::new(file_name).body(contents, content_type)),
+        )
+    } else {
+        builder.singlepart(SinglePart::plain(transcript.text.clone()))
+    }
+    .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    transport(config)?
+        .send(message)
+        .await
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    Ok(subject)
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::oauth::{self, Provider};
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+use crate::{secrets, subtitle};
+
+pub const REFRESH_TOKEN: &str = "google-drive-refresh-token";
+pub const CLIENT_SECRET: &str = "google-drive-client-secret";
+
+const PROVIDER: Provider = Provider {
+    name: "Google Drive",
+    auth_url: "https://accounts.google.com/o/oauth2/v2/auth",
+    token_url: "https://oauth2.googleapis.com/token",
+    // Only files created by the app are visible to it
+    scope: Some("https://www.googleapis.com/auth/drive.file"),
+    extra_auth_params: &[("access_type", "offline"), ("prompt", "consent")],
+};
+
+fn default_format() -> String {
+    "txt".to_string()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GoogleDriveConfig {
+    // OAuth client of type "Desktop app" from the Google Cloud console
+    pub client_id: String,
+    // Destination folder; the Drive root when unset
+    #[serde(default)]
+    pub folder_id: Option<String>,
+    #[serde(default = "default_format")]
+    pub format: String,
+    #[serde(default)]
+    pub include_audio: bool,
+    #[serde(default)]
+    pub auto_upload: bool,
+}
+
+fn client(config: &GoogleDriveConfig) -> Result<oauth::Client, AppError> {
+    Ok(oauth::Client { client_id: config.client_id.clone(), client_secret: secrets::get(CLIENT_SECRET)? })
+}
+
+pub async fn connect(config: &GoogleDriveConfig, client_secret: Option<String>) -> Result<(), AppError> {
+    match &client_secret {
+        Some(secret) => secrets::set(CLIENT_SECRET, secret)?,
+        None => secrets::delete(CLIENT_SECRET)?,
+    }
+    let tokens = oauth::authorize(&PROVIDER, &client(config)?, None).await?;
+    let refresh_token = tokens.refresh_token.ok_or("Google did not return a refresh token")?;
+    secrets::set(REFRESH_TOKEN, &refresh_token)
+}
+
+pub fn disconnect() -> Result<(), AppError> {
+    secrets::delete(REFRESH_TOKEN)?;
+    secrets::delete(CLIENT_SECRET)
+}
+
+async fn access_token(config: &GoogleDriveConfig) -> Result<String, AppError> {
+    let refresh_token = secrets::get(REFRESH_TOKEN)?.ok_or(AppError::not_configured("Google Drive is not connected"))?;
+    Ok(oauth::refresh(&PROVIDER, &client(config)?, &refresh_token).await?.access_token)
+}
+
+// Resumable upload: create the session with metadata, then PUT the bytes
+async fn upload_file(
+    token: &str,
+    folder_id: Option<&str>,
+    name: &str,
+    content_type: &str,
+    body: Vec<u8>,
+) -> Result<String, AppError> {
+    let http = crate::proxy::client()?;
+    let mut metadata = serde_json::json!({ "name": name });
+    if let Some(folder_id) = folder_id {
+        metadata["parents"] = serde_json::json!([folder_id]);
+    }
+
+    let session = http
+        .post("https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields=id,webViewLink")
+        .bearer_auth(token)
+        .header("X-Upload-Content-Type", content_type)
+        .json(&metadata)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("Google Drive upload failed: {}", e)))?;
+    if !session.status().is_success() {
+        return Err(AppError::upstream(format!("Google Drive upload failed: HTTP {}", session.status())));
+    }
+    let upload_url = session
+        .headers()
+        .get("Location")
+        .and_then(|value| value.to_str().ok())
+        .ok_or("Google Drive did not return an upload URL")?
+        .to_string();
+
+    let response = http
+        .put(&upload_url)
+        .header("Content-Type", content_type)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("Google Drive upload failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::upstream(format!("Google Drive upload failed: HTTP {}", response.status())));
+    }
+    let file: serde_json::Value = response.json().await.map_err(|e| AppError::invalid(format!("Invalid Google Drive response: {}", e)))?;
+    Ok(file["webViewLink"].as_str().or(file["id"].as_str()).unwrap_or_default().to_string())
+}
+
+// Upload the transcript (and the source audio when enabled); returns the transcript link
+pub async fn upload_transcript(config: &GoogleDriveConfig, record: &JobRecord) -> Result<String, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript to upload")?;
+    let token = access_token(config).await?;
+    let folder_id = config.folder_id.as_deref();
+
+    let name = super::render_template("{stem}.{ext}", record, &config.format);
+    let body = subtitle::render(transcript, &config.format)?;
+    let link = upload_file(&token, folder_id, &name, subtitle::content_type(&config.format), body.into_bytes()).await?;
+
+    if config.include_audio {
+        let source = std::path::Path::new(&record.file_path);
+        let audio = tokio::fs::read(source)
+            .await
+            .map_err(|e| format!("Failed to read source audio {:?}: {}", source, e))?;
+        let audio_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| record.id.clone());
+        upload_file(&token, folder_id, &audio_name, "application/octet-stream", audio).await?;
+    }
+
+    info!("Uploaded transcript for {} to Google Drive", record.id);
+    Ok(link)
+}
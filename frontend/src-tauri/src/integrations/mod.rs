@@ -0,0 +1,139 @@
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::error::AppError;
+use crate::jobs::{self, JobRecord, JobRegistry, JobStatus};
+use crate::stream::{StreamEvent, StreamHub};
+
+pub mod anki;
+pub mod calendar;
+pub mod chat;
+pub mod deepl;
+pub mod dropbox;
+pub mod email;
+pub mod gdrive;
+pub mod notion;
+pub mod oauth;
+pub mod obsidian;
+pub mod s3;
+pub mod webhooks;
+
+// Watch the hub for finished jobs and hand them to every configured integration
+pub fn start(hub: &StreamHub, registry: JobRegistry) {
+    let mut events = hub.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let job_id = match events.recv().await {
+                Ok(StreamEvent::Completed { job_id, .. }) | Ok(StreamEvent::Failed { job_id, .. }) => job_id,
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            let registry = registry.clone();
+            tauri::async_runtime::spawn(async move {
+                // Read back from history when the registry has already forgotten the job
+                let lookup = registry.clone();
+                match tauri::async_runtime::spawn_blocking(move || jobs::find(&lookup, &job_id)).await {
+                    Ok(Ok(record)) => on_job_finished(record, &registry).await,
+                    Ok(Err(e)) => warn!("Finished job not found for integrations: {}", e),
+                    Err(e) => warn!("Integrations lookup task failed: {}", e),
+                }
+            });
+        }
+    });
+}
+
+// Keep an automatic translation in history too, where exports after a restart read it
+async fn save_translation(job_id: &str, translation: Option<crate::engine::Translation>) {
+    let id = job_id.to_string();
+    let saved = tauri::async_runtime::spawn_blocking(move || {
+        crate::history::annotate(&id, |transcript| {
+            transcript.translation = translation;
+            Ok(())
+        })
+    })
+    .await;
+    match saved {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => warn!("Could not save the translation of {} to history: {}", job_id, e),
+        Err(e) => warn!("History task for {} failed: {}", job_id, e),
+    }
+}
+
+async fn on_job_finished(mut record: JobRecord, registry: &JobRegistry) {
+    let config = match tauri::async_runtime::spawn_blocking(crate::config::load).await {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Config task for {} failed: {}", record.id, e);
+            return;
+        }
+    };
+
+    // Post-processing runs first so webhooks and exports see the translation
+    if let (Some(deepl_config), Some(transcript)) = (config.deepl.as_ref(), record.transcript.as_mut()) {
+        if deepl_config.auto_translate {
+            match deepl::translate(deepl_config, transcript).await {
+                Ok(translation) => {
+                    transcript.translation = Some(translation);
+                    jobs::set_transcript(registry, &record.id, transcript.clone());
+                    save_translation(&record.id, transcript.translation.clone()).await;
+                }
+                Err(e) => warn!("Automatic DeepL translation for {} failed: {}", record.id, e),
+            }
+        }
+    }
+
+    // Everything below leaves the app, so it gets the redacted copy
+    let Some(record) = crate::redact::for_auto_export(record) else {
+        return;
+    };
+    webhooks::dispatch(&config.webhooks, &record);
+
+    if record.status != JobStatus::Completed {
+        return;
+    }
+    if let Some(s3_config) = config.s3.filter(|s3_config| s3_config.auto_upload) {
+        let record = record.clone();
+        spawn_export("S3", record.id.clone(), async move { s3::upload_transcript(&s3_config, &record).await });
+    }
+    if let Some(drive_config) = config.google_drive.filter(|drive_config| drive_config.auto_upload) {
+        let record = record.clone();
+        spawn_export("Google Drive", record.id.clone(), async move { gdrive::upload_transcript(&drive_config, &record).await });
+    }
+    if let Some(obsidian_config) = config.obsidian.filter(|obsidian_config| obsidian_config.auto_export) {
+        let record = record.clone();
+        spawn_export("Obsidian", record.id.clone(), async move { obsidian::export_note(&obsidian_config, &record) });
+    }
+    if let Some(notion_config) = config.notion.filter(|notion_config| notion_config.auto_export) {
+        let record = record.clone();
+        spawn_export("Notion", record.id.clone(), async move { notion::export_page(&notion_config, &record).await });
+    }
+}
+
+fn spawn_export<F>(target: &'static str, job_id: String, export: F)
+where
+    F: std::future::Future<Output = Result<String, AppError>> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        match export.await {
+            Ok(location) => crate::audit::exported(&job_id, target, &location),
+            Err(e) => warn!("Automatic {} export failed: {}", target, e),
+        }
+    });
+}
+
+// Expand {job_id} {stem} {date} {ext} in remote object names; {stem} is the job title when one is set
+pub fn render_template(template: &str, record: &JobRecord, ext: &str) -> String {
+    let stem = match &record.title {
+        // Titles come from calendar events and may contain path separators
+        Some(title) => title.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "-"),
+        None => std::path::Path::new(&record.file_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| record.id.clone()),
+    };
+    template
+        .replace("{job_id}", &record.id)
+        .replace("{stem}", &stem)
+        .replace("{date}", &chrono::Local::now().format("%Y-%m-%d").to_string())
+        .replace("{ext}", ext)
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+use crate::{secrets, subtitle};
+
+pub const TOKEN: &str = "notion-token";
+
+const API_BASE: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+// Notion limits: 2000 characters per rich text object, 100 blocks per request
+const MAX_TEXT: usize = 2000;
+const MAX_BLOCKS: usize = 100;
+
+fn default_title_property() -> String {
+    "Name".to_string()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NotionConfig {
+    // The database must be shared with the integration
+    pub database_id: String,
+    #[serde(default = "default_title_property")]
+    pub title_property: String,
+    #[serde(default)]
+    pub auto_export: bool,
+}
+
+fn rich_text(content: &str) -> Vec<Value> {
+    let chars: Vec<char> = content.chars().collect();
+    chars
+        .chunks(MAX_TEXT)
+        .map(|chunk| json!({"type": "text", "text": {"content": chunk.iter().collect::<String>()}}))
+        .collect()
+}
+
+// One toggle per segment: "[00:01:02] first words…" expanding to the full segment text
+fn segment_blocks(record: &JobRecord) -> Vec<Value> {
+    let transcript = match &record.transcript {
+        Some(transcript) => transcript,
+        None => return Vec::new(),
+    };
+    if transcript.segments.is_empty() {
+        return vec![json!({"object": "block", "type": "paragraph", "paragraph": {"rich_text": rich_text(&transcript.text)}})];
+    }
+    transcript
+        .segments
+        .iter()
+        .map(|segment| {
+            let text = segment.text.trim();
+            let preview: String = text.chars().take(80).collect();
+            let ellipsis = if preview.len() < text.len() { "…" } else { "" };
+            let label = format!("[{}] {}{}", &subtitle::format_timestamp(segment.start, '.')[..8], preview, ellipsis);
+            json!({
+                "object": "block",
+                "type": "toggle",
+                "toggle": {
+                    "rich_text": rich_text(&label),
+                    "children": [
+                        {"object": "block", "type": "paragraph", "paragraph": {"rich_text": rich_text(text)}}
+                    ]
+                }
+            })
+        })
+        .collect()
+}
+
+async fn send(request: reqwest::RequestBuilder, token: &str, body: &Value) -> Result<Value, AppError> {
+    let response = request
+        .bearer_auth(token)
+        .header("Notion-Version", NOTION_VERSION)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("Notion request failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::upstream(format!("Notion request failed: HTTP {} {}", status, detail.trim())));
+    }
+    response.json().await.map_err(|e| AppError::invalid(format!("Invalid Notion response: {}", e)))
+}
+
+// Create the page with the first batch of blocks and append the rest; returns the page URL
+pub async fn export_page(config: &NotionConfig, record: &JobRecord) -> Result<String, AppError> {
+    if record.transcript.is_none() {
+        return Err("Job has no transcript to export".into());
+    }
+    let token = secrets::get(TOKEN)?.ok_or(AppError::not_configured("Notion token is not configured"))?;
+    let http = crate::proxy::client()?;
+
+    let title = super::render_template("{stem}", record, "");
+    let blocks = segment_blocks(record);
+    let mut batches = blocks.chunks(MAX_BLOCKS);
+
+    let mut properties = serde_json::Map::new();
+    properties.insert(config.title_property.clone(), json!({"title": rich_text(&title)}));
+    let page = send(
+        http.post(format!("{}/pages", API_BASE)),
+        &token,
+        &json!({
+            "parent": {"database_id": config.database_id},
+            "properties": properties,
+            "children": batches.next().unwrap_or_default(),
+        }),
+    )
+    .await?;
+
+    let page_id = page["id"].as_str().ok_or("Notion did not return a page id")?;
+    for batch in batches {
+        send(
+            http.patch(format!("{}/blocks/{}/children", API_BASE, page_id)),
+            &token,
+            &json!({"children": batch}),
+        )
+        .await?;
+    }
+
+    let url = page["url"].as_str().unwrap_or(page_id).to_string();
+    info!("Exported transcript for {} to Notion: {}", record.id, url);
+    Ok(url)
+}
@@ -0,0 +1,170 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::info;
+use crate::error::AppError;
+
+// Authorization-code flow with PKCE for desktop apps: the consent page opens in the
+// system browser and the redirect is captured on a one-shot loopback listener.
+pub struct Provider {
+    pub name: &'static str,
+    pub auth_url: &'static str,
+    pub token_url: &'static str,
+    pub scope: Option<&'static str>,
+    // Extra query parameters needed to get a refresh token
+    pub extra_auth_params: &'static [(&'static str, &'static str)],
+}
+
+pub struct Client {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+fn random_string(length: usize) -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(length).map(char::from).collect()
+}
+
+pub fn open_in_browser(url: &str) -> Result<(), AppError> {
+    let result = if cfg!(target_os = "windows") {
+        // The empty title keeps `start` from treating the quoted URL as a window title
+        std::process::Command::new("cmd").args(["/c", "start", "", url]).spawn()
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg(url).spawn()
+    } else {
+        std::process::Command::new("xdg-open").arg(url).spawn()
+    };
+    result.map(|_| ()).map_err(|e| format!("Failed to open browser: {}", e).into())
+}
+
+// Run the browser consent flow and exchange the code for tokens.
+// `port` pins the loopback port for providers that require an exact redirect URI.
+pub async fn authorize(provider: &Provider, client: &Client, port: Option<u16>) -> Result<TokenResponse, AppError> {
+    let listener = tokio::net::TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("Failed to start OAuth callback listener: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+
+    let verifier = random_string(64);
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = random_string(32);
+
+    let mut auth_url = reqwest::Url::parse(provider.auth_url).map_err(|e| e.to_string())?;
+    {
+        let mut query = auth_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &client.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state);
+        if let Some(scope) = provider.scope {
+            query.append_pair("scope", scope);
+        }
+        for (key, value) in provider.extra_auth_params {
+            query.append_pair(key, value);
+        }
+    }
+
+    info!("Opening {} authorization page", provider.name);
+    open_in_browser(auth_url.as_str())?;
+
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| format!("Timed out waiting for {} authorization", provider.name))??;
+
+    let mut form = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+        ("client_id", client.client_id.clone()),
+        ("code_verifier", verifier),
+    ];
+    if let Some(secret) = &client.client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    request_token(provider, &form).await
+}
+
+pub async fn refresh(provider: &Provider, client: &Client, refresh_token: &str) -> Result<TokenResponse, AppError> {
+    let mut form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh_token.to_string()),
+        ("client_id", client.client_id.clone()),
+    ];
+    if let Some(secret) = &client.client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    request_token(provider, &form).await
+}
+
+async fn request_token(provider: &Provider, form: &[(&str, String)]) -> Result<TokenResponse, AppError> {
+    let response = crate::proxy::client()?
+        .post(provider.token_url)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("{} token request failed: {}", provider.name, e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::upstream(format!("{} token request failed: HTTP {} {}", provider.name, status, detail.trim())));
+    }
+    response
+        .json::<TokenResponse>()
+        .await
+        .map_err(|e| AppError::invalid(format!("Invalid {} token response: {}", provider.name, e)))
+}
+
+// Accept connections until the browser delivers ?code=...&state=... to /callback
+async fn wait_for_code(listener: &tokio::net::TcpListener, expected_state: &str) -> Result<String, AppError> {
+    loop {
+        let (mut socket, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let mut buffer = vec![0u8; 8192];
+        let read = socket.read(&mut buffer).await.unwrap_or(0);
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        let path = request.lines().next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or("/");
+        if !path.starts_with("/callback") {
+            let _ = socket.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n").await;
+            continue;
+        }
+
+        let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", path)).map_err(|e| e.to_string())?;
+        let param = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.to_string());
+        let outcome = match (param("code"), param("state"), param("error")) {
+            (_, _, Some(error)) => Err(format!("Authorization denied: {}", error).into()),
+            (Some(code), Some(state), None) if state == expected_state => Ok(code),
+            _ => Err(AppError::invalid("Invalid authorization callback")),
+        };
+
+        let message = if outcome.is_ok() {
+            "Web Whisper is connected. You can close this tab."
+        } else {
+            "Authorization failed. You can close this tab and try again."
+        };
+        let body = format!("<html><body><p>{}</p></body></html>", message);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = socket.write_all(response.as_bytes()).await;
+        return outcome;
+    }
+}
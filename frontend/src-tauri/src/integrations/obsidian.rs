@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::info;
+
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+use crate::subtitle;
+
+fn default_filename_template() -> String {
+    "{date} {stem}".to_string()
+}
+
+fn default_tags() -> Vec<String> {
+    vec!["transcript".to_string()]
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ObsidianConfig {
+    pub vault_path: String,
+    // Subfolder inside the vault, e.g. "Transcripts"
+    #[serde(default)]
+    pub folder: String,
+    // Same placeholders as other export targets; ".md" is appended
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    #[serde(default = "default_tags")]
+    pub tags: Vec<String>,
+    // Prefix each segment with its [hh:mm:ss] start time instead of writing plain text
+    #[serde(default)]
+    pub include_timestamps: bool,
+    #[serde(default)]
+    pub auto_export: bool,
+}
+
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn render_note(config: &ObsidianConfig, record: &JobRecord) -> Result<String, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript to export")?;
+    let source = std::path::Path::new(&record.file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut note = String::from("---\n");
+    note.push_str(&format!("date: {}\n", chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")));
+    if let Some(duration) = transcript.duration {
+        note.push_str(&format!("duration: {}\n", subtitle::format_timestamp(duration, '.')));
+    }
+    note.push_str(&format!("source: {}\n", yaml_string(&source)));
+    if let Some(language) = &transcript.language {
+        note.push_str(&format!("language: {}\n", yaml_string(language)));
+    }
+    note.push_str(&format!("job_id: {}\n", yaml_string(&record.id)));
+    note.push_str("tags:\n");
+    for tag in &config.tags {
+        note.push_str(&format!("  - {}\n", yaml_string(tag)));
+    }
+    note.push_str("---\n\n");
+
+    if config.include_timestamps && !transcript.segments.is_empty() {
+        for segment in &transcript.segments {
+            let timestamp = subtitle::format_timestamp(segment.start, '.');
+            // Drop the milliseconds: [00:01:02]
+            note.push_str(&format!("[{}] {}\n\n", &timestamp[..8], segment.text.trim()));
+        }
+    } else {
+        note.push_str(transcript.text.trim());
+        note.push('\n');
+    }
+
+    if !transcript.bookmarks.is_empty() {
+        note.push_str("\n## Bookmarks\n\n");
+        for bookmark in &transcript.bookmarks {
+            let timestamp = subtitle::format_timestamp(bookmark.time, '.');
+            note.push_str(&format!("- [{}] **{}**", &timestamp[..8], bookmark.label));
+            if !bookmark.note.is_empty() {
+                note.push_str(&format!(": {}", bookmark.note));
+            }
+            note.push('\n');
+        }
+    }
+    Ok(note)
+}
+
+// Write the note into the vault without overwriting existing notes; returns its path
+pub fn export_note(config: &ObsidianConfig, record: &JobRecord) -> Result<String, AppError> {
+    let note = render_note(config, record)?;
+    let vault = PathBuf::from(&config.vault_path);
+    if !vault.is_dir() {
+        return Err(AppError::unknown(format!("Obsidian vault not found: {}", config.vault_path)));
+    }
+    let folder = vault.join(&config.folder);
+    std::fs::create_dir_all(&folder).map_err(|e| format!("Failed to create vault folder: {}", e))?;
+
+    // Characters Obsidian or the filesystem reject in note names
+    let name: String = super::render_template(&config.filename_template, record, "md")
+        .chars()
+        .map(|c| if "\\/:*?\"<>|#^[]".contains(c) { '-' } else { c })
+        .collect();
+    let mut path = folder.join(format!("{}.md", name));
+    let mut counter = 2;
+    while path.exists() {
+        path = folder.join(format!("{} ({}).md", name, counter));
+        counter += 1;
+    }
+
+    std::fs::write(&path, note).map_err(|e| format!("Failed to write note: {}", e))?;
+    info!("Exported transcript for {} to {:?}", record.id, path);
+    Ok(path.to_string_lossy().to_string())
+}
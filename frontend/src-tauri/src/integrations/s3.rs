@@ -0,0 +1,141 @@
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+use crate::{secrets, subtitle};
+
+pub const ACCESS_KEY_ID: &str = "s3-access-key-id";
+pub const SECRET_ACCESS_KEY: &str = "s3-secret-access-key";
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_key_template() -> String {
+    "transcripts/{date}/{stem}.{ext}".to_string()
+}
+
+fn default_format() -> String {
+    "txt".to_string()
+}
+
+// Any S3-compatible store (AWS, MinIO, R2, Wasabi, ...); keys come from the keychain
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct S3Config {
+    // e.g. https://s3.eu-central-1.amazonaws.com or http://127.0.0.1:9000
+    pub endpoint: String,
+    pub bucket: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    // Placeholders: {job_id} {stem} {date} {ext}
+    #[serde(default = "default_key_template")]
+    pub key_template: String,
+    // txt, srt, vtt or json
+    #[serde(default = "default_format")]
+    pub format: String,
+    // Address the bucket as endpoint/bucket/key instead of bucket.endpoint/key (MinIO needs this)
+    #[serde(default)]
+    pub path_style: bool,
+    #[serde(default)]
+    pub auto_upload: bool,
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+// RFC 3986 encoding of each path segment, keeping the separators
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            segment
+                .bytes()
+                .map(|byte| match byte {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+                    _ => format!("%{:02X}", byte),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// PUT an object signed with AWS Signature Version 4; returns the object URL
+pub async fn put_object(config: &S3Config, key: &str, body: Vec<u8>, content_type: &str) -> Result<String, AppError> {
+    let access_key_id = secrets::get(ACCESS_KEY_ID)?.ok_or(AppError::not_configured("S3 access key is not configured"))?;
+    let secret_access_key = secrets::get(SECRET_ACCESS_KEY)?.ok_or(AppError::not_configured("S3 secret key is not configured"))?;
+
+    let endpoint = reqwest::Url::parse(&config.endpoint)
+        .map_err(|e| AppError::invalid(format!("Invalid S3 endpoint '{}': {}", config.endpoint, e)))?;
+    let endpoint_host = endpoint.host_str().ok_or("S3 endpoint has no host")?;
+    let endpoint_host = match endpoint.port() {
+        Some(port) => format!("{}:{}", endpoint_host, port),
+        None => endpoint_host.to_string(),
+    };
+    let (host, canonical_uri) = if config.path_style {
+        (endpoint_host, format!("/{}/{}", config.bucket, encode_path(key)))
+    } else {
+        (format!("{}.{}", config.bucket, endpoint_host), format!("/{}", encode_path(key)))
+    };
+    let url = format!("{}://{}{}", endpoint.scheme(), host, canonical_uri);
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(&body));
+
+    let signed_headers = "content-type;host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\ncontent-type:{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        canonical_uri, content_type, host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date_stamp, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), &date_stamp);
+    let k_region = hmac_sha256(&k_date, &config.region);
+    let k_service = hmac_sha256(&k_region, "s3");
+    let k_signing = hmac_sha256(&k_service, "aws4_request");
+    let signature = hex::encode(hmac_sha256(&k_signing, &string_to_sign));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    );
+
+    let response = crate::proxy::client()?
+        .put(&url)
+        .header("Content-Type", content_type)
+        .header("x-amz-content-sha256", &payload_hash)
+        .header("x-amz-date", &amz_date)
+        .header("Authorization", authorization)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::upstream(format!("S3 upload failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::upstream(format!("S3 upload failed: HTTP {} {}", status, detail.trim())));
+    }
+    Ok(url)
+}
+
+pub async fn upload_transcript(config: &S3Config, record: &JobRecord) -> Result<String, AppError> {
+    let transcript = record.transcript.as_ref().ok_or("Job has no transcript to upload")?;
+    let body = subtitle::render(transcript, &config.format)?;
+    let key = super::render_template(&config.key_template, record, &config.format);
+    let url = put_object(config, &key, body.into_bytes(), subtitle::content_type(&config.format)).await?;
+    info!("Uploaded transcript for {} to {}", record.id, url);
+    Ok(url)
+}
@@ -0,0 +1,120 @@
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::error::AppError;
+use crate::jobs::{JobRecord, JobStatus};
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // When set, each request carries X-WebWhisper-Signature: sha256=<hex HMAC of the body>
+    #[serde(default)]
+    pub secret: Option<String>,
+    // false sends only a transcript_url pointing at the local API instead of the text
+    #[serde(default = "default_true")]
+    pub include_transcript: bool,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+pub fn build_payload(record: &JobRecord, include_transcript: bool) -> serde_json::Value {
+    let file_name = std::path::Path::new(&record.file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let event = if record.status == JobStatus::Completed { "job.completed" } else { "job.failed" };
+    let finished_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let mut payload = serde_json::json!({
+        "event": event,
+        "job_id": record.id,
+        "file_name": file_name,
+        "status": record.status,
+        "error": record.error,
+        "finished_at": finished_at,
+    });
+    if record.status == JobStatus::Completed {
+        if include_transcript {
+            payload["transcript"] = serde_json::json!(record.transcript);
+        } else if let Some(port) = crate::api::configured_port() {
+            payload["transcript_url"] = serde_json::json!(format!("http://127.0.0.1:{}/v1/jobs/{}", port, record.id));
+        }
+    }
+    payload
+}
+
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// Fire-and-forget delivery to every enabled webhook
+pub fn dispatch(webhooks: &[WebhookConfig], record: &JobRecord) {
+    for webhook in webhooks.iter().filter(|w| w.enabled) {
+        let webhook = webhook.clone();
+        let body = build_payload(record, webhook.include_transcript).to_string();
+        let job_id = record.id.clone();
+        tauri::async_runtime::spawn(async move {
+            match deliver(&webhook, body).await {
+                Ok(()) => crate::audit::exported(&job_id, "Webhook", &webhook.url),
+                Err(e) => warn!("Webhook {} failed: {}", webhook.url, e),
+            }
+        });
+    }
+}
+
+// POST with exponential backoff (1s, 2s, 4s, ...) on network errors, 429 and 5xx
+pub async fn deliver(webhook: &WebhookConfig, body: String) -> Result<(), AppError> {
+    let client = crate::proxy::apply(reqwest::Client::builder())?
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| AppError::upstream(format!("Failed to build HTTP client: {}", e)))?;
+    let signature = webhook.secret.as_deref().map(|secret| sign(secret, body.as_bytes()));
+    let attempts = webhook.max_attempts.max(1);
+
+    let mut last_error = String::new();
+    for attempt in 1..=attempts {
+        let mut request = client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "web-whisper-webhook")
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header("X-WebWhisper-Signature", signature);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                last_error = format!("HTTP {}", status);
+                if !(status.is_server_error() || status.as_u16() == 429) {
+                    return Err(AppError::upstream(last_error));
+                }
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+
+        if attempt < attempts {
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1).min(6))).await;
+        }
+    }
+    Err(AppError::upstream(format!("{} (after {} attempts)", last_error, attempts)))
+}
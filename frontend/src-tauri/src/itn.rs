@@ -0,0 +1,417 @@
+use crate::editing;
+use crate::engine::Transcript;
+use crate::error::AppError;
+use crate::postprocess::Processor;
+
+// Inverse text normalization: spoken forms to written ones ("twenty five percent" → "25%",
+// "march third" → "March 3", "二十五パーセント" → "25%"). English and Japanese have rules;
+// other languages pass through unchanged.
+pub struct InverseNormalizer;
+
+const ONES: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve",
+    "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS: &[&str] = &["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const ORDINAL_ONES: &[&str] = &[
+    "zeroth", "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+    "eleventh", "twelfth", "thirteenth", "fourteenth", "fifteenth", "sixteenth", "seventeenth", "eighteenth",
+    "nineteenth",
+];
+const ORDINAL_TENS: &[&str] =
+    &["", "", "twentieth", "thirtieth", "fortieth", "fiftieth", "sixtieth", "seventieth", "eightieth", "ninetieth"];
+const MONTHS: &[&str] = &[
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+    "december",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Ones(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+}
+
+// A number word and whether it is an ordinal ("third", "twentieth")
+fn number_word(word: &str) -> Option<(Kind, bool)> {
+    if let Some(value) = ONES.iter().position(|w| *w == word) {
+        return Some((if value < 10 { Kind::Ones(value as u64) } else { Kind::Tens(value as u64) }, false));
+    }
+    if let Some(value) = TENS.iter().position(|w| !w.is_empty() && *w == word) {
+        return Some((Kind::Tens(value as u64 * 10), false));
+    }
+    if let Some(value) = ORDINAL_ONES.iter().position(|w| *w == word) {
+        return Some((if value < 10 { Kind::Ones(value as u64) } else { Kind::Tens(value as u64) }, true));
+    }
+    if let Some(value) = ORDINAL_TENS.iter().position(|w| !w.is_empty() && *w == word) {
+        return Some((Kind::Tens(value as u64 * 10), true));
+    }
+    match word {
+        "hundred" => Some((Kind::Hundred, false)),
+        "hundredth" => Some((Kind::Hundred, true)),
+        "thousand" => Some((Kind::Scale(1_000), false)),
+        "thousandth" => Some((Kind::Scale(1_000), true)),
+        "million" => Some((Kind::Scale(1_000_000), false)),
+        "billion" => Some((Kind::Scale(1_000_000_000), false)),
+        _ => None,
+    }
+}
+
+// A word as spoken, its lowercase core and the punctuation after it
+struct Token<'a> {
+    original: &'a str,
+    core: String,
+    trailing: &'a str,
+}
+
+fn token(original: &str) -> Token<'_> {
+    let end = original.trim_end_matches(|c: char| ",.?!;:\"')".contains(c)).len();
+    Token { original, core: original[..end].to_lowercase(), trailing: &original[end..] }
+}
+
+struct Number {
+    value: u64,
+    // Digits after "point"
+    decimals: String,
+    ordinal: bool,
+    // Tokens used, including "and"/"point", and the number words among them
+    consumed: usize,
+    words: usize,
+}
+
+// Read the longest spoken number at the start of `tokens`
+fn parse_number(tokens: &[Token]) -> Option<Number> {
+    let (mut total, mut current) = (0u64, 0u64);
+    let (mut last, mut ordinal, mut consumed, mut words) = (None::<Kind>, false, 0, 0);
+    'tokens: while consumed < tokens.len() && !ordinal {
+        let token = &tokens[consumed];
+        // "one hundred and five"
+        if token.core == "and" && matches!(last, Some(Kind::Hundred | Kind::Scale(_))) && token.trailing.is_empty() {
+            match tokens.get(consumed + 1).and_then(|next| number_word(&next.core)) {
+                Some((Kind::Ones(_) | Kind::Tens(_), _)) => {
+                    consumed += 1;
+                    continue;
+                }
+                _ => break,
+            }
+        }
+        let parts: Vec<&str> = token.core.split('-').collect();
+        let mut kinds = Vec::new();
+        for part in &parts {
+            match number_word(part) {
+                Some(kind) => kinds.push(kind),
+                None => break 'tokens,
+            }
+        }
+        let before = (current, total, ordinal);
+        for (kind, is_ordinal) in kinds {
+            let fits = match (last, kind) {
+                (None, _) => true,
+                (Some(Kind::Tens(tens)), Kind::Ones(value)) => tens % 10 == 0 && value > 0,
+                (Some(Kind::Ones(_) | Kind::Tens(_)), Kind::Hundred | Kind::Scale(_)) => true,
+                (Some(Kind::Hundred), Kind::Ones(_) | Kind::Tens(_) | Kind::Scale(_)) => true,
+                (Some(Kind::Scale(_)), Kind::Ones(_) | Kind::Tens(_)) => true,
+                _ => false,
+            };
+            if !fits {
+                (current, total, ordinal) = before;
+                break 'tokens;
+            }
+            match kind {
+                Kind::Ones(value) | Kind::Tens(value) => current += value,
+                Kind::Hundred => current = current.max(1) * 100,
+                Kind::Scale(scale) => {
+                    total += current.max(1) * scale;
+                    current = 0;
+                }
+            }
+            last = Some(kind);
+            ordinal |= is_ordinal;
+        }
+        consumed += 1;
+        words += 1;
+        if !token.trailing.is_empty() {
+            break;
+        }
+    }
+    if words == 0 {
+        return None;
+    }
+    let mut number = Number { value: total + current, decimals: String::new(), ordinal, consumed, words };
+    // "three point five"
+    if !ordinal && tokens[consumed - 1].trailing.is_empty() && tokens.get(consumed).is_some_and(|token| token.core == "point") {
+        let mut index = consumed + 1;
+        while let Some(Kind::Ones(digit)) = tokens.get(index).and_then(|token| number_word(&token.core)).filter(|(_, ordinal)| !ordinal).map(|(kind, _)| kind) {
+            number.decimals.push_str(&digit.to_string());
+            index += 1;
+            if !tokens[index - 1].trailing.is_empty() {
+                break;
+            }
+        }
+        if !number.decimals.is_empty() {
+            number.words += index - consumed;
+            number.consumed = index;
+        }
+    }
+    Some(number)
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn month(core: &str) -> Option<String> {
+    MONTHS.iter().find(|month| **month == core).map(|month| {
+        let mut chars = month.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    })
+}
+
+fn normalize_english(text: &str) -> String {
+    let tokens: Vec<Token> = text.split_whitespace().map(token).collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        // "the third of march" → "March 3"
+        if tokens[index].core == "the" {
+            if let Some(number) = parse_number(&tokens[index + 1..]).filter(|number| number.ordinal) {
+                let of = index + 1 + number.consumed;
+                if let (Some("of"), Some(month)) =
+                    (tokens.get(of).map(|token| token.core.as_str()), tokens.get(of + 1).and_then(|token| month(&token.core)))
+                {
+                    out.push(format!("{} {}{}", month, number.value, tokens[of + 1].trailing));
+                    index = of + 2;
+                    continue;
+                }
+            }
+        }
+        // "march third" → "March 3"; "may" is also a verb, so it needs an ordinal
+        if let Some(month) = month(&tokens[index].core).filter(|_| tokens[index].trailing.is_empty()) {
+            let date = |number: &Number| {
+                (1..=31).contains(&number.value) && number.decimals.is_empty() && (number.ordinal || month != "May")
+            };
+            if let Some(number) = parse_number(&tokens[index + 1..]).filter(date) {
+                let last = &tokens[index + number.consumed];
+                out.push(format!("{} {}{}", month, number.value, last.trailing));
+                index += 1 + number.consumed;
+                continue;
+            }
+        }
+        let Some(number) = parse_number(&tokens[index..]) else {
+            out.push(tokens[index].original.to_string());
+            index += 1;
+            continue;
+        };
+        let last = &tokens[index + number.consumed - 1];
+        let next = tokens.get(index + number.consumed).filter(|_| last.trailing.is_empty());
+        let digits = if number.decimals.is_empty() { number.value.to_string() } else { format!("{}.{}", number.value, number.decimals) };
+        let unit = next.map(|next| next.core.as_str());
+        let per_cent = unit == Some("per") && tokens.get(index + number.consumed + 1).is_some_and(|token| token.core == "cent");
+        if !number.ordinal && (unit == Some("percent") || per_cent) {
+            let unit_token = &tokens[index + number.consumed + usize::from(per_cent)];
+            out.push(format!("{}%{}", digits, unit_token.trailing));
+            index += number.consumed + 1 + usize::from(per_cent);
+        } else if !number.ordinal && matches!(unit, Some("dollars" | "dollar")) {
+            out.push(format!("${}{}", digits, next.map_or("", |next| next.trailing)));
+            index += number.consumed + 1;
+        } else if number.ordinal {
+            // Lone ordinals stay words: "first of all", "wait a second"
+            if number.words > 1 {
+                out.push(format!("{}{}{}", number.value, ordinal_suffix(number.value), last.trailing));
+                index += number.consumed;
+            } else {
+                out.push(tokens[index].original.to_string());
+                index += 1;
+            }
+        } else if number.words == 1 && number.value < 10 {
+            // "one of them" reads better than "1 of them"
+            out.push(tokens[index].original.to_string());
+            index += 1;
+        } else {
+            out.push(format!("{}{}", digits, last.trailing));
+            index += number.consumed;
+        }
+    }
+    out.join(" ")
+}
+
+fn kanji_digit(c: char) -> Option<u64> {
+    "〇一二三四五六七八九".chars().position(|digit| digit == c).map(|value| value as u64)
+}
+
+fn kanji_unit(c: char) -> Option<u64> {
+    match c {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1_000),
+        '万' => Some(10_000),
+        '億' => Some(100_000_000),
+        _ => None,
+    }
+}
+
+// "二十五" → 25, "三千五百" → 3500, "二〇二四" → 2024
+fn kanji_number(numeral: &[char]) -> u64 {
+    if numeral.iter().all(|c| kanji_digit(*c).is_some()) {
+        return numeral.iter().fold(0, |value, c| value * 10 + kanji_digit(*c).unwrap_or(0));
+    }
+    let (mut total, mut section, mut digit) = (0u64, 0u64, 0u64);
+    for c in numeral {
+        match (kanji_digit(*c), kanji_unit(*c)) {
+            (Some(value), _) => digit = digit * 10 + value,
+            (_, Some(unit)) if unit >= 10_000 => {
+                total += (section + digit).max(1) * unit;
+                section = 0;
+                digit = 0;
+            }
+            (_, Some(unit)) => {
+                section += digit.max(1) * unit;
+                digit = 0;
+            }
+            _ => {}
+        }
+    }
+    total + section + digit
+}
+
+fn normalize_japanese(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let is_numeral = |c: char| kanji_digit(c).is_some() || kanji_unit(c).is_some();
+        if !is_numeral(chars[index]) {
+            out.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        let end = (index..chars.len()).find(|&i| !is_numeral(chars[i])).unwrap_or(chars.len());
+        let numeral = &chars[index..end];
+        // Single digits are usually words (一緒, 一番), 十 alone often means "enough",
+        // and a numeral that starts with 万 or 億 is an idiom (万一)
+        let convert = numeral.len() > 1 && !matches!(numeral[0], '万' | '億');
+        if convert {
+            out.push_str(&kanji_number(numeral).to_string());
+        } else {
+            out.extend(numeral);
+        }
+        index = end;
+    }
+    out.replace("パーセント", "%")
+}
+
+pub fn normalize(text: &str, language: &str) -> String {
+    if language.starts_with("en") {
+        normalize_english(text)
+    } else if language.starts_with("ja") {
+        normalize_japanese(text)
+    } else {
+        text.to_string()
+    }
+}
+
+impl Processor for InverseNormalizer {
+    fn name(&self) -> &'static str {
+        "inverse text normalization"
+    }
+
+    fn process(&self, transcript: &mut Transcript) -> Result<(), AppError> {
+        let language = transcript.language.clone().unwrap_or_default().to_lowercase();
+        if transcript.segments.is_empty() {
+            transcript.text = normalize(&transcript.text, &language);
+            return Ok(());
+        }
+        let mut changed = false;
+        for segment in &mut transcript.segments {
+            let normalized = normalize(segment.text.trim(), &language);
+            if normalized != segment.text.trim() {
+                segment.text = normalized;
+                // Word timings no longer line up with the rewritten text
+                segment.words.clear();
+                changed = true;
+            }
+        }
+        if changed {
+            editing::rejoin(transcript);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english() {
+        let cases = [
+            ("twenty five percent", "25%"),
+            ("about twenty-five per cent.", "about 25%."),
+            ("one hundred and five", "105"),
+            ("two thousand and twenty four", "2024"),
+            ("three point one four", "3.14"),
+            ("it costs fifty dollars", "it costs $50"),
+            ("the twenty first floor", "the 21st floor"),
+            ("march third", "March 3"),
+            ("the third of march, then", "March 3, then"),
+            ("may fifth", "May 5"),
+            ("you may two", "you may two"),
+            ("first of all", "first of all"),
+            ("one of them", "one of them"),
+            ("seven, eight", "seven, eight"),
+            ("twelve apples", "12 apples"),
+            ("one hundred and", "100 and"),
+            ("Twenty Five Percent", "25%"),
+        ];
+        for (spoken, written) in cases {
+            assert_eq!(normalize(spoken, "en"), written, "{}", spoken);
+        }
+    }
+
+    #[test]
+    fn japanese() {
+        let cases = [
+            ("二十五パーセント", "25%"),
+            ("三千五百円", "3500円"),
+            ("二〇二四年", "2024年"),
+            ("一万二千人", "12000人"),
+            ("一緒に行く", "一緒に行く"),
+            ("万一の場合", "万一の場合"),
+        ];
+        for (spoken, written) in cases {
+            assert_eq!(normalize(spoken, "ja"), written, "{}", spoken);
+        }
+    }
+
+    #[test]
+    fn segments_are_rewritten_and_the_text_rejoined() {
+        use crate::engine::{Segment, Word};
+
+        let word = Word { start: 0.0, end: 1.0, text: " twenty".to_string() };
+        let mut transcript = Transcript {
+            language: Some("EN".to_string()),
+            segments: vec![
+                Segment { start: 0.0, end: 2.0, text: " twenty five percent".to_string(), words: vec![word.clone()] },
+                Segment { start: 2.0, end: 3.0, text: " of them".to_string(), words: vec![word] },
+            ],
+            ..Default::default()
+        };
+        InverseNormalizer.process(&mut transcript).unwrap();
+        assert_eq!(transcript.text, "25% of them");
+        assert!(transcript.segments[0].words.is_empty());
+        assert_eq!(transcript.segments[1].words.len(), 1);
+    }
+
+    #[test]
+    fn other_languages_pass_through() {
+        assert_eq!(normalize("veinticinco por ciento", "es"), "veinticinco por ciento");
+        assert_eq!(normalize("twenty five", "de"), "twenty five");
+    }
+}
@@ -0,0 +1,300 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+use crate::audit::{self, AuditAction};
+use crate::editing;
+use crate::engine::{self, Segment, TranscribeOptions, Transcript};
+use crate::error::AppError;
+use crate::events::{self, JobEvent};
+use crate::history;
+use crate::idle;
+use crate::journal;
+use crate::keywords;
+use crate::languages;
+use crate::outputs;
+use crate::postprocess;
+use crate::queue;
+use crate::telemetry;
+use crate::temp;
+use crate::stream::{self, StreamEvent, StreamHub};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    // Waiting for a worker slot
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        !matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JobRecord {
+    pub id: String,
+    pub file_path: String,
+    // Display name, e.g. the calendar event the recording was made in
+    pub title: Option<String>,
+    pub status: JobStatus,
+    #[serde(skip)]
+    pub pid: Option<u32>,
+    pub transcript: Option<Transcript>,
+    pub error: Option<String>,
+    // Per-job output folder from the job's options
+    pub output_dir: Option<String>,
+    // Media transcribed so far, and its share of the whole when the length is known
+    pub processed_seconds: f64,
+    pub percent: Option<f64>,
+}
+
+pub type JobRegistry = Arc<Mutex<HashMap<String, JobRecord>>>;
+
+pub fn new_registry() -> JobRegistry {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+pub fn get(registry: &JobRegistry, job_id: &str) -> Option<JobRecord> {
+    registry.lock().unwrap().get(job_id).cloned()
+}
+
+// Hold `job_id` for a job about to run, e.g. one whose id an API client chose. False when
+// the registry already has a job by that id; run_job replaces the placeholder.
+pub fn reserve(registry: &JobRegistry, job_id: &str, file_path: &str) -> bool {
+    let mut jobs = registry.lock().unwrap();
+    if jobs.contains_key(job_id) {
+        return false;
+    }
+    jobs.insert(job_id.to_string(), JobRecord {
+        id: job_id.to_string(),
+        file_path: file_path.to_string(),
+        title: None,
+        status: JobStatus::Queued,
+        pid: None,
+        transcript: None,
+        error: None,
+        output_dir: None,
+        processed_seconds: 0.0,
+        percent: None,
+    });
+    true
+}
+
+// The job with its transcript, rebuilt from history when the registry no longer has it
+// (after a restart or once finished jobs are forgotten). Reads history, so it blocks.
+pub fn find(registry: &JobRegistry, job_id: &str) -> Result<JobRecord, AppError> {
+    let record = get(registry, job_id);
+    if let Some(record) = record.as_ref().filter(|record| record.transcript.is_some()) {
+        return Ok(record.clone());
+    }
+    match history::get(job_id) {
+        Ok(entry) => Ok(JobRecord {
+            id: entry.job_id,
+            file_path: entry.source,
+            title: Some(entry.title),
+            status: JobStatus::Completed,
+            pid: None,
+            processed_seconds: entry.transcript.segments.last().map_or(0.0, |segment| segment.end),
+            transcript: Some(entry.transcript),
+            error: None,
+            output_dir: None,
+            percent: None,
+        }),
+        // A queued, running or failed job has no transcript yet, and no history entry
+        Err(e) => record.ok_or(e),
+    }
+}
+
+// Drop finished jobs, transcripts included, from memory
+pub fn forget_finished(registry: &JobRegistry) {
+    registry.lock().unwrap().retain(|_, record| !record.status.is_finished());
+}
+
+fn update(registry: &JobRegistry, job_id: &str, apply: impl FnOnce(&mut JobRecord)) {
+    if let Some(record) = registry.lock().unwrap().get_mut(job_id) {
+        apply(record);
+    }
+}
+
+pub fn set_transcript(registry: &JobRegistry, job_id: &str, transcript: Transcript) {
+    update(registry, job_id, |record| record.transcript = Some(transcript));
+}
+
+// Transcribe a file as a tracked job, publishing each segment and the outcome to the hub.
+// Blocks until the engine exits; call from spawn_blocking in async contexts.
+pub fn run_job(
+    registry: &JobRegistry,
+    hub: &StreamHub,
+    job_id: &str,
+    file_path: &str,
+    options: &TranscribeOptions,
+) -> Result<Transcript, AppError> {
+    let title = crate::integrations::calendar::title_for_recording(file_path);
+    registry.lock().unwrap().insert(job_id.to_string(), JobRecord {
+        id: job_id.to_string(),
+        file_path: file_path.to_string(),
+        title: title.clone(),
+        status: JobStatus::Queued,
+        pid: None,
+        transcript: None,
+        error: None,
+        output_dir: options.output_dir.clone(),
+        processed_seconds: 0.0,
+        percent: None,
+    });
+    events::emit(JobEvent::Added {
+        job_id: job_id.to_string(),
+        file_path: file_path.to_string(),
+        title,
+        engine: options.engine.clone(),
+        priority: options.priority,
+    });
+
+    temp::track(job_id, std::path::Path::new(file_path));
+    audit::record(Some(job_id), AuditAction::Read, "engine", file_path);
+    let _activity = idle::job_started();
+
+    // For progress; local files only, URLs and failed probes report segments without a percentage
+    let duration = engine::probe_duration(file_path).filter(|duration| *duration > 0.0);
+    let produced_segments = std::cell::Cell::new(false);
+    let spotter = keywords::Spotter::new(job_id);
+    let mut on_start = |pid: u32| {
+        update(registry, job_id, |record| record.pid = Some(pid));
+        // A cancel that arrived before the engine started still has to stop it
+        if get(registry, job_id).is_some_and(|record| record.status == JobStatus::Cancelled) {
+            let _ = engine::kill_process(pid);
+        }
+    };
+    let mut on_segment = |segment: &Segment| {
+        produced_segments.set(true);
+        stream::publish(hub, StreamEvent::Final { job_id: job_id.to_string(), segment: segment.clone() });
+        events::emit(JobEvent::Segment { job_id: job_id.to_string(), segment: segment.clone() });
+        let percent = duration.map(|duration| (segment.end / duration * 100.0).clamp(0.0, 100.0));
+        update(registry, job_id, |record| {
+            record.processed_seconds = segment.end;
+            record.percent = percent;
+        });
+        events::emit(JobEvent::Progress {
+            job_id: job_id.to_string(),
+            processed_seconds: segment.end,
+            duration_seconds: duration,
+            percent,
+        });
+        spotter.scan(segment);
+    };
+
+    let is_cancelled = || get(registry, job_id).is_some_and(|record| record.status == JobStatus::Cancelled);
+    let engine_options = TranscribeOptions { job_id: Some(job_id.to_string()), ..options.clone() };
+    // Engine that ran last and when it started, for the usage metrics
+    let engine_run = std::cell::Cell::new(None::<(&'static str, std::time::Instant)>);
+    let mut result = engine::engine_for(&options.engine).and_then(|engine| {
+        let _permit = queue::acquire(engine.worker_class(), job_id, options.priority, is_cancelled)?;
+        update(registry, job_id, |record| {
+            if record.status == JobStatus::Queued {
+                record.status = JobStatus::Running;
+            }
+        });
+        journal::mark_started(job_id);
+        events::emit(JobEvent::Started { job_id: job_id.to_string(), engine: engine.name(), class: engine.worker_class() });
+        info!("Job {} running on the {} engine", job_id, engine.name());
+        engine_run.set(Some((engine.name(), std::time::Instant::now())));
+        engine.transcribe(file_path, &engine_options, &mut on_start, &mut on_segment)
+    });
+
+    // Cloud fallback only when opted in for this job, and never after a cancel or partial output
+    if let Err(e) = &result {
+        if options.cloud_fallback && options.engine != "openai" && !is_cancelled() && !produced_segments.get() {
+            warn!("Local engine failed for job {} ({}); retrying with the OpenAI API", job_id, e);
+            update(registry, job_id, |record| record.pid = None);
+            result = engine::engine_for("openai").and_then(|engine| {
+                let _permit = queue::acquire(engine.worker_class(), job_id, options.priority, is_cancelled)?;
+                events::emit(JobEvent::Started { job_id: job_id.to_string(), engine: engine.name(), class: engine.worker_class() });
+                engine_run.set(Some((engine.name(), std::time::Instant::now())));
+                engine.transcribe(file_path, &engine_options, &mut on_start, &mut on_segment)
+            });
+        }
+    }
+
+    let result = if is_cancelled() { Err(AppError::cancelled("Job cancelled")) } else { result };
+    if let Some((engine_name, started)) = engine_run.get() {
+        let outcome = match &result {
+            Ok(_) => telemetry::JobOutcome::Completed,
+            Err(_) if is_cancelled() => telemetry::JobOutcome::Cancelled,
+            Err(_) => telemetry::JobOutcome::Failed,
+        };
+        telemetry::record_job(engine_name, outcome, duration, Some(started.elapsed()));
+    }
+    let result = result.map(|mut transcript| {
+        postprocess::apply(&mut transcript, options);
+        languages::post_process(&mut transcript);
+        transcript
+    });
+    temp::finish(job_id, result.is_ok());
+
+    match &result {
+        Ok(transcript) => {
+            update(registry, job_id, |record| {
+                record.status = JobStatus::Completed;
+                record.transcript = Some(transcript.clone());
+                record.pid = None;
+            });
+            stream::publish(hub, StreamEvent::Completed { job_id: job_id.to_string(), text: transcript.text.clone() });
+            events::emit(JobEvent::Completed {
+                job_id: job_id.to_string(),
+                text: transcript.text.clone(),
+                language: transcript.language.clone(),
+            });
+            // Saved to history so the transcript can still be exported after a restart
+            if let Err(e) = editing::ensure_in_history(registry, job_id) {
+                warn!("Could not save job {} to history: {}", job_id, e);
+            }
+            outputs::auto_save(registry, job_id, &options.save_formats, options.append_to.as_deref());
+        }
+        Err(e) => {
+            update(registry, job_id, |record| {
+                if record.status != JobStatus::Cancelled {
+                    record.status = JobStatus::Failed;
+                }
+                record.error = Some(e.to_string());
+                record.pid = None;
+            });
+            stream::publish(hub, StreamEvent::Failed { job_id: job_id.to_string(), error: e.to_string() });
+            events::emit(JobEvent::Failed { job_id: job_id.to_string(), error: e.to_string(), cancelled: is_cancelled() });
+        }
+    }
+    if registry.lock().unwrap().values().all(|record| record.status.is_finished()) {
+        events::emit(JobEvent::QueueDrained {});
+    }
+    result
+}
+
+// Stop a running job by killing its engine process
+pub fn cancel(registry: &JobRegistry, job_id: &str) -> Result<(), AppError> {
+    let pid = {
+        let mut jobs = registry.lock().unwrap();
+        let record = jobs.get_mut(job_id).ok_or_else(|| AppError::unknown(format!("Unknown job: {}", job_id)))?;
+        if record.status.is_finished() {
+            return Err(format!("Job {} has already finished", job_id).into());
+        }
+        journal::log(journal::JournalRecord::Cancelled { job_id: job_id.to_string() });
+        record.status = JobStatus::Cancelled;
+        record.pid
+    };
+    // A queued job gives up its place in line
+    queue::notify();
+    if let Some(pid) = pid {
+        info!("Cancelling job {} (PID {})", job_id, pid);
+        engine::kill_process(pid)?;
+    } else if !crate::backend_api::cancel(job_id) {
+        // Jobs on the backend server and the mock engine have no process of their own
+        engine::mock::cancel(job_id);
+    }
+    Ok(())
+}
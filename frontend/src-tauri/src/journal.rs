@@ -0,0 +1,434 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::config;
+use crate::engine::{TranscribeOptions, Transcript};
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::queue::Priority;
+use crate::stream::StreamHub;
+
+// Serializes appends and compaction of queue_journal.jsonl across worker threads
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+// The journal is rewritten with only the jobs in flight once it grows past this
+const COMPACT_BYTES: u64 = 1024 * 1024;
+
+// A job found in flight after this many launches is dropped instead of retried,
+// so a file that takes the app down cannot do so on every start
+const MAX_ATTEMPTS: u32 = 3;
+
+// A submitted job that has not finished yet
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JournalEntry {
+    pub job_id: String,
+    pub file_path: String,
+    pub options: TranscribeOptions,
+    // The input is a staged copy (download, gRPC upload) to delete once the job is done
+    #[serde(default)]
+    pub temporary: bool,
+    // Set when the job leaves the queue and starts on an engine
+    #[serde(default)]
+    pub started: bool,
+    // Launches on which the job was found in flight and queued again
+    #[serde(default)]
+    pub attempts: u32,
+    // RFC 3339
+    pub queued_at: String,
+}
+
+// A queue mutation, appended before it is applied. Replaying the records in order
+// gives the jobs that were queued or running when the app stopped.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalRecord {
+    Queued { entry: Box<JournalEntry> },
+    Started { job_id: String },
+    Priority { job_id: String, priority: Priority },
+    // Position among the jobs waiting in its pool, as given to queue::move_to
+    Moved { job_id: String, position: usize },
+    Cancelled { job_id: String },
+    Finished { job_id: String },
+}
+
+fn journal_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("queue_journal.jsonl"))
+}
+
+// The snapshot file used before the journal became append-only; read once to migrate
+fn legacy_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("queue_journal.json"))
+}
+
+fn read_records(path: &Path) -> Result<Vec<JournalRecord>, AppError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read queue journal: {}", e))?;
+    let mut records = Vec::new();
+    for (number, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            // Usually the last line, cut short by a crash mid-append
+            Err(e) => warn!("Skipping unreadable queue journal line {}: {}", number + 1, e),
+        }
+    }
+    Ok(records)
+}
+
+// Jobs still in flight after `records`, in queue order
+fn replay(records: Vec<JournalRecord>) -> Vec<JournalEntry> {
+    let mut entries: Vec<JournalEntry> = Vec::new();
+    for record in records {
+        match record {
+            JournalRecord::Queued { entry } => {
+                entries.retain(|existing| existing.job_id != entry.job_id);
+                entries.push(*entry);
+            }
+            JournalRecord::Started { job_id } => {
+                if let Some(entry) = entries.iter_mut().find(|entry| entry.job_id == job_id) {
+                    entry.started = true;
+                }
+            }
+            JournalRecord::Priority { job_id, priority } => {
+                if let Some(entry) = entries.iter_mut().find(|entry| entry.job_id == job_id) {
+                    entry.options.priority = priority;
+                }
+            }
+            JournalRecord::Moved { job_id, position } => {
+                let Some(index) = entries.iter().position(|entry| entry.job_id == job_id) else { continue };
+                let entry = entries.remove(index);
+                // Pools are not journaled, so the position counts every waiting job
+                let target = entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| !entry.started)
+                    .nth(position)
+                    .map_or(entries.len(), |(index, _)| index);
+                entries.insert(target, entry);
+            }
+            JournalRecord::Cancelled { job_id } | JournalRecord::Finished { job_id } => {
+                entries.retain(|entry| entry.job_id != job_id);
+            }
+        }
+    }
+    entries
+}
+
+fn read_entries(path: &Path) -> Result<Vec<JournalEntry>, AppError> {
+    read_records(path).map(replay)
+}
+
+// Append one record and flush it to disk before the caller acts on it
+fn append(path: &Path, record: &JournalRecord) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let mut line = serde_json::to_string(record).map_err(|e| format!("Failed to serialize queue journal: {}", e))?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open queue journal: {}", e))?;
+    file.write_all(line.as_bytes()).map_err(|e| format!("Failed to write queue journal: {}", e))?;
+    file.sync_data().map_err(|e| format!("Failed to write queue journal: {}", e))?;
+    if file.metadata().is_ok_and(|metadata| metadata.len() > COMPACT_BYTES) {
+        compact(path)?;
+    }
+    Ok(())
+}
+
+// Replace the journal with one Queued record per job in flight.
+// Write-then-rename so a crash mid-write cannot leave a truncated journal.
+fn write_entries(path: &Path, entries: &[JournalEntry]) -> Result<(), AppError> {
+    let mut contents = String::new();
+    for entry in entries {
+        let record = JournalRecord::Queued { entry: Box::new(entry.clone()) };
+        contents.push_str(&serde_json::to_string(&record).map_err(|e| format!("Failed to serialize queue journal: {}", e))?);
+        contents.push('\n');
+        if entry.started {
+            let record = JournalRecord::Started { job_id: entry.job_id.clone() };
+            contents.push_str(&serde_json::to_string(&record).map_err(|e| format!("Failed to serialize queue journal: {}", e))?);
+            contents.push('\n');
+        }
+    }
+    let staging = path.with_extension("jsonl.tmp");
+    let mut file = std::fs::File::create(&staging).map_err(|e| format!("Failed to write queue journal: {}", e))?;
+    file.write_all(contents.as_bytes()).map_err(|e| format!("Failed to write queue journal: {}", e))?;
+    file.sync_data().map_err(|e| format!("Failed to write queue journal: {}", e))?;
+    std::fs::rename(&staging, path).map_err(|e| format!("Failed to write queue journal: {}", e).into())
+}
+
+fn compact(path: &Path) -> Result<(), AppError> {
+    write_entries(path, &read_entries(path)?)
+}
+
+// Record a queue mutation under the journal lock. Losing a record only costs crash
+// recovery, not the mutation, so failures are logged and the caller carries on.
+pub fn log(record: JournalRecord) {
+    let _guard = JOURNAL_LOCK.lock().unwrap();
+    if let Err(e) = journal_path().and_then(|path| append(&path, &record)) {
+        warn!("{}", e);
+    }
+}
+
+// Entries left in the old snapshot file by an earlier version
+fn read_legacy() -> Result<Vec<JournalEntry>, AppError> {
+    let path = legacy_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read queue journal: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse queue journal: {}", e).into())
+}
+
+fn read_entries_with_legacy() -> Result<Vec<JournalEntry>, AppError> {
+    let mut entries = read_entries(&journal_path()?)?;
+    entries.extend(read_legacy()?);
+    Ok(entries)
+}
+
+// Inputs of jobs that have not finished, so temp cleanup leaves them in place
+pub fn pending_files() -> Vec<PathBuf> {
+    let _guard = JOURNAL_LOCK.lock().unwrap();
+    match journal_path().and_then(|path| read_entries(&path)) {
+        Ok(entries) => entries.into_iter().map(|entry| PathBuf::from(entry.file_path)).collect(),
+        Err(e) => {
+            warn!("{}", e);
+            Vec::new()
+        }
+    }
+}
+
+// Called by run_job when a job leaves the queue for an engine
+pub fn mark_started(job_id: &str) {
+    log(JournalRecord::Started { job_id: job_id.to_string() });
+}
+
+fn finish(entry: &JournalEntry) {
+    log(JournalRecord::Finished { job_id: entry.job_id.clone() });
+    if entry.temporary {
+        let _ = std::fs::remove_file(&entry.file_path);
+    }
+}
+
+fn run_entry(registry: &JobRegistry, hub: &StreamHub, entry: &JournalEntry) -> Result<Transcript, AppError> {
+    let result = jobs::run_job(registry, hub, &entry.job_id, &entry.file_path, &entry.options);
+    finish(entry);
+    result
+}
+
+// run_job for jobs that should survive a quit or crash: the job stays in the journal
+// until it finishes, whatever the outcome. `temporary` inputs are deleted afterwards.
+pub fn run(
+    registry: &JobRegistry,
+    hub: &StreamHub,
+    job_id: &str,
+    file_path: &str,
+    options: &TranscribeOptions,
+    temporary: bool,
+) -> Result<Transcript, AppError> {
+    let entry = JournalEntry {
+        job_id: job_id.to_string(),
+        file_path: file_path.to_string(),
+        options: options.clone(),
+        temporary,
+        started: false,
+        attempts: 0,
+        queued_at: chrono::Local::now().to_rfc3339(),
+    };
+    log(JournalRecord::Queued { entry: Box::new(entry.clone()) });
+    run_entry(registry, hub, &entry)
+}
+
+// Pick which journaled jobs to queue again on launch. Jobs that were running count an
+// attempt; those over the limit or whose input is gone are dropped.
+fn plan_restore(entries: Vec<JournalEntry>) -> (Vec<JournalEntry>, Vec<JournalEntry>) {
+    let (mut restored, mut dropped) = (Vec::new(), Vec::new());
+    for mut entry in entries {
+        if !Path::new(&entry.file_path).exists() {
+            warn!("Dropping journaled job {}: {} no longer exists", entry.job_id, entry.file_path);
+            dropped.push(entry);
+            continue;
+        }
+        if entry.started {
+            entry.attempts += 1;
+            entry.started = false;
+            if entry.attempts >= MAX_ATTEMPTS {
+                warn!("Dropping journaled job {}: it was interrupted {} times", entry.job_id, entry.attempts);
+                dropped.push(entry);
+                continue;
+            }
+            info!("Job {} was running when the app closed; retrying it", entry.job_id);
+        }
+        restored.push(entry);
+    }
+    // Interrupted jobs go back in line first, the rest in their journaled queue order
+    restored.sort_by_key(|entry| entry.attempts == 0);
+    (restored, dropped)
+}
+
+// Queue the jobs left over from the previous session under their original ids
+pub fn restore(hub: StreamHub, registry: JobRegistry) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let restored = {
+            let _guard = JOURNAL_LOCK.lock().unwrap();
+            let entries = match read_entries_with_legacy() {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("{}", e);
+                    return;
+                }
+            };
+            let (restored, dropped) = plan_restore(entries);
+            for entry in dropped.iter().filter(|entry| entry.temporary) {
+                let _ = std::fs::remove_file(&entry.file_path);
+            }
+            // Start the new session's journal from the restored jobs alone
+            match journal_path().and_then(|path| write_entries(&path, &restored)) {
+                Ok(()) => {
+                    if let Ok(legacy) = legacy_path() {
+                        let _ = std::fs::remove_file(legacy);
+                    }
+                }
+                Err(e) => warn!("{}", e),
+            }
+            restored
+        };
+        if !restored.is_empty() {
+            info!("Restoring {} queued job(s) from the previous session", restored.len());
+        }
+        for entry in restored {
+            let (hub, registry) = (hub.clone(), registry.clone());
+            tauri::async_runtime::spawn_blocking(move || {
+                let _ = run_entry(&registry, &hub, &entry);
+            });
+            // Let each job take its place in the queue before the next one arrives
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("web-whisper-test-{}-{}", name, crate::engine::new_job_id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(job_id: &str, file_path: &Path) -> JournalEntry {
+        JournalEntry {
+            job_id: job_id.to_string(),
+            file_path: file_path.to_string_lossy().to_string(),
+            options: TranscribeOptions::default(),
+            temporary: false,
+            started: false,
+            attempts: 0,
+            queued_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    fn queued(job_id: &str, file_path: &Path) -> JournalRecord {
+        JournalRecord::Queued { entry: Box::new(entry(job_id, file_path)) }
+    }
+
+    fn job_ids(entries: &[JournalEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.job_id.as_str()).collect()
+    }
+
+    #[test]
+    fn truncated_last_line_is_skipped() {
+        let dir = scratch_dir("journal-truncated");
+        let path = dir.join("queue_journal.jsonl");
+        append(&path, &queued("job-a", Path::new("a.mp3"))).unwrap();
+        append(&path, &queued("job-b", Path::new("b.mp3"))).unwrap();
+        // A crash mid-append leaves half a record with no newline
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"op":"finished","job_"#).unwrap();
+
+        assert_eq!(read_records(&path).unwrap().len(), 2);
+        assert_eq!(job_ids(&read_entries(&path).unwrap()), ["job-a", "job-b"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compaction_keeps_only_jobs_in_flight() {
+        let dir = scratch_dir("journal-compact");
+        let path = dir.join("queue_journal.jsonl");
+        for index in 0..20 {
+            let job_id = format!("job-{}", index);
+            append(&path, &queued(&job_id, Path::new("a.mp3"))).unwrap();
+            append(&path, &JournalRecord::Finished { job_id }).unwrap();
+        }
+        append(&path, &queued("job-left", Path::new("a.mp3"))).unwrap();
+        append(&path, &JournalRecord::Started { job_id: "job-left".to_string() }).unwrap();
+        append(&path, &queued("job-waiting", Path::new("b.mp3"))).unwrap();
+        let before = std::fs::metadata(&path).unwrap().len();
+
+        compact(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < before);
+        assert_eq!(read_records(&path).unwrap().len(), 3);
+        let entries = read_entries(&path).unwrap();
+        assert_eq!(job_ids(&entries), ["job-left", "job-waiting"]);
+        assert!(entries[0].started && !entries[1].started);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replay_applies_records_in_order() {
+        let records = vec![
+            queued("job-a", Path::new("a.mp3")),
+            queued("job-b", Path::new("b.mp3")),
+            queued("job-c", Path::new("c.mp3")),
+            JournalRecord::Priority { job_id: "job-b".to_string(), priority: Priority::High },
+            JournalRecord::Moved { job_id: "job-c".to_string(), position: 0 },
+            JournalRecord::Cancelled { job_id: "job-a".to_string() },
+        ];
+        let entries = replay(records);
+        assert_eq!(job_ids(&entries), ["job-c", "job-b"]);
+        assert_eq!(entries[1].options.priority, Priority::High);
+    }
+
+    #[test]
+    fn interrupted_jobs_are_queued_again_first() {
+        let dir = scratch_dir("journal-restore");
+        let (running, waiting) = (dir.join("running.mp3"), dir.join("waiting.mp3"));
+        std::fs::write(&running, b"x").unwrap();
+        std::fs::write(&waiting, b"x").unwrap();
+        let records = vec![
+            queued("job-waiting", &waiting),
+            queued("job-running", &running),
+            JournalRecord::Started { job_id: "job-running".to_string() },
+            queued("job-gone", &dir.join("deleted.mp3")),
+        ];
+
+        let (restored, dropped) = plan_restore(replay(records));
+        assert_eq!(job_ids(&restored), ["job-running", "job-waiting"]);
+        assert!(restored.iter().all(|entry| !entry.started));
+        assert_eq!(restored[0].attempts, 1);
+        assert_eq!(job_ids(&dropped), ["job-gone"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn jobs_interrupted_too_often_are_dropped() {
+        let dir = scratch_dir("journal-attempts");
+        let file = dir.join("crash.mp3");
+        std::fs::write(&file, b"x").unwrap();
+        let mut crashing = entry("job-crash", &file);
+        crashing.started = true;
+        crashing.attempts = MAX_ATTEMPTS - 1;
+
+        let (restored, dropped) = plan_restore(vec![crashing]);
+        assert!(restored.is_empty());
+        assert_eq!(job_ids(&dropped), ["job-crash"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use crate::engine::{Segment, Transcript};
+use crate::error::AppError;
+
+// 1080p canvas; players scale the styles with the video
+const ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+PlayResX: 1920
+PlayResY: 1080
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Karaoke,Arial,64,&H0000FFFF,&H00FFFFFF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,3,0,2,60,60,60,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+fn require_words(transcript: &Transcript) -> Result<(), AppError> {
+    if transcript.segments.iter().all(|segment| segment.words.is_empty()) {
+        return Err("Transcript has no word timings; transcribe it with word_timestamps turned on".into());
+    }
+    Ok(())
+}
+
+// Segments with their words, for caption renderers that animate word by word
+pub fn to_word_json(transcript: &Transcript) -> Result<String, AppError> {
+    require_words(transcript)?;
+    let document = serde_json::json!({
+        "language": transcript.language,
+        "duration": transcript.duration,
+        "segments": transcript.segments,
+    });
+    serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to serialize word timings: {}", e).into())
+}
+
+// 0:01:02.34; ASS times have centisecond precision
+pub fn ass_timestamp(seconds: f64) -> String {
+    let total_cs = (seconds.max(0.0) * 100.0).round() as u64;
+    format!("{}:{:02}:{:02}.{:02}", total_cs / 360_000, total_cs / 6000 % 60, total_cs / 100 % 60, total_cs % 100)
+}
+
+// Braces start override blocks and a backslash starts a tag in ASS text
+pub fn ass_text(text: &str) -> String {
+    text.replace('\\', "/").replace('{', "(").replace('}', ")").replace('\n', "\\N")
+}
+
+// One Dialogue line per segment; each word fills in over its own duration (\kf)
+fn karaoke_line(segment: &Segment) -> String {
+    if segment.words.is_empty() {
+        return ass_text(segment.text.trim());
+    }
+    // Tag lengths come from rounded offsets so they never drift from the audio
+    let offset = |seconds: f64| ((seconds - segment.start).max(0.0) * 100.0).round() as i64;
+    let mut line = String::new();
+    let mut position = 0;
+    for (index, word) in segment.words.iter().enumerate() {
+        let (start, end) = (offset(word.start), offset(word.end).max(offset(word.start)));
+        if start > position {
+            line.push_str(&format!("{{\\k{}}}", start - position));
+        }
+        let text = if index == 0 { word.text.trim_start() } else { word.text.as_str() };
+        line.push_str(&format!("{{\\kf{}}}{}", (end - start.max(position)).max(0), ass_text(text)));
+        position = end.max(position);
+    }
+    line
+}
+
+pub fn to_ass(transcript: &Transcript) -> Result<String, AppError> {
+    require_words(transcript)?;
+    let mut out = String::from(ASS_HEADER);
+    for segment in &transcript.segments {
+        let end = segment.words.last().map_or(segment.end, |word| word.end.max(segment.end));
+        out.push_str(&format!(
+            "Dialogue: 0,{},{},Karaoke,,0,0,0,,{}\n",
+            ass_timestamp(segment.start),
+            ass_timestamp(end),
+            karaoke_line(segment)
+        ));
+    }
+    Ok(out)
+}
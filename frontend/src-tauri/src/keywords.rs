@@ -0,0 +1,92 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use tracing::warn;
+
+use crate::engine::Segment;
+use crate::error::AppError;
+use crate::events::{self, JobEvent};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct KeywordConfig {
+    // Matched case-insensitively in each finished segment; ASCII keywords only as whole words
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    // Also show a system notification, once per keyword and job
+    #[serde(default)]
+    pub notify: bool,
+}
+
+pub fn keyword_config() -> KeywordConfig {
+    crate::config::load().keywords.unwrap_or_default()
+}
+
+fn compile(keyword: &str) -> Result<Regex, AppError> {
+    // \b never matches inside CJK text, where a word runs into the following particle
+    let pattern = if keyword.is_ascii() {
+        format!(r"(?i)\b{}\b", regex::escape(keyword))
+    } else {
+        format!("(?i){}", regex::escape(keyword))
+    };
+    Regex::new(&pattern).map_err(|e| AppError::invalid(format!("Invalid keyword '{}': {}", keyword, e)))
+}
+
+pub fn validate(config: &KeywordConfig) -> Result<(), AppError> {
+    for keyword in &config.keywords {
+        compile(keyword.trim())?;
+    }
+    Ok(())
+}
+
+// Scans one job's segments as they arrive
+pub struct Spotter {
+    job_id: String,
+    keywords: Vec<(String, Regex)>,
+    notify: bool,
+    notified: RefCell<HashSet<String>>,
+}
+
+impl Spotter {
+    // Keywords are read once per job, so edits apply from the next job on
+    pub fn new(job_id: &str) -> Spotter {
+        let config = keyword_config();
+        let keywords = config
+            .keywords
+            .iter()
+            .map(|keyword| keyword.trim())
+            .filter(|keyword| !keyword.is_empty())
+            .filter_map(|keyword| match compile(keyword) {
+                Ok(regex) => Some((keyword.to_string(), regex)),
+                Err(e) => {
+                    warn!("{}", e);
+                    None
+                }
+            })
+            .collect();
+        Spotter { job_id: job_id.to_string(), keywords, notify: config.notify, notified: RefCell::new(HashSet::new()) }
+    }
+
+    pub fn scan(&self, segment: &Segment) {
+        for (keyword, regex) in &self.keywords {
+            if !regex.is_match(&segment.text) {
+                continue;
+            }
+            events::emit(JobEvent::KeywordHit {
+                job_id: self.job_id.clone(),
+                keyword: keyword.clone(),
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.trim().to_string(),
+            });
+            if self.notify && self.notified.borrow_mut().insert(keyword.clone()) {
+                events::notify(&format!("\"{}\" mentioned", keyword), &format!("{} {}", timestamp(segment.start), segment.text.trim()));
+            }
+        }
+    }
+}
+
+fn timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!("[{:02}:{:02}:{:02}]", total / 3600, total / 60 % 60, total % 60)
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+use crate::engine::{self, LocalEngine, Transcript, TranscriptionEngine};
+use crate::error::AppError;
+use crate::queue::{self, Priority};
+use crate::replace::{self, FindQuery};
+use crate::{events, folders};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReplaceRule {
+    #[serde(flatten)]
+    pub query: FindQuery,
+    pub replacement: String,
+}
+
+// Settings for audio in one language; unset fields keep the global defaults
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LanguageProfile {
+    // Local engine model, e.g. large-v3 for Japanese or small for English memos
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub compute_type: Option<String>,
+    // Initial prompt with names, jargon or the punctuation style to follow
+    #[serde(default)]
+    pub prompt: Option<String>,
+    // Find/replace rules run on every finished transcript in this language, in order
+    #[serde(default)]
+    pub rules: Vec<ReplaceRule>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LanguageConfig {
+    // Keyed by language code as Whisper reports it: "ja", "en", ...
+    #[serde(default)]
+    pub profiles: BTreeMap<String, LanguageProfile>,
+    // Model for the quick detection pass on jobs with language "auto"
+    #[serde(default = "default_detect_model")]
+    pub detect_model: String,
+}
+
+fn default_detect_model() -> String {
+    "base".to_string()
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        LanguageConfig { profiles: BTreeMap::new(), detect_model: default_detect_model() }
+    }
+}
+
+pub fn language_config() -> LanguageConfig {
+    crate::config::load().languages.unwrap_or_default()
+}
+
+pub fn validate(config: &LanguageConfig) -> Result<(), AppError> {
+    for (language, profile) in &config.profiles {
+        for rule in &profile.rules {
+            replace::validate(&rule.query).map_err(|e| AppError::invalid(format!("{} (language '{}')", e, language)))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn profile_for(language: &str) -> Option<LanguageProfile> {
+    let language = language.to_lowercase();
+    // "ja-JP" from cloud engines matches a "ja" profile
+    let base = language.split(['-', '_']).next().unwrap_or(&language).to_string();
+    let profiles = language_config().profiles;
+    profiles.get(&language).or_else(|| profiles.get(&base)).cloned()
+}
+
+// Apply the rules of the transcript's language; a rule that fails is logged and skipped
+pub fn post_process(transcript: &mut Transcript) {
+    let Some(profile) = transcript.language.as_deref().and_then(profile_for) else { return };
+    for rule in &profile.rules {
+        match replace::replace_in(transcript, &rule.query, &rule.replacement) {
+            Ok(0) => {}
+            Ok(replaced) => info!("Rule '{}' replaced {} match(es)", rule.query.pattern, replaced),
+            Err(e) => warn!("Skipping post-processing rule: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileLanguage {
+    pub file_path: String,
+    pub language: Option<String>,
+    pub probability: Option<f64>,
+    // Model the language profile picks for this file; None means the global default
+    pub model: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LanguageReport {
+    pub files: Vec<FileLanguage>,
+    // Files per detected language; "unknown" counts files that failed or could not be told
+    pub by_language: BTreeMap<String, usize>,
+}
+
+// Detect the language of files and of the media inside folders before queuing them, so a
+// mixed-language archive can be sent to the right models. Each file is also sent as a
+// "language-detect-progress" event. Blocking; call from spawn_blocking.
+pub fn detect_report(paths: &[String], recursive: bool) -> Result<LanguageReport, AppError> {
+    let files: Vec<String> = folders::expand(paths, recursive)?
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    if files.is_empty() {
+        return Err(AppError::invalid("Invalid paths: no media files found"));
+    }
+    let id = format!("detect-{}", engine::new_job_id().trim_start_matches("job-"));
+    // The detection model shares the device with local jobs
+    let _permit = queue::acquire(LocalEngine.worker_class(), &id, Priority::High, || false)?;
+    info!("Detecting the language of {} file(s)", files.len());
+
+    let total = files.len();
+    let mut results: Vec<FileLanguage> = Vec::new();
+    engine::detect_languages(&files, |detected| {
+        let model = detected.language.as_deref().and_then(profile_for).and_then(|profile| profile.model);
+        let result = FileLanguage {
+            file_path: detected.file_path,
+            language: detected.language,
+            probability: detected.probability,
+            model,
+            error: detected.error,
+        };
+        events::emit_named(
+            "language-detect-progress",
+            &serde_json::json!({ "done": results.len() + 1, "total": total, "file": result }),
+        );
+        results.push(result);
+    })?;
+
+    // Report in the order given; files the backend skipped show up as failures
+    let files: Vec<FileLanguage> = files
+        .into_iter()
+        .map(|file_path| {
+            let index = results.iter().position(|result| result.file_path == file_path);
+            index.map(|index| results.swap_remove(index)).unwrap_or_else(|| FileLanguage {
+                file_path,
+                language: None,
+                probability: None,
+                model: None,
+                error: Some("No result from language detection".to_string()),
+            })
+        })
+        .collect();
+    let mut by_language = BTreeMap::new();
+    for file in &files {
+        *by_language.entry(file.language.clone().unwrap_or_else(|| "unknown".to_string())).or_insert(0) += 1;
+    }
+    Ok(LanguageReport { files, by_language })
+}
@@ -0,0 +1,121 @@
+use serde::Serialize;
+
+use crate::captions;
+use crate::engine::Segment;
+use crate::subtitle::format_timestamp;
+
+// Common broadcast and platform limit for one subtitle line
+const DEFAULT_MAX_LINE_LENGTH: usize = 42;
+
+// The caption settings' line limit when one is set
+pub fn default_max_line_length() -> usize {
+    captions::caption_config().max_chars_per_line.unwrap_or(DEFAULT_MAX_LINE_LENGTH)
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    // Platforms reject the file or players show it wrong
+    Error,
+    // Valid, but hard to read
+    Warning,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    OutOfOrder,
+    Overlap,
+    ZeroDuration,
+    EmptyCue,
+    LineTooLong,
+    ReadingSpeed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LintIssue {
+    pub kind: IssueKind,
+    pub severity: Severity,
+    // 1-based, as numbered in the SRT file
+    pub cue: usize,
+    pub start: f64,
+    pub end: f64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LintReport {
+    pub cues: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    // In cue order
+    pub issues: Vec<LintIssue>,
+}
+
+fn issue(kind: IssueKind, severity: Severity, index: usize, segment: &Segment, message: String) -> LintIssue {
+    LintIssue { kind, severity, cue: index + 1, start: segment.start, end: segment.end, message }
+}
+
+// Check cues as they would be written to an SRT file
+pub fn check(segments: &[Segment], max_line_length: usize, max_cps: Option<f64>) -> LintReport {
+    let mut issues = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        if let Some(previous) = index.checked_sub(1).map(|previous| &segments[previous]) {
+            if segment.start < previous.start {
+                issues.push(issue(
+                    IssueKind::OutOfOrder,
+                    Severity::Error,
+                    index,
+                    segment,
+                    format!("Starts at {}, before cue {} at {}", format_timestamp(segment.start, ','), index, format_timestamp(previous.start, ',')),
+                ));
+            } else if segment.start < previous.end {
+                issues.push(issue(
+                    IssueKind::Overlap,
+                    Severity::Error,
+                    index,
+                    segment,
+                    format!("Starts at {}, before cue {} ends at {}", format_timestamp(segment.start, ','), index, format_timestamp(previous.end, ',')),
+                ));
+            }
+        }
+        // Millisecond precision is all SRT keeps
+        if (segment.end - segment.start) < 0.001 {
+            issues.push(issue(
+                IssueKind::ZeroDuration,
+                Severity::Error,
+                index,
+                segment,
+                format!("Ends at {}, no later than it starts", format_timestamp(segment.end, ',')),
+            ));
+        }
+        if segment.text.trim().is_empty() {
+            issues.push(issue(IssueKind::EmptyCue, Severity::Warning, index, segment, "Has no text".to_string()));
+        }
+        for (line_number, line) in segment.text.trim().lines().enumerate() {
+            let length = line.trim().chars().count();
+            if length > max_line_length {
+                issues.push(issue(
+                    IssueKind::LineTooLong,
+                    Severity::Warning,
+                    index,
+                    segment,
+                    format!("Line {} has {} characters; the limit is {}", line_number + 1, length, max_line_length),
+                ));
+            }
+        }
+        if let (Some(max_cps), Some(cps)) = (max_cps, captions::cps(segment)) {
+            if cps > max_cps {
+                issues.push(issue(
+                    IssueKind::ReadingSpeed,
+                    Severity::Warning,
+                    index,
+                    segment,
+                    format!("Reads at {:.1} characters per second; the limit is {}", cps, max_cps),
+                ));
+            }
+        }
+    }
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    LintReport { cues: segments.len(), errors, warnings: issues.len() - errors, issues }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::engine::{self, Segment};
+use crate::error::AppError;
+use crate::stream::{self, StreamEvent, StreamHub};
+use crate::{events, meeting};
+
+// PCM format shared with transcribe_simple.py --live
+const SAMPLE_RATE: &str = "16000";
+
+const DEFAULT_CHUNK_SECONDS: f64 = 5.0;
+
+// Where a live session's audio comes from
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveSource {
+    // The meeting microphone and loopback devices, captured by ffmpeg
+    Device,
+    // Frames sent by the webview with push_live_audio
+    App,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LiveStatus {
+    pub session_id: Option<String>,
+    pub source: Option<LiveSource>,
+    // RFC 3339
+    pub started_at: Option<String>,
+}
+
+struct Session {
+    id: String,
+    source: LiveSource,
+    started_at: String,
+    capture: Option<Child>,
+    engine: Child,
+    // Engine stdin for app-fed sessions; with device capture the pump thread owns it
+    input: Option<ChildStdin>,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LiveLine {
+    Ready,
+    Segment(Segment),
+}
+
+// transcribe_simple.py reading PCM from stdin, with the configured model and the
+// language profile's prompt
+fn spawn_engine(language: &str, chunk_seconds: f64) -> Result<Child, AppError> {
+    let backend_dir = engine::find_backend_dir("transcribe_simple.py")?;
+    let plan = crate::gpu::plan_model(&crate::gpu::local_model_config(), crate::gpu::query());
+    let profile = crate::languages::profile_for(language).unwrap_or_default();
+    let mut command = Command::new(engine::python_command());
+    command
+        .args([
+            backend_dir.join("transcribe_simple.py").to_str().unwrap(),
+            "-",
+            "--live",
+            "--language", language,
+            "--chunk-seconds", &chunk_seconds.to_string(),
+            "--model", &plan.model,
+            "--compute-type", &plan.compute_type,
+        ])
+        .current_dir(&backend_dir)
+        .env("PATH", engine::path_with_ffmpeg())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    engine::use_app_dirs(&mut command);
+    if let Some(prompt) = profile.prompt.as_deref().filter(|prompt| !prompt.trim().is_empty()) {
+        command.args(["--prompt", prompt]);
+    }
+    if let Some(threads) = crate::power::thread_limit() {
+        command.args(["--threads", &threads.to_string()]).env("OMP_NUM_THREADS", threads.to_string());
+    }
+    crate::proxy::configure_command(&mut command)?;
+    command.spawn().map_err(|e| format!("Failed to start live transcription: {}", e).into())
+}
+
+// ffmpeg mixing the meeting devices down to raw PCM on stdout; no file is written
+fn spawn_capture() -> Result<Child, AppError> {
+    let inputs = meeting::inputs(&meeting::meeting_config())?;
+    let mut command = Command::new("ffmpeg");
+    command.args(["-v", "error"]);
+    for input in &inputs {
+        command.args(input);
+    }
+    if inputs.len() > 1 {
+        command.args(["-filter_complex", &format!("amix=inputs={}:duration=longest", inputs.len())]);
+    }
+    command
+        .args(["-ac", "1", "-ar", SAMPLE_RATE, "-f", "s16le", "-"])
+        .env("PATH", engine::path_with_ffmpeg())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    command.spawn().map_err(|e| format!("Failed to start audio capture: {}", e).into())
+}
+
+// Forward segments to the stream hub and the webview until the engine exits
+fn read_segments(hub: StreamHub, session_id: String, stdout: ChildStdout) {
+    let mut texts = Vec::new();
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        match serde_json::from_str::<LiveLine>(&line) {
+            Ok(LiveLine::Ready) => events::emit_named("live-ready", &serde_json::json!({ "session_id": session_id })),
+            Ok(LiveLine::Segment(segment)) => {
+                texts.push(segment.text.trim().to_string());
+                events::emit_named("live-segment", &serde_json::json!({ "session_id": session_id, "segment": segment }));
+                stream::publish(&hub, StreamEvent::Final { job_id: session_id.clone(), segment });
+            }
+            Err(_) => {}
+        }
+    }
+    let text = texts.join(" ");
+    events::emit_named("live-stopped", &serde_json::json!({ "session_id": session_id, "text": text }));
+    stream::publish(&hub, StreamEvent::Completed { job_id: session_id.clone(), text });
+
+    // The engine ended on its own, e.g. the capture device went away
+    let mut session = SESSION.lock().unwrap();
+    if session.as_ref().is_some_and(|current| current.id == session_id) {
+        if let Some(current) = session.take() {
+            shut_down(current);
+        }
+    }
+}
+
+fn shut_down(mut session: Session) {
+    if let Some(mut capture) = session.capture.take() {
+        let _ = capture.kill();
+        let _ = capture.wait();
+    }
+    // Closing stdin makes the engine decode what it has buffered and exit
+    drop(session.input.take());
+    std::thread::spawn(move || {
+        let _ = session.engine.wait();
+        info!("Live session {} ended", session.id);
+    });
+}
+
+// Start a live session; returns its id, which segment events carry as job_id
+pub fn start(hub: StreamHub, source: LiveSource, language: Option<String>, chunk_seconds: Option<f64>) -> Result<String, AppError> {
+    crate::flags::require(crate::flags::Flag::LiveMode, "Live transcription")?;
+    let chunk_seconds = chunk_seconds.unwrap_or(DEFAULT_CHUNK_SECONDS);
+    if !(1.0..=30.0).contains(&chunk_seconds) {
+        return Err(AppError::invalid(format!("Invalid chunk_seconds {}: use 1 to 30", chunk_seconds)));
+    }
+    let mut session = SESSION.lock().unwrap();
+    if session.is_some() {
+        return Err(AppError::invalid("Invalid request: a live session is already running"));
+    }
+
+    let language = language.unwrap_or_else(|| "auto".to_string());
+    let mut engine = spawn_engine(&language, chunk_seconds)?;
+    let mut input = engine.stdin.take();
+    let capture = match source {
+        LiveSource::Device => match spawn_capture() {
+            Ok(mut capture) => {
+                if let (Some(mut pcm), Some(mut stdin)) = (capture.stdout.take(), input.take()) {
+                    // Frames go straight from ffmpeg to the engine; ends when capture stops
+                    std::thread::spawn(move || {
+                        if let Err(e) = std::io::copy(&mut pcm, &mut stdin) {
+                            debug!("Live audio pipe closed: {}", e);
+                        }
+                    });
+                }
+                Some(capture)
+            }
+            Err(e) => {
+                let _ = engine.kill();
+                let _ = engine.wait();
+                return Err(e);
+            }
+        },
+        LiveSource::App => None,
+    };
+
+    let id = format!("live-{}", engine::new_job_id().trim_start_matches("job-"));
+    if let Some(stdout) = engine.stdout.take() {
+        let (hub, id) = (hub.clone(), id.clone());
+        std::thread::spawn(move || read_segments(hub, id, stdout));
+    }
+    if let Some(stderr) = engine.stderr.take() {
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                debug!("Live engine: {}", line);
+            }
+        });
+    }
+    info!("Started live session {} ({:?}, {})", id, source, language);
+    *session = Some(Session { id: id.clone(), source, started_at: chrono::Local::now().to_rfc3339(), capture, engine, input });
+    Ok(id)
+}
+
+// Write PCM frames (16 kHz mono s16le) from the webview to the engine
+pub fn push(data: &[u8]) -> Result<(), AppError> {
+    let mut session = SESSION.lock().unwrap();
+    let session = session.as_mut().ok_or(AppError::invalid("Invalid request: no live session is running"))?;
+    if session.source != LiveSource::App {
+        return Err(AppError::invalid("Invalid request: the live session captures its own audio"));
+    }
+    let input = session.input.as_mut().ok_or("Live engine input is closed")?;
+    input.write_all(data).map_err(|e| format!("Failed to send audio to the live engine: {}", e).into())
+}
+
+// Stop capturing; the last buffered audio is still transcribed before `live-stopped`
+pub fn stop() -> Result<(), AppError> {
+    let session = SESSION.lock().unwrap().take().ok_or(AppError::invalid("Invalid request: no live session is running"))?;
+    info!("Stopping live session {}", session.id);
+    shut_down(session);
+    Ok(())
+}
+
+pub fn status() -> LiveStatus {
+    let session = SESSION.lock().unwrap();
+    LiveStatus {
+        session_id: session.as_ref().map(|session| session.id.clone()),
+        source: session.as_ref().map(|session| session.source),
+        started_at: session.as_ref().map(|session| session.started_at.clone()),
+    }
+}
+
@@ -0,0 +1,209 @@
+use std::collections::{HashSet, VecDeque};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::config;
+use crate::error::AppError;
+
+pub const DEFAULT_LEVEL: &str = "info";
+
+// Daily files; older ones beyond this count are deleted
+const MAX_LOG_FILES: usize = 7;
+
+// Most lines get_engine_log_tail returns at once
+pub const MAX_TAIL_LINES: usize = 5000;
+
+// How often a followed log is checked for new lines
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+// Bumped by each follow and unfollow; a follower thread stops once it no longer matches
+static FOLLOW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+static FILTER: OnceLock<FilterHandle> = OnceLock::new();
+// Flushes the background file writer on exit; must live as long as the process
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+pub fn log_dir() -> Result<PathBuf, AppError> {
+    if config::portable_root().is_none() && cfg!(target_os = "macos") {
+        let home = dirs::home_dir().ok_or("Failed to resolve home directory")?;
+        return Ok(home.join("Library").join("Logs").join("web-whisper"));
+    }
+    Ok(config::data_dir()?.join("logs"))
+}
+
+fn parse(directives: &str) -> Result<EnvFilter, AppError> {
+    EnvFilter::try_new(directives).map_err(|e| AppError::invalid(format!("Invalid log level '{}': {}", directives, e)))
+}
+
+// RUST_LOG wins over the saved setting, which wins over the default
+fn initial_filter() -> EnvFilter {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    }
+    config::load()
+        .log_level
+        .and_then(|directives| parse(&directives).ok())
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_LEVEL))
+}
+
+// Log to stderr and to rotating files in the log dir. stdout stays free for the JSON-RPC channel.
+pub fn init() {
+    let (filter, handle) = reload::Layer::new(initial_filter());
+    let stderr_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+
+    let appender = log_dir().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("web-whisper")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(&dir)
+            .map_err(|e| format!("Failed to open log file in {:?}: {}", dir, e).into())
+    });
+    let file_layer = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = FILE_GUARD.set(guard);
+            Some(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(writer))
+        }
+        Err(e) => {
+            eprintln!("{}; logging to stderr only", e);
+            None
+        }
+    };
+
+    if tracing_subscriber::registry().with(filter).with(stderr_layer).with(file_layer).try_init().is_ok() {
+        let _ = FILTER.set(handle);
+    }
+}
+
+// Delete log files not written to for `max_age`; returns how many were removed.
+// Rotation already caps the count at MAX_LOG_FILES, this caps their age.
+pub fn prune(max_age: Duration) -> usize {
+    let Ok(dir) = log_dir() else { return 0 };
+    let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut removed = 0;
+    crate::temp::sweep_dir(&dir, cutoff, &HashSet::new(), &mut removed);
+    removed
+}
+
+pub fn current_level() -> String {
+    config::load().log_level.unwrap_or_else(|| DEFAULT_LEVEL.to_string())
+}
+
+// Apply new directives (e.g. "info,web_whisper::engine=debug") now and remember them
+pub fn set_level(directives: &str) -> Result<(), AppError> {
+    let filter = parse(directives)?;
+    if let Some(handle) = FILTER.get() {
+        handle.reload(filter).map_err(|e| format!("Failed to apply log level: {}", e))?;
+    }
+    config::update(|app_config| {
+        app_config.log_level = Some(directives.to_string());
+        Ok(())
+    })
+}
+
+// Backend server output is logged with the "sidecar" target (see launch_server)
+fn is_engine_line(line: &str) -> bool {
+    line.contains(" sidecar: ")
+}
+
+// Log files, newest first
+pub fn log_files() -> Vec<PathBuf> {
+    let Ok(dir) = log_dir() else { return Vec::new() };
+    let Ok(entries) = std::fs::read_dir(&dir) else { return Vec::new() };
+    let mut files: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().is_some_and(|name| name.to_string_lossy().starts_with("web-whisper")))
+        .filter_map(|path| Some((std::fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .collect();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+// The last `lines` lines of backend output from the log files, oldest first,
+// reaching back into earlier days' files when today's has too few
+pub fn engine_tail(lines: usize) -> Result<Vec<String>, AppError> {
+    let lines = lines.clamp(1, MAX_TAIL_LINES);
+    let mut tail: VecDeque<String> = VecDeque::new();
+    for path in log_files() {
+        let contents = std::fs::read(&path).map_err(|e| format!("Failed to read log file {:?}: {}", path, e))?;
+        let contents = String::from_utf8_lossy(&contents);
+        for line in contents.lines().rev().filter(|line| is_engine_line(line)) {
+            if tail.len() >= lines {
+                break;
+            }
+            tail.push_front(line.to_string());
+        }
+        if tail.len() >= lines {
+            break;
+        }
+    }
+    Ok(tail.into())
+}
+
+// Complete lines appended to `path` since `offset`; moves `offset` past them
+fn read_new_lines(path: &Path, offset: &mut u64) -> Vec<String> {
+    let Ok(mut file) = std::fs::File::open(path) else { return Vec::new() };
+    let length = file.metadata().map_or(0, |metadata| metadata.len());
+    if length < *offset {
+        // Truncated or replaced; start over
+        *offset = 0;
+    }
+    let mut buffer = Vec::new();
+    if file.seek(SeekFrom::Start(*offset)).is_err() || file.read_to_end(&mut buffer).is_err() {
+        return Vec::new();
+    }
+    // A line still being written is picked up on the next check
+    let Some(end) = buffer.iter().rposition(|byte| *byte == b'\n') else { return Vec::new() };
+    *offset += end as u64 + 1;
+    String::from_utf8_lossy(&buffer[..end]).lines().filter(|line| is_engine_line(line)).map(str::to_string).collect()
+}
+
+// Send new backend output as "engine-log-follow" events ({lines}) until unfollow is called.
+// "engine-log" ({stream, line}) is the live feed from launch_server, a line at a time.
+// Following again replaces the earlier follower.
+pub fn follow_engine() {
+    let generation = FOLLOW_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        // Start at the current end; the tail covers what came before
+        let mut current = log_files().into_iter().next();
+        let mut offset = current.as_ref().and_then(|path| std::fs::metadata(path).ok()).map_or(0, |metadata| metadata.len());
+        while FOLLOW_GENERATION.load(Ordering::SeqCst) == generation {
+            std::thread::sleep(FOLLOW_INTERVAL);
+            let newest = log_files().into_iter().next();
+            if newest != current {
+                // Rotated to a new day's file
+                if let Some(previous) = &current {
+                    emit_lines(read_new_lines(previous, &mut offset));
+                }
+                current = newest;
+                offset = 0;
+            }
+            if let Some(path) = &current {
+                emit_lines(read_new_lines(path, &mut offset));
+            }
+        }
+    });
+}
+
+fn emit_lines(lines: Vec<String>) {
+    if !lines.is_empty() {
+        crate::events::emit_named("engine-log-follow", &serde_json::json!({ "lines": lines }));
+    }
+}
+
+pub fn unfollow_engine() {
+    FOLLOW_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
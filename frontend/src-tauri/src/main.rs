@@ -1,15 +1,90 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use tauri::{Manager, State, Emitter};
+mod api;
+mod archives;
+mod audit;
+mod audio;
+mod backend_api;
+mod backend_ui;
+mod benchmark;
+mod bookmarks;
+mod bundles;
+mod captions;
+mod chapters;
+mod config;
+mod crash;
+mod crypto;
+mod dashboard;
+mod dedupe;
+mod diagnostics;
+mod disk;
+mod download;
+mod editing;
+mod engine;
+mod error;
+mod events;
+mod feeds;
+mod fingerprint;
+mod fixtures;
+mod flags;
+mod folders;
+mod glossary;
+mod gpu;
+mod hardware;
+mod heartbeat;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod history;
+mod idle;
+mod imports;
+mod integrations;
+mod itn;
+mod jobs;
+mod journal;
+mod karaoke;
+mod keywords;
+mod languages;
+mod lint;
+mod live;
+mod logging;
+mod meeting;
+mod naming;
+mod outputs;
+mod postprocess;
+mod power;
+mod projects;
+mod proxy;
+mod punctuation;
+mod queue;
+mod recording_dirs;
+mod redact;
+mod replace;
+mod resources;
+mod retention;
+mod rpc;
+mod schedule;
+mod secrets;
+mod selftest;
+mod session;
+mod stream;
+mod subtitle;
+mod telemetry;
+mod temp;
+mod uploads;
+mod watch;
+
+use tauri::{State, Emitter};
 use tauri_plugin_shell::ShellExt;
 use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 use std::env;
-use std::path::PathBuf;
 use std::process::Command;
 use std::net::{TcpListener, SocketAddrV4, Ipv4Addr};
 use std::io::{BufRead, BufReader};
+use tracing::{debug, error, info, warn};
+
+use error::AppError;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 struct ServerInfo {
@@ -21,91 +96,95 @@ struct ServerInfo {
 type ServerState = Arc<Mutex<Option<ServerInfo>>>;
 type ProcessState = Arc<Mutex<Option<u32>>>; // Store process ID
 
+// Commands that read or write files or the keychain run their body here, off the async runtime
+async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T, AppError> + Send + 'static) -> Result<T, AppError> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| format!("Background task failed: {}", e))?
+}
+
+// The settings, read off the async runtime
+async fn load_config() -> Result<config::AppConfig, AppError> {
+    run_blocking(|| Ok(config::load())).await
+}
+
+// A job's record for exports; jobs no longer in the registry are read back from history
+async fn find_job(registry: &jobs::JobRegistry, job_id: &str) -> Result<jobs::JobRecord, AppError> {
+    let (registry, job_id) = (registry.clone(), job_id.to_string());
+    run_blocking(move || jobs::find(&registry, &job_id)).await
+}
+
 #[tauri::command]
 async fn start_gradio_server(
     app: tauri::AppHandle,
     state: State<'_, ServerState>,
     process_state: State<'_, ProcessState>,
-) -> Result<ServerInfo, String> {
-    // First check if server is already running
-    let client = reqwest::Client::new();
-    let default_url = "http://127.0.0.1:7860";
-    
-    if let Ok(response) = client.get(default_url).send().await {
-        if response.status().is_success() {
-            println!("Found existing server at {}", default_url);
-            let server_info = ServerInfo {
-                url: default_url.to_string(),
-                port: 7860,
-                status: "running".to_string(),
-            };
-            
-            // Store server info in state
-            {
-                let mut state_guard = state.lock().unwrap();
-                *state_guard = Some(server_info.clone());
+) -> Result<ServerInfo, AppError> {
+    idle::set_status(&app, idle::BackendStatus::Starting);
+    let result = launch_server(&app, &state, &process_state).await;
+    let status = if result.is_ok() { idle::BackendStatus::Running } else { idle::BackendStatus::Stopped };
+    idle::set_status(&app, status);
+    result
+}
+
+// Port from the line a server prints once it is listening: Gradio's
+// "Running on local URL:  http://127.0.0.1:7860" or uvicorn's
+// "Uvicorn running on http://127.0.0.1:7860 (Press CTRL+C to quit)"
+fn ready_port(line: &str) -> Option<u16> {
+    let rest = ["Running on local URL:", "Uvicorn running on"]
+        .iter()
+        .find_map(|marker| line.split_once(marker).map(|(_, rest)| rest))?;
+    let url = rest.split_whitespace().next()?;
+    let authority = url.split_once("://")?.1.split('/').next()?;
+    authority.rsplit_once(':')?.1.parse().ok()
+}
+
+// HTTP polling for servers whose startup output is not recognized
+async fn poll_until_ready(url: &str) -> bool {
+    let client = proxy::local_client();
+    for attempt in 1..=30 { // up to ~30 * 300ms = 9s
+        match client.get(url).send().await {
+            Ok(response) if response.status().is_success() => return true,
+            _ => {
+                if attempt % 10 == 0 {
+                    debug!("Still polling for server startup... (attempt {})", attempt);
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
             }
-            
-            return Ok(server_info);
         }
     }
+    false
+}
+
+// Stop a server that failed to come up and reap it
+fn abandon_server(mut child: std::process::Child, process_state: &ProcessState) {
+    let _ = engine::kill_process(child.id());
+    let _ = child.wait();
+    *process_state.lock().unwrap() = None;
+}
+
+async fn launch_server(app: &tauri::AppHandle, state: &ServerState, process_state: &ProcessState) -> Result<ServerInfo, AppError> {
+    // A server left over from another launch holds a different session token, so it is
+    // never reused: a new one is always started on a free port
     let _shell = app.shell(); // Keep for potential future use
     let app_handle = app.clone();
     
     // Resolve app binary directory (works in dev and bundled app)
-    let current_exe = env::current_exe().map_err(|e| format!("Failed to get current exe: {}", e))?;
+    let current_exe = env::current_exe().map_err(|e| AppError::backend_unavailable(format!("Failed to get current exe: {}", e)))?;
     let app_dir = current_exe.parent().unwrap();
     
     // Look for Python backend - try multiple possible locations (cross-platform)
-    let backend_dir = if let Some(parent) = app_dir.parent() {
-        if let Some(grandparent) = parent.parent() {
-            let candidate1 = grandparent.join("backend");
-            let candidate2 = grandparent.join("../backend");
-            
-            // Cross-platform fallback paths
-            let candidate3 = if cfg!(target_os = "windows") {
-                // Windows: Try common development locations
-                let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-                let mut candidates = vec![
-                    PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile)),
-                    PathBuf::from("C:\\web-whisper\\backend"),
-                    PathBuf::from("backend"), // Relative to current directory
-                ];
-                
-                // Find first existing candidate
-                candidates.into_iter().find(|p| p.join("main.py").exists())
-                    .unwrap_or_else(|| PathBuf::from("backend"))
-            } else {
-                // Default fallback
-                PathBuf::from("backend")
-            };
-            
-            if candidate1.join("main.py").exists() {
-                candidate1
-            } else if candidate2.join("main.py").exists() {
-                candidate2
-            } else {
-                candidate3
-            }
-        } else {
-            // Windows fallback
-            let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-            PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile))
-        }
-    } else {
-        // Windows fallback
-        let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-        PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile))
-    };
+    let backend_dir = engine::find_backend_dir("main.py")?;
     
     let main_py = backend_dir.join("main.py");
     
-    println!("Backend directory: {:?}", backend_dir);
-    println!("Main.py path: {:?}", main_py);
+    debug!("Backend directory: {:?}", backend_dir);
+    debug!("Main.py path: {:?}", main_py);
     
-    println!("Trying to start Python server: {:?}", main_py);
+    info!("Trying to start Python server: {:?}", main_py);
 
-    // Choose a port: prefer 7860 if free, otherwise allocate a free port
+    // Choose a port: prefer 7860 if free, otherwise let the server bind any free port and
+    // report it, so no other process can take a port between our probe and its bind
     let desired_port: u16 = 7860;
     let chosen_port: u16 = match TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, desired_port)) {
         Ok(listener) => {
@@ -115,13 +194,8 @@ async fn start_gradio_server(
             port
         },
         Err(_) => {
-            // find an ephemeral free port
-            let tmp = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
-                .map_err(|e| format!("Failed to acquire a free port: {}", e))?;
-            let port = tmp.local_addr().unwrap().port();
-            drop(tmp);
-            println!("Port {} in use; selected free port {}", desired_port, port);
-            port
+            info!("Port {} in use; letting the server pick a free port", desired_port);
+            0
         }
     };
     
@@ -147,14 +221,14 @@ async fn start_gradio_server(
             if candidate.contains(":\\") {
                 // Full path - check if exists
                 if std::path::Path::new(&candidate).exists() {
-                    println!("Using Python: {}", candidate);
+                    info!("Using Python: {}", candidate);
                     found_python = candidate;
                     break;
                 }
             } else {
                 // Command - try to execute
-                if Command::new(&candidate).arg("--version").output().is_ok() {
-                    println!("Using Python: {}", candidate);
+                if tokio::process::Command::new(&candidate).arg("--version").output().await.is_ok() {
+                    info!("Using Python: {}", candidate);
                     found_python = candidate;
                     break;
                 }
@@ -162,7 +236,7 @@ async fn start_gradio_server(
         }
         
         if found_python == "python" {
-            println!("No Python found, using default 'python'");
+            warn!("No Python found, using default 'python'");
         }
         found_python
     } else {
@@ -179,51 +253,45 @@ async fn start_gradio_server(
 
     let mut child: std::process::Child;
     if let Some(bin_path) = sidecar_candidates.into_iter().find(|p| p.exists()) {
-        println!("Launching bundled sidecar: {:?}", bin_path);
+        info!("Launching bundled sidecar: {:?}", bin_path);
         let _ = app_handle.emit("engine-progress", serde_json::json!({"percent": 5, "message": "Launching sidecar"}));
         let mut cmd = Command::new(bin_path);
-        cmd.args(&["--server.name", "127.0.0.1", "--server.port", &chosen_port.to_string()])
+        cmd.args(["--server.name", "127.0.0.1", "--server.port", &chosen_port.to_string()])
             .current_dir(&backend_dir)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
+        engine::use_app_dirs(&mut cmd);
+        cmd.env("WEB_WHISPER_TOKEN", session::token());
+        // Print lines as they happen so the "running on" line is seen right away
+        cmd.env("PYTHONUNBUFFERED", "1");
+        proxy::configure_command(&mut cmd)?;
         child = cmd.spawn()
             .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
     } else {
-        println!("No bundled sidecar found; falling back to Python: {}", python_cmd);
+        info!("No bundled sidecar found; falling back to Python: {}", python_cmd);
         let _ = app_handle.emit("engine-progress", serde_json::json!({"percent": 5, "message": "Launching Python backend"}));
         
         // Verify backend directory and main.py exist
         if !backend_dir.exists() {
-            return Err(format!("Backend directory not found: {:?}", backend_dir));
+            return Err(AppError::backend_unavailable(format!("Backend directory not found: {:?}", backend_dir)));
         }
         if !main_py.exists() {
-            return Err(format!("main.py not found: {:?}", main_py));
+            return Err(AppError::backend_unavailable(format!("main.py not found: {:?}", main_py)));
         }
         
         let mut cmd = Command::new(python_cmd.clone());
-        cmd.args(&[main_py.to_str().unwrap(), "--server.name", "127.0.0.1", "--server.port", &chosen_port.to_string()])
+        cmd.args([main_py.to_str().unwrap(), "--server.name", "127.0.0.1", "--server.port", &chosen_port.to_string()])
             .current_dir(&backend_dir)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
         
         // Add ffmpeg paths to environment (Windows), including Lite cache path
-        let current_path = env::var("PATH").unwrap_or_default();
-        let mut ffmpeg_paths: Vec<String> = vec![
-            "C:\\ffmpeg\\bin".to_string(),
-            "C:\\Program Files\\FFmpeg\\bin".to_string(),
-            "C:\\Program Files (x86)\\FFmpeg\\bin".to_string(),
-        ];
-        if let Ok(local_appdata) = env::var("LOCALAPPDATA") {
-            ffmpeg_paths.push(format!("{}\\\\WebWhisper\\\\bin", local_appdata));
-        }
-        
-        let mut new_path = current_path.clone();
-        for ffmpeg_path in ffmpeg_paths {
-            if !new_path.contains(&ffmpeg_path) {
-                new_path = format!("{};{}", ffmpeg_path, new_path);
-            }
-        }
-        cmd.env("PATH", new_path);
+        cmd.env("PATH", engine::path_with_ffmpeg());
+        engine::use_app_dirs(&mut cmd);
+        cmd.env("WEB_WHISPER_TOKEN", session::token());
+        // Print lines as they happen so the "running on" line is seen right away
+        cmd.env("PYTHONUNBUFFERED", "1");
+        proxy::configure_command(&mut cmd)?;
         
         child = cmd.spawn()
             .map_err(|e| format!("Failed to spawn Python process: {}", e))?;
@@ -237,16 +305,30 @@ async fn start_gradio_server(
         *process_guard = Some(process_id);
     }
     
-    println!("Started Python server with PID: {}", process_id);
-    let server_url = format!("http://127.0.0.1:{}", chosen_port);
+    info!("Started Python server with PID: {}", process_id);
+
+    // Whichever stream prints the server's "running on" line first reports the bound port.
+    // The sender is dropped once both streams close, i.e. when the process exits.
+    let (ready_sender, mut ready_receiver) = tokio::sync::oneshot::channel::<u16>();
+    let ready_sender = Arc::new(Mutex::new(Some(ready_sender)));
+    let report_ready = |sender: &Arc<Mutex<Option<tokio::sync::oneshot::Sender<u16>>>>, line: &str| {
+        if let Some(port) = ready_port(line) {
+            if let Some(sender) = sender.lock().unwrap().take() {
+                let _ = sender.send(port);
+            }
+        }
+    };
 
     // Stream child stdout/stderr to help diagnostics
     if let Some(stdout) = child.stdout.take() {
         let reader = BufReader::new(stdout);
         let app_for_logs = app_handle.clone();
+        let ready_sender = ready_sender.clone();
         std::thread::spawn(move || {
-            for line in reader.lines().flatten() {
-                println!("[sidecar stdout] {}", line);
+            for line in reader.lines().map_while(Result::ok) {
+                info!(target: "sidecar", "{}", line);
+                report_ready(&ready_sender, &line);
+                idle::observe_log(&line);
                 let _ = app_for_logs.emit("engine-log", serde_json::json!({"stream": "stdout", "line": line}));
             }
         });
@@ -254,45 +336,74 @@ async fn start_gradio_server(
     if let Some(stderr) = child.stderr.take() {
         let reader = BufReader::new(stderr);
         let app_for_logs = app_handle.clone();
+        let ready_sender = ready_sender.clone();
         std::thread::spawn(move || {
-            for line in reader.lines().flatten() {
-                eprintln!("[sidecar stderr] {}", line);
+            for line in reader.lines().map_while(Result::ok) {
+                info!(target: "sidecar", "{}", line);
+                report_ready(&ready_sender, &line);
                 let _ = app_for_logs.emit("engine-log", serde_json::json!({"stream": "stderr", "line": line}));
             }
         });
     }
-    
-    // Try to connect to verify server is running
-    let client = reqwest::Client::new();
-    let mut ready = false;
-    for attempt in 1..=30 { // up to ~30 * 300ms = 9s
-        match client.get(&server_url).send().await {
-            Ok(response) if response.status().is_success() => {
-                println!("Server is responding at {}", server_url);
-                ready = true;
-                let _ = app_handle.emit("engine-progress", serde_json::json!({"percent": 100, "message": "Engine ready"}));
+    drop(ready_sender);
+
+    // Wait for the server to announce where it is listening
+    let mut announced = None;
+    for attempt in 1..=100 { // up to ~100 * 300ms = 30s
+        match ready_receiver.try_recv() {
+            Ok(port) => {
+                announced = Some(port);
                 break;
             }
-            _ => {
-                // Optionally check if process already exited
-                // We cannot directly check without the child handle; rely on retries
+            // Both streams closed without the line: the process exited or prints something else
+            Err(tokio::sync::oneshot::error::TryRecvError::Closed) => break,
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => {
                 if attempt % 10 == 0 {
-                    println!("Still waiting for server startup... (attempt {})", attempt);
+                    debug!("Still waiting for server startup... (attempt {})", attempt);
                 }
-                let percent = 10 + attempt * 3; // 13..100 cap below
-                let p = if percent > 95 { 95 } else { percent };
-                let _ = app_handle.emit("engine-progress", serde_json::json!({"percent": p, "message": "Starting engine..."}));
+                let percent = (10 + attempt * 3 / 4).min(95);
+                let _ = app_handle.emit("engine-progress", serde_json::json!({"percent": percent, "message": "Starting engine..."}));
                 tokio::time::sleep(std::time::Duration::from_millis(300)).await;
             }
         }
     }
-    if !ready {
-        return Err(format!("Server failed to start or is not responding at {}", server_url));
+
+    let port = match announced {
+        Some(port) => {
+            if chosen_port != 0 && port != chosen_port {
+                warn!("Server bound port {} instead of the requested {}", port, chosen_port);
+            }
+            port
+        }
+        // Output not recognized; fall back to polling the port we asked for
+        None if chosen_port != 0 => {
+            warn!("Server did not report its address; polling port {}", chosen_port);
+            if !poll_until_ready(&session::url(chosen_port)).await {
+                abandon_server(child, process_state);
+                return Err(AppError::backend_unavailable(format!("Server failed to start or is not responding on port {}", chosen_port)));
+            }
+            chosen_port
+        }
+        None => {
+            abandon_server(child, process_state);
+            return Err(AppError::backend_unavailable("Server failed to start or did not report its port"));
+        }
+    };
+    info!("Server is responding on port {}", port);
+    let _ = app_handle.emit("engine-progress", serde_json::json!({"percent": 100, "message": "Engine ready"}));
+    let server_url = session::url(port);
+    if let Err(e) = session::verify(port).await {
+        error!("Security check failed: {}", e);
+        let _ = app_handle.emit("security-warning", serde_json::json!({"message": e.to_string()}));
+        abandon_server(child, process_state);
+        return Err(AppError::backend_unavailable(e.to_string()));
     }
+    // Reap the server whenever it exits, whoever stops it, so it does not linger as a zombie
+    std::thread::spawn(move || child.wait());
     
     let server_info = ServerInfo {
         url: server_url.clone(),
-        port: chosen_port,
+        port,
         status: "running".to_string(),
     };
     
@@ -302,12 +413,31 @@ async fn start_gradio_server(
         *state_guard = Some(server_info.clone());
     }
     
-    println!("Whisper server started at: {}", server_url);
+    info!("Whisper server started on port {}", port);
     Ok(server_info)
 }
 
+// Start the server again if it was stopped for being idle
+async fn wake_server(
+    app: &tauri::AppHandle,
+    state: &State<'_, ServerState>,
+    process_state: &State<'_, ProcessState>,
+) -> Result<(), AppError> {
+    if idle::status() == idle::BackendStatus::Sleeping {
+        info!("Waking the backend server");
+        start_gradio_server(app.clone(), state.clone(), process_state.clone()).await?;
+    }
+    idle::touch();
+    Ok(())
+}
+
 #[tauri::command]
-async fn get_server_info(state: State<'_, ServerState>) -> Result<ServerInfo, String> {
+async fn get_server_info(
+    app: tauri::AppHandle,
+    state: State<'_, ServerState>,
+    process_state: State<'_, ProcessState>,
+) -> Result<ServerInfo, AppError> {
+    wake_server(&app, &state, &process_state).await?;
     let server_info = {
         let state_guard = state.lock().unwrap();
         state_guard.clone()
@@ -315,18 +445,28 @@ async fn get_server_info(state: State<'_, ServerState>) -> Result<ServerInfo, St
 
     match server_info {
         Some(info) => Ok(info),
-        None => Err("Server not started".to_string())
+        None => Err(AppError::backend_unavailable("Server not started"))
     }
 }
 
+// With `in_app`, the UI opens in its own app window instead of the default browser
 #[tauri::command]
-async fn open_whisper_gui(_app: tauri::AppHandle, state: State<'_, ServerState>) -> Result<(), String> {
+async fn open_whisper_gui(
+    app: tauri::AppHandle,
+    in_app: Option<bool>,
+    state: State<'_, ServerState>,
+    process_state: State<'_, ProcessState>,
+) -> Result<(), AppError> {
+    wake_server(&app, &state, &process_state).await?;
     let server_info = {
         let state_guard = state.lock().unwrap();
         state_guard.clone()
     };
     
     if let Some(info) = server_info {
+        if in_app.unwrap_or(false) {
+            return backend_ui::open(&app, &info.url);
+        }
         // Open URL in default browser (Windows)
         std::process::Command::new("cmd")
             .args(["/c", "start", &info.url])
@@ -334,67 +474,129 @@ async fn open_whisper_gui(_app: tauri::AppHandle, state: State<'_, ServerState>)
             .map_err(|e| format!("Failed to open URL: {}", e))?;
         Ok(())
     } else {
-        Err("Whisper server is not running".to_string())
+        Err(AppError::backend_unavailable("Whisper server is not running"))
     }
 }
 
+// Back, forward, reload or home in the in-app backend UI window
+#[tauri::command]
+async fn navigate_whisper_gui(app: tauri::AppHandle, action: String, state: State<'_, ServerState>) -> Result<(), AppError> {
+    let home = state.lock().unwrap().as_ref().map(|info| info.url.clone());
+    let home = home.ok_or_else(|| AppError::backend_unavailable("Whisper server is not running"))?;
+    backend_ui::navigate(&app, &action, &home)
+}
+
 #[tauri::command]
 async fn save_temp_file(
     file_data: Vec<u8>,
     file_name: String
-) -> Result<String, String> {
-    use std::io::Write;
-    
-    // Create temp directory if it doesn't exist
-    let temp_dir = std::env::temp_dir().join("web-whisper");
-    if !temp_dir.exists() {
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
-    }
-    
-    // Generate unique filename to avoid conflicts
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    let temp_file_path = temp_dir.join(format!("{}_{}", timestamp, file_name));
-    
-    // Write file data to temp location
-    let mut file = std::fs::File::create(&temp_file_path)
-        .map_err(|e| format!("Failed to create temp file: {}", e))?;
-    file.write_all(&file_data)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
-    
+) -> Result<String, AppError> {
+    config::check_upload_size(file_data.len() as u64, config::max_upload_bytes())?;
+    let file_name = engine::sanitize_file_name(&file_name).map_err(|e| AppError::invalid_input("file_name", e.to_string()))?;
+    let temp_file_path = tauri::async_runtime::spawn_blocking(move || engine::write_temp_file(&file_data, &file_name))
+        .await
+        .map_err(|e| format!("Failed to save temp file: {}", e))??;
     Ok(temp_file_path.to_string_lossy().to_string())
 }
 
+// Chunked alternative to save_temp_file: large media never has to be held in memory as a whole
+#[tauri::command]
+async fn begin_upload(
+    file_name: String,
+    size: Option<u64>,
+    uploads: State<'_, uploads::UploadRegistry>,
+) -> Result<String, AppError> {
+    let file_name = engine::sanitize_file_name(&file_name).map_err(|e| AppError::invalid_input("file_name", e.to_string()))?;
+    uploads::begin(&uploads, &file_name, size).await
+}
+
+// Free space on the volume holding `path`, e.g. to warn before picking an output folder
+#[tauri::command]
+async fn check_disk_space(path: String, required_bytes: Option<u64>) -> Result<disk::DiskSpace, AppError> {
+    Ok(disk::check(std::path::Path::new(&path), required_bytes.unwrap_or(0)))
+}
+
+// Raw binary body; the upload id travels in the `x-upload-id` header
+#[tauri::command]
+async fn append_chunk(request: tauri::ipc::Request<'_>, uploads: State<'_, uploads::UploadRegistry>) -> Result<u64, AppError> {
+    let upload_id = request
+        .headers()
+        .get("x-upload-id")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(AppError::invalid("Missing x-upload-id header"))?;
+    let tauri::ipc::InvokeBody::Raw(data) = request.body() else {
+        return Err(AppError::invalid_input("body", "append_chunk expects a binary body"));
+    };
+    uploads::append(&uploads, upload_id, data).await
+}
+
+#[tauri::command]
+async fn finish_upload(upload_id: String, uploads: State<'_, uploads::UploadRegistry>) -> Result<String, AppError> {
+    uploads::finish(&uploads, &upload_id).await
+}
+
+#[tauri::command]
+async fn abort_upload(upload_id: String, uploads: State<'_, uploads::UploadRegistry>) -> Result<(), AppError> {
+    uploads::abort(&uploads, &upload_id).await
+}
+
+// Send a local file to the backend server over HTTP; returns where the server stored it
+#[tauri::command]
+async fn upload_to_backend(
+    app: tauri::AppHandle,
+    file_path: String,
+    state: State<'_, ServerState>,
+    process_state: State<'_, ProcessState>,
+) -> Result<backend_api::BackendUpload, AppError> {
+    if !tokio::fs::try_exists(&file_path).await.unwrap_or(false) {
+        return Err(AppError::not_found(&file_path));
+    }
+    wake_server(&app, &state, &process_state).await?;
+    let port = state.lock().unwrap().as_ref().map(|info| info.port);
+    let port = port.ok_or_else(|| AppError::backend_unavailable("Whisper server is not running"))?;
+    tauri::async_runtime::spawn_blocking(move || backend_api::upload(port, &file_path))
+        .await
+        .map_err(|e| format!("Upload task failed: {}", e))?
+}
+
 #[tauri::command]
 async fn save_transcription(
     app: tauri::AppHandle,
     content: String,
-    original_file_name: String
-) -> Result<String, String> {
+    original_file_name: String,
+    project_id: Option<String>,
+    language: Option<String>,
+    format: Option<String>,
+) -> Result<String, AppError> {
     use tauri_plugin_dialog::{DialogExt};
     
-    // Get file stem from original file name
-    let original_path = std::path::Path::new(&original_file_name);
-    let file_stem = original_path.file_stem()
-        .ok_or("Failed to get file stem")?
-        .to_string_lossy();
-    
-    let default_filename = format!("{}.txt", file_stem);
+    // Name the file with the configured template, "{stem}.{ext}" by default
+    let ext = format.filter(|ext| !ext.trim().is_empty()).unwrap_or_else(|| "txt".to_string());
+    let relative = naming::output_name(&original_file_name, &ext, language.as_deref(), project_id.as_deref())?;
+    let default_filename = relative.file_name().ok_or("Failed to get file name")?.to_string_lossy().to_string();
+    let export_dir = projects::export_dir(project_id.as_deref())?;
+    let export_dir = match relative.parent() {
+        Some(subdir) => export_dir.join(subdir),
+        None => export_dir,
+    };
     
     // Try different approaches for file saving
     
     // Approach 1: Show file save dialog
-    let file_path = app
+    // The callback variant keeps the async runtime free while the dialog is open
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    app
         .dialog()
         .file()
         .set_title("転写テキストを保存")
+        .set_directory(&export_dir)
         .set_file_name(&default_filename)
-        .add_filter("テキストファイル", &["txt"])
+        .add_filter("テキストファイル", &[ext.as_str()])
         .add_filter("すべてのファイル", &["*"])
-        .blocking_save_file();
+        .save_file(move |path| {
+            let _ = sender.send(path);
+        });
+    let file_path = receiver.await.map_err(|_| "Save dialog closed unexpectedly".to_string())?;
     
     if let Some(path) = file_path {
         // Get the actual path from FilePath
@@ -402,289 +604,2141 @@ async fn save_transcription(
             .ok_or("Failed to get path from FilePath")?;
         let path_buf = path_ref.to_path_buf();
         
+        let content = redact::text_for_export(&content)?;
+
         // Try standard file operations first
-        match std::fs::write(&path_buf, content.as_bytes()) {
+        match tokio::fs::write(&path_buf, content.as_bytes()).await {
             Ok(_) => {
-                return Ok(path_buf.to_string_lossy().to_string());
+                let saved = path_buf.to_string_lossy().to_string();
+                audit::record(None, audit::AuditAction::Write, "file", &saved);
+                Ok(saved)
             }
             Err(e) => {
                 // If that fails, save to Downloads folder
-                println!("Standard file write failed: {}, saving to Downloads folder", e);
-                return save_to_downloads(&content, &default_filename).await;
+                warn!("Standard file write failed: {}, saving to Downloads folder", e);
+                save_to_downloads(&content, &default_filename, export_dir).await
             }
         }
     } else {
-        Err("Save cancelled by user".to_string())
+        Err(AppError::Cancelled { message: "Save cancelled by user".to_string() })
     }
 }
 
-// Fallback function to save to Downloads folder
-async fn save_to_downloads(content: &str, filename: &str) -> Result<String, String> {
-    use std::io::Write;
-    
-    let user_profile = std::env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-    let downloads_dir = std::path::PathBuf::from(&user_profile).join("Downloads");
+// Fallback function to save to the output folder (Downloads unless configured) or a project's folder
+async fn save_to_downloads(content: &str, filename: &str, downloads_dir: std::path::PathBuf) -> Result<String, AppError> {
+    let filename = engine::sanitize_file_name(filename).map_err(|e| AppError::invalid_input("file_name", e.to_string()))?;
     
     // Ensure Downloads directory exists
-    if !downloads_dir.exists() {
-        std::fs::create_dir_all(&downloads_dir)
-            .map_err(|e| format!("Failed to create Downloads directory: {}", e))?;
-    }
+    tokio::fs::create_dir_all(&downloads_dir)
+        .await
+        .map_err(|e| format!("Failed to create Downloads directory: {}", e))?;
     
     // Create unique filename if file already exists
     let mut counter = 1;
-    let mut final_path = downloads_dir.join(filename);
-    let stem = std::path::Path::new(filename).file_stem()
-        .ok_or("Invalid filename")?
+    let mut final_path = downloads_dir.join(&filename);
+    let name_path = std::path::Path::new(&filename);
+    let stem = name_path.file_stem()
+        .ok_or(AppError::invalid("Invalid filename"))?
         .to_string_lossy();
+    let extension = name_path.extension().map(|ext| ext.to_string_lossy()).unwrap_or_else(|| "txt".into());
     
-    while final_path.exists() {
-        let new_filename = format!("{}_{}.txt", stem, counter);
+    while tokio::fs::try_exists(&final_path).await.unwrap_or(false) {
+        let new_filename = format!("{}_{}.{}", stem, counter, extension);
         final_path = downloads_dir.join(new_filename);
         counter += 1;
     }
     
     // Write file
-    let mut file = std::fs::File::create(&final_path)
-        .map_err(|e| format!("Failed to create file in Downloads: {}", e))?;
-    
-    file.write_all(content.as_bytes())
+    tokio::fs::write(&final_path, content.as_bytes())
+        .await
         .map_err(|e| format!("Failed to write file in Downloads: {}", e))?;
+    audit::record(None, audit::AuditAction::Write, "file", &final_path.to_string_lossy());
     
-    Ok(format!("Downloads フォルダに保存: {}", final_path.to_string_lossy()))
+    Ok(format!("保存しました: {}", final_path.to_string_lossy()))
 }
 
-// Direct command to save to Downloads folder
+// Direct command to save to the output folder. Without `file_name`, the file is named
+// from `original_file_name` with the file name template.
 #[tauri::command]
-async fn save_to_downloads_direct(content: String, file_name: String) -> Result<String, String> {
-    save_to_downloads(&content, &file_name).await
+async fn save_to_downloads_direct(
+    content: String,
+    file_name: Option<String>,
+    original_file_name: Option<String>,
+    project_id: Option<String>,
+    language: Option<String>,
+    format: Option<String>,
+) -> Result<String, AppError> {
+    let content = redact::text_for_export(&content)?;
+    let dir = projects::export_dir(project_id.as_deref())?;
+    if let Some(file_name) = file_name.filter(|name| !name.trim().is_empty()) {
+        return save_to_downloads(&content, &file_name, dir).await;
+    }
+    let original_file_name = original_file_name.ok_or_else(|| AppError::invalid_input("file_name", "Invalid file name: pass file_name or original_file_name"))?;
+    let ext = format.filter(|ext| !ext.trim().is_empty()).unwrap_or_else(|| "txt".to_string());
+    let relative = naming::output_name(&original_file_name, &ext, language.as_deref(), project_id.as_deref())?;
+    let file_name = relative.file_name().ok_or("Failed to get file name")?.to_string_lossy().to_string();
+    let dir = match relative.parent() {
+        Some(subdir) => dir.join(subdir),
+        None => dir,
+    };
+    save_to_downloads(&content, &file_name, dir).await
 }
 
 #[tauri::command]
-async fn get_gpu_info() -> Result<String, String> {
-    // Get GPU information by running the GPU detection script
-    let current_exe = env::current_exe().map_err(|e| format!("Failed to get current exe: {}", e))?;
-    let app_dir = current_exe.parent().unwrap();
-    
-    // Find backend directory (cross-platform) - reuse same logic as start_gradio_server
-    let backend_dir = if let Some(parent) = app_dir.parent() {
-        if let Some(grandparent) = parent.parent() {
-            let candidate1 = grandparent.join("backend");
-            let candidate2 = grandparent.join("../backend");
-            
-            // Cross-platform fallback paths
-            let candidate3 = if cfg!(target_os = "windows") {
-                // Windows: Try common development locations
-                let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-                let mut candidates = vec![
-                    PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile)),
-                    PathBuf::from("C:\\web-whisper\\backend"),
-                    PathBuf::from("backend"), // Relative to current directory
-                ];
-                
-                // Find first existing candidate
-                candidates.into_iter().find(|p| p.join("patch_gpu.py").exists())
-                    .unwrap_or_else(|| PathBuf::from("backend"))
-            } else {
-                // Default fallback
-                PathBuf::from("backend")
-            };
-            
-            if candidate1.join("patch_gpu.py").exists() {
-                candidate1
-            } else if candidate2.join("patch_gpu.py").exists() {
-                candidate2
-            } else {
-                candidate3
-            }
-        } else {
-            // Windows fallback
-            let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-            PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile))
-        }
-    } else {
-        // Windows fallback
-        let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-        PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile))
-    };
-    
-    // Get Python executable (Windows only)
-    let python_cmd = "python".to_string();
-    
-    // Run GPU detection script
-    let output = Command::new(&python_cmd)
-        .args(&["-c", "from patch_gpu import get_gpu_info; print(get_gpu_info())"])
+async fn get_gpu_info() -> Result<Option<gpu::GpuInfo>, AppError> {
+    let native = tauri::async_runtime::spawn_blocking(gpu::detect)
+        .await
+        .map_err(|e| format!("GPU detection task failed: {}", e))?;
+    if native.is_some() {
+        return Ok(native);
+    }
+
+    // No native API reported a GPU; ask the backend's detection script instead
+    let backend_dir = engine::find_backend_dir("patch_gpu.py")?;
+    let python_cmd = engine::python_command();
+    let output = tokio::process::Command::new(&python_cmd)
+        .args(["-c", "from patch_gpu import get_gpu_info; print(get_gpu_info())"])
         .current_dir(&backend_dir)
         .output()
-        .map_err(|e| format!("Failed to execute GPU info script: {}", e))?;
+        .await
+        .map_err(|e| AppError::backend_unavailable(format!("Failed to execute GPU info script: {}", e)))?;
     
     if output.status.success() {
         let result = String::from_utf8_lossy(&output.stdout);
-        Ok(result.trim().to_string())
+        Ok(Some(gpu::GpuInfo {
+            vendor: "Unknown",
+            name: result.trim().to_string(),
+            vram_bytes: None,
+            driver: None,
+            source: "python",
+        }))
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        Ok(format!("GPU detection unavailable: {}", stderr.trim()))
+        warn!("GPU detection unavailable: {}", stderr.trim());
+        Ok(None)
     }
 }
 
+// Rejects with a `duplicate` error when the same content is already queued or transcribed,
+// or when the recording sounds like one in history; pass `force` to transcribe it again anyway
 #[tauri::command]
 async fn transcribe_audio(
     file_path: String,
-    state: State<'_, ServerState>,
-    process_state: State<'_, ProcessState>
-) -> Result<String, String> {
-    // Simply call Python script directly
-    let current_exe = env::current_exe().map_err(|e| format!("Failed to get current exe: {}", e))?;
-    let app_dir = current_exe.parent().unwrap();
-    
-    // Find backend directory - reuse same logic as start_gradio_server
-    let backend_dir = if let Some(parent) = app_dir.parent() {
-        if let Some(grandparent) = parent.parent() {
-            let candidate1 = grandparent.join("backend");
-            let candidate2 = grandparent.join("../backend");
-            
-            // Cross-platform fallback paths
-            let candidate3 = if cfg!(target_os = "windows") {
-                // Windows: Try common development locations
-                let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-                let mut candidates = vec![
-                    PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile)),
-                    PathBuf::from("C:\\web-whisper\\backend"),
-                    PathBuf::from("backend"), // Relative to current directory
-                ];
-                
-                // Find first existing candidate
-                candidates.into_iter().find(|p| p.join("transcribe_simple.py").exists())
-                    .unwrap_or_else(|| PathBuf::from("backend"))
-            } else {
-                // macOS/Linux: Default to repo-relative 'backend'
-                PathBuf::from("backend")
-            };
-            
-            if candidate1.join("transcribe_simple.py").exists() {
-                candidate1
-            } else if candidate2.join("transcribe_simple.py").exists() {
-                candidate2
-            } else {
-                candidate3
-            }
-        } else {
-            // Windows fallback
-            let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-            PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile))
-        }
-    } else {
-        // Windows fallback
-        let user_profile = env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
-        PathBuf::from(format!("{}\\Documents\\web-whisper\\backend", user_profile))
-    };
-    
-    let transcribe_script = backend_dir.join("transcribe_simple.py");
-    
-    // Get Python executable (Windows only)
-    let python_cmd = "python".to_string();
-    
-    println!("Transcribing file: {}", file_path);
-    
-    // Verify transcription script exists
-    if !transcribe_script.exists() {
-        return Err(format!("Transcription script not found: {:?}", transcribe_script));
+    options: Option<engine::TranscribeOptions>,
+    force: Option<bool>,
+    project_id: Option<String>,
+    hub: State<'_, stream::StreamHub>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<String, AppError> {
+    if !tokio::fs::try_exists(&file_path).await.unwrap_or(false) {
+        return Err(AppError::not_found(&file_path));
     }
-    
-    // Call transcription script directly with proper environment
-    let mut cmd = Command::new(&python_cmd);
-    cmd.args(&[
-            transcribe_script.to_str().unwrap(),
-            &file_path,
-            "--language", "auto",
-            "--format", "text"
-        ])
-        .current_dir(&backend_dir);
-    
-    // Add ffmpeg path to environment (Windows), including Lite cache path
-    let current_path = env::var("PATH").unwrap_or_default();
-    let mut ffmpeg_paths: Vec<String> = vec![
-        "C:\\ffmpeg\\bin".to_string(),
-        "C:\\Program Files\\FFmpeg\\bin".to_string(),
-        "C:\\Program Files (x86)\\FFmpeg\\bin".to_string(),
-    ];
-    if let Ok(local_appdata) = env::var("LOCALAPPDATA") {
-        ffmpeg_paths.push(format!("{}\\\\WebWhisper\\\\bin", local_appdata));
+    let project = project_id.clone();
+    let options = run_blocking(move || projects::options_for(project.as_deref(), options)).await?;
+    let path = file_path.clone();
+    let hash = tauri::async_runtime::spawn_blocking(move || dedupe::hash_file(&path))
+        .await
+        .map_err(|e| format!("Hash task failed: {}", e))??;
+    let job_id = engine::new_job_id();
+    let force = force.unwrap_or(false);
+    if let Some((existing, status)) = dedupe::existing(&job_registry, &hash).filter(|_| !force) {
+        return Err(AppError::duplicate(&existing, status));
     }
-    
-    let mut new_path = current_path.clone();
-    for ffmpeg_path in ffmpeg_paths {
-        if !new_path.contains(&ffmpeg_path) {
-            new_path = format!("{};{}", ffmpeg_path, new_path);
-        }
+    // Fingerprinting decodes the file, so it runs only once the exact-copy check has passed
+    let (path, id) = (file_path.clone(), job_id.clone());
+    let similar = tauri::async_runtime::spawn_blocking(move || fingerprint::screen(&path, Some(&id), force))
+        .await
+        .map_err(|e| format!("Fingerprint task failed: {}", e))?;
+    if let Some(found) = similar {
+        return Err(AppError::similar_recording(&found));
     }
-    
-    cmd.env("PATH", new_path);
-    
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to execute transcription: {}", e))?;
-    
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Transcription failed: {}", stderr));
+    if let Some((existing, status)) = dedupe::claim(&job_registry, &hash, &job_id, force) {
+        return Err(AppError::duplicate(&existing, status));
     }
-    
-    let result = String::from_utf8_lossy(&output.stdout);
-    Ok(result.trim().to_string())
+    if project_id.is_some() {
+        let id = job_id.clone();
+        if let Err(e) = run_blocking(move || projects::assign(&id, project_id.as_deref())).await {
+            dedupe::release(&hash, &job_id);
+            return Err(e);
+        }
+    }
+    let (hub, job_registry) = (hub.inner().clone(), job_registry.inner().clone());
+    // Cloud engines use a blocking HTTP client, which must not run on the async runtime
+    let transcript = tauri::async_runtime::spawn_blocking(move || {
+        journal::run(&job_registry, &hub, &job_id, &file_path, &options, false)
+    })
+    .await
+    .map_err(|e| format!("Transcription task failed: {}", e))??;
+    Ok(transcript.text)
 }
 
+// Queue the media in a folder in the background; `dry_run` only reports what would be queued
 #[tauri::command]
-async fn stop_whisper_server(process_state: State<'_, ProcessState>) -> Result<(), String> {
-    let process_id = {
-        let process_guard = process_state.lock().unwrap();
-        process_guard.clone()
-    };
-    
-    if let Some(pid) = process_id {
-        println!("Stopping Python server with PID: {}", pid);
-        
-        // Kill the process (Windows)
-        Command::new("taskkill")
-            .args(&["/F", "/PID", &pid.to_string()])
-            .output()
-            .map_err(|e| format!("Failed to kill process: {}", e))?;
-        
-        // Clear process state
-        {
-            let mut process_guard = process_state.lock().unwrap();
-            *process_guard = None;
+async fn enqueue_folder(
+    path: String,
+    filter: Option<folders::FolderFilter>,
+    options: Option<engine::TranscribeOptions>,
+    project_id: Option<String>,
+    dry_run: Option<bool>,
+    hub: State<'_, stream::StreamHub>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<folders::FolderImport, AppError> {
+    let (hub, job_registry) = (hub.inner().clone(), job_registry.inner().clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        let options = projects::options_for(project_id.as_deref(), options)?;
+        let filter = filter.unwrap_or_default();
+        folders::enqueue_folder(&job_registry, &hub, &path, &filter, &options, project_id.as_deref(), dry_run.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Folder import task failed: {}", e))?
+}
+
+// Extract the recordings in a .zip and queue them under a project named after it
+#[tauri::command]
+async fn import_zip(
+    file_path: String,
+    options: Option<engine::TranscribeOptions>,
+    project_id: Option<String>,
+    hub: State<'_, stream::StreamHub>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<archives::ArchiveImport, AppError> {
+    let (hub, job_registry) = (hub.inner().clone(), job_registry.inner().clone());
+    tauri::async_runtime::spawn_blocking(move || archives::import_zip(&job_registry, &hub, &file_path, options, project_id))
+        .await
+        .map_err(|e| format!("Archive import task failed: {}", e))?
+}
+
+// Add a file to history with the transcript of the history entry it matched
+#[tauri::command]
+async fn reuse_transcript(job_id: String, file_path: String, title: Option<String>) -> Result<history::HistoryEntry, AppError> {
+    run_blocking(move || fingerprint::reuse(&job_id, &file_path, title)).await
+}
+
+// Fingerprint older history entries so new imports can be matched against them
+#[tauri::command]
+async fn fingerprint_history() -> Result<usize, AppError> {
+    tauri::async_runtime::spawn_blocking(fingerprint::index_history)
+        .await
+        .map_err(|e| format!("Fingerprint task failed: {}", e))?
+}
+
+// Download media with yt-dlp and transcribe it in the background; returns the job id
+#[tauri::command]
+async fn transcribe_url(
+    app: tauri::AppHandle,
+    url: String,
+    options: Option<engine::TranscribeOptions>,
+    project_id: Option<String>,
+    hub: State<'_, stream::StreamHub>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<String, AppError> {
+    let job_id = engine::new_job_id();
+    let id = job_id.clone();
+    let transcribe_options = run_blocking(move || {
+        let options = projects::options_for(project_id.as_deref(), options)?;
+        if project_id.is_some() {
+            projects::assign(&id, project_id.as_deref())?;
         }
-        
-        println!("Python server stopped");
+        Ok(options)
+    })
+    .await?;
+    let (hub, job_registry, id) = (hub.inner().clone(), job_registry.inner().clone(), job_id.clone());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let _ = app.emit("download-progress", serde_json::json!({"job_id": id, "url": url, "percent": 0.0}));
+        let downloaded = download::download_audio(&url, |percent| {
+            let _ = app.emit("download-progress", serde_json::json!({"job_id": id, "url": url, "percent": percent}));
+        });
+        match downloaded {
+            Ok(path) => {
+                let path = path.to_string_lossy().to_string();
+                let _ = journal::run(&job_registry, &hub, &id, &path, &transcribe_options, true);
+            }
+            Err(e) => {
+                error!("Download of {} failed: {}", url, e);
+                let _ = app.emit("download-failed", serde_json::json!({"job_id": id, "url": url, "error": e}));
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+async fn list_feeds() -> Result<Vec<feeds::FeedSubscription>, AppError> {
+    run_blocking(feeds::list).await
+}
+
+// backfill: how many of the most recent episodes to transcribe right away (default 1)
+#[tauri::command]
+async fn subscribe_feed(url: String, language: Option<String>, backfill: Option<usize>) -> Result<feeds::FeedSubscription, AppError> {
+    feeds::subscribe(&url, language, backfill.unwrap_or(1)).await
+}
+
+#[tauri::command]
+async fn unsubscribe_feed(url: String) -> Result<(), AppError> {
+    feeds::unsubscribe(&url).await
+}
+
+#[tauri::command]
+async fn refresh_feeds(
+    hub: State<'_, stream::StreamHub>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<usize, AppError> {
+    feeds::poll(&hub, &job_registry).await
+}
+
+#[tauri::command]
+async fn list_watch_folders() -> Result<Vec<watch::WatchFolder>, AppError> {
+    run_blocking(watch::list).await
+}
+
+#[tauri::command]
+async fn add_watch_folder(path: String, language: Option<String>, extensions: Option<Vec<String>>) -> Result<watch::WatchFolder, AppError> {
+    watch::add(&path, language, extensions.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn remove_watch_folder(path: String) -> Result<(), AppError> {
+    watch::remove(&path).await
+}
+
+// Zoom/Teams/OBS recording folders found on this machine, for one-click enrollment via add_watch_folder
+#[tauri::command]
+async fn detect_recording_folders() -> Result<Vec<recording_dirs::DetectedFolder>, AppError> {
+    tauri::async_runtime::spawn_blocking(recording_dirs::detect)
+        .await
+        .map_err(|e| AppError::internal(format!("Detection task failed: {}", e)))
+}
+
+#[tauri::command]
+async fn get_worker_config() -> Result<queue::WorkerConfig, AppError> {
+    run_blocking(|| Ok(queue::worker_config())).await
+}
+
+#[tauri::command]
+async fn set_worker_config(workers: queue::WorkerConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.workers = Some(workers);
+            Ok(())
+        })?;
+        // Waiting jobs pick up a raised limit right away
+        queue::notify();
         Ok(())
-    } else {
-        Err("No server process found".to_string())
-    }
+    })
+    .await
 }
 
-fn main() {
-    let server_state: ServerState = Arc::new(Mutex::new(None));
-    let process_state: ProcessState = Arc::new(Mutex::new(None));
-    
-    tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_plugin_dialog::init())
-        .manage(server_state)
-        .manage(process_state.clone())
-        .invoke_handler(tauri::generate_handler![
-            start_gradio_server,
-            get_server_info,
-            open_whisper_gui,
-            save_temp_file,
-            transcribe_audio,
-            save_transcription,
-            save_to_downloads_direct,
-            get_gpu_info,
-            stop_whisper_server
-        ])
-        .setup({
-            let process_state_clone = process_state.clone();
-            move |app| {
+#[tauri::command]
+async fn get_power_config() -> Result<power::PowerConfig, AppError> {
+    run_blocking(|| Ok(power::power_config())).await
+}
+
+#[tauri::command]
+async fn set_power_config(power: power::PowerConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.power = Some(power);
+            Ok(())
+        })?;
+        queue::notify();
+        Ok(())
+    })
+    .await
+}
+
+// Power source and whether battery saver or the battery pause is in effect right now
+#[tauri::command]
+async fn get_power_status() -> Result<power::PowerStatus, AppError> {
+    tauri::async_runtime::spawn_blocking(power::status)
+        .await
+        .map_err(|e| AppError::internal(format!("Power status task failed: {}", e)))
+}
+
+#[tauri::command]
+async fn get_idle_config() -> Result<idle::IdleConfig, AppError> {
+    run_blocking(|| Ok(idle::idle_config())).await
+}
+
+#[tauri::command]
+async fn set_idle_config(idle_shutdown: idle::IdleConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.idle_shutdown = Some(idle_shutdown);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_backend_status() -> Result<idle::BackendStatus, AppError> {
+    Ok(idle::status())
+}
+
+// Backend status, queue, job progress, recent results and storage for the home screen
+#[tauri::command]
+async fn get_dashboard(state: State<'_, ServerState>, job_registry: State<'_, jobs::JobRegistry>) -> Result<dashboard::Dashboard, AppError> {
+    let server_port = state.lock().unwrap().as_ref().map(|info| info.port);
+    let job_registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || dashboard::snapshot(&job_registry, server_port))
+        .await
+        .map_err(|e| AppError::internal(format!("Dashboard task failed: {}", e)))
+}
+
+#[tauri::command]
+async fn get_heartbeat_config() -> Result<heartbeat::HeartbeatConfig, AppError> {
+    run_blocking(|| Ok(heartbeat::heartbeat_config())).await
+}
+
+#[tauri::command]
+async fn set_heartbeat_config(heartbeat: heartbeat::HeartbeatConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.heartbeat = Some(heartbeat);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_resource_config() -> Result<resources::ResourceConfig, AppError> {
+    run_blocking(|| Ok(resources::resource_config())).await
+}
+
+#[tauri::command]
+async fn set_resource_config(resources: resources::ResourceConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.resources = Some(resources);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_proxy_config() -> Result<proxy::ProxyConfig, AppError> {
+    run_blocking(|| Ok(proxy::proxy_config())).await
+}
+
+#[tauri::command]
+async fn set_proxy_config(proxy: proxy::ProxyConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.proxy = Some(proxy);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_proxy_password(password: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match password {
+            Some(password) => secrets::set(proxy::PASSWORD, &password),
+            None => secrets::delete(proxy::PASSWORD),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_temp_config() -> Result<temp::TempConfig, AppError> {
+    run_blocking(|| Ok(temp::temp_config())).await
+}
+
+#[tauri::command]
+async fn set_temp_config(temp_files: temp::TempConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.temp_files = Some(temp_files);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_retention_config() -> Result<retention::RetentionConfig, AppError> {
+    run_blocking(|| Ok(retention::retention_config())).await
+}
+
+#[tauri::command]
+async fn set_retention_config(retention: retention::RetentionConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.retention = Some(retention);
+            Ok(())
+        })
+    })
+    .await
+}
+
+// Privacy reset; settings and keychain secrets are kept
+#[tauri::command]
+async fn delete_all_data(job_registry: State<'_, jobs::JobRegistry>) -> Result<(), AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || retention::delete_everything(&registry))
+        .await
+        .map_err(|e| format!("Delete task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_schedule_config() -> Result<schedule::ScheduleConfig, AppError> {
+    run_blocking(|| Ok(schedule::schedule_config())).await
+}
+
+#[tauri::command]
+async fn set_schedule_config(schedule: schedule::ScheduleConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        schedule::validate(&schedule).map_err(|e| AppError::invalid_input("schedule", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.schedule = Some(schedule);
+            Ok(())
+        })?;
+        queue::notify();
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_schedule_status() -> Result<schedule::ScheduleStatus, AppError> {
+    Ok(schedule::status())
+}
+
+// Start queued jobs now despite the processing window; lasts until the window next opens
+#[tauri::command]
+async fn set_schedule_override(enabled: bool) -> Result<(), AppError> {
+    schedule::set_override(enabled);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_language_config() -> Result<languages::LanguageConfig, AppError> {
+    run_blocking(|| Ok(languages::language_config())).await
+}
+
+#[tauri::command]
+async fn set_language_config(languages: languages::LanguageConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        languages::validate(&languages).map_err(|e| AppError::invalid_input("profiles", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.languages = Some(languages);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn detect_languages(paths: Vec<String>, recursive: Option<bool>) -> Result<languages::LanguageReport, AppError> {
+    tauri::async_runtime::spawn_blocking(move || languages::detect_report(&paths, recursive.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Language detection task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_punctuation_config() -> Result<postprocess::PunctuationConfig, AppError> {
+    run_blocking(|| Ok(postprocess::punctuation_config())).await
+}
+
+#[tauri::command]
+async fn set_punctuation_config(punctuation: postprocess::PunctuationConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        postprocess::validate_punctuation(&punctuation).map_err(|e| AppError::invalid_input("processor", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.punctuation = Some(punctuation);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_glossary_config() -> Result<glossary::GlossaryConfig, AppError> {
+    run_blocking(|| Ok(glossary::glossary_config())).await
+}
+
+#[tauri::command]
+async fn set_glossary_config(glossary: glossary::GlossaryConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        glossary::validate(&glossary).map_err(|e| AppError::invalid_input("terms", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.glossary = Some(glossary);
+            Ok(())
+        })
+    })
+    .await
+}
+
+// Run the glossary over an existing transcript, e.g. after adding terms; returns what changed
+#[tauri::command]
+async fn apply_glossary(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<glossary::GlossaryReport, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = glossary::glossary_config();
+        let mut corrections = Vec::new();
+        editing::edit(&registry, &job_id, |transcript| {
+            corrections = glossary::apply(transcript, &config);
+            Ok(())
+        })?;
+        Ok::<_, AppError>(glossary::GlossaryReport::from_corrections(&corrections))
+    })
+    .await
+    .map_err(|e| format!("Glossary task failed: {}", e))?
+}
+
+// Every glossary fix made to a job's transcript so far
+#[tauri::command]
+async fn get_glossary_report(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<glossary::GlossaryReport, AppError> {
+    let registry = job_registry.inner().clone();
+    run_blocking(move || {
+        let transcript = jobs::find(&registry, &job_id)?.transcript.ok_or("Job has no transcript yet")?;
+        Ok(glossary::GlossaryReport::from_corrections(&transcript.corrections))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_itn_config() -> Result<postprocess::ItnConfig, AppError> {
+    run_blocking(|| Ok(postprocess::itn_config())).await
+}
+
+#[tauri::command]
+async fn set_itn_config(itn: postprocess::ItnConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.itn = Some(itn);
+            Ok(())
+        })
+    })
+    .await
+}
+
+// Save the cleanup settings (language rules, glossary, punctuation, ITN) as one JSON file
+#[tauri::command]
+async fn export_rule_bundle(file_path: String) -> Result<String, AppError> {
+    run_blocking(move || bundles::export(&file_path)).await
+}
+
+// Apply a bundle from export_rule_bundle; `merge` adds to the current rules instead of replacing them
+#[tauri::command]
+async fn import_rule_bundle(file_path: String, merge: Option<bool>) -> Result<bundles::RuleBundle, AppError> {
+    run_blocking(move || bundles::import(&file_path, merge.unwrap_or(false))).await
+}
+
+#[tauri::command]
+async fn get_local_model_config() -> Result<gpu::LocalModelConfig, AppError> {
+    run_blocking(|| Ok(gpu::local_model_config())).await
+}
+
+#[tauri::command]
+async fn set_local_model_config(local_model: gpu::LocalModelConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.local_model = Some(local_model);
+            Ok(())
+        })
+    })
+    .await
+}
+
+// CPU, memory, GPUs, OS and free disk space, for support requests and model recommendations
+#[tauri::command]
+async fn get_system_profile() -> Result<hardware::SystemProfile, AppError> {
+    tauri::async_runtime::spawn_blocking(hardware::profile)
+        .await
+        .map_err(|e| AppError::internal(format!("Hardware survey failed: {}", e)))
+}
+
+// The largest model this machine runs comfortably; applied as the default until one is chosen
+#[tauri::command]
+async fn recommend_model() -> Result<gpu::ModelRecommendation, AppError> {
+    tauri::async_runtime::spawn_blocking(|| gpu::recommend_model(&hardware::profile()))
+        .await
+        .map_err(|e| AppError::internal(format!("Hardware survey failed: {}", e)))
+}
+
+// The model the next local job would load, with the VRAM reading behind the choice
+#[tauri::command]
+async fn get_model_plan() -> Result<gpu::ModelPlan, AppError> {
+    tauri::async_runtime::spawn_blocking(|| gpu::plan_model(&gpu::local_model_config(), gpu::query()))
+        .await
+        .map_err(|e| AppError::internal(format!("GPU query failed: {}", e)))
+}
+
+// Time a transcription of the bundled sample (or `file_path`) with the current settings
+#[tauri::command]
+async fn run_benchmark(engine: Option<String>, file_path: Option<String>) -> Result<benchmark::BenchmarkResult, AppError> {
+    let engine = engine.unwrap_or_else(|| "local".to_string());
+    tauri::async_runtime::spawn_blocking(move || benchmark::run(&engine, file_path))
+        .await
+        .map_err(|e| format!("Benchmark task failed: {}", e))?
+}
+
+// Write a synthetic test WAV (tone, noise or speech-like syllables) and return its path
+#[tauri::command]
+async fn generate_test_audio(
+    kind: Option<fixtures::FixtureKind>,
+    seconds: Option<f64>,
+    file_path: Option<String>,
+) -> Result<String, AppError> {
+    let kind = kind.unwrap_or(fixtures::FixtureKind::Speech);
+    let seconds = seconds.unwrap_or(10.0);
+    tauri::async_runtime::spawn_blocking(move || fixtures::create(file_path.as_deref(), kind, seconds))
+        .await
+        .map_err(|e| format!("Test audio task failed: {}", e))?
+}
+
+// Key for the local HTTP API, created on first use
+#[tauri::command]
+async fn get_api_key() -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(api::api_key)
+        .await
+        .map_err(|e| format!("API key task failed: {}", e))?
+}
+
+// Experimental subsystems and whether each is on, with where the setting came from
+#[tauri::command]
+async fn get_feature_flags() -> Result<Vec<flags::FeatureFlag>, AppError> {
+    run_blocking(|| Ok(flags::list())).await
+}
+
+// Turn a flag on or off for this user; `enabled: null` goes back to the default
+#[tauri::command]
+async fn set_feature_flag(flag: flags::Flag, enabled: Option<bool>) -> Result<flags::FeatureFlag, AppError> {
+    run_blocking(move || flags::set(flag, enabled)).await
+}
+
+// Run the bundled sample through decode, engine and export and report each stage
+#[tauri::command]
+async fn run_self_test(engine: Option<String>) -> Result<selftest::SelfTestReport, AppError> {
+    let engine = engine.unwrap_or_else(|| "local".to_string());
+    tauri::async_runtime::spawn_blocking(move || selftest::run(&engine))
+        .await
+        .map_err(|e| AppError::internal(format!("Self-test task failed: {}", e)))
+}
+
+#[tauri::command]
+async fn list_benchmarks() -> Result<Vec<benchmark::BenchmarkResult>, AppError> {
+    run_blocking(benchmark::list).await
+}
+
+// Crash reports left by earlier sessions, for the "save crash report" prompt on launch
+#[tauri::command]
+async fn list_crash_reports() -> Result<Vec<crash::CrashReport>, AppError> {
+    run_blocking(crash::pending).await
+}
+
+#[tauri::command]
+async fn save_crash_report(app: tauri::AppHandle, id: String) -> Result<String, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let report = {
+        let id = id.clone();
+        run_blocking(move || crash::get(&id)).await?
+    };
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("クラッシュレポートを保存")
+        .set_file_name(format!("{}.json", report.id))
+        .add_filter("JSON", &["json"])
+        .save_file(move |path| {
+            let _ = sender.send(path);
+        });
+    let path = receiver
+        .await
+        .map_err(|_| "Save dialog closed unexpectedly".to_string())?
+        .ok_or(AppError::cancelled("Save cancelled by user"))?;
+    let path = path.as_path().ok_or("Failed to get path from FilePath")?.to_path_buf();
+    let contents = serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    tokio::fs::write(&path, contents).await.map_err(|e| format!("Failed to write crash report: {}", e))?;
+    run_blocking(move || crash::dismiss(&id)).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn dismiss_crash_report(id: String) -> Result<(), AppError> {
+    run_blocking(move || crash::dismiss(&id)).await
+}
+
+// One zip to attach to a GitHub issue; saved to the output folder unless `file_path` is given
+#[tauri::command]
+async fn export_diagnostics_bundle(file_path: Option<String>) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || diagnostics::export(file_path.as_deref()))
+        .await
+        .map_err(|e| format!("Diagnostics task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_telemetry_config() -> Result<telemetry::TelemetryConfig, AppError> {
+    run_blocking(|| Ok(telemetry::telemetry_config())).await
+}
+
+// Opt in to or out of usage metrics; opting out deletes the counters collected so far
+#[tauri::command]
+async fn set_telemetry_config(telemetry: telemetry::TelemetryConfig) -> Result<(), AppError> {
+    run_blocking(move || telemetry::set_config(telemetry)).await
+}
+
+// The aggregate counters exactly as a submission would send them
+#[tauri::command]
+async fn get_usage_metrics() -> Result<telemetry::UsageReport, AppError> {
+    run_blocking(|| Ok(telemetry::report())).await
+}
+
+#[tauri::command]
+async fn submit_usage_metrics() -> Result<telemetry::UsageReport, AppError> {
+    tauri::async_runtime::spawn_blocking(telemetry::submit)
+        .await
+        .map_err(|e| format!("Usage metrics task failed: {}", e))?
+}
+
+// Show the per-user data folder (history, logs, crash reports, saved outputs) in the file manager
+#[tauri::command]
+async fn open_data_dir() -> Result<String, AppError> {
+    run_blocking(move || {
+        let data_dir = config::data_dir()?;
+        std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+        let path = data_dir.to_string_lossy().to_string();
+        integrations::oauth::open_in_browser(&path)?;
+        Ok(path)
+    })
+    .await
+}
+
+// The template saved outputs are named with, e.g. "{date}_{stem}.{ext}"
+#[tauri::command]
+async fn get_filename_template() -> Result<String, AppError> {
+    run_blocking(|| Ok(naming::filename_template())).await
+}
+
+// None goes back to "{stem}.{ext}"
+#[tauri::command]
+async fn set_filename_template(template: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        let template = template.filter(|template| !template.trim().is_empty());
+        if let Some(template) = &template {
+            naming::validate(template).map_err(|e| AppError::invalid_input("template", e.to_string()))?;
+        }
+        config::update(|app_config| {
+            app_config.filename_template = template;
+            Ok(())
+        })
+    })
+    .await
+}
+
+// The folder save_to_downloads_direct and the save dialog start from, resolved
+#[tauri::command]
+async fn get_output_dir() -> Result<String, AppError> {
+    run_blocking(|| Ok(config::output_dir()?.to_string_lossy().to_string())).await
+}
+
+// None goes back to the platform Downloads folder
+#[tauri::command]
+async fn set_output_dir(dir: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        let dir = dir.filter(|dir| !dir.trim().is_empty());
+        if let Some(dir) = &dir {
+            if !std::path::Path::new(dir).is_absolute() {
+                return Err(AppError::invalid_input("dir", format!("Invalid output directory '{}': use an absolute path", dir)));
+            }
+        }
+        config::update(|app_config| {
+            app_config.output_dir = dir;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_log_level() -> Result<String, AppError> {
+    Ok(logging::current_level())
+}
+
+// Filter directives such as "debug" or "info,web_whisper::engine=debug,sidecar=warn"
+#[tauri::command]
+async fn set_log_level(level: String) -> Result<(), AppError> {
+    run_blocking(move || logging::set_level(&level)).await
+}
+
+// The last `lines` lines of backend output from the log files, oldest first
+#[tauri::command]
+async fn get_engine_log_tail(lines: Option<usize>) -> Result<Vec<String>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || logging::engine_tail(lines.unwrap_or(200)))
+        .await
+        .map_err(|e| format!("Log task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn follow_engine_log() -> Result<(), AppError> {
+    logging::follow_engine();
+    Ok(())
+}
+
+#[tauri::command]
+async fn unfollow_engine_log() -> Result<(), AppError> {
+    logging::unfollow_engine();
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_queue() -> Result<Vec<queue::QueueEntry>, AppError> {
+    Ok(queue::list())
+}
+
+#[tauri::command]
+async fn set_job_priority(job_id: String, priority: queue::Priority) -> Result<(), AppError> {
+    queue::set_priority(&job_id, priority)
+}
+
+#[tauri::command]
+async fn move_queued_job(job_id: String, position: usize) -> Result<(), AppError> {
+    queue::move_to(&job_id, position)
+}
+
+// Audit entries, oldest first; `since` is an RFC 3339 timestamp
+#[tauri::command]
+async fn get_audit_log(job_id: Option<String>, since: Option<String>, limit: Option<usize>) -> Result<Vec<audit::AuditEntry>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || audit::query(job_id.as_deref(), since.as_deref(), limit))
+        .await
+        .map_err(|e| format!("Audit log task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_redaction_config() -> Result<redact::RedactionConfig, AppError> {
+    run_blocking(|| Ok(redact::redaction_config())).await
+}
+
+#[tauri::command]
+async fn set_redaction_config(redaction: redact::RedactionConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        // Reject patterns that do not compile now rather than failing every later export
+        redact::Redactor::new(&redaction).map_err(|e| AppError::invalid_input("patterns", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.redaction = Some(redaction);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_keyword_config() -> Result<keywords::KeywordConfig, AppError> {
+    run_blocking(|| Ok(keywords::keyword_config())).await
+}
+
+#[tauri::command]
+async fn set_keyword_config(keywords: keywords::KeywordConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        keywords::validate(&keywords).map_err(|e| AppError::invalid_input("keywords", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.keywords = Some(keywords);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_meeting_config() -> Result<meeting::MeetingConfig, AppError> {
+    run_blocking(|| Ok(meeting::meeting_config())).await
+}
+
+#[tauri::command]
+async fn set_meeting_config(meeting: meeting::MeetingConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.meeting = Some(meeting);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_meeting_status() -> Result<meeting::MeetingStatus, AppError> {
+    Ok(meeting::status())
+}
+
+// Returns the path being recorded to
+#[tauri::command]
+async fn start_meeting_recording() -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(meeting::start_manual)
+        .await
+        .map_err(|e| format!("Recording task failed: {}", e))?
+}
+
+// Returns the job id the recording is transcribed under
+#[tauri::command]
+async fn stop_meeting_recording() -> Result<Option<String>, AppError> {
+    tauri::async_runtime::spawn_blocking(meeting::stop)
+        .await
+        .map_err(|e| format!("Recording task failed: {}", e))?
+}
+
+// Transcribe audio as it is captured, without intermediate files; returns the session id
+#[tauri::command]
+async fn start_live_transcription(
+    source: Option<live::LiveSource>,
+    language: Option<String>,
+    chunk_seconds: Option<f64>,
+    hub: State<'_, stream::StreamHub>,
+) -> Result<String, AppError> {
+    let hub = hub.inner().clone();
+    let source = source.unwrap_or(live::LiveSource::Device);
+    tauri::async_runtime::spawn_blocking(move || live::start(hub, source, language, chunk_seconds))
+        .await
+        .map_err(|e| format!("Live transcription task failed: {}", e))?
+}
+
+// Raw binary body of 16 kHz mono s16le PCM, for sessions started with source "app"
+#[tauri::command]
+async fn push_live_audio(request: tauri::ipc::Request<'_>) -> Result<(), AppError> {
+    let tauri::ipc::InvokeBody::Raw(data) = request.body() else {
+        return Err(AppError::invalid_input("body", "push_live_audio expects a binary body"));
+    };
+    if data.len() > uploads::MAX_CHUNK_BYTES {
+        return Err(AppError::invalid_input("body", format!("Chunk of {} bytes exceeds the {} byte limit", data.len(), uploads::MAX_CHUNK_BYTES)));
+    }
+    // Blocks while the engine is decoding and its stdin pipe is full
+    let data = data.clone();
+    tauri::async_runtime::spawn_blocking(move || live::push(&data))
+        .await
+        .map_err(|e| format!("Live transcription task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn stop_live_transcription() -> Result<(), AppError> {
+    live::stop()
+}
+
+#[tauri::command]
+async fn get_live_status() -> Result<live::LiveStatus, AppError> {
+    Ok(live::status())
+}
+
+#[tauri::command]
+async fn get_encryption_config() -> Result<crypto::EncryptionConfig, AppError> {
+    run_blocking(|| Ok(crypto::encryption_config())).await
+}
+
+// Saves the setting, then rewrites history so existing entries follow it
+#[tauri::command]
+async fn set_encryption_config(encryption: crypto::EncryptionConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.encryption = Some(encryption);
+            Ok(())
+        })?;
+        history::reencrypt()
+    })
+    .await
+}
+
+// Pass `project_id` to list only that project's entries
+#[tauri::command]
+async fn get_history(project_id: Option<String>) -> Result<Vec<history::HistoryEntry>, AppError> {
+    run_blocking(move || {
+        let entries = history::list()?;
+        let Some(project_id) = project_id else { return Ok(entries) };
+        let project = projects::get(&project_id)?;
+        Ok(entries.into_iter().filter(|entry| project.job_ids.contains(&entry.job_id)).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_projects() -> Result<Vec<projects::Project>, AppError> {
+    run_blocking(projects::list).await
+}
+
+#[tauri::command]
+async fn create_project(name: String) -> Result<projects::Project, AppError> {
+    run_blocking(move || projects::create(&name)).await
+}
+
+#[tauri::command]
+async fn rename_project(project_id: String, name: String) -> Result<(), AppError> {
+    run_blocking(move || projects::rename(&project_id, &name)).await
+}
+
+#[tauri::command]
+async fn delete_project(project_id: String) -> Result<(), AppError> {
+    run_blocking(move || projects::delete(&project_id)).await
+}
+
+#[tauri::command]
+async fn set_project_defaults(
+    project_id: String,
+    default_options: Option<engine::TranscribeOptions>,
+    export_dir: Option<String>,
+) -> Result<(), AppError> {
+    run_blocking(move || projects::set_defaults(&project_id, default_options, export_dir)).await
+}
+
+// Move a job into a project, or out of its project with `project_id: null`
+#[tauri::command]
+async fn assign_to_project(job_id: String, project_id: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || projects::assign(&job_id, project_id.as_deref())).await
+}
+
+// Correct one segment's text after transcription; later exports use the corrected text
+#[tauri::command]
+async fn update_segment(
+    job_id: String,
+    index: usize,
+    text: String,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<engine::Transcript, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || editing::update_segment(&registry, &job_id, index, &text))
+        .await
+        .map_err(|e| format!("Edit task failed: {}", e))?
+}
+
+// Transcribe [start, end) again with `options` (e.g. a larger `model`) and splice the result
+// into the stored transcript
+#[tauri::command]
+async fn retranscribe_range(
+    job_id: String,
+    start: f64,
+    end: f64,
+    options: Option<engine::TranscribeOptions>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<engine::Transcript, AppError> {
+    let registry = job_registry.inner().clone();
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || editing::retranscribe_range(&registry, &job_id, start, end, &options))
+        .await
+        .map_err(|e| format!("Re-transcription task failed: {}", e))?
+}
+
+// Shift a job's timings by `offset_seconds` and stretch them by `factor` (1.0 keeps the speed)
+#[tauri::command]
+async fn retime_transcript(
+    job_id: String,
+    offset_seconds: f64,
+    factor: Option<f64>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<engine::Transcript, AppError> {
+    let registry = job_registry.inner().clone();
+    let factor = factor.unwrap_or(1.0);
+    tauri::async_runtime::spawn_blocking(move || {
+        editing::edit(&registry, &job_id, |transcript| subtitle::retime_transcript(transcript, offset_seconds, factor))
+    })
+    .await
+    .map_err(|e| format!("Edit task failed: {}", e))?
+}
+
+// The same for an SRT file on disk; returns the path of the retimed copy
+#[tauri::command]
+async fn retime_subtitle_file(
+    file_path: String,
+    offset_seconds: f64,
+    factor: Option<f64>,
+    output_path: Option<String>,
+) -> Result<String, AppError> {
+    let factor = factor.unwrap_or(1.0);
+    tauri::async_runtime::spawn_blocking(move || subtitle::retime_file(&file_path, offset_seconds, factor, output_path.as_deref()))
+        .await
+        .map_err(|e| format!("Retime task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn list_transcript_versions(job_id: String) -> Result<Vec<history::VersionInfo>, AppError> {
+    run_blocking(move || history::versions(&job_id)).await
+}
+
+#[tauri::command]
+async fn diff_transcript_versions(job_id: String, from: u32, to: u32) -> Result<Vec<editing::SegmentChange>, AppError> {
+    run_blocking(move || editing::diff(&job_id, from, to)).await
+}
+
+#[tauri::command]
+async fn revert_transcript(
+    job_id: String,
+    version: u32,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<engine::Transcript, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || editing::revert(&registry, &job_id, version))
+        .await
+        .map_err(|e| format!("Revert task failed: {}", e))?
+}
+
+// Preview for `replace_in_transcript`: match counts and each match with its surrounding text
+#[tauri::command]
+async fn find_in_transcript(
+    job_id: String,
+    query: replace::FindQuery,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<replace::FindResult, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || replace::find(&registry, &job_id, &query))
+        .await
+        .map_err(|e| format!("Find task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn replace_in_transcript(
+    job_id: String,
+    query: replace::FindQuery,
+    replacement: String,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<replace::ReplaceResult, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || replace::replace(&registry, &job_id, &query, &replacement))
+        .await
+        .map_err(|e| format!("Replace task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn list_bookmarks(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<Vec<bookmarks::Bookmark>, AppError> {
+    let registry = job_registry.inner().clone();
+    run_blocking(move || bookmarks::list(&registry, &job_id)).await
+}
+
+// `time` is in seconds from the start of the recording
+#[tauri::command]
+async fn add_bookmark(
+    job_id: String,
+    time: f64,
+    label: String,
+    note: Option<String>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<bookmarks::Bookmark, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        bookmarks::add(&registry, &job_id, time, &label, note.as_deref().unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Bookmark task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn update_bookmark(
+    job_id: String,
+    bookmark_id: String,
+    time: f64,
+    label: String,
+    note: Option<String>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<bookmarks::Bookmark, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        bookmarks::update(&registry, &job_id, &bookmark_id, time, &label, note.as_deref().unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Bookmark task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn delete_bookmark(job_id: String, bookmark_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<(), AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || bookmarks::delete(&registry, &job_id, &bookmark_id))
+        .await
+        .map_err(|e| format!("Bookmark task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn cancel_job(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<(), AppError> {
+    jobs::cancel(&job_registry, &job_id)
+}
+
+#[tauri::command]
+async fn get_webhooks() -> Result<Vec<integrations::webhooks::WebhookConfig>, AppError> {
+    run_blocking(|| Ok(config::load().webhooks)).await
+}
+
+#[tauri::command]
+async fn set_webhooks(webhooks: Vec<integrations::webhooks::WebhookConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.webhooks = webhooks;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn test_webhook(webhook: integrations::webhooks::WebhookConfig) -> Result<(), AppError> {
+    let body = serde_json::json!({"event": "ping", "job_id": null}).to_string();
+    integrations::webhooks::deliver(&webhook, body).await
+}
+
+#[tauri::command]
+async fn get_s3_config() -> Result<Option<integrations::s3::S3Config>, AppError> {
+    run_blocking(|| Ok(config::load().s3)).await
+}
+
+#[tauri::command]
+async fn set_s3_config(s3: Option<integrations::s3::S3Config>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.s3 = s3;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_s3_credentials(access_key_id: String, secret_access_key: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        secrets::set(integrations::s3::ACCESS_KEY_ID, &access_key_id)?;
+        secrets::set(integrations::s3::SECRET_ACCESS_KEY, &secret_access_key)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn clear_s3_credentials() -> Result<(), AppError> {
+    run_blocking(move || {
+        secrets::delete(integrations::s3::ACCESS_KEY_ID)?;
+        secrets::delete(integrations::s3::SECRET_ACCESS_KEY)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn upload_transcript(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let s3 = load_config().await?.s3.ok_or(AppError::not_configured("S3 upload is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let url = integrations::s3::upload_transcript(&s3, &record).await?;
+    audit::exported(&job_id, "S3", &url);
+    Ok(url)
+}
+
+#[tauri::command]
+async fn get_google_drive_config() -> Result<Option<integrations::gdrive::GoogleDriveConfig>, AppError> {
+    run_blocking(|| Ok(config::load().google_drive)).await
+}
+
+#[tauri::command]
+async fn set_google_drive_config(google_drive: Option<integrations::gdrive::GoogleDriveConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.google_drive = google_drive;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn connect_google_drive(client_secret: Option<String>) -> Result<(), AppError> {
+    let google_drive = load_config().await?.google_drive.ok_or(AppError::not_configured("Google Drive is not configured"))?;
+    integrations::gdrive::connect(&google_drive, client_secret).await
+}
+
+#[tauri::command]
+async fn disconnect_google_drive() -> Result<(), AppError> {
+    integrations::gdrive::disconnect()
+}
+
+#[tauri::command]
+async fn upload_to_google_drive(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let google_drive = load_config().await?.google_drive.ok_or(AppError::not_configured("Google Drive is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let link = integrations::gdrive::upload_transcript(&google_drive, &record).await?;
+    audit::exported(&job_id, "Google Drive", &link);
+    Ok(link)
+}
+
+#[tauri::command]
+async fn get_dropbox_config() -> Result<Option<integrations::dropbox::DropboxConfig>, AppError> {
+    run_blocking(|| Ok(config::load().dropbox)).await
+}
+
+#[tauri::command]
+async fn set_dropbox_config(dropbox: Option<integrations::dropbox::DropboxConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.dropbox = dropbox;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn connect_dropbox() -> Result<(), AppError> {
+    let dropbox = load_config().await?.dropbox.ok_or(AppError::not_configured("Dropbox is not configured"))?;
+    integrations::dropbox::connect(&dropbox).await
+}
+
+#[tauri::command]
+async fn disconnect_dropbox() -> Result<(), AppError> {
+    integrations::dropbox::disconnect()
+}
+
+#[tauri::command]
+async fn send_to_dropbox(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let dropbox = load_config().await?.dropbox.ok_or(AppError::not_configured("Dropbox is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let path = integrations::dropbox::send_transcript(&dropbox, &record).await?;
+    audit::exported(&job_id, "Dropbox", &path);
+    Ok(path)
+}
+
+#[tauri::command]
+async fn get_obsidian_config() -> Result<Option<integrations::obsidian::ObsidianConfig>, AppError> {
+    run_blocking(|| Ok(config::load().obsidian)).await
+}
+
+#[tauri::command]
+async fn set_obsidian_config(obsidian: Option<integrations::obsidian::ObsidianConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.obsidian = obsidian;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn export_to_obsidian(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let obsidian = load_config().await?.obsidian.ok_or(AppError::not_configured("Obsidian export is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let path = tauri::async_runtime::spawn_blocking(move || integrations::obsidian::export_note(&obsidian, &record))
+        .await
+        .map_err(|e| format!("Obsidian export task failed: {}", e))??;
+    audit::exported(&job_id, "Obsidian", &path);
+    Ok(path)
+}
+
+#[tauri::command]
+async fn get_notion_config() -> Result<Option<integrations::notion::NotionConfig>, AppError> {
+    run_blocking(|| Ok(config::load().notion)).await
+}
+
+#[tauri::command]
+async fn set_notion_config(notion: Option<integrations::notion::NotionConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.notion = notion;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_notion_token(token: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match token {
+            Some(token) => secrets::set(integrations::notion::TOKEN, &token),
+            None => secrets::delete(integrations::notion::TOKEN),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+async fn export_to_notion(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let notion = load_config().await?.notion.ok_or(AppError::not_configured("Notion export is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let url = integrations::notion::export_page(&notion, &record).await?;
+    audit::exported(&job_id, "Notion", &url);
+    Ok(url)
+}
+
+#[tauri::command]
+async fn get_chat_channels() -> Result<Vec<integrations::chat::ChatChannel>, AppError> {
+    run_blocking(|| Ok(config::load().chat_channels)).await
+}
+
+#[tauri::command]
+async fn set_chat_channels(channels: Vec<integrations::chat::ChatChannel>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.chat_channels = channels;
+            Ok(())
+        })
+    })
+    .await
+}
+
+// Post a job's summary or full transcript to a configured Slack/Discord channel
+#[tauri::command]
+async fn share_to_channel(
+    job_id: String,
+    channel: String,
+    content: integrations::chat::ShareContent,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<usize, AppError> {
+    let channel = load_config().await?
+        .chat_channels
+        .into_iter()
+        .find(|candidate| candidate.name == channel)
+        .ok_or_else(|| AppError::unknown(format!("Unknown chat channel: {}", channel)))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let messages = integrations::chat::share(&channel, &record, content).await?;
+    audit::exported(&job_id, "Chat", &channel.name);
+    Ok(messages)
+}
+
+#[tauri::command]
+async fn get_email_config() -> Result<Option<integrations::email::EmailConfig>, AppError> {
+    run_blocking(|| Ok(config::load().email)).await
+}
+
+#[tauri::command]
+async fn set_email_config(email: Option<integrations::email::EmailConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.email = email;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_smtp_password(password: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match password {
+            Some(password) => secrets::set(integrations::email::PASSWORD, &password),
+            None => secrets::delete(integrations::email::PASSWORD),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+async fn email_transcript(job_id: String, to: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let email = load_config().await?.email.ok_or(AppError::not_configured("Email export is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let subject = integrations::email::send_transcript(&email, &record, &to).await?;
+    audit::exported(&job_id, "Email", &to);
+    Ok(subject)
+}
+
+#[tauri::command]
+async fn get_calendar_config() -> Result<Option<integrations::calendar::CalendarConfig>, AppError> {
+    run_blocking(|| Ok(config::load().calendar)).await
+}
+
+#[tauri::command]
+async fn set_calendar_config(calendar: Option<integrations::calendar::CalendarConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.calendar = calendar;
+            Ok(())
+        })
+    })
+    .await
+}
+
+// The meeting running right now, so the UI can preview how a new recording will be named
+#[tauri::command]
+async fn current_meeting() -> Result<Option<integrations::calendar::Meeting>, AppError> {
+    let calendar = load_config().await?.calendar.ok_or(AppError::not_configured("Calendar integration is not configured"))?;
+    tauri::async_runtime::spawn_blocking(move || integrations::calendar::meeting_at(&calendar, chrono::Utc::now()))
+        .await
+        .map_err(|e| format!("Calendar task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_anki_config() -> Result<Option<integrations::anki::AnkiConfig>, AppError> {
+    run_blocking(|| Ok(config::load().anki)).await
+}
+
+#[tauri::command]
+async fn set_anki_config(anki: Option<integrations::anki::AnkiConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.anki = anki;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn export_to_anki(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<integrations::anki::AnkiExport, AppError> {
+    let anki = load_config().await?.anki.ok_or(AppError::not_configured("Anki export is not configured"))?;
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    // One ffmpeg run per card
+    let export = tauri::async_runtime::spawn_blocking(move || integrations::anki::export_deck(&anki, &record))
+        .await
+        .map_err(|e| format!("Anki export task failed: {}", e))??;
+    audit::exported(&job_id, "Anki", &export.deck_path);
+    Ok(export)
+}
+
+// Cut the selected segments out of the source audio; `format` defaults to mp3
+#[tauri::command]
+async fn export_segment_audio(
+    job_id: String,
+    segment_indices: Vec<usize>,
+    format: Option<String>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<Vec<audio::SegmentClip>, AppError> {
+    let registry = job_registry.inner().clone();
+    let format = format.unwrap_or_else(|| "mp3".to_string());
+    tauri::async_runtime::spawn_blocking(move || audio::export_segment_clips(&registry, &job_id, &segment_indices, &format))
+        .await
+        .map_err(|e| format!("Clip export task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_openai_config() -> Result<engine::openai::OpenAiConfig, AppError> {
+    run_blocking(|| Ok(config::load().openai.unwrap_or_default())).await
+}
+
+#[tauri::command]
+async fn set_openai_config(openai: engine::openai::OpenAiConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.openai = Some(openai);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_openai_api_key(api_key: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match api_key {
+            Some(api_key) => secrets::set(engine::openai::API_KEY, &api_key),
+            None => secrets::delete(engine::openai::API_KEY),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_azure_speech_config() -> Result<Option<engine::azure::AzureSpeechConfig>, AppError> {
+    run_blocking(|| Ok(config::load().azure_speech)).await
+}
+
+#[tauri::command]
+async fn set_azure_speech_config(azure_speech: Option<engine::azure::AzureSpeechConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.azure_speech = azure_speech;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_azure_speech_key(api_key: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match api_key {
+            Some(api_key) => secrets::set(engine::azure::API_KEY, &api_key),
+            None => secrets::delete(engine::azure::API_KEY),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_google_speech_config() -> Result<Option<engine::google::GoogleSpeechConfig>, AppError> {
+    run_blocking(|| Ok(config::load().google_speech)).await
+}
+
+#[tauri::command]
+async fn set_google_speech_config(google_speech: Option<engine::google::GoogleSpeechConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.google_speech = google_speech;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_google_speech_api_key(api_key: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match api_key {
+            Some(api_key) => secrets::set(engine::google::API_KEY, &api_key),
+            None => secrets::delete(engine::google::API_KEY),
+        }
+    })
+    .await
+}
+
+// Size check and cost estimate shown before a job is sent to the OpenAI API
+#[tauri::command]
+async fn estimate_cloud_cost(file_path: String) -> Result<engine::openai::CostEstimate, AppError> {
+    let openai = load_config().await?.openai.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || engine::openai::estimate(&openai, &file_path))
+        .await
+        .map_err(|e| format!("Estimate task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_deepl_config() -> Result<Option<integrations::deepl::DeepLConfig>, AppError> {
+    run_blocking(|| Ok(config::load().deepl)).await
+}
+
+#[tauri::command]
+async fn set_deepl_config(deepl: Option<integrations::deepl::DeepLConfig>) -> Result<(), AppError> {
+    run_blocking(move || {
+        config::update(|app_config| {
+            app_config.deepl = deepl;
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_deepl_api_key(api_key: Option<String>) -> Result<(), AppError> {
+    run_blocking(move || {
+        match api_key {
+            Some(api_key) => secrets::set(integrations::deepl::API_KEY, &api_key),
+            None => secrets::delete(integrations::deepl::API_KEY),
+        }
+    })
+    .await
+}
+
+// Translate a finished job with DeepL and keep the result next to the original text
+#[tauri::command]
+async fn translate_job(job_id: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<engine::Translation, AppError> {
+    let deepl = load_config().await?.deepl.ok_or(AppError::not_configured("DeepL translation is not configured"))?;
+    let record = find_job(&job_registry, &job_id).await?;
+    let mut transcript = record.transcript.ok_or("Job has no transcript to translate")?;
+    let translation = integrations::deepl::translate(&deepl, &transcript).await?;
+    let registry = job_registry.inner().clone();
+    let (saved_id, saved) = (job_id.clone(), translation.clone());
+    run_blocking(move || {
+        editing::ensure_in_history(&registry, &saved_id)?;
+        history::annotate(&saved_id, |transcript| {
+            transcript.translation = Some(saved);
+            Ok(())
+        })
+        .map(|_| ())
+    })
+    .await?;
+    transcript.translation = Some(translation.clone());
+    jobs::set_transcript(&job_registry, &job_id, transcript);
+    Ok(translation)
+}
+
+// Write a job's transcript in each format straight to its output folder, no dialog.
+// `output_dir` overrides the job's, project's and global folders for this save.
+#[tauri::command]
+async fn save_job_output(
+    job_id: String,
+    formats: Vec<String>,
+    output_dir: Option<String>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<Vec<String>, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || outputs::save(&registry, &job_id, &formats, output_dir.as_deref()))
+        .await
+        .map_err(|e| format!("Save task failed: {}", e))?
+}
+
+// Append a job's transcript to an existing notes file, with a separator and a time header
+#[tauri::command]
+async fn append_job_output(job_id: String, file_path: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let registry = job_registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || outputs::append(&registry, &job_id, &file_path))
+        .await
+        .map_err(|e| format!("Append task failed: {}", e))?
+}
+
+// Read a transcript file saved automatically when a job finished, which may be encrypted
+#[tauri::command]
+async fn read_job_output(file_path: String) -> Result<String, AppError> {
+    run_blocking(move || outputs::read(&file_path)).await
+}
+
+// Combine an original and a translated SRT into one bilingual SRT or ASS; returns its path
+#[tauri::command]
+async fn merge_subtitles(
+    original: String,
+    translated: String,
+    layout: Option<subtitle::MergeLayout>,
+    output_path: Option<String>,
+) -> Result<String, AppError> {
+    let layout = layout.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || subtitle::merge_files(&original, &translated, &layout, output_path.as_deref()))
+        .await
+        .map_err(|e| format!("Merge task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_caption_config() -> Result<captions::CaptionConfig, AppError> {
+    run_blocking(|| Ok(captions::caption_config())).await
+}
+
+#[tauri::command]
+async fn set_caption_config(captions: captions::CaptionConfig) -> Result<(), AppError> {
+    run_blocking(move || {
+        captions::validate(&captions).map_err(|e| AppError::invalid_input("captions", e.to_string()))?;
+        config::update(|app_config| {
+            app_config.captions = Some(captions);
+            Ok(())
+        })
+    })
+    .await
+}
+
+// Check a job's subtitles, as exported, for overlaps, zero-length and out-of-order cues and long lines
+#[tauri::command]
+async fn lint_transcript(
+    job_id: String,
+    max_line_length: Option<usize>,
+    max_cps: Option<f64>,
+    job_registry: State<'_, jobs::JobRegistry>,
+) -> Result<lint::LintReport, AppError> {
+    let registry = job_registry.inner().clone();
+    run_blocking(move || {
+        let transcript = jobs::find(&registry, &job_id)?.transcript.ok_or("Job has no transcript yet")?;
+        let max_cps = max_cps.or(captions::caption_config().max_cps);
+        Ok(lint::check(&captions::cues(&transcript), max_line_length.unwrap_or_else(lint::default_max_line_length), max_cps))
+    })
+    .await
+}
+
+// The same checks for an SRT file on disk
+#[tauri::command]
+async fn lint_subtitle_file(
+    file_path: String,
+    max_line_length: Option<usize>,
+    max_cps: Option<f64>,
+) -> Result<lint::LintReport, AppError> {
+    run_blocking(move || {
+        let segments = subtitle::read_srt(&file_path)?;
+        let max_cps = max_cps.or(captions::caption_config().max_cps);
+        Ok(lint::check(&segments, max_line_length.unwrap_or_else(lint::default_max_line_length), max_cps))
+    })
+    .await
+}
+
+// Add an SRT or WebVTT file to history as a transcript, tied to `media_path` or a media
+// file of the same name next to it
+#[tauri::command]
+async fn import_subtitles(
+    file_path: String,
+    media_path: Option<String>,
+    title: Option<String>,
+    language: Option<String>,
+) -> Result<history::HistoryEntry, AppError> {
+    tauri::async_runtime::spawn_blocking(move || imports::import_subtitles(&file_path, media_path.as_deref(), title, language))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))?
+}
+
+// Render a job's transcript in an export format (see subtitle::render)
+#[tauri::command]
+async fn render_transcript(job_id: String, format: String, job_registry: State<'_, jobs::JobRegistry>) -> Result<String, AppError> {
+    let record = redact::for_export(find_job(&job_registry, &job_id).await?)?;
+    let transcript = record.transcript.ok_or("Job has no transcript")?;
+    subtitle::render(&transcript, &format)
+}
+
+#[tauri::command]
+async fn stop_whisper_server(app: tauri::AppHandle, process_state: State<'_, ProcessState>) -> Result<(), AppError> {
+    let process_id = {
+        let process_guard = process_state.lock().unwrap();
+        *process_guard
+    };
+    
+    if let Some(pid) = process_id {
+        info!("Stopping Python server with PID: {}", pid);
+        
+        tauri::async_runtime::spawn_blocking(move || engine::kill_process(pid))
+            .await
+            .map_err(|e| format!("Stop task failed: {}", e))??;
+        
+        // Clear process state
+        {
+            let mut process_guard = process_state.lock().unwrap();
+            *process_guard = None;
+        }
+        idle::set_status(&app, idle::BackendStatus::Stopped);
+        
+        info!("Python server stopped");
+        Ok(())
+    } else {
+        Err(AppError::backend_unavailable("No server process found"))
+    }
+}
+
+fn main() {
+    logging::init();
+    crash::install();
+
+    // Headless JSON-RPC mode for editor plugins; never opens a window
+    if rpc::is_requested() {
+        rpc::run();
+        return;
+    }
+
+    let server_state: ServerState = Arc::new(Mutex::new(None));
+    let process_state: ProcessState = Arc::new(Mutex::new(None));
+    let stream_hub = stream::new_hub();
+    let job_registry = jobs::new_registry();
+    
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(server_state)
+        .manage(process_state.clone())
+        .manage(stream_hub.clone())
+        .manage(job_registry.clone())
+        .manage(uploads::new_registry())
+        .invoke_handler(tauri::generate_handler![
+            start_gradio_server,
+            get_server_info,
+            open_whisper_gui,
+            navigate_whisper_gui,
+            save_temp_file,
+            begin_upload,
+            check_disk_space,
+            append_chunk,
+            finish_upload,
+            abort_upload,
+            upload_to_backend,
+            transcribe_audio,
+            reuse_transcript,
+            fingerprint_history,
+            save_transcription,
+            save_to_downloads_direct,
+            get_gpu_info,
+            transcribe_url,
+            enqueue_folder,
+            import_zip,
+            list_feeds,
+            subscribe_feed,
+            unsubscribe_feed,
+            refresh_feeds,
+            list_watch_folders,
+            add_watch_folder,
+            remove_watch_folder,
+            detect_recording_folders,
+            get_worker_config,
+            set_worker_config,
+            get_power_config,
+            set_power_config,
+            get_power_status,
+            get_idle_config,
+            set_idle_config,
+            get_backend_status,
+            get_dashboard,
+            get_heartbeat_config,
+            set_heartbeat_config,
+            get_resource_config,
+            set_resource_config,
+            get_proxy_config,
+            set_proxy_config,
+            set_proxy_password,
+            get_temp_config,
+            set_temp_config,
+            get_retention_config,
+            set_retention_config,
+            delete_all_data,
+            get_schedule_config,
+            set_schedule_config,
+            get_schedule_status,
+            set_schedule_override,
+            get_local_model_config,
+            set_local_model_config,
+            get_model_plan,
+            get_language_config,
+            set_language_config,
+            detect_languages,
+            get_punctuation_config,
+            set_punctuation_config,
+            get_glossary_config,
+            set_glossary_config,
+            apply_glossary,
+            get_glossary_report,
+            get_itn_config,
+            set_itn_config,
+            export_rule_bundle,
+            import_rule_bundle,
+            get_system_profile,
+            recommend_model,
+            run_benchmark,
+            list_benchmarks,
+            run_self_test,
+            generate_test_audio,
+            get_api_key,
+            get_feature_flags,
+            set_feature_flag,
+            list_crash_reports,
+            save_crash_report,
+            dismiss_crash_report,
+            export_diagnostics_bundle,
+            get_telemetry_config,
+            set_telemetry_config,
+            get_usage_metrics,
+            submit_usage_metrics,
+            open_data_dir,
+            get_output_dir,
+            set_output_dir,
+            get_filename_template,
+            set_filename_template,
+            get_log_level,
+            set_log_level,
+            get_engine_log_tail,
+            follow_engine_log,
+            unfollow_engine_log,
+            list_queue,
+            set_job_priority,
+            move_queued_job,
+            get_history,
+            list_projects,
+            create_project,
+            rename_project,
+            delete_project,
+            set_project_defaults,
+            assign_to_project,
+            update_segment,
+            retranscribe_range,
+            list_transcript_versions,
+            diff_transcript_versions,
+            revert_transcript,
+            find_in_transcript,
+            replace_in_transcript,
+            list_bookmarks,
+            add_bookmark,
+            update_bookmark,
+            delete_bookmark,
+            get_encryption_config,
+            set_encryption_config,
+            get_redaction_config,
+            get_audit_log,
+            set_redaction_config,
+            get_keyword_config,
+            set_keyword_config,
+            get_meeting_config,
+            set_meeting_config,
+            get_meeting_status,
+            start_meeting_recording,
+            stop_meeting_recording,
+            start_live_transcription,
+            push_live_audio,
+            stop_live_transcription,
+            get_live_status,
+            cancel_job,
+            get_webhooks,
+            set_webhooks,
+            test_webhook,
+            get_s3_config,
+            set_s3_config,
+            set_s3_credentials,
+            clear_s3_credentials,
+            upload_transcript,
+            get_google_drive_config,
+            set_google_drive_config,
+            connect_google_drive,
+            disconnect_google_drive,
+            upload_to_google_drive,
+            get_dropbox_config,
+            set_dropbox_config,
+            connect_dropbox,
+            disconnect_dropbox,
+            send_to_dropbox,
+            get_obsidian_config,
+            set_obsidian_config,
+            export_to_obsidian,
+            get_notion_config,
+            set_notion_config,
+            set_notion_token,
+            export_to_notion,
+            get_chat_channels,
+            set_chat_channels,
+            share_to_channel,
+            get_email_config,
+            set_email_config,
+            set_smtp_password,
+            email_transcript,
+            get_calendar_config,
+            set_calendar_config,
+            current_meeting,
+            get_anki_config,
+            set_anki_config,
+            export_to_anki,
+            export_segment_audio,
+            get_openai_config,
+            set_openai_config,
+            set_openai_api_key,
+            estimate_cloud_cost,
+            get_azure_speech_config,
+            set_azure_speech_config,
+            set_azure_speech_key,
+            get_google_speech_config,
+            set_google_speech_config,
+            set_google_speech_api_key,
+            get_deepl_config,
+            set_deepl_config,
+            set_deepl_api_key,
+            translate_job,
+            render_transcript,
+            save_job_output,
+            append_job_output,
+            read_job_output,
+            merge_subtitles,
+            retime_transcript,
+            retime_subtitle_file,
+            get_caption_config,
+            set_caption_config,
+            lint_transcript,
+            lint_subtitle_file,
+            import_subtitles,
+            stop_whisper_server
+        ])
+        .setup({
+            let process_state_clone = process_state.clone();
+            move |app| {
+                events::init(app.handle().clone());
+                integrations::start(&stream_hub, job_registry.clone());
+                journal::restore(stream_hub.clone(), job_registry.clone());
+                retention::start();
+                tauri::async_runtime::spawn_blocking(gpu::apply_recommended_default);
+                idle::start_monitor(app.handle().clone());
+                resources::start_monitor(app.handle().clone());
+                heartbeat::start(app.handle().clone());
+                tauri::async_runtime::spawn_blocking(telemetry::start);
+                feeds::start_polling(stream_hub.clone(), job_registry.clone());
+                watch::start_watching(stream_hub.clone(), job_registry.clone());
+                meeting::start(stream_hub.clone(), job_registry.clone());
+
+                if let Some(port) = api::configured_port() {
+                    let hub = stream_hub.clone();
+                    let job_registry = job_registry.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = api::serve(port, hub, job_registry).await {
+                            error!("{}", e);
+                        }
+                    });
+                }
+
+                #[cfg(feature = "grpc")]
+                if let Some(port) = grpc::configured_port() {
+                    let hub = stream_hub.clone();
+                    let job_registry = job_registry.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = grpc::serve(port, hub, job_registry).await {
+                            error!("{}", e);
+                        }
+                    });
+                }
+
                 #[cfg(desktop)]
                 {
                     use tauri::Manager;
@@ -700,12 +2754,10 @@ fn main() {
                             // Stop the server process before closing
                             if let Some(pid) = {
                                 let guard = process_state_for_close.lock().unwrap();
-                                guard.clone()
+                                *guard
                             } {
-                                println!("Cleaning up Python server process: {}", pid);
-                                let _ = Command::new("taskkill")
-                                    .args(&["/F", "/PID", &pid.to_string()])
-                                    .output();
+                                info!("Cleaning up Python server process: {}", pid);
+                                let _ = engine::kill_process(pid);
                             }
                         }
                     });
@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tracing::{info, warn};
+
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::events;
+use crate::jobs::JobRegistry;
+use crate::stream::StreamHub;
+use crate::{config, history, journal};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// A meeting counts as over after this many polls without its trigger, so a muted
+// microphone or a restarting app does not cut the recording
+const END_AFTER_POLLS: u32 = 3;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MeetingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    // Start recording as soon as a meeting is detected instead of asking with a `meeting-detected` event
+    #[serde(default)]
+    pub auto_start: bool,
+    // Process names of meeting apps, matched case-insensitively without ".exe"
+    #[serde(default = "default_apps")]
+    pub apps: Vec<String>,
+    // Also treat another app capturing from a microphone as a meeting (Windows and Linux)
+    #[serde(default = "default_true")]
+    pub watch_microphone: bool,
+    // ffmpeg input device names; the defaults per platform are in the README
+    #[serde(default)]
+    pub microphone_device: Option<String>,
+    #[serde(default)]
+    pub loopback_device: Option<String>,
+    #[serde(default = "default_max_minutes")]
+    pub max_minutes: u64,
+    // Transcription language for recorded meetings; detected when unset
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+// Zoom's CptHost and Teams' call process only run during a call
+fn default_apps() -> Vec<String> {
+    ["CptHost", "zoom.us", "ms-teams", "Teams", "CiscoCollabHost", "Webex"].iter().map(|app| app.to_string()).collect()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_minutes() -> u64 {
+    240
+}
+
+impl Default for MeetingConfig {
+    fn default() -> Self {
+        MeetingConfig {
+            enabled: false,
+            auto_start: false,
+            apps: default_apps(),
+            watch_microphone: default_true(),
+            microphone_device: None,
+            loopback_device: None,
+            max_minutes: default_max_minutes(),
+            language: None,
+        }
+    }
+}
+
+pub fn meeting_config() -> MeetingConfig {
+    config::load().meeting.unwrap_or_default()
+}
+
+// What made the session manager think a meeting started
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Trigger {
+    App { name: String },
+    Microphone,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MeetingStatus {
+    pub detected: Option<Trigger>,
+    pub recording: bool,
+    pub path: Option<String>,
+    // RFC 3339
+    pub started_at: Option<String>,
+    pub trigger: Option<Trigger>,
+}
+
+struct Recording {
+    child: Child,
+    path: PathBuf,
+    started_at: String,
+    // None when started by hand; such recordings only stop by hand or at max_minutes
+    trigger: Option<Trigger>,
+    missed_polls: u32,
+}
+
+struct Session {
+    detected: Option<Trigger>,
+    recording: Option<Recording>,
+}
+
+static SESSION: Mutex<Session> = Mutex::new(Session { detected: None, recording: None });
+
+// Set by `start`; finished recordings are transcribed as regular jobs
+static JOBS: OnceLock<(StreamHub, JobRegistry)> = OnceLock::new();
+
+fn running_app(apps: &[String]) -> Option<String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new());
+    system.processes().values().find_map(|process| {
+        let name = process.name().to_string_lossy();
+        let name = name.strip_suffix(".exe").unwrap_or(&name);
+        apps.iter().find(|app| app.eq_ignore_ascii_case(name)).cloned()
+    })
+}
+
+// PulseAudio and PipeWire hold the hardware open for every client, so ask them who is
+// recording. Without pactl, fall back to ALSA capture streams in the RUNNING state.
+#[cfg(target_os = "linux")]
+fn microphone_in_use(own_pid: Option<u32>) -> bool {
+    if let Ok(output) = Command::new("pactl").args(["list", "source-outputs"]).output() {
+        if output.status.success() {
+            let own = own_pid.map(|pid| format!("application.process.id = \"{}\"", pid));
+            return String::from_utf8_lossy(&output.stdout)
+                .split("Source Output #")
+                .skip(1)
+                .any(|client| own.as_ref().is_none_or(|own| !client.contains(own.as_str())));
+        }
+    }
+    let Ok(cards) = std::fs::read_dir("/proc/asound") else { return false };
+    for card in cards.flatten().filter(|card| card.file_name().to_string_lossy().starts_with("card")) {
+        let Ok(devices) = std::fs::read_dir(card.path()) else { continue };
+        for device in devices.flatten() {
+            let name = device.file_name().to_string_lossy().to_string();
+            if !(name.starts_with("pcm") && name.ends_with('c')) {
+                continue;
+            }
+            let Ok(substreams) = std::fs::read_dir(device.path()) else { continue };
+            for substream in substreams.flatten() {
+                let Ok(status) = std::fs::read_to_string(substream.path().join("status")) else { continue };
+                let owner = status
+                    .lines()
+                    .find_map(|line| line.strip_prefix("owner_pid"))
+                    .and_then(|value| value.trim_start_matches([' ', '\t', ':']).trim().parse::<u32>().ok());
+                if status.contains("state: RUNNING") && (owner.is_none() || owner != own_pid) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+// Windows records microphone use per app; LastUsedTimeStop is 0 while an app is capturing
+#[cfg(windows)]
+fn microphone_in_use(_own_pid: Option<u32>) -> bool {
+    let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+    let Ok(output) = Command::new("reg").args(["query", key, "/s", "/v", "LastUsedTimeStop"]).output() else {
+        return false;
+    };
+    let mut app = String::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.starts_with("HKEY_") {
+            app = line.to_lowercase();
+        } else if line.contains("LastUsedTimeStop") && line.trim_end().ends_with("0x0") && !app.contains("ffmpeg") {
+            return true;
+        }
+    }
+    false
+}
+
+// No public API reports other apps' microphone use on macOS; meeting apps are still detected
+#[cfg(not(any(target_os = "linux", windows)))]
+fn microphone_in_use(_own_pid: Option<u32>) -> bool {
+    false
+}
+
+fn detect(config: &MeetingConfig, own_pid: Option<u32>) -> Option<Trigger> {
+    if let Some(name) = running_app(&config.apps) {
+        return Some(Trigger::App { name });
+    }
+    (config.watch_microphone && microphone_in_use(own_pid)).then_some(Trigger::Microphone)
+}
+
+// ffmpeg inputs for the microphone and, when available, what the speakers play
+pub fn inputs(config: &MeetingConfig) -> Result<Vec<Vec<String>>, AppError> {
+    let input = |format: &str, device: String| vec!["-f".to_string(), format.to_string(), "-i".to_string(), device];
+    let mut inputs = Vec::new();
+    if cfg!(target_os = "linux") {
+        inputs.push(input("pulse", config.microphone_device.clone().unwrap_or_else(|| "default".to_string())));
+        inputs.push(input("pulse", config.loopback_device.clone().unwrap_or_else(|| "@DEFAULT_MONITOR@".to_string())));
+    } else if cfg!(target_os = "macos") {
+        // System audio needs a virtual device such as BlackHole set as loopback_device
+        inputs.push(input("avfoundation", format!(":{}", config.microphone_device.as_deref().unwrap_or("0"))));
+        if let Some(device) = &config.loopback_device {
+            inputs.push(input("avfoundation", format!(":{}", device)));
+        }
+    } else {
+        let microphone = config
+            .microphone_device
+            .as_ref()
+            .ok_or(AppError::not_configured("Microphone device is not configured; set meeting.microphone_device to a DirectShow device name"))?;
+        inputs.push(input("dshow", format!("audio={}", microphone)));
+        // e.g. "Stereo Mix" or a virtual cable
+        if let Some(device) = &config.loopback_device {
+            inputs.push(input("dshow", format!("audio={}", device)));
+        }
+    }
+    if inputs.len() == 1 {
+        warn!("No loopback device configured; recording the microphone only");
+    }
+    Ok(inputs)
+}
+
+fn recordings_dir() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("recordings"))
+}
+
+fn spawn_recorder(config: &MeetingConfig) -> Result<(Child, PathBuf), AppError> {
+    let dir = recordings_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create recordings folder: {}", e))?;
+    crate::disk::ensure_space(&dir, 0)?;
+    let path = dir.join(format!("meeting-{}.m4a", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")));
+
+    let inputs = inputs(config)?;
+    let mut command = Command::new("ffmpeg");
+    command.args(["-y", "-v", "error"]);
+    for input in &inputs {
+        command.args(input);
+    }
+    if inputs.len() > 1 {
+        command.args(["-filter_complex", &format!("amix=inputs={}:duration=longest", inputs.len())]);
+    }
+    command
+        .args(["-t", &(config.max_minutes.max(1) * 60).to_string(), "-c:a", "aac", "-b:a", "96k"])
+        .arg(&path)
+        .env("PATH", engine::path_with_ffmpeg())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let child = command.spawn().map_err(|e| format!("Failed to start recording: {}", e))?;
+    Ok((child, path))
+}
+
+fn start_recording(session: &mut Session, trigger: Option<Trigger>) -> Result<String, AppError> {
+    if session.recording.is_some() {
+        return Err(AppError::invalid("Invalid request: a meeting is already being recorded"));
+    }
+    let (child, path) = spawn_recorder(&meeting_config())?;
+    let started_at = chrono::Local::now().to_rfc3339();
+    info!("Recording meeting to {:?}", path);
+    events::emit_named("meeting-recording-started", &serde_json::json!({
+        "path": path.to_string_lossy(),
+        "trigger": trigger,
+    }));
+    let path_string = path.to_string_lossy().to_string();
+    session.recording = Some(Recording { child, path, started_at, trigger, missed_polls: 0 });
+    Ok(path_string)
+}
+
+// Ask ffmpeg to finish the file ("q"), then queue it for transcription
+fn finish(mut recording: Recording) -> Option<String> {
+    if let Some(stdin) = recording.child.stdin.as_mut() {
+        let _ = stdin.write_all(b"q");
+    }
+    drop(recording.child.stdin.take());
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while recording.child.try_wait().ok().flatten().is_none() {
+        if Instant::now() >= deadline {
+            warn!("Recorder did not stop; killing it");
+            let _ = recording.child.kill();
+            let _ = recording.child.wait();
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    let file_path = recording.path.to_string_lossy().to_string();
+    if !recording.path.exists() {
+        warn!("Meeting recording {} was not written", file_path);
+        return None;
+    }
+    let job_id = engine::new_job_id();
+    events::emit_named("meeting-recording-stopped", &serde_json::json!({ "path": file_path, "job_id": job_id }));
+    let (hub, registry) = JOBS.get()?.clone();
+    let options = TranscribeOptions {
+        language: meeting_config().language.unwrap_or_else(|| "auto".to_string()),
+        ..Default::default()
+    };
+    let (id, started_at) = (job_id.clone(), recording.started_at.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        match journal::run(&registry, &hub, &id, &file_path, &options, false) {
+            Ok(transcript) => {
+                let title = crate::integrations::calendar::title_for_recording(&file_path)
+                    .unwrap_or_else(|| format!("Meeting {}", started_at.get(..16).unwrap_or(&started_at).replace('T', " ")));
+                let mut tags = BTreeMap::new();
+                tags.insert("meeting".to_string(), started_at);
+                if let Err(e) = history::add(&id, &title, &file_path, &transcript, tags) {
+                    warn!("Failed to save meeting transcript: {}", e);
+                }
+            }
+            Err(e) => warn!("Meeting transcription failed: {}", e),
+        }
+    });
+    Some(job_id)
+}
+
+fn poll() {
+    let config = meeting_config();
+    let mut session = SESSION.lock().unwrap();
+
+    // The recorder exits on its own at max_minutes or when a device disappears
+    if let Some(recording) = session.recording.as_mut() {
+        if recording.child.try_wait().ok().flatten().is_some() {
+            let recording = session.recording.take().unwrap();
+            finish(recording);
+        }
+    }
+    if !config.enabled {
+        session.detected = None;
+        return;
+    }
+
+    let own_pid = session.recording.as_ref().map(|recording| recording.child.id());
+    let detected = detect(&config, own_pid);
+    if let Some(recording) = session.recording.as_mut() {
+        if recording.trigger.is_some() {
+            recording.missed_polls = if detected.is_some() { 0 } else { recording.missed_polls + 1 };
+            if recording.missed_polls >= END_AFTER_POLLS {
+                info!("Meeting ended; stopping the recording");
+                let recording = session.recording.take().unwrap();
+                finish(recording);
+            }
+        }
+    } else if detected.is_some() && session.detected.is_none() {
+        let trigger = detected.clone();
+        if config.auto_start {
+            if let Err(e) = start_recording(&mut session, trigger) {
+                warn!("Failed to start meeting recording: {}", e);
+            }
+        } else {
+            events::emit_named("meeting-detected", &trigger);
+        }
+    }
+    session.detected = detected;
+}
+
+// The session manager: watch for meetings and stop recordings when they end
+pub fn start(hub: StreamHub, registry: JobRegistry) {
+    let _ = JOBS.set((hub, registry));
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = tauri::async_runtime::spawn_blocking(poll).await {
+                warn!("Meeting detection failed: {}", e);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+// Started by hand or after a `meeting-detected` prompt. A detected meeting's recording
+// stops by itself when the meeting ends; one started with nothing detected does not.
+pub fn start_manual() -> Result<String, AppError> {
+    let mut session = SESSION.lock().unwrap();
+    let trigger = session.detected.clone();
+    start_recording(&mut session, trigger)
+}
+
+// Returns the job id the recording is transcribed under
+pub fn stop() -> Result<Option<String>, AppError> {
+    let recording = SESSION.lock().unwrap().recording.take().ok_or(AppError::invalid("Invalid request: no meeting is being recorded"))?;
+    Ok(finish(recording))
+}
+
+pub fn status() -> MeetingStatus {
+    let session = SESSION.lock().unwrap();
+    let recording = session.recording.as_ref();
+    MeetingStatus {
+        detected: session.detected.clone(),
+        recording: recording.is_some(),
+        path: recording.map(|recording| recording.path.to_string_lossy().to_string()),
+        started_at: recording.map(|recording| recording.started_at.clone()),
+        trigger: recording.and_then(|recording| recording.trigger.clone()),
+    }
+}
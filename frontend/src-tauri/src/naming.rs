@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use crate::{config, engine, projects};
+use crate::error::AppError;
+
+pub const DEFAULT_TEMPLATE: &str = "{stem}.{ext}";
+
+// What a template can refer to when naming one saved output
+pub struct NameContext<'a> {
+    // Source file name without its extension
+    pub stem: &'a str,
+    pub ext: &'a str,
+    pub language: Option<&'a str>,
+    pub project: Option<&'a str>,
+}
+
+pub fn filename_template() -> String {
+    config::load().filename_template.filter(|template| !template.trim().is_empty()).unwrap_or_else(|| DEFAULT_TEMPLATE.to_string())
+}
+
+fn value(name: &str, context: &NameContext) -> Result<String, AppError> {
+    let now = chrono::Local::now();
+    Ok(match name {
+        "stem" => context.stem.to_string(),
+        "ext" => context.ext.to_string(),
+        // "und" is the BCP 47 code for an undetermined language
+        "lang" => context.language.filter(|lang| !lang.is_empty() && *lang != "auto").unwrap_or("und").to_string(),
+        "date" => now.format("%Y-%m-%d").to_string(),
+        "time" => now.format("%H-%M-%S").to_string(),
+        "project" => context.project.unwrap_or_default().to_string(),
+        _ => return Err(AppError::invalid(format!("Invalid file name template: unknown placeholder {{{}}}", name))),
+    })
+}
+
+// Fill in the placeholders and return a path relative to the output folder. "/" in the
+// template makes subfolders; folders that come out empty (no project) are dropped.
+pub fn render(template: &str, context: &NameContext) -> Result<PathBuf, AppError> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let close = rest[open..].find('}').ok_or(AppError::invalid("Invalid file name template: unclosed '{'"))? + open;
+        rendered.push_str(&value(&rest[open + 1..close], context)?);
+        rest = &rest[close + 1..];
+    }
+    rendered.push_str(rest);
+
+    let parts: Vec<&str> = rendered.split(['/', '\\']).collect();
+    let (file_name, dirs) = parts.split_last().ok_or(AppError::invalid("Invalid file name template: it is empty"))?;
+    let mut path = PathBuf::new();
+    for dir in dirs.iter().map(|dir| dir.trim()).filter(|dir| !dir.is_empty()) {
+        if dir == ".." {
+            return Err(AppError::invalid("Invalid file name template: it must stay inside the output folder"));
+        }
+        path.push(engine::sanitize_file_name(dir)?);
+    }
+    path.push(engine::sanitize_file_name(file_name)?);
+    Ok(path)
+}
+
+// Checked with sample values so a bad template is rejected when it is set, not at save time
+pub fn validate(template: &str) -> Result<(), AppError> {
+    if template.starts_with(['/', '\\']) || std::path::Path::new(template).is_absolute() {
+        return Err(AppError::invalid("Invalid file name template: use a path relative to the output folder"));
+    }
+    render(template, &NameContext { stem: "sample", ext: "txt", language: Some("en"), project: Some("project") }).map(|_| ())
+}
+
+// Relative path for saving a transcript of `original_file_name` with the configured template
+pub fn output_name(original_file_name: &str, ext: &str, language: Option<&str>, project_id: Option<&str>) -> Result<PathBuf, AppError> {
+    let stem = std::path::Path::new(original_file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .ok_or("Failed to get file stem")?;
+    let project = match project_id {
+        Some(project_id) => Some(projects::get(project_id)?.name),
+        None => None,
+    };
+    render(&filename_template(), &NameContext { stem: &stem, ext, language, project: project.as_deref() })
+}
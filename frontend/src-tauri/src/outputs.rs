@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::audit::{self, AuditAction};
+use crate::engine::Transcript;
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::{config, crypto, naming, projects, redact, subtitle};
+
+// Key purpose for the transcript files written when a job finishes
+const OUTPUT_PURPOSE: &str = "outputs";
+
+// Folder for a job's files: the job's own override, else its project's export folder,
+// else the global output folder
+pub fn dir_for(job_id: &str, job_dir: Option<&str>) -> Result<PathBuf, AppError> {
+    if let Some(dir) = job_dir.filter(|dir| !dir.trim().is_empty()) {
+        if !Path::new(dir).is_absolute() {
+            return Err(AppError::invalid(format!("Invalid output directory '{}': use an absolute path", dir)));
+        }
+        return Ok(PathBuf::from(dir));
+    }
+    if let Some(dir) = projects::project_of(job_id)?.and_then(|project| project.export_dir) {
+        return Ok(PathBuf::from(dir));
+    }
+    config::output_dir()
+}
+
+// `path`, or "name_1.ext", "name_2.ext", ... when it is taken
+pub fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|counter| path.with_file_name(format!("{}_{}{}", stem, counter, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+// Source path, per-job folder and transcript of a running/finished job or a history entry
+fn job_output(registry: &JobRegistry, job_id: &str) -> Result<(String, Option<String>, Transcript), AppError> {
+    let record = jobs::find(registry, job_id)?;
+    let transcript = record.transcript.ok_or_else(|| AppError::unknown(format!("Unknown job: {}", job_id)))?;
+    Ok((record.file_path, record.output_dir, transcript))
+}
+
+// Write the transcript in each format (see subtitle::render) without a dialog. Files are
+// named with the file name template; missing folders are created and taken names numbered.
+pub fn save(registry: &JobRegistry, job_id: &str, formats: &[String], output_dir: Option<&str>) -> Result<Vec<String>, AppError> {
+    write_formats(registry, job_id, formats, output_dir, false)
+}
+
+// `encrypt` seals the files when encryption at rest is enabled
+fn write_formats(
+    registry: &JobRegistry,
+    job_id: &str,
+    formats: &[String],
+    output_dir: Option<&str>,
+    encrypt: bool,
+) -> Result<Vec<String>, AppError> {
+    if formats.is_empty() {
+        return Err(AppError::invalid("Invalid formats: choose at least one"));
+    }
+    let (source, job_dir, transcript) = job_output(registry, job_id)?;
+    let transcript = redact::transcript_for_export(transcript)?;
+    let dir = dir_for(job_id, output_dir.or(job_dir.as_deref()))?;
+    let project_id = projects::project_of(job_id)?.map(|project| project.id);
+
+    let mut saved = Vec::new();
+    for format in formats {
+        let content = subtitle::render(&transcript, format)?;
+        let relative = naming::output_name(&source, format, transcript.language.as_deref(), project_id.as_deref())?;
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create output directory: {}", e))?;
+        }
+        let path = unique_path(path);
+        if encrypt {
+            crypto::write_file(&path, OUTPUT_PURPOSE, &content)?;
+        } else {
+            std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+        let path = path.to_string_lossy().to_string();
+        audit::record(Some(job_id), AuditAction::Write, "file", &path);
+        saved.push(path);
+    }
+    Ok(saved)
+}
+
+// Add the transcript to the end of a running notes file under a separator and a header
+// with the time and source name. The file is created when it does not exist yet.
+pub fn append(registry: &JobRegistry, job_id: &str, file_path: &str) -> Result<String, AppError> {
+    append_entry(registry, job_id, file_path, false)
+}
+
+// `encrypt` seals the whole file when encryption at rest is enabled. Encrypted files cannot
+// be appended to in place, so they are read back and written whole.
+fn append_entry(registry: &JobRegistry, job_id: &str, file_path: &str, encrypt: bool) -> Result<String, AppError> {
+    use std::io::Write;
+
+    let path = Path::new(file_path);
+    if !path.is_absolute() {
+        return Err(AppError::invalid(format!("Invalid file path '{}': use an absolute path", file_path)));
+    }
+    let (source, _, transcript) = job_output(registry, job_id)?;
+    let transcript = redact::transcript_for_export(transcript)?;
+    let name = Path::new(&source).file_name().map_or_else(|| source.clone(), |name| name.to_string_lossy().to_string());
+    let header = format!("[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M"), name);
+
+    let sealed = std::fs::read(path).is_ok_and(|data| crypto::is_encrypted(&data));
+    let existing = std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+    let mut entry = String::new();
+    if existing > 0 {
+        entry.push_str("\n\n----------------------------------------\n\n");
+    }
+    entry.push_str(&format!("{}\n\n{}\n", header, transcript.text.trim()));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+    if sealed || (encrypt && crypto::encryption_config().enabled) {
+        let mut contents = if existing > 0 { crypto::read_file(path, OUTPUT_PURPOSE)? } else { String::new() };
+        contents.push_str(&entry);
+        crypto::write_file(path, OUTPUT_PURPOSE, &contents)?;
+        audit::record(Some(job_id), AuditAction::Write, "file", file_path);
+        return Ok(file_path.to_string());
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(entry.as_bytes()).map_err(|e| format!("Failed to append to {}: {}", path.display(), e))?;
+    audit::record(Some(job_id), AuditAction::Write, "file", file_path);
+    Ok(file_path.to_string())
+}
+
+// For jobs started with `save_formats` or `append_to`; a failure is logged since there is no caller to tell
+pub fn auto_save(registry: &JobRegistry, job_id: &str, formats: &[String], append_to: Option<&str>) {
+    if !formats.is_empty() {
+        match write_formats(registry, job_id, formats, None, true) {
+            Ok(saved) => info!("Saved {} output(s) for job {}", saved.len(), job_id),
+            Err(e) => warn!("Automatic save for job {} failed: {}", job_id, e),
+        }
+    }
+    if let Some(file_path) = append_to.filter(|path| !path.trim().is_empty()) {
+        match append_entry(registry, job_id, file_path, true) {
+            Ok(_) => info!("Appended job {} to {}", job_id, file_path),
+            Err(e) => warn!("Appending job {} failed: {}", job_id, e),
+        }
+    }
+}
+
+// Contents of a file written by `auto_save`, decrypted when it was encrypted
+pub fn read(file_path: &str) -> Result<String, AppError> {
+    crypto::read_file(Path::new(file_path), OUTPUT_PURPOSE)
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::engine::{TranscribeOptions, Transcript};
+use crate::{glossary, itn, punctuation};
+use crate::error::AppError;
+
+// One step of the post-processing pipeline run on every finished transcript
+pub trait Processor {
+    fn name(&self) -> &'static str;
+    fn process(&self, transcript: &mut Transcript) -> Result<(), AppError>;
+}
+
+fn default_processor() -> String {
+    "rules".to_string()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PunctuationConfig {
+    // Default for jobs that do not set restore_punctuation themselves
+    #[serde(default)]
+    pub enabled: bool,
+    // Only "rules" for now
+    #[serde(default = "default_processor")]
+    pub processor: String,
+}
+
+impl Default for PunctuationConfig {
+    fn default() -> Self {
+        PunctuationConfig { enabled: false, processor: default_processor() }
+    }
+}
+
+pub fn punctuation_config() -> PunctuationConfig {
+    crate::config::load().punctuation.unwrap_or_default()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ItnConfig {
+    // Default for jobs that do not set inverse_normalize themselves
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+pub fn itn_config() -> ItnConfig {
+    crate::config::load().itn.unwrap_or_default()
+}
+
+fn punctuation_processor(name: &str) -> Result<Box<dyn Processor>, AppError> {
+    match name {
+        "rules" => Ok(Box::new(punctuation::RuleRestorer)),
+        other => Err(AppError::invalid(format!("Invalid punctuation processor '{}': use rules", other))),
+    }
+}
+
+pub fn validate_punctuation(config: &PunctuationConfig) -> Result<(), AppError> {
+    punctuation_processor(&config.processor).map(|_| ())
+}
+
+// The processors a job's options turn on, in the order they run
+fn pipeline(options: &TranscribeOptions) -> Vec<Box<dyn Processor>> {
+    let mut processors = Vec::new();
+    let config = punctuation_config();
+    if options.restore_punctuation.unwrap_or(config.enabled) {
+        match punctuation_processor(&config.processor) {
+            Ok(processor) => processors.push(processor),
+            Err(e) => warn!("Skipping punctuation restoration: {}", e),
+        }
+    }
+    // After punctuation, so "twenty five percent." keeps its period
+    if options.inverse_normalize.unwrap_or_else(|| itn_config().enabled) {
+        processors.push(Box::new(itn::InverseNormalizer));
+    }
+    let glossary = glossary::glossary_config();
+    if !glossary.terms.is_empty() {
+        processors.push(Box::new(glossary::Glossary(glossary)));
+    }
+    processors
+}
+
+// Run before the language profile's find/replace rules, so those see the final text.
+// A processor that fails is logged and skipped; the transcript is still delivered.
+pub fn apply(transcript: &mut Transcript, options: &TranscribeOptions) {
+    for processor in pipeline(options) {
+        match processor.process(transcript) {
+            Ok(()) => info!("Applied {} post-processing", processor.name()),
+            Err(e) => warn!("{} post-processing failed: {}", processor.name(), e),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PowerConfig {
+    // Battery saver on regardless of the power source
+    #[serde(default)]
+    pub battery_saver: bool,
+    // Turn battery saver on by itself while running on battery
+    #[serde(default = "default_auto_on_battery")]
+    pub auto_on_battery: bool,
+    // Hold queued jobs until the machine is plugged in again; running jobs finish
+    #[serde(default)]
+    pub pause_on_battery: bool,
+    // Engine threads while battery saver is on
+    #[serde(default = "default_max_threads")]
+    pub max_threads: usize,
+}
+
+fn default_auto_on_battery() -> bool {
+    true
+}
+
+fn default_max_threads() -> usize {
+    2
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        PowerConfig {
+            battery_saver: false,
+            auto_on_battery: default_auto_on_battery(),
+            pause_on_battery: false,
+            max_threads: default_max_threads(),
+        }
+    }
+}
+
+pub fn power_config() -> PowerConfig {
+    crate::config::load().power.unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PowerStatus {
+    // None when the power source cannot be read, e.g. desktops without a battery API
+    pub on_battery: Option<bool>,
+    pub battery_saver: bool,
+    pub queue_paused: bool,
+}
+
+// The queue asks every second; reading the power source can spawn a process (pmset)
+const CACHE_TTL: Duration = Duration::from_secs(15);
+
+static ON_BATTERY: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+
+pub fn on_battery() -> Option<bool> {
+    let mut cached = ON_BATTERY.lock().unwrap();
+    if let Some((read_at, value)) = *cached {
+        if read_at.elapsed() < CACHE_TTL {
+            return value;
+        }
+    }
+    let value = read_power_source();
+    *cached = Some((Instant::now(), value));
+    value
+}
+
+#[cfg(target_os = "linux")]
+fn read_power_source() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let (mut has_battery, mut mains_online) = (false, false);
+    for entry in entries.flatten() {
+        let read = |name: &str| std::fs::read_to_string(entry.path().join(name)).map(|value| value.trim().to_string());
+        match read("type").ok().as_deref() {
+            Some("Battery") => has_battery = true,
+            Some("Mains") | Some("USB") => mains_online |= read("online").is_ok_and(|online| online == "1"),
+            _ => {}
+        }
+    }
+    has_battery.then_some(!mains_online)
+}
+
+#[cfg(target_os = "macos")]
+fn read_power_source() -> Option<bool> {
+    // "Now drawing from 'Battery Power'" or "'AC Power'"
+    let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        Some(true)
+    } else if text.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+fn read_power_source() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    // BatteryFlag 128: no system battery; ACLineStatus 255: unknown
+    match (status.BatteryFlag, status.ACLineStatus) {
+        (128, _) | (_, 255) => None,
+        (_, ac) => Some(ac == 0),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn read_power_source() -> Option<bool> {
+    None
+}
+
+pub fn saver_active(config: &PowerConfig) -> bool {
+    config.battery_saver || (config.auto_on_battery && on_battery() == Some(true))
+}
+
+// Whether queued jobs should wait for AC power
+pub fn queue_paused() -> bool {
+    let config = power_config();
+    config.pause_on_battery && on_battery() == Some(true)
+}
+
+// Thread cap for engine processes, when battery saver is on
+pub fn thread_limit() -> Option<usize> {
+    let config = power_config();
+    saver_active(&config).then_some(config.max_threads.max(1))
+}
+
+pub fn status() -> PowerStatus {
+    let config = power_config();
+    let on_battery = on_battery();
+    PowerStatus {
+        on_battery,
+        battery_saver: saver_active(&config),
+        queue_paused: config.pause_on_battery && on_battery == Some(true),
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config;
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+
+// Serializes read-modify-write cycles on projects.json across threads
+static PROJECTS_LOCK: Mutex<()> = Mutex::new(());
+
+// A named group of jobs, e.g. an interview series or a course's lectures
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    // RFC 3339
+    pub created_at: String,
+    // Used for new jobs in the project that are started without options
+    #[serde(default)]
+    pub default_options: Option<TranscribeOptions>,
+    // Save dialogs for the project's transcripts open here instead of the output folder
+    #[serde(default)]
+    pub export_dir: Option<String>,
+    // Jobs (and so history entries) in the project, oldest first
+    #[serde(default)]
+    pub job_ids: Vec<String>,
+}
+
+fn projects_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("projects.json"))
+}
+
+fn read_projects() -> Result<Vec<Project>, AppError> {
+    let path = projects_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read projects: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse projects: {}", e).into())
+}
+
+fn write_projects(projects: &[Project]) -> Result<(), AppError> {
+    let path = projects_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(projects).map_err(|e| format!("Failed to serialize projects: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write projects: {}", e).into())
+}
+
+fn modify<T>(apply: impl FnOnce(&mut Vec<Project>) -> Result<T, AppError>) -> Result<T, AppError> {
+    let _guard = PROJECTS_LOCK.lock().unwrap();
+    let mut projects = read_projects()?;
+    let result = apply(&mut projects)?;
+    write_projects(&projects)?;
+    Ok(result)
+}
+
+fn find<'a>(projects: &'a mut [Project], id: &str) -> Result<&'a mut Project, AppError> {
+    projects.iter_mut().find(|project| project.id == id).ok_or_else(|| AppError::unknown(format!("Unknown project: {}", id)))
+}
+
+fn valid_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid("Invalid project name: it must not be empty"));
+    }
+    Ok(name.to_string())
+}
+
+pub fn list() -> Result<Vec<Project>, AppError> {
+    let _guard = PROJECTS_LOCK.lock().unwrap();
+    read_projects()
+}
+
+pub fn get(id: &str) -> Result<Project, AppError> {
+    list()?.into_iter().find(|project| project.id == id).ok_or_else(|| AppError::unknown(format!("Unknown project: {}", id)))
+}
+
+// The project a job was filed under, if any
+pub fn project_of(job_id: &str) -> Result<Option<Project>, AppError> {
+    Ok(list()?.into_iter().find(|project| project.job_ids.iter().any(|id| id == job_id)))
+}
+
+pub fn create(name: &str) -> Result<Project, AppError> {
+    let project = Project {
+        id: format!("project-{}", engine::new_job_id().trim_start_matches("job-")),
+        name: valid_name(name)?,
+        created_at: chrono::Local::now().to_rfc3339(),
+        default_options: None,
+        export_dir: None,
+        job_ids: Vec::new(),
+    };
+    modify(|projects| {
+        projects.push(project.clone());
+        Ok(project)
+    })
+}
+
+pub fn rename(id: &str, name: &str) -> Result<(), AppError> {
+    let name = valid_name(name)?;
+    modify(|projects| {
+        find(projects, id)?.name = name;
+        Ok(())
+    })
+}
+
+// The project's jobs and their transcripts are kept, just no longer grouped
+pub fn delete(id: &str) -> Result<(), AppError> {
+    modify(|projects| {
+        let before = projects.len();
+        projects.retain(|project| project.id != id);
+        if projects.len() == before {
+            return Err(AppError::unknown(format!("Unknown project: {}", id)));
+        }
+        Ok(())
+    })
+}
+
+pub fn set_defaults(id: &str, default_options: Option<TranscribeOptions>, export_dir: Option<String>) -> Result<(), AppError> {
+    let export_dir = export_dir.filter(|dir| !dir.trim().is_empty());
+    if let Some(dir) = &export_dir {
+        if !std::path::Path::new(dir).is_absolute() {
+            return Err(AppError::invalid(format!("Invalid export directory '{}': use an absolute path", dir)));
+        }
+    }
+    modify(|projects| {
+        let project = find(projects, id)?;
+        project.default_options = default_options;
+        project.export_dir = export_dir;
+        Ok(())
+    })
+}
+
+// Move `job_id` into `project_id`, or out of any project when None. A job is in at most one project.
+pub fn assign(job_id: &str, project_id: Option<&str>) -> Result<(), AppError> {
+    modify(|projects| {
+        if let Some(project_id) = project_id {
+            find(projects, project_id)?;
+        }
+        for project in projects.iter_mut() {
+            project.job_ids.retain(|id| id != job_id);
+            if Some(project.id.as_str()) == project_id {
+                project.job_ids.push(job_id.to_string());
+            }
+        }
+        Ok(())
+    })
+}
+
+// Options for a new job: the caller's, else the project's defaults, else the global defaults
+pub fn options_for(project_id: Option<&str>, options: Option<TranscribeOptions>) -> Result<TranscribeOptions, AppError> {
+    if let Some(options) = options {
+        return Ok(options);
+    }
+    Ok(match project_id {
+        Some(project_id) => get(project_id)?.default_options.unwrap_or_default(),
+        None => TranscribeOptions::default(),
+    })
+}
+
+// Where to save a transcript: the project's export folder, else the configured output folder
+pub fn export_dir(project_id: Option<&str>) -> Result<PathBuf, AppError> {
+    if let Some(project_id) = project_id {
+        if let Some(dir) = get(project_id)?.export_dir {
+            return Ok(PathBuf::from(dir));
+        }
+    }
+    config::output_dir()
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::{config, secrets};
+use crate::error::AppError;
+
+// Keychain entry for the manual proxy's password
+pub const PASSWORD: &str = "proxy-password";
+
+// Loopback traffic to the local backend must never leave the machine
+const LOCAL_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    // HTTP_PROXY/HTTPS_PROXY/NO_PROXY and the OS proxy settings
+    #[default]
+    System,
+    Manual,
+    // Connect directly even when the environment names a proxy
+    Off,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ProxyConfig {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    // Manual mode, e.g. "http://proxy.corp.example:8080"
+    #[serde(default)]
+    pub url: Option<String>,
+    // Basic auth user; the password is stored in the keychain
+    #[serde(default)]
+    pub username: Option<String>,
+    // Hosts or domains (".corp.example") reached directly in manual mode
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+pub fn proxy_config() -> ProxyConfig {
+    config::load().proxy.unwrap_or_default()
+}
+
+fn no_proxy_list(proxy: &ProxyConfig) -> String {
+    std::iter::once(LOCAL_HOSTS.to_string()).chain(proxy.no_proxy.iter().cloned()).collect::<Vec<_>>().join(",")
+}
+
+fn manual_proxy(proxy: &ProxyConfig) -> Result<reqwest::Proxy, AppError> {
+    let url = proxy.url.as_deref().filter(|url| !url.trim().is_empty()).ok_or(AppError::not_configured("Proxy URL is not configured"))?;
+    let mut manual = reqwest::Proxy::all(url).map_err(|e| AppError::invalid(format!("Invalid proxy URL '{}': {}", url, e)))?;
+    if let Some(username) = &proxy.username {
+        let password = secrets::get(PASSWORD)?.unwrap_or_default();
+        manual = manual.basic_auth(username, &password);
+    }
+    Ok(manual.no_proxy(reqwest::NoProxy::from_string(&no_proxy_list(proxy))))
+}
+
+// Route an async client per the proxy settings
+pub fn apply(builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, AppError> {
+    let proxy = proxy_config();
+    Ok(match proxy.mode {
+        ProxyMode::System => builder,
+        ProxyMode::Manual => builder.proxy(manual_proxy(&proxy)?),
+        ProxyMode::Off => builder.no_proxy(),
+    })
+}
+
+pub fn apply_blocking(builder: reqwest::blocking::ClientBuilder) -> Result<reqwest::blocking::ClientBuilder, AppError> {
+    let proxy = proxy_config();
+    Ok(match proxy.mode {
+        ProxyMode::System => builder,
+        ProxyMode::Manual => builder.proxy(manual_proxy(&proxy)?),
+        ProxyMode::Off => builder.no_proxy(),
+    })
+}
+
+// Client for calls leaving the machine: cloud engines, integrations, feeds
+pub fn client() -> Result<reqwest::Client, AppError> {
+    apply(reqwest::Client::builder())?.build().map_err(|e| AppError::upstream(format!("Failed to create HTTP client: {}", e)))
+}
+
+pub fn blocking_client() -> Result<reqwest::blocking::Client, AppError> {
+    apply_blocking(reqwest::blocking::Client::builder())?
+        .build()
+        .map_err(|e| AppError::upstream(format!("Failed to create HTTP client: {}", e)))
+}
+
+// Client for health checks against the local backend, which bypass any proxy
+pub fn local_client() -> reqwest::Client {
+    reqwest::Client::builder().no_proxy().build().unwrap_or_default()
+}
+
+// Blocking counterpart for backend job calls made from engine threads
+pub fn local_blocking_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder().no_proxy().build().unwrap_or_default()
+}
+
+// Proxy URL with credentials, in the form child processes expect in HTTP(S)_PROXY
+fn manual_proxy_env(proxy: &ProxyConfig) -> Result<String, AppError> {
+    let url = proxy.url.as_deref().filter(|url| !url.trim().is_empty()).ok_or(AppError::not_configured("Proxy URL is not configured"))?;
+    let mut parsed = reqwest::Url::parse(url).map_err(|e| AppError::invalid(format!("Invalid proxy URL '{}': {}", url, e)))?;
+    if let Some(username) = &proxy.username {
+        let password = secrets::get(PASSWORD)?.unwrap_or_default();
+        let _ = parsed.set_username(username);
+        let _ = parsed.set_password(Some(&password));
+    }
+    Ok(parsed.to_string())
+}
+
+// Pass the proxy settings to a child process (Python backend, yt-dlp) through the
+// standard environment variables; model downloads from Hugging Face honor them
+pub fn configure_command(command: &mut Command) -> Result<(), AppError> {
+    let proxy = proxy_config();
+    match proxy.mode {
+        ProxyMode::System => {}
+        ProxyMode::Manual => {
+            let url = manual_proxy_env(&proxy)?;
+            let no_proxy = no_proxy_list(&proxy);
+            for (key, value) in [("HTTP_PROXY", &url), ("HTTPS_PROXY", &url), ("NO_PROXY", &no_proxy)] {
+                command.env(key, value).env(key.to_lowercase(), value);
+            }
+        }
+        ProxyMode::Off => {
+            for key in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"] {
+                command.env_remove(key).env_remove(key.to_lowercase());
+            }
+        }
+    }
+    Ok(())
+}
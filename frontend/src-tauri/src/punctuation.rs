@@ -0,0 +1,130 @@
+use crate::editing;
+use crate::engine::{Segment, Transcript};
+use crate::error::AppError;
+use crate::postprocess::Processor;
+
+// A pause this long after a segment is taken as the end of a sentence
+const SENTENCE_PAUSE: f64 = 0.6;
+
+const TERMINAL: &[char] = &['.', '?', '!', '…', '。', '？', '！'];
+const CLAUSE: &[char] = &[',', ';', ':', '、', '，', '；', '：'];
+
+const ENGLISH_QUESTION_WORDS: &[&str] = &[
+    "who", "what", "when", "where", "why", "how", "which", "is", "are", "was", "were", "do", "does", "did", "can",
+    "could", "would", "will", "should", "shall", "may", "have", "has",
+];
+
+// Adds what raw engine output often lacks: sentence-final marks at segment ends that
+// are followed by a pause, capitals at sentence starts and, in English, "I". Text that
+// is already punctuated is left as it is.
+pub struct RuleRestorer;
+
+fn is_unspaced(language: &str) -> bool {
+    language.starts_with("ja") || language.starts_with("zh")
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Capitalize the first word after leading spaces and opening quotes, unless it is a number
+fn capitalize_start(text: &str) -> String {
+    match text.find(|c: char| c.is_alphanumeric()) {
+        Some(index) => format!("{}{}", &text[..index], capitalize_first(&text[index..])),
+        None => text.to_string(),
+    }
+}
+
+fn english_fixes(text: &str) -> String {
+    text.split(' ')
+        .map(|word| match word {
+            "i" => "I".to_string(),
+            _ if word.starts_with("i'") => capitalize_first(word),
+            _ => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn final_mark(text: &str, language: &str) -> &'static str {
+    if is_unspaced(language) {
+        return "。";
+    }
+    let first_word = text.split_whitespace().next().unwrap_or_default().to_lowercase();
+    if language.starts_with("en") && ENGLISH_QUESTION_WORDS.contains(&first_word.as_str()) {
+        "?"
+    } else {
+        "."
+    }
+}
+
+fn ends_sentence(segments: &[Segment], index: usize, language: &str) -> bool {
+    let text = segments[index].text.trim_end();
+    if text.ends_with(TERMINAL) {
+        return true;
+    }
+    if text.ends_with(CLAUSE) {
+        return false;
+    }
+    match segments.get(index + 1) {
+        Some(next) => next.start - segments[index].end >= SENTENCE_PAUSE || (is_unspaced(language) && next.start > segments[index].end),
+        None => true,
+    }
+}
+
+impl Processor for RuleRestorer {
+    fn name(&self) -> &'static str {
+        "punctuation"
+    }
+
+    fn process(&self, transcript: &mut Transcript) -> Result<(), AppError> {
+        let language = transcript.language.clone().unwrap_or_default().to_lowercase();
+        if transcript.segments.is_empty() {
+            let mut text = capitalize_start(transcript.text.trim());
+            if !text.is_empty() && !text.ends_with(TERMINAL) {
+                text.push_str(final_mark(&text, &language));
+            }
+            transcript.text = if language.starts_with("en") { english_fixes(&text) } else { text };
+            return Ok(());
+        }
+
+        let mut sentence_start = true;
+        for index in 0..transcript.segments.len() {
+            let ends = ends_sentence(&transcript.segments, index, &language);
+            let segment = &mut transcript.segments[index];
+            let original = segment.text.clone();
+            let mut text = segment.text.trim().to_string();
+            if text.is_empty() {
+                continue;
+            }
+            if sentence_start {
+                text = capitalize_start(&text);
+            }
+            if language.starts_with("en") {
+                text = english_fixes(&text);
+            }
+            if ends && !text.ends_with(TERMINAL) {
+                let mark = final_mark(&text, &language);
+                text.push_str(mark);
+                if let Some(word) = segment.words.last_mut() {
+                    word.text.push_str(mark);
+                }
+            }
+            if sentence_start {
+                if let Some(word) = segment.words.first_mut() {
+                    word.text = capitalize_start(&word.text);
+                }
+            }
+            sentence_start = text.ends_with(TERMINAL);
+            if text != original.trim() {
+                segment.text = if original.starts_with(' ') { format!(" {}", text) } else { text };
+            }
+        }
+        editing::rejoin(transcript);
+        Ok(())
+    }
+}
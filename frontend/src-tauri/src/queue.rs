@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use crate::config;
+use crate::error::AppError;
+use crate::journal::{self, JournalRecord};
+
+// Engines declare which pool they draw from; cloud engines count as CPU since they never touch the GPU
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerClass {
+    Gpu,
+    Cpu,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WorkerConfig {
+    // Concurrent jobs on the GPU; more than one usually just splits VRAM
+    #[serde(default = "default_gpu_workers")]
+    pub gpu: usize,
+    #[serde(default = "default_cpu_workers")]
+    pub cpu: usize,
+    // The local backend runs on CPU on this machine, so it may use the CPU pool
+    #[serde(default)]
+    pub local_on_cpu: bool,
+}
+
+fn default_gpu_workers() -> usize {
+    1
+}
+
+// Half the cores: each CPU transcription is itself multi-threaded
+fn default_cpu_workers() -> usize {
+    std::thread::available_parallelism().map(|n| (n.get() / 2).max(1)).unwrap_or(1)
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        WorkerConfig { gpu: default_gpu_workers(), cpu: default_cpu_workers(), local_on_cpu: false }
+    }
+}
+
+pub fn worker_config() -> WorkerConfig {
+    config::load().workers.unwrap_or_default()
+}
+
+fn limit(class: WorkerClass) -> usize {
+    // Battery saver runs one job at a time in each pool
+    if crate::power::saver_active(&crate::power::power_config()) {
+        return 1;
+    }
+    let workers = worker_config();
+    match class {
+        WorkerClass::Gpu => workers.gpu.max(1),
+        WorkerClass::Cpu => workers.cpu.max(1),
+    }
+}
+
+struct Waiting {
+    job_id: String,
+    priority: Priority,
+}
+
+#[derive(Default)]
+struct Pool {
+    running: usize,
+    // Jobs waiting for a slot, head first: by priority, then arrival unless reordered
+    waiting: Vec<Waiting>,
+}
+
+impl Pool {
+    // After the last job of the same or higher priority
+    fn enqueue(&mut self, job_id: &str, priority: Priority) {
+        let index = self.waiting.iter().rposition(|w| w.priority >= priority).map_or(0, |i| i + 1);
+        self.waiting.insert(index, Waiting { job_id: job_id.to_string(), priority });
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QueueEntry {
+    pub job_id: String,
+    pub class: WorkerClass,
+    pub priority: Priority,
+    // 0 runs next within its pool
+    pub position: usize,
+}
+
+static POOLS: Mutex<Option<HashMap<WorkerClass, Pool>>> = Mutex::new(None);
+static WAKE: Condvar = Condvar::new();
+
+// Re-check limits on this interval too, so config changes apply without a notify
+const RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// Held while a job runs; frees the slot on drop
+pub struct Permit {
+    class: WorkerClass,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut pools = POOLS.lock().unwrap();
+        if let Some(pool) = pools.get_or_insert_with(HashMap::new).get_mut(&self.class) {
+            pool.running = pool.running.saturating_sub(1);
+        }
+        WAKE.notify_all();
+    }
+}
+
+// Block until `job_id` reaches the head of its pool's queue and a slot is free.
+// Gives up with an error when `cancelled` turns true while waiting.
+pub fn acquire(class: WorkerClass, job_id: &str, priority: Priority, cancelled: impl Fn() -> bool) -> Result<Permit, AppError> {
+    POOLS.lock().unwrap().get_or_insert_with(HashMap::new).entry(class).or_default().enqueue(job_id, priority);
+
+    loop {
+        // These read the config and may run pmset, so they are checked before taking the lock
+        let limit = limit(class);
+        let paused = crate::power::queue_paused() || crate::resources::queue_paused() || crate::schedule::queue_paused();
+
+        let mut pools = POOLS.lock().unwrap();
+        let pool = pools.get_or_insert_with(HashMap::new).entry(class).or_default();
+        if cancelled() {
+            pool.waiting.retain(|w| w.job_id != job_id);
+            WAKE.notify_all();
+            return Err(AppError::cancelled("Job cancelled"));
+        }
+        if pool.waiting.first().is_some_and(|w| w.job_id == job_id) && pool.running < limit && !paused {
+            pool.waiting.remove(0);
+            pool.running += 1;
+            // The next job in line may also fit
+            WAKE.notify_all();
+            return Ok(Permit { class });
+        }
+        drop(WAKE.wait_timeout(pools, RECHECK_INTERVAL).unwrap());
+    }
+}
+
+// Wake waiters so they re-check cancellation and limits
+pub fn notify() {
+    WAKE.notify_all();
+}
+
+pub fn list() -> Vec<QueueEntry> {
+    let mut pools = POOLS.lock().unwrap();
+    let mut entries: Vec<QueueEntry> = Vec::new();
+    for (class, pool) in pools.get_or_insert_with(HashMap::new).iter() {
+        entries.extend(pool.waiting.iter().enumerate().map(|(position, w)| QueueEntry {
+            job_id: w.job_id.clone(),
+            class: *class,
+            priority: w.priority,
+            position,
+        }));
+    }
+    entries.sort_by_key(|entry| (entry.class == WorkerClass::Cpu, entry.position));
+    entries
+}
+
+fn with_waiting<T>(job_id: &str, apply: impl FnOnce(&mut Pool, usize) -> T) -> Result<T, AppError> {
+    let mut pools = POOLS.lock().unwrap();
+    for pool in pools.get_or_insert_with(HashMap::new).values_mut() {
+        if let Some(index) = pool.waiting.iter().position(|w| w.job_id == job_id) {
+            let result = apply(pool, index);
+            WAKE.notify_all();
+            return Ok(result);
+        }
+    }
+    Err(format!("Job {} is not waiting in the queue", job_id).into())
+}
+
+// Re-queue behind the last job of the new priority
+pub fn set_priority(job_id: &str, priority: Priority) -> Result<(), AppError> {
+    with_waiting(job_id, |pool, index| {
+        journal::log(JournalRecord::Priority { job_id: job_id.to_string(), priority });
+        pool.waiting.remove(index);
+        pool.enqueue(job_id, priority);
+    })
+}
+
+// Move to `position` within its pool regardless of priority; later arrivals still queue by priority
+pub fn move_to(job_id: &str, position: usize) -> Result<(), AppError> {
+    with_waiting(job_id, |pool, index| {
+        journal::log(JournalRecord::Moved { job_id: job_id.to_string(), position });
+        let entry = pool.waiting.remove(index);
+        let position = position.min(pool.waiting.len());
+        pool.waiting.insert(position, entry);
+    })
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+// A recorder's local output folder that can be enrolled as a watch folder
+#[derive(Debug, Serialize, Clone)]
+pub struct DetectedFolder {
+    pub app: String,
+    pub path: String,
+    // Suggested watch filter, e.g. Zoom's audio-only track instead of the video
+    pub extensions: Vec<String>,
+    pub enrolled: bool,
+}
+
+fn zoom_dirs(home: &Path) -> Vec<PathBuf> {
+    vec![home.join("Documents").join("Zoom")]
+}
+
+// New Teams saves meeting recordings to OneDrive; business accounts sync to "OneDrive - <Org>"
+fn teams_dirs(home: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(home) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("OneDrive") {
+                found.push(entry.path().join("Recordings"));
+            }
+        }
+    }
+    // macOS File Provider location
+    if let Ok(entries) = std::fs::read_dir(home.join("Library").join("CloudStorage")) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("OneDrive") {
+                found.push(entry.path().join("Recordings"));
+            }
+        }
+    }
+    found
+}
+
+// OBS keeps the recording path per profile in basic.ini: FilePath (simple mode) or RecFilePath (advanced)
+fn obs_dirs() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let profiles = match dirs::config_dir().map(|config| config.join("obs-studio").join("basic").join("profiles")) {
+        Some(profiles) => profiles,
+        None => return found,
+    };
+    if let Ok(entries) = std::fs::read_dir(profiles) {
+        for entry in entries.flatten() {
+            let contents = match std::fs::read_to_string(entry.path().join("basic.ini")) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            for line in contents.lines() {
+                if let Some(path) = line.strip_prefix("FilePath=").or_else(|| line.strip_prefix("RecFilePath=")) {
+                    found.push(PathBuf::from(path.trim()));
+                }
+            }
+        }
+    }
+    // OBS default when no profile overrides it
+    if let Some(videos) = dirs::video_dir() {
+        found.push(videos);
+    }
+    found
+}
+
+// Existing recording folders of Zoom, Teams and OBS, marked if already watched
+pub fn detect() -> Vec<DetectedFolder> {
+    let home = match dirs::home_dir() {
+        Some(home) => home,
+        None => return Vec::new(),
+    };
+    let watched: Vec<String> = crate::watch::list().unwrap_or_default().into_iter().map(|folder| folder.path).collect();
+
+    let candidates = zoom_dirs(&home)
+        .into_iter()
+        .map(|dir| ("Zoom", dir, vec!["m4a".to_string()]))
+        .chain(teams_dirs(&home).into_iter().map(|dir| ("Microsoft Teams", dir, vec!["mp4".to_string()])))
+        .chain(obs_dirs().into_iter().map(|dir| ("OBS Studio", dir, vec!["mkv".to_string(), "mp4".to_string(), "mov".to_string()])));
+
+    let mut detected: Vec<DetectedFolder> = Vec::new();
+    for (app, dir, extensions) in candidates {
+        let path = dir.to_string_lossy().to_string();
+        if !dir.is_dir() || detected.iter().any(|folder| folder.path == path) {
+            continue;
+        }
+        detected.push(DetectedFolder {
+            app: app.to_string(),
+            enrolled: watched.contains(&path),
+            path,
+            extensions,
+        });
+    }
+    detected
+}
@@ -0,0 +1,277 @@
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::engine::Transcript;
+use crate::error::AppError;
+use crate::jobs::JobRecord;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RedactionConfig {
+    // Mask personal data in everything that leaves the app: exports, uploads, webhooks, saved files
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub emails: bool,
+    #[serde(default = "default_true")]
+    pub phone_numbers: bool,
+    // Digit runs of card length that pass the Luhn check
+    #[serde(default = "default_true")]
+    pub card_numbers: bool,
+    // Matched case-insensitively; ASCII names only as whole words
+    #[serde(default)]
+    pub names: Vec<String>,
+    // Extra regular expressions, masked as [REDACTED]
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    // Replaces every match instead of the per-kind labels ([EMAIL], [PHONE], ...)
+    #[serde(default)]
+    pub mask: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        RedactionConfig {
+            enabled: false,
+            emails: true,
+            phone_numbers: true,
+            card_numbers: true,
+            names: Vec::new(),
+            patterns: Vec::new(),
+            mask: None,
+        }
+    }
+}
+
+pub fn redaction_config() -> RedactionConfig {
+    crate::config::load().redaction.unwrap_or_default()
+}
+
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+const CARD: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+const PHONE: &str = r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,4}\)[ .-]?)?\d{2,4}[ .-]?\d{2,4}[ .-]?\d{3,4}\b";
+
+#[derive(Clone, Copy)]
+enum Check {
+    None,
+    Luhn,
+    // Shorter digit runs are more likely counts, years or times than phone numbers,
+    // and so are runs of years such as "2019-2024"
+    Phone,
+}
+
+struct Rule {
+    pattern: Regex,
+    label: &'static str,
+    check: Check,
+}
+
+// Rules run in order, so card numbers are masked before the looser phone pattern sees them
+pub struct Redactor {
+    rules: Vec<Rule>,
+    mask: Option<String>,
+}
+
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { if d * 2 > 9 { d * 2 - 9 } else { d * 2 } } else { d })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn is_year_span(matched: &str) -> bool {
+    matched
+        .split([' ', '.', '-'])
+        .filter(|part| !part.is_empty())
+        .all(|part| part.len() == 4 && matches!(part.parse::<u32>(), Ok(1900..=2099)))
+}
+
+fn passes(check: Check, matched: &str) -> bool {
+    let digits: Vec<u32> = matched.chars().filter_map(|c| c.to_digit(10)).collect();
+    match check {
+        Check::None => true,
+        Check::Luhn => luhn_valid(&digits),
+        Check::Phone => digits.len() >= 7 && !is_year_span(matched),
+    }
+}
+
+impl Redactor {
+    // Errors name the custom pattern that does not compile
+    pub fn new(config: &RedactionConfig) -> Result<Redactor, AppError> {
+        let compile = |pattern: &str| Regex::new(pattern).map_err(|e| AppError::invalid(format!("Invalid redaction pattern '{}': {}", pattern, e)));
+        let mut rules = Vec::new();
+        if config.card_numbers {
+            rules.push(Rule { pattern: compile(CARD)?, label: "[CARD]", check: Check::Luhn });
+        }
+        if config.emails {
+            rules.push(Rule { pattern: compile(EMAIL)?, label: "[EMAIL]", check: Check::None });
+        }
+        if config.phone_numbers {
+            rules.push(Rule { pattern: compile(PHONE)?, label: "[PHONE]", check: Check::Phone });
+        }
+        for name in config.names.iter().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+            // \b never matches inside CJK text, where names run into the following particle
+            let pattern = if name.is_ascii() {
+                format!(r"(?i)\b{}\b", regex::escape(name))
+            } else {
+                format!("(?i){}", regex::escape(name))
+            };
+            rules.push(Rule { pattern: compile(&pattern)?, label: "[NAME]", check: Check::None });
+        }
+        for pattern in &config.patterns {
+            rules.push(Rule { pattern: compile(pattern)?, label: "[REDACTED]", check: Check::None });
+        }
+        Ok(Redactor { rules, mask: config.mask.clone() })
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for rule in &self.rules {
+            let mask = self.mask.as_deref().unwrap_or(rule.label);
+            text = rule
+                .pattern
+                .replace_all(&text, |caps: &Captures| {
+                    let matched = &caps[0];
+                    if passes(rule.check, matched) { mask.to_string() } else { matched.to_string() }
+                })
+                .into_owned();
+        }
+        text
+    }
+
+    pub fn apply_transcript(&self, transcript: &mut Transcript) {
+        transcript.text = self.apply(&transcript.text);
+        for segment in &mut transcript.segments {
+            segment.text = self.apply(&segment.text);
+        }
+        if let Some(translation) = &mut transcript.translation {
+            translation.text = self.apply(&translation.text);
+            for segment in &mut translation.segments {
+                segment.text = self.apply(&segment.text);
+            }
+        }
+        for chapter in &mut transcript.chapters {
+            chapter.title = self.apply(&chapter.title);
+        }
+    }
+}
+
+// The enabled redactor, or None when redaction is off. A custom pattern that does not
+// compile fails the export rather than letting the transcript out unmasked.
+fn active() -> Result<Option<Redactor>, AppError> {
+    let config = redaction_config();
+    if !config.enabled {
+        return Ok(None);
+    }
+    Redactor::new(&config).map(Some)
+}
+
+// Copy of the job with its transcript masked, for exports and uploads
+pub fn for_export(mut record: JobRecord) -> Result<JobRecord, AppError> {
+    if let (Some(redactor), Some(transcript)) = (active()?, record.transcript.as_mut()) {
+        redactor.apply_transcript(transcript);
+    }
+    Ok(record)
+}
+
+pub fn text_for_export(text: &str) -> Result<String, AppError> {
+    Ok(match active()? {
+        Some(redactor) => redactor.apply(text),
+        None => text.to_string(),
+    })
+}
+
+pub fn transcript_for_export(mut transcript: Transcript) -> Result<Transcript, AppError> {
+    if let Some(redactor) = active()? {
+        redactor.apply_transcript(&mut transcript);
+    }
+    Ok(transcript)
+}
+
+// Automatic exports have no caller to report to; skip them when the rules are broken
+pub fn for_auto_export(record: JobRecord) -> Option<JobRecord> {
+    match for_export(record) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            warn!("Skipping automatic exports: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor(configure: impl FnOnce(&mut RedactionConfig)) -> Redactor {
+        let mut config = RedactionConfig { enabled: true, ..Default::default() };
+        configure(&mut config);
+        Redactor::new(&config).unwrap()
+    }
+
+    #[test]
+    fn emails_are_masked() {
+        let redactor = redactor(|_| {});
+        assert_eq!(redactor.apply("Write to jane.doe+work@mail.example.co.uk today."), "Write to [EMAIL] today.");
+    }
+
+    #[test]
+    fn phone_numbers_are_masked() {
+        let redactor = redactor(|_| {});
+        assert_eq!(redactor.apply("Call 555-123-4567 now"), "Call [PHONE] now");
+        assert_eq!(redactor.apply("Call +44 20 7946 0958 now"), "Call [PHONE] now");
+        assert_eq!(redactor.apply("Call (030) 1234 5678 now"), "Call [PHONE] now");
+    }
+
+    #[test]
+    fn card_numbers_need_a_valid_checksum() {
+        let redactor = redactor(|config| config.phone_numbers = false);
+        assert_eq!(redactor.apply("Card 4111 1111 1111 1111 ok"), "Card [CARD] ok");
+        assert_eq!(redactor.apply("Card 4111-1111-1111-1111 ok"), "Card [CARD] ok");
+        assert_eq!(redactor.apply("Order 4111 1111 1111 1112 ok"), "Order 4111 1111 1111 1112 ok");
+    }
+
+    #[test]
+    fn names_match_whole_words_in_any_case() {
+        let redactor = redactor(|config| config.names = vec!["Jane".to_string(), " 田中 ".to_string(), String::new()]);
+        assert_eq!(redactor.apply("JANE met Janet and jane"), "[NAME] met Janet and [NAME]");
+        assert_eq!(redactor.apply("田中さんが来た"), "[NAME]さんが来た");
+    }
+
+    #[test]
+    fn mask_replaces_every_label() {
+        let redactor = redactor(|config| {
+            config.mask = Some("***".to_string());
+            config.patterns = vec![r"ID-\d+".to_string()];
+        });
+        assert_eq!(redactor.apply("jane@example.com has ID-42"), "*** has ***");
+    }
+
+    #[test]
+    fn broken_custom_pattern_is_an_error() {
+        let config = RedactionConfig { patterns: vec!["(".to_string()], ..Default::default() };
+        assert!(Redactor::new(&config).is_err());
+    }
+
+    #[test]
+    fn timestamps_years_and_numbers_are_left_alone() {
+        let redactor = redactor(|_| {});
+        for text in [
+            "At 01:02:03.456 the speaker paused",
+            "From 2019-2024 sales grew",
+            "Founded in 1999, 2004 and 2011",
+            "We sold 1500 units for 3.14159 each",
+            "About 12,500,000 people on 2024-01-15",
+            "Room 101 at 10.30",
+        ] {
+            assert_eq!(redactor.apply(text), text);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::editing;
+use crate::engine::Transcript;
+use crate::error::AppError;
+use crate::history;
+use crate::jobs::{self, JobRegistry};
+
+// Characters of surrounding text shown on each side of a match
+const CONTEXT_CHARS: usize = 40;
+// Previews list at most this many matches; `total` still counts all of them
+const MAX_PREVIEW_MATCHES: usize = 500;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FindQuery {
+    pub pattern: String,
+    // Treat `pattern` as a regular expression; replacements may then use $1, ${name}
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FindMatch {
+    pub segment_index: usize,
+    // Segment start, in seconds
+    pub time: f64,
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FindResult {
+    pub total: usize,
+    pub segments: usize,
+    pub matches: Vec<FindMatch>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReplaceResult {
+    pub replaced: usize,
+    pub transcript: Transcript,
+}
+
+fn compile(query: &FindQuery) -> Result<Regex, AppError> {
+    if query.pattern.is_empty() {
+        return Err(AppError::invalid("Invalid pattern: it must not be empty"));
+    }
+    let pattern = if query.regex { query.pattern.clone() } else { regex::escape(&query.pattern) };
+    let pattern = if query.whole_word { format!(r"\b(?:{})\b", pattern) } else { pattern };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .map_err(|e| AppError::invalid(format!("Invalid pattern '{}': {}", query.pattern, e)))?;
+    // e.g. `a*`, which would insert the replacement between every character
+    if regex.is_match("") {
+        return Err(AppError::invalid(format!("Invalid pattern '{}': it matches empty text", query.pattern)));
+    }
+    Ok(regex)
+}
+
+pub fn validate(query: &FindQuery) -> Result<(), AppError> {
+    compile(query).map(|_| ())
+}
+
+// The stored transcript when the job is in history, else the one in memory
+fn current(registry: &JobRegistry, job_id: &str) -> Result<Transcript, AppError> {
+    if let Ok(entry) = history::get(job_id) {
+        return Ok(entry.transcript);
+    }
+    jobs::get(registry, job_id)
+        .and_then(|record| record.transcript)
+        .ok_or_else(|| AppError::unknown(format!("Unknown job: {}", job_id)))
+}
+
+fn context_before(text: &str) -> String {
+    let start = text.char_indices().rev().nth(CONTEXT_CHARS - 1).map_or(0, |(index, _)| index);
+    text[start..].to_string()
+}
+
+fn context_after(text: &str) -> String {
+    text.chars().take(CONTEXT_CHARS).collect()
+}
+
+pub fn find(registry: &JobRegistry, job_id: &str, query: &FindQuery) -> Result<FindResult, AppError> {
+    let regex = compile(query)?;
+    let transcript = current(registry, job_id)?;
+    let mut result = FindResult { total: 0, segments: 0, matches: Vec::new() };
+    for (index, segment) in transcript.segments.iter().enumerate() {
+        let mut found = false;
+        for found_match in regex.find_iter(&segment.text) {
+            found = true;
+            result.total += 1;
+            if result.matches.len() < MAX_PREVIEW_MATCHES {
+                result.matches.push(FindMatch {
+                    segment_index: index,
+                    time: segment.start,
+                    before: context_before(&segment.text[..found_match.start()]),
+                    matched: found_match.as_str().to_string(),
+                    after: context_after(&segment.text[found_match.end()..]),
+                });
+            }
+        }
+        if found {
+            result.segments += 1;
+        }
+    }
+    Ok(result)
+}
+
+// Replace every match in the segments and rebuild the text; returns how many were replaced.
+// Transcripts without segments are replaced in the text itself.
+pub fn replace_in(transcript: &mut Transcript, query: &FindQuery, replacement: &str) -> Result<usize, AppError> {
+    let regex = compile(query)?;
+    let replace = |text: &str| {
+        if query.regex {
+            regex.replace_all(text, replacement).into_owned()
+        } else {
+            regex.replace_all(text, NoExpand(replacement)).into_owned()
+        }
+    };
+    if transcript.segments.is_empty() {
+        let count = regex.find_iter(&transcript.text).count();
+        transcript.text = replace(&transcript.text);
+        return Ok(count);
+    }
+    let mut replaced = 0;
+    for segment in transcript.segments.iter_mut() {
+        let count = regex.find_iter(&segment.text).count();
+        if count == 0 {
+            continue;
+        }
+        replaced += count;
+        segment.text = replace(&segment.text);
+        segment.words.clear();
+    }
+    if replaced > 0 {
+        editing::rejoin(transcript);
+    }
+    Ok(replaced)
+}
+
+// Replace every match as one edit, so a single revert undoes it
+pub fn replace(registry: &JobRegistry, job_id: &str, query: &FindQuery, replacement: &str) -> Result<ReplaceResult, AppError> {
+    let mut replaced = 0;
+    let transcript = editing::edit(registry, job_id, |transcript| {
+        replaced = replace_in(transcript, query, replacement)?;
+        if replaced == 0 {
+            return Err(AppError::unknown(format!("Pattern '{}' not found in the transcript", query.pattern)));
+        }
+        Ok(())
+    })?;
+    Ok(ReplaceResult { replaced, transcript })
+}
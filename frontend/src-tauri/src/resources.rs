@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::jobs::{JobRegistry, JobStatus};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ResourceConfig {
+    // How often `engine-resources` is emitted
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    // System memory in use, as a percentage of total, at which pressure is "high"
+    #[serde(default = "default_warn_percent")]
+    pub warn_percent: u8,
+    // ... and "critical"
+    #[serde(default = "default_critical_percent")]
+    pub critical_percent: u8,
+    // Hold queued jobs while pressure is critical, until it drops below warn_percent
+    #[serde(default = "default_pause_on_critical")]
+    pub pause_on_critical: bool,
+}
+
+fn default_interval_secs() -> u64 {
+    5
+}
+
+fn default_warn_percent() -> u8 {
+    90
+}
+
+fn default_critical_percent() -> u8 {
+    95
+}
+
+fn default_pause_on_critical() -> bool {
+    true
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        ResourceConfig {
+            interval_secs: default_interval_secs(),
+            warn_percent: default_warn_percent(),
+            critical_percent: default_critical_percent(),
+            pause_on_critical: default_pause_on_critical(),
+        }
+    }
+}
+
+pub fn resource_config() -> ResourceConfig {
+    crate::config::load().resources.unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryPressure {
+    Normal,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    // None for the backend server, otherwise the job the engine process runs
+    pub job_id: Option<String>,
+    // Resident set size, including child processes (the sidecar bootloader spawns one)
+    pub memory_bytes: u64,
+    // Percent of one core; can exceed 100 on multi-threaded engines
+    pub cpu_percent: f32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ResourceUsage {
+    pub processes: Vec<ProcessUsage>,
+    pub memory_bytes: u64,
+    pub cpu_percent: f32,
+    pub system_total_memory: u64,
+    pub system_available_memory: u64,
+    pub pressure: MemoryPressure,
+    pub queue_paused: bool,
+}
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+// Whether queued jobs should wait for memory to free up
+pub fn queue_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+fn pressure(system: &System, config: &ResourceConfig) -> MemoryPressure {
+    let total = system.total_memory();
+    if total == 0 {
+        return MemoryPressure::Normal;
+    }
+    let used_percent = total.saturating_sub(system.available_memory()) * 100 / total;
+    if used_percent >= config.critical_percent as u64 {
+        MemoryPressure::Critical
+    } else if used_percent >= config.warn_percent as u64 {
+        MemoryPressure::High
+    } else {
+        MemoryPressure::Normal
+    }
+}
+
+fn usage_of(system: &System, pid: u32, job_id: Option<String>) -> Option<ProcessUsage> {
+    let root = Pid::from_u32(pid);
+    let mut memory_bytes = 0;
+    let mut cpu_percent = 0.0;
+    let mut found = false;
+    for (id, process) in system.processes() {
+        if *id == root || process.parent() == Some(root) {
+            found = true;
+            memory_bytes += process.memory();
+            cpu_percent += process.cpu_usage();
+        }
+    }
+    found.then_some(ProcessUsage { pid, job_id, memory_bytes, cpu_percent })
+}
+
+// The backend server plus the engine process of every running job
+fn sample(system: &mut System, app: &AppHandle, config: &ResourceConfig) -> ResourceUsage {
+    let server = *app.state::<crate::ProcessState>().lock().unwrap();
+    let jobs: Vec<(String, u32)> = app
+        .state::<JobRegistry>()
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|record| record.status == JobStatus::Running)
+        .filter_map(|record| record.pid.map(|pid| (record.id.clone(), pid)))
+        .collect();
+
+    system.refresh_memory();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new().with_memory().with_cpu());
+
+    let processes: Vec<ProcessUsage> = server
+        .and_then(|pid| usage_of(system, pid, None))
+        .into_iter()
+        .chain(jobs.into_iter().filter_map(|(job_id, pid)| usage_of(system, pid, Some(job_id))))
+        .collect();
+    let pressure = pressure(system, config);
+    ResourceUsage {
+        memory_bytes: processes.iter().map(|process| process.memory_bytes).sum(),
+        cpu_percent: processes.iter().map(|process| process.cpu_percent).sum(),
+        processes,
+        system_total_memory: system.total_memory(),
+        system_available_memory: system.available_memory(),
+        pressure,
+        queue_paused: queue_paused(),
+    }
+}
+
+// Pause on critical, resume once back to normal, so the queue does not flap around the threshold
+fn guard(app: &AppHandle, usage: &mut ResourceUsage, previous: MemoryPressure, config: &ResourceConfig) {
+    if usage.pressure != previous {
+        match usage.pressure {
+            MemoryPressure::Normal => info!("Memory pressure back to normal"),
+            pressure => warn!(
+                "Memory pressure {:?}: {} MB of {} MB available",
+                pressure,
+                usage.system_available_memory / (1024 * 1024),
+                usage.system_total_memory / (1024 * 1024)
+            ),
+        }
+        let _ = app.emit("memory-pressure", serde_json::json!({ "pressure": usage.pressure }));
+    }
+    let paused = match usage.pressure {
+        MemoryPressure::Critical => config.pause_on_critical,
+        MemoryPressure::High => queue_paused() && config.pause_on_critical,
+        MemoryPressure::Normal => false,
+    };
+    if paused != PAUSED.swap(paused, Ordering::Relaxed) {
+        if paused {
+            warn!("Pausing the job queue until memory frees up");
+        } else {
+            info!("Resuming the job queue");
+            crate::queue::notify();
+        }
+    }
+    usage.queue_paused = paused;
+}
+
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut system = System::new();
+        let mut previous = MemoryPressure::Normal;
+        loop {
+            let config = resource_config();
+            let mut usage = sample(&mut system, &app, &config);
+            guard(&app, &mut usage, previous, &config);
+            previous = usage.pressure;
+            let _ = app.emit("engine-resources", &usage);
+            tokio::time::sleep(Duration::from_secs(config.interval_secs.max(1))).await;
+        }
+    });
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::{audit, crash, engine, history, logging, temp};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RetentionConfig {
+    // History entries and the server UI's saved transcripts older than this are deleted; kept forever when unset
+    #[serde(default)]
+    pub transcript_days: Option<u64>,
+    #[serde(default = "default_log_days")]
+    pub log_days: u64,
+    // How often the cleanup runs; it also runs once at startup
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+}
+
+fn default_log_days() -> u64 {
+    7
+}
+
+fn default_interval_hours() -> u64 {
+    6
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        RetentionConfig { transcript_days: None, log_days: default_log_days(), interval_hours: default_interval_hours() }
+    }
+}
+
+pub fn retention_config() -> RetentionConfig {
+    crate::config::load().retention.unwrap_or_default()
+}
+
+fn days(days: u64) -> Duration {
+    Duration::from_secs(days.saturating_mul(24 * 60 * 60))
+}
+
+// Apply the policy once. Temp audio follows the temp_files max age.
+fn enforce() {
+    let config = retention_config();
+    if let Some(transcript_days) = config.transcript_days {
+        let cutoff = SystemTime::now().checked_sub(days(transcript_days)).unwrap_or(SystemTime::UNIX_EPOCH);
+        match history::prune(cutoff) {
+            Ok(0) => {}
+            Ok(removed) => info!("Removed {} history entries older than {} days", removed, transcript_days),
+            Err(e) => warn!("Failed to prune history: {}", e),
+        }
+        if let Ok(output_dir) = engine::backend_output_dir() {
+            let mut removed = 0;
+            temp::sweep_dir(&output_dir, cutoff, &HashSet::new(), &mut removed);
+            if removed > 0 {
+                info!("Removed {} saved output(s) older than {} days", removed, transcript_days);
+            }
+        }
+    }
+    temp::sweep_stale(days(temp::temp_config().max_age_days));
+    let removed = logging::prune(days(config.log_days));
+    if removed > 0 {
+        info!("Removed {} log file(s) older than {} days", removed, config.log_days);
+    }
+}
+
+// Run the cleanup at startup and then every `interval_hours`
+pub fn start() {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = tauri::async_runtime::spawn_blocking(enforce).await {
+                warn!("Retention cleanup failed: {}", e);
+            }
+            let interval = retention_config().interval_hours.max(1);
+            tokio::time::sleep(Duration::from_secs(interval * 60 * 60)).await;
+        }
+    });
+}
+
+// Privacy reset: delete history, saved outputs, temp files, logs, crash reports and the
+// audit log right away. Inputs of queued and running jobs are kept so they can finish.
+pub fn delete_everything(registry: &JobRegistry) -> Result<(), AppError> {
+    history::clear()?;
+    jobs::forget_finished(registry);
+    if let Ok(output_dir) = engine::backend_output_dir() {
+        let mut removed = 0;
+        temp::sweep_dir(&output_dir, SystemTime::now(), &HashSet::new(), &mut removed);
+    }
+    temp::sweep_stale(Duration::ZERO);
+    for report in crash::pending()? {
+        crash::dismiss(&report.id)?;
+    }
+    audit::clear()?;
+    // The file being written to may not be removable on Windows until the next rotation
+    logging::prune(Duration::ZERO);
+    info!("Deleted all transcripts, temp files, logs and reports");
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+
+use crate::engine::{self, TranscribeOptions};
+use crate::jobs::{self, JobRegistry};
+use crate::stream::{self, StreamEvent, StreamHub};
+
+// Newline-delimited JSON-RPC 2.0 on stdin/stdout for editor plugins:
+//   -> {"jsonrpc":"2.0","id":1,"method":"submit","params":{"file_path":"...","language":"auto"}}
+//   <- {"jsonrpc":"2.0","id":1,"result":{"job_id":"job-..."}}
+//   <- {"jsonrpc":"2.0","method":"progress","params":{"job_id":"job-...","segment":{...}}}
+//   <- {"jsonrpc":"2.0","method":"result","params":{"job_id":"job-...","transcript":{...}}}
+// Logs go to stderr so stdout stays a clean protocol channel.
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+pub fn is_requested() -> bool {
+    std::env::args().any(|arg| arg == "--stdio")
+}
+
+pub fn run() {
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+    runtime.block_on(serve());
+    // Don't wait for engine processes of jobs that were still running
+    runtime.shutdown_background();
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct SubmitParams {
+    file_path: String,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JobParams {
+    job_id: String,
+}
+
+type Outbox = mpsc::UnboundedSender<Value>;
+
+struct Session {
+    hub: StreamHub,
+    jobs: JobRegistry,
+    outbox: Outbox,
+}
+
+async fn serve() {
+    let (outbox, mut outgoing) = mpsc::unbounded_channel::<Value>();
+
+    // Single writer so responses and notifications never interleave mid-line
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = outgoing.recv().await {
+            let line = format!("{}\n", message);
+            if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let session = Session { hub: stream::new_hub(), jobs: jobs::new_registry(), outbox };
+    let forwarder = forward_progress(&session);
+    crate::integrations::start(&session.hub, session.jobs.clone());
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: RpcRequest = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let code = if serde_json::from_str::<Value>(&line).is_ok() { INVALID_REQUEST } else { PARSE_ERROR };
+                send(&session.outbox, error_response(Value::Null, code, &e.to_string()));
+                continue;
+            }
+        };
+        if request.method == "shutdown" {
+            if let Some(id) = request.id {
+                send(&session.outbox, json!({"jsonrpc": "2.0", "id": id, "result": null}));
+            }
+            break;
+        }
+        handle(&session, request);
+    }
+
+    // Close the outbox (running jobs may still hold a sender) and flush pending lines
+    forwarder.abort();
+    drop(session);
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(1), writer).await;
+}
+
+fn send(outbox: &Outbox, message: Value) {
+    let _ = outbox.send(message);
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "params": params})
+}
+
+// Relay decoded segments from every job as `progress` notifications
+fn forward_progress(session: &Session) -> tokio::task::JoinHandle<()> {
+    let mut events = session.hub.subscribe();
+    let outbox = session.outbox.clone();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(StreamEvent::Final { job_id, segment }) => {
+                    send(&outbox, notification("progress", json!({"job_id": job_id, "segment": segment})));
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+fn handle(session: &Session, request: RpcRequest) {
+    let result = match request.method.as_str() {
+        "submit" => submit(session, request.params),
+        "cancel" => serde_json::from_value::<JobParams>(request.params)
+            .map_err(|e| (INVALID_PARAMS, e.to_string()))
+            .and_then(|params| jobs::cancel(&session.jobs, &params.job_id).map_err(|e| (SERVER_ERROR, e.to_string())))
+            .map(|_| Value::Null),
+        "status" => serde_json::from_value::<JobParams>(request.params)
+            .map_err(|e| (INVALID_PARAMS, e.to_string()))
+            .and_then(|params| {
+                jobs::get(&session.jobs, &params.job_id)
+                    .ok_or_else(|| (SERVER_ERROR, format!("Unknown job: {}", params.job_id)))
+            })
+            .map(|record| json!(record)),
+        "models" => Ok(json!([{"id": engine::MODEL_ID, "owned_by": "web-whisper"}])),
+        other => Err((METHOD_NOT_FOUND, format!("Method not found: {}", other))),
+    };
+
+    // Requests without an id are notifications and get no response
+    let id = match request.id {
+        Some(id) => id,
+        None => return,
+    };
+    let response = match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err((code, message)) => error_response(id, code, &message),
+    };
+    send(&session.outbox, response);
+}
+
+// Start a job in the background; its outcome arrives as a `result` or `failed` notification
+fn submit(session: &Session, params: Value) -> Result<Value, (i64, String)> {
+    let params: SubmitParams = serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+    if !std::path::Path::new(&params.file_path).exists() {
+        return Err((INVALID_PARAMS, format!("File not found: {}", params.file_path)));
+    }
+
+    let options = TranscribeOptions {
+        language: params.language.unwrap_or_else(|| "auto".to_string()),
+        ..Default::default()
+    };
+    let job_id = engine::new_job_id();
+    let (hub, registry, outbox, id) = (session.hub.clone(), session.jobs.clone(), session.outbox.clone(), job_id.clone());
+    tokio::task::spawn_blocking(move || {
+        let message = match jobs::run_job(&registry, &hub, &id, &params.file_path, &options) {
+            Ok(transcript) => notification("result", json!({"job_id": id, "transcript": transcript})),
+            Err(error) => notification("failed", json!({"job_id": id, "error": error})),
+        };
+        send(&outbox, message);
+    });
+
+    Ok(json!({"job_id": job_id}))
+}
@@ -0,0 +1,110 @@
+use chrono::{Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::error::AppError;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScheduleConfig {
+    // Only start queued jobs between `start` and `end` (local time, HH:MM); running jobs finish
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_start")]
+    pub start: String,
+    // May be earlier than `start` for a window across midnight, e.g. 22:00-07:00
+    #[serde(default = "default_end")]
+    pub end: String,
+}
+
+fn default_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_end() -> String {
+    "07:00".to_string()
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        ScheduleConfig { enabled: false, start: default_start(), end: default_end() }
+    }
+}
+
+pub fn schedule_config() -> ScheduleConfig {
+    crate::config::load().schedule.unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduleStatus {
+    pub in_window: bool,
+    pub overridden: bool,
+    pub queue_paused: bool,
+    // RFC 3339; when the window next opens or closes, None when the schedule is off
+    pub next_change: Option<String>,
+}
+
+// Lets the queue run outside the window until the window next opens
+static OVERRIDE: AtomicBool = AtomicBool::new(false);
+
+fn parse_time(value: &str) -> Result<NaiveTime, AppError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| AppError::invalid(format!("Invalid time '{}': use HH:MM", value)))
+}
+
+pub fn validate(config: &ScheduleConfig) -> Result<(), AppError> {
+    parse_time(&config.start)?;
+    parse_time(&config.end).map(|_| ())
+}
+
+// Equal start and end leave the window open all day
+fn window_contains(start: NaiveTime, end: NaiveTime, time: NaiveTime) -> bool {
+    match start.cmp(&end) {
+        std::cmp::Ordering::Less => start <= time && time < end,
+        std::cmp::Ordering::Greater => time >= start || time < end,
+        std::cmp::Ordering::Equal => true,
+    }
+}
+
+fn window(config: &ScheduleConfig) -> Option<(NaiveTime, NaiveTime)> {
+    if !config.enabled {
+        return None;
+    }
+    // set_schedule_config rejects bad times; one edited by hand turns the schedule off
+    Some((parse_time(&config.start).ok()?, parse_time(&config.end).ok()?))
+}
+
+fn in_window(config: &ScheduleConfig) -> bool {
+    let Some((start, end)) = window(config) else { return true };
+    let open = window_contains(start, end, Local::now().time());
+    if open {
+        // The override only bridges the time until the next window
+        OVERRIDE.store(false, Ordering::Relaxed);
+    }
+    open
+}
+
+// Whether queued jobs should wait for the processing window
+pub fn queue_paused() -> bool {
+    !in_window(&schedule_config()) && !OVERRIDE.load(Ordering::Relaxed)
+}
+
+// Run queued jobs now even though the window is closed, or go back to waiting
+pub fn set_override(enabled: bool) {
+    OVERRIDE.store(enabled, Ordering::Relaxed);
+    crate::queue::notify();
+}
+
+fn next_occurrence(time: NaiveTime) -> Option<String> {
+    let now = Local::now();
+    let today = now.date_naive().and_time(time);
+    let next = if today > now.naive_local() { today } else { today + chrono::Duration::days(1) };
+    Local.from_local_datetime(&next).earliest().map(|next| next.to_rfc3339())
+}
+
+pub fn status() -> ScheduleStatus {
+    let config = schedule_config();
+    let in_window = in_window(&config);
+    let next_change = window(&config).filter(|(start, end)| start != end).and_then(|(start, end)| {
+        next_occurrence(if in_window { end } else { start })
+    });
+    let overridden = OVERRIDE.load(Ordering::Relaxed);
+    ScheduleStatus { in_window, overridden, queue_paused: !in_window && !overridden, next_change }
+}
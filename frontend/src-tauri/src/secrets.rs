@@ -0,0 +1,29 @@
+use crate::error::AppError;
+
+// Credentials for integrations live in the OS keychain, never in config.json
+const SERVICE: &str = "web-whisper";
+
+fn entry(key: &str) -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(SERVICE, key).map_err(|e| format!("Failed to open keychain entry '{}': {}", key, e).into())
+}
+
+pub fn get(key: &str) -> Result<Option<String>, AppError> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read '{}' from keychain: {}", key, e).into()),
+    }
+}
+
+pub fn set(key: &str, value: &str) -> Result<(), AppError> {
+    entry(key)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store '{}' in keychain: {}", key, e).into())
+}
+
+pub fn delete(key: &str) -> Result<(), AppError> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete '{}' from keychain: {}", key, e).into()),
+    }
+}
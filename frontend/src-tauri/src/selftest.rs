@@ -0,0 +1,141 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::engine::{self, TranscribeOptions, Transcript};
+use crate::error::AppError;
+use crate::queue::{self, Priority};
+use crate::{fixtures, subtitle, temp};
+
+// Every export format is rendered and written once
+const EXPORT_FORMATS: &[&str] = &["txt", "srt", "vtt", "json"];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Passed,
+    Failed,
+    // Not run because an earlier stage failed
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StageResult {
+    // "sample", "decode", "engine" or "export"
+    pub stage: &'static str,
+    pub status: StageStatus,
+    pub seconds: f64,
+    // What was checked, or why it failed
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub engine: String,
+    pub stages: Vec<StageResult>,
+}
+
+struct Runner {
+    stages: Vec<StageResult>,
+}
+
+impl Runner {
+    // Run one stage unless an earlier one failed; Err is reported as the stage's failure
+    fn stage<T>(&mut self, stage: &'static str, run: impl FnOnce() -> Result<(T, String), AppError>) -> Option<T> {
+        if self.stages.iter().any(|result| result.status != StageStatus::Passed) {
+            self.stages.push(StageResult { stage, status: StageStatus::Skipped, seconds: 0.0, detail: String::new() });
+            return None;
+        }
+        let started = Instant::now();
+        let outcome = run();
+        let seconds = started.elapsed().as_secs_f64();
+        match outcome {
+            Ok((value, detail)) => {
+                self.stages.push(StageResult { stage, status: StageStatus::Passed, seconds, detail });
+                Some(value)
+            }
+            Err(e) => {
+                let detail = e.to_string();
+                warn!("Self-test stage {} failed: {}", stage, detail);
+                self.stages.push(StageResult { stage, status: StageStatus::Failed, seconds, detail });
+                None
+            }
+        }
+    }
+}
+
+// Decode to 16 kHz mono WAV, the same conversion the engines do, to check ffmpeg works
+fn decode(sample: &Path, dir: &Path) -> Result<(PathBuf, String), AppError> {
+    let duration = engine::probe_duration(&sample.to_string_lossy()).ok_or("ffprobe could not read the sample (is ffmpeg installed?)")?;
+    let wav = dir.join("selftest.wav");
+    let output = Command::new("ffmpeg")
+        .args(["-y", "-v", "error", "-i"])
+        .arg(sample)
+        .args(["-ac", "1", "-ar", "16000"])
+        .arg(&wav)
+        .env("PATH", engine::path_with_ffmpeg())
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok((wav, format!("{:.1}s of audio decoded", duration)))
+}
+
+fn transcribe(engine_name: &str, wav: &Path) -> Result<(Transcript, String), AppError> {
+    let engine = engine::engine_for(engine_name)?;
+    let id = format!("selftest-{}", engine::new_job_id().trim_start_matches("job-"));
+    let _permit = queue::acquire(engine.worker_class(), &id, Priority::High, || false)?;
+    let transcript = engine.transcribe(&wav.to_string_lossy(), &TranscribeOptions::default(), &mut |_| {}, &mut |_| {})?;
+    // A tone or silence has no words; an empty result still shows the engine ran
+    let detail = match transcript.segments.len() {
+        0 => "The engine ran and returned no speech".to_string(),
+        count => format!("{} segments: {}", count, transcript.text.trim()),
+    };
+    Ok((transcript, detail))
+}
+
+fn export(transcript: &Transcript, dir: &Path) -> Result<((), String), AppError> {
+    for format in EXPORT_FORMATS {
+        let contents = subtitle::render(transcript, format)?;
+        let path = dir.join(format!("selftest.{}", format));
+        std::fs::write(&path, contents).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    }
+    Ok(((), format!("Wrote {}", EXPORT_FORMATS.join(", "))))
+}
+
+// Run the sample through decode, engine and export, reporting each stage.
+// Blocks for the whole run; call from spawn_blocking.
+pub fn run(engine_name: &str) -> SelfTestReport {
+    info!("Running self-test on the {} engine", engine_name);
+    let dir = temp::dir().join(format!("selftest-{}", engine::new_job_id().trim_start_matches("job-")));
+    let mut runner = Runner { stages: Vec::new() };
+
+    let sample = runner.stage("sample", || {
+        let sample = fixtures::sample_clip()?;
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp folder: {}", e))?;
+        let detail = format!("Using {}", sample.to_string_lossy());
+        Ok((sample, detail))
+    });
+    // A missing input means an earlier stage failed, so these only record a skip
+    let wav = match sample {
+        Some(sample) => runner.stage("decode", || decode(&sample, &dir)),
+        None => runner.stage("decode", || Err(String::new().into())),
+    };
+    let transcript = match wav {
+        Some(wav) => runner.stage("engine", || transcribe(engine_name, &wav)),
+        None => runner.stage("engine", || Err(String::new().into())),
+    };
+    match transcript {
+        Some(transcript) => runner.stage("export", || export(&transcript, &dir)),
+        None => runner.stage("export", || Err(String::new().into())),
+    };
+
+    let _ = std::fs::remove_dir_all(&dir);
+    let passed = runner.stages.iter().all(|result| result.status == StageStatus::Passed);
+    info!("Self-test {}", if passed { "passed" } else { "failed" });
+    SelfTestReport { passed, engine: engine_name.to_string(), stages: runner.stages }
+}
@@ -0,0 +1,53 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::sync::OnceLock;
+use std::time::Duration;
+use crate::error::AppError;
+
+// Random per launch; the backend rejects requests that do not carry it (see backend/main.py)
+static TOKEN: OnceLock<String> = OnceLock::new();
+
+pub fn token() -> &'static str {
+    TOKEN.get_or_init(|| rand::thread_rng().sample_iter(&Alphanumeric).take(32).map(char::from).collect())
+}
+
+// Address of the backend on `port`, token included
+pub fn url(port: u16) -> String {
+    format!("http://127.0.0.1:{}/?token={}", port, token())
+}
+
+// The address this machine reaches the network from. Connecting a UDP socket sends nothing;
+// None when offline, in which case there is no other interface to be exposed on either.
+fn outbound_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    let address = socket.local_addr().ok()?.ip();
+    (!address.is_loopback() && !address.is_unspecified()).then_some(address)
+}
+
+// Check a freshly started backend is only usable from this machine and this app:
+// the port must not accept connections on the LAN address, and requests without
+// the token must be refused. Errors name the problem for the security warning.
+pub async fn verify(port: u16) -> Result<(), AppError> {
+    let exposed = tauri::async_runtime::spawn_blocking(move || {
+        outbound_address().filter(|address| {
+            TcpStream::connect_timeout(&SocketAddr::new(*address, port), Duration::from_secs(1)).is_ok()
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to check backend binding: {}", e))?;
+    if let Some(address) = exposed {
+        return Err(format!("Backend server is reachable from the network at {}:{}; refusing to use it", address, port).into());
+    }
+
+    let response = crate::proxy::local_client()
+        .get(format!("http://127.0.0.1:{}/", port))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await;
+    if response.is_ok_and(|response| response.status().is_success()) {
+        return Err(format!("Backend server on port {} accepts requests without the session token; refusing to use it", port).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::engine::Segment;
+
+// Segment events fanned out to WebSocket subscribers (OBS overlays, caption consumers)
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Final { job_id: String, segment: Segment },
+    Completed { job_id: String, text: String },
+    Failed { job_id: String, error: String },
+}
+
+impl StreamEvent {
+    pub fn job_id(&self) -> &str {
+        match self {
+            StreamEvent::Final { job_id, .. }
+            | StreamEvent::Completed { job_id, .. }
+            | StreamEvent::Failed { job_id, .. } => job_id,
+        }
+    }
+}
+
+pub type StreamHub = broadcast::Sender<StreamEvent>;
+
+pub fn new_hub() -> StreamHub {
+    let (sender, _) = broadcast::channel(1024);
+    sender
+}
+
+// Sending fails only when nobody is subscribed, which is fine
+pub fn publish(hub: &StreamHub, event: StreamEvent) {
+    let _ = hub.send(event);
+}
@@ -0,0 +1,369 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::engine::{Segment, Transcript};
+use crate::error::AppError;
+use crate::karaoke::{ass_text, ass_timestamp};
+
+// 00:01:02,345 (SRT) or 00:01:02.345 (WebVTT)
+pub fn format_timestamp(seconds: f64, decimal_separator: char) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let secs = (total_ms % 60_000) / 1000;
+    let millis = total_ms % 1000;
+    format!("{:02}:{:02}:{:02}{}{:03}", hours, minutes, secs, decimal_separator, millis)
+}
+
+pub fn to_srt(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(segment.start, ','),
+            format_timestamp(segment.end, ','),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+pub fn to_vtt(segments: &[Segment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for segment in segments {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(segment.start, '.'),
+            format_timestamp(segment.end, '.'),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+// Render a transcript for export; `format` is a file extension
+// (txt, srt, vtt, json, bilingual.srt, bilingual.txt, chapters.txt, words.json, karaoke.ass)
+pub fn render(transcript: &Transcript, format: &str) -> Result<String, AppError> {
+    match format {
+        "txt" => Ok(format!("{}\n", transcript.text)),
+        "srt" => Ok(to_srt(&crate::captions::cues(transcript))),
+        "vtt" => Ok(to_vtt(&crate::captions::cues(transcript))),
+        "json" => serde_json::to_string_pretty(transcript)
+            .map_err(|e| format!("Failed to serialize transcript: {}", e).into()),
+        "bilingual.srt" | "bilingual.txt" => {
+            let translation = transcript.translation.as_ref().ok_or("Transcript has not been translated")?;
+            if format == "bilingual.txt" {
+                return Ok(format!("{}\n\n{}\n", transcript.text, translation.text));
+            }
+            // Original line on top, translation underneath, same cue timing
+            let merged: Vec<Segment> = transcript
+                .segments
+                .iter()
+                .zip(&translation.segments)
+                .map(|(original, translated)| Segment {
+                    start: original.start,
+                    end: original.end,
+                    text: format!("{}\n{}", original.text.trim(), translated.text.trim()),
+                    words: Vec::new(),
+                })
+                .collect();
+            Ok(to_srt(&merged))
+        }
+        // YouTube description chapter list
+        "chapters.txt" => crate::chapters::to_youtube(transcript),
+        "words.json" => crate::karaoke::to_word_json(transcript),
+        "karaoke.ass" => crate::karaoke::to_ass(transcript),
+        other => Err(AppError::invalid(format!("Unsupported export format: {}", other))),
+    }
+}
+
+pub fn content_type(format: &str) -> &'static str {
+    match format {
+        "vtt" => "text/vtt; charset=utf-8",
+        "json" | "words.json" => "application/json",
+        _ => "text/plain; charset=utf-8",
+    }
+}
+
+// "00:01:02,345", "00:01:02.345" or "01:02.345" (WebVTT without hours)
+pub fn parse_timestamp(text: &str) -> Option<f64> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    let seconds: f64 = parts.last()?.replace(',', ".").parse().ok()?;
+    let (hours, minutes) = match parts.len() {
+        3 => (parts[0].parse::<u64>().ok()?, parts[1].parse::<u64>().ok()?),
+        2 => (0, parts[0].parse::<u64>().ok()?),
+        _ => return None,
+    };
+    Some((hours * 3600 + minutes * 60) as f64 + seconds)
+}
+
+// Cues of an SRT (or WebVTT) file in file order. Cue numbers and settings after the end
+// time are ignored; a block without a timing line is an error.
+pub fn parse_srt(content: &str) -> Result<Vec<Segment>, AppError> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut segments = Vec::new();
+    for block in content.split("\n\n").map(str::trim).filter(|block| !block.is_empty()) {
+        if block.starts_with("WEBVTT") || block.starts_with("NOTE") {
+            continue;
+        }
+        let mut lines = block.lines();
+        let timing = lines
+            .by_ref()
+            .find(|line| line.contains("-->"))
+            .ok_or_else(|| AppError::invalid(format!("Invalid subtitle cue without a timing line: {}", block.lines().next().unwrap_or_default())))?;
+        let (start, end) = timing.split_once("-->").unwrap_or_default();
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            return Err(AppError::invalid(format!("Invalid subtitle timing '{}'", timing.trim())));
+        };
+        segments.push(Segment { start, end, text: lines.collect::<Vec<_>>().join("\n"), words: Vec::new() });
+    }
+    Ok(segments)
+}
+
+pub fn read_srt(path: &str) -> Result<Vec<Segment>, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read subtitle file {}: {}", path, e))?;
+    parse_srt(&content)
+}
+
+fn default_merge_format() -> String {
+    "srt".to_string()
+}
+
+fn default_secondary_color() -> String {
+    "#FFFF00".to_string()
+}
+
+fn default_secondary_scale() -> u32 {
+    80
+}
+
+// How merge_subtitles lays out the two languages
+#[derive(Debug, Deserialize, Clone)]
+pub struct MergeLayout {
+    // "srt" or "ass"
+    #[serde(default = "default_merge_format")]
+    pub format: String,
+    // Put the translation above the original instead of underneath
+    #[serde(default)]
+    pub translation_first: bool,
+    // Color of the translated line, as #RRGGBB
+    #[serde(default = "default_secondary_color")]
+    pub secondary_color: String,
+    #[serde(default)]
+    pub secondary_italic: bool,
+    // Font size of the translated line in percent of the original's; ASS only
+    #[serde(default = "default_secondary_scale")]
+    pub secondary_scale: u32,
+    // ASS only: show the translation at the top of the screen
+    #[serde(default)]
+    pub secondary_on_top: bool,
+}
+
+impl Default for MergeLayout {
+    fn default() -> Self {
+        MergeLayout {
+            format: default_merge_format(),
+            translation_first: false,
+            secondary_color: default_secondary_color(),
+            secondary_italic: false,
+            secondary_scale: default_secondary_scale(),
+            secondary_on_top: false,
+        }
+    }
+}
+
+// (red, green, blue) from "#RRGGBB"
+fn parse_color(color: &str) -> Result<(u8, u8, u8), AppError> {
+    let hex = color.trim().trim_start_matches('#');
+    let channel = |range: std::ops::Range<usize>| hex.get(range).and_then(|digits| u8::from_str_radix(digits, 16).ok());
+    match (hex.len(), channel(0..2), channel(2..4), channel(4..6)) {
+        (6, Some(red), Some(green), Some(blue)) => Ok((red, green, blue)),
+        _ => Err(AppError::invalid(format!("Invalid color '{}': use #RRGGBB", color))),
+    }
+}
+
+// One bilingual cue: the original's timing with the translated cues it overlaps most.
+// Translated cues that overlap no original cue are kept on their own.
+fn align(original: &[Segment], translated: &[Segment]) -> Vec<(f64, f64, String, String)> {
+    let overlap = |a: &Segment, b: &Segment| a.end.min(b.end) - a.start.max(b.start);
+    let mut cues: Vec<(f64, f64, String, String)> =
+        original.iter().map(|cue| (cue.start, cue.end, cue.text.trim().to_string(), String::new())).collect();
+    for cue in translated {
+        let best = original
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| (index, overlap(candidate, cue)))
+            .filter(|(_, overlap)| *overlap > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((index, _)) => {
+                let secondary = &mut cues[index].3;
+                if !secondary.is_empty() {
+                    secondary.push(' ');
+                }
+                secondary.push_str(cue.text.trim());
+            }
+            None => cues.push((cue.start, cue.end, String::new(), cue.text.trim().to_string())),
+        }
+    }
+    cues.sort_by(|a, b| a.0.total_cmp(&b.0));
+    cues
+}
+
+fn merged_srt(cues: &[(f64, f64, String, String)], layout: &MergeLayout) -> Result<String, AppError> {
+    let (red, green, blue) = parse_color(&layout.secondary_color)?;
+    let mut out = String::new();
+    for (index, (start, end, primary, secondary)) in cues.iter().enumerate() {
+        let mut secondary = secondary.clone();
+        if !secondary.is_empty() {
+            if layout.secondary_italic {
+                secondary = format!("<i>{}</i>", secondary);
+            }
+            secondary = format!("<font color=\"#{:02X}{:02X}{:02X}\">{}</font>", red, green, blue, secondary);
+        }
+        let lines: Vec<&str> = if layout.translation_first { vec![&secondary, primary] } else { vec![primary, &secondary] };
+        let text = lines.into_iter().filter(|line| !line.is_empty()).collect::<Vec<_>>().join("\n");
+        out.push_str(&format!("{}\n{} --> {}\n{}\n\n", index + 1, format_timestamp(*start, ','), format_timestamp(*end, ','), text));
+    }
+    Ok(out)
+}
+
+fn merged_ass(cues: &[(f64, f64, String, String)], layout: &MergeLayout) -> Result<String, AppError> {
+    let (red, green, blue) = parse_color(&layout.secondary_color)?;
+    let size = (64 * layout.secondary_scale.clamp(20, 200) / 100).max(8);
+    // Bottom-center (2) or top-center (8); on the same edge the translation sits above or below the original
+    let (alignment, margin) = match (layout.secondary_on_top, layout.translation_first) {
+        (true, _) => (8, 60),
+        (false, true) => (2, 140),
+        (false, false) => (2, 60),
+    };
+    let primary_margin = if !layout.secondary_on_top && !layout.translation_first { 60 + size + 16 } else { 60 };
+    let mut out = format!(
+        "[Script Info]
+ScriptType: v4.00+
+PlayResX: 1920
+PlayResY: 1080
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Original,Arial,64,&H00FFFFFF,&H00FFFFFF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,3,0,2,60,60,{},1
+Style: Translation,Arial,{},&H00{:02X}{:02X}{:02X},&H00FFFFFF,&H00000000,&H80000000,0,{},0,0,100,100,0,0,1,3,0,{},60,60,{},1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+",
+        primary_margin,
+        size,
+        blue,
+        green,
+        red,
+        if layout.secondary_italic { -1 } else { 0 },
+        alignment,
+        margin,
+    );
+    for (start, end, primary, secondary) in cues {
+        for (style, text) in [("Original", primary), ("Translation", secondary)] {
+            if !text.is_empty() {
+                out.push_str(&format!(
+                    "Dialogue: 0,{},{},{},,0,0,0,,{}\n",
+                    ass_timestamp(*start),
+                    ass_timestamp(*end),
+                    style,
+                    ass_text(text)
+                ));
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Combine an original and a translated subtitle file into one bilingual file, written
+// next to the original as "<name>.bilingual.srt" (or .ass) unless `output_path` is given
+pub fn merge_files(original: &str, translated: &str, layout: &MergeLayout, output_path: Option<&str>) -> Result<String, AppError> {
+    let cues = align(&read_srt(original)?, &read_srt(translated)?);
+    let content = match layout.format.as_str() {
+        "srt" => merged_srt(&cues, layout)?,
+        "ass" => merged_ass(&cues, layout)?,
+        other => return Err(AppError::invalid(format!("Invalid merge format '{}': use srt or ass", other))),
+    };
+    let path = match output_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let source = Path::new(original);
+            let stem = source.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+            crate::outputs::unique_path(source.with_file_name(format!("{}.bilingual.{}", stem, layout.format)))
+        }
+    };
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    let path = path.to_string_lossy().to_string();
+    crate::audit::record(None, crate::audit::AuditAction::Write, "file", &path);
+    Ok(path)
+}
+
+fn check_retime(offset: f64, factor: f64) -> Result<(), AppError> {
+    if !offset.is_finite() {
+        return Err(AppError::invalid("Invalid offset: it must be a number of seconds"));
+    }
+    if !factor.is_finite() || factor <= 0.0 {
+        return Err(AppError::invalid(format!("Invalid stretch factor {}: it must be greater than 0", factor)));
+    }
+    Ok(())
+}
+
+// new = old * factor + offset, never before 0. For a framerate mismatch the factor is the
+// ratio of the two rates, e.g. 25 / 23.976 for subtitles timed for PAL on a film-rate video.
+fn retime(seconds: f64, offset: f64, factor: f64) -> f64 {
+    (seconds * factor + offset).max(0.0)
+}
+
+pub fn retime_segments(segments: &mut [Segment], offset: f64, factor: f64) {
+    for segment in segments {
+        segment.start = retime(segment.start, offset, factor);
+        segment.end = retime(segment.end, offset, factor);
+        for word in &mut segment.words {
+            word.start = retime(word.start, offset, factor);
+            word.end = retime(word.end, offset, factor);
+        }
+    }
+}
+
+// Shift and stretch everything timed in a stored transcript: segments, words, the
+// translation's segments, chapters and bookmarks
+pub fn retime_transcript(transcript: &mut Transcript, offset: f64, factor: f64) -> Result<(), AppError> {
+    check_retime(offset, factor)?;
+    retime_segments(&mut transcript.segments, offset, factor);
+    if let Some(translation) = transcript.translation.as_mut() {
+        retime_segments(&mut translation.segments, offset, factor);
+    }
+    for chapter in &mut transcript.chapters {
+        chapter.start = retime(chapter.start, offset, factor);
+    }
+    for bookmark in &mut transcript.bookmarks {
+        bookmark.time = retime(bookmark.time, offset, factor);
+    }
+    Ok(())
+}
+
+// Retime an SRT file and write the result to `output_path`, or next to it as
+// "<name>.retimed.srt"; returns the path written
+pub fn retime_file(path: &str, offset: f64, factor: f64, output_path: Option<&str>) -> Result<String, AppError> {
+    check_retime(offset, factor)?;
+    let mut segments = read_srt(path)?;
+    retime_segments(&mut segments, offset, factor);
+    let output = match output_path {
+        Some(output_path) => PathBuf::from(output_path),
+        None => {
+            let source = Path::new(path);
+            let stem = source.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+            crate::outputs::unique_path(source.with_file_name(format!("{}.retimed.srt", stem)))
+        }
+    };
+    std::fs::write(&output, to_srt(&segments)).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    let output = output.to_string_lossy().to_string();
+    crate::audit::record(None, crate::audit::AuditAction::Write, "file", &output);
+    Ok(output)
+}
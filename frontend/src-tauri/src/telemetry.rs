@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::{config, crash, proxy};
+use crate::error::AppError;
+
+// Serializes read-modify-write cycles on telemetry.json
+static TELEMETRY_LOCK: Mutex<()> = Mutex::new(());
+
+// Automatic submissions are at most this far apart
+const SUBMIT_INTERVAL_DAYS: i64 = 7;
+
+// Off unless the user turns it on. Counters stay on this machine unless `submit` is also on.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    // Send the counters to `endpoint` once a week
+    #[serde(default)]
+    pub submit: bool,
+    // Where reports are POSTed as JSON; nothing is sent while unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+pub fn telemetry_config() -> TelemetryConfig {
+    config::load().telemetry.unwrap_or_default()
+}
+
+// Aggregate counters only: no file names, paths, text or identifiers
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct UsageMetrics {
+    // RFC 3339; when counting started or the last submission
+    pub since: String,
+    pub jobs_completed: u64,
+    pub jobs_failed: u64,
+    pub jobs_cancelled: u64,
+    // Finished jobs by engine name, e.g. "local", "openai"
+    pub jobs_by_engine: HashMap<String, u64>,
+    pub audio_seconds: f64,
+    // Sum and count behind average_rtf
+    pub rtf_total: f64,
+    pub rtf_samples: u64,
+    pub crashes: u64,
+    // created_at of the newest crash report counted, so reports are counted once
+    #[serde(default)]
+    pub last_crash_at: Option<String>,
+    #[serde(default)]
+    pub last_submitted_at: Option<String>,
+}
+
+// What a submission sends, and what get_usage_metrics shows before it is sent
+#[derive(Debug, Serialize, Clone)]
+pub struct UsageReport {
+    pub app_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub since: String,
+    pub jobs_completed: u64,
+    pub jobs_failed: u64,
+    pub jobs_cancelled: u64,
+    pub jobs_by_engine: HashMap<String, u64>,
+    pub audio_hours: f64,
+    // Processing time divided by audio length, over completed jobs; None before the first one
+    pub average_rtf: Option<f64>,
+    pub crashes: u64,
+}
+
+fn metrics_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("telemetry.json"))
+}
+
+fn read_metrics() -> UsageMetrics {
+    let metrics = metrics_path().ok().and_then(|path| std::fs::read_to_string(path).ok());
+    metrics.and_then(|contents| serde_json::from_str(&contents).ok()).unwrap_or_else(|| UsageMetrics {
+        since: chrono::Local::now().to_rfc3339(),
+        ..Default::default()
+    })
+}
+
+fn write_metrics(metrics: &UsageMetrics) -> Result<(), AppError> {
+    let path = metrics_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data folder: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(metrics).map_err(|e| format!("Failed to serialize usage metrics: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write usage metrics: {}", e).into())
+}
+
+// Apply `change` to the stored counters; does nothing while telemetry is off
+fn update(change: impl FnOnce(&mut UsageMetrics)) {
+    if !telemetry_config().enabled {
+        return;
+    }
+    let _guard = TELEMETRY_LOCK.lock().unwrap();
+    let mut metrics = read_metrics();
+    change(&mut metrics);
+    if let Err(e) = write_metrics(&metrics) {
+        warn!("{}", e);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobOutcome {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// Count a finished job. `audio_seconds` and `processing` are only known for some jobs;
+// the real-time factor needs both.
+pub fn record_job(engine: &str, outcome: JobOutcome, audio_seconds: Option<f64>, processing: Option<Duration>) {
+    update(|metrics| {
+        match outcome {
+            JobOutcome::Completed => metrics.jobs_completed += 1,
+            JobOutcome::Failed => metrics.jobs_failed += 1,
+            JobOutcome::Cancelled => metrics.jobs_cancelled += 1,
+        }
+        *metrics.jobs_by_engine.entry(engine.to_string()).or_default() += 1;
+        if outcome != JobOutcome::Completed {
+            return;
+        }
+        if let Some(audio_seconds) = audio_seconds {
+            metrics.audio_seconds += audio_seconds;
+            if let Some(processing) = processing {
+                metrics.rtf_total += processing.as_secs_f64() / audio_seconds;
+                metrics.rtf_samples += 1;
+            }
+        }
+    });
+}
+
+// Count crash reports written since the last check; call once at startup
+pub fn record_crashes() {
+    update(|metrics| {
+        let reports = crash::pending().unwrap_or_default();
+        let new: Vec<_> = reports
+            .iter()
+            .filter(|report| metrics.last_crash_at.as_ref().is_none_or(|last| report.created_at > *last))
+            .collect();
+        metrics.crashes += new.len() as u64;
+        if let Some(newest) = new.first() {
+            metrics.last_crash_at = Some(newest.created_at.clone());
+        }
+    });
+}
+
+pub fn report() -> UsageReport {
+    let metrics = {
+        let _guard = TELEMETRY_LOCK.lock().unwrap();
+        read_metrics()
+    };
+    UsageReport {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        since: metrics.since,
+        jobs_completed: metrics.jobs_completed,
+        jobs_failed: metrics.jobs_failed,
+        jobs_cancelled: metrics.jobs_cancelled,
+        jobs_by_engine: metrics.jobs_by_engine,
+        audio_hours: metrics.audio_seconds / 3600.0,
+        average_rtf: (metrics.rtf_samples > 0).then(|| metrics.rtf_total / metrics.rtf_samples as f64),
+        crashes: metrics.crashes,
+    }
+}
+
+// Save the settings; turning telemetry off deletes the counters collected so far
+pub fn set_config(telemetry: TelemetryConfig) -> Result<(), AppError> {
+    config::update(|app_config| {
+        let was_enabled = app_config.telemetry.as_ref().is_some_and(|telemetry| telemetry.enabled);
+        if !telemetry.enabled {
+            let _guard = TELEMETRY_LOCK.lock().unwrap();
+            let path = metrics_path()?;
+            if path.exists() {
+                std::fs::remove_file(&path).map_err(|e| format!("Failed to delete usage metrics: {}", e))?;
+            }
+        }
+        if telemetry.enabled != was_enabled {
+            info!("Usage metrics turned {}", if telemetry.enabled { "on" } else { "off" });
+        }
+        app_config.telemetry = Some(telemetry);
+        Ok(())
+    })
+}
+
+// POST the report to the configured endpoint and start a new counting period.
+// Blocking; call from spawn_blocking.
+pub fn submit() -> Result<UsageReport, AppError> {
+    let telemetry = telemetry_config();
+    if !telemetry.enabled {
+        return Err(AppError::not_configured("Usage metrics are not configured: turn them on first"));
+    }
+    let endpoint = telemetry.endpoint.filter(|endpoint| !endpoint.trim().is_empty());
+    let endpoint = endpoint.ok_or(AppError::not_configured("Usage metrics endpoint is not configured"))?;
+    let report = report();
+    let response = proxy::blocking_client()?
+        .post(&endpoint)
+        .json(&report)
+        .timeout(Duration::from_secs(30))
+        .send()
+        .map_err(|e| AppError::upstream(format!("Usage metrics request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::upstream(format!("Usage metrics request failed: HTTP {}", response.status())));
+    }
+
+    let _guard = TELEMETRY_LOCK.lock().unwrap();
+    let previous = read_metrics();
+    let now = chrono::Local::now().to_rfc3339();
+    write_metrics(&UsageMetrics {
+        since: now.clone(),
+        last_crash_at: previous.last_crash_at,
+        last_submitted_at: Some(now),
+        ..Default::default()
+    })?;
+    info!("Submitted usage metrics to {}", endpoint);
+    Ok(report)
+}
+
+// Count new crash reports, then send the weekly report when submission is on
+pub fn start() {
+    record_crashes();
+    let telemetry = telemetry_config();
+    if !(telemetry.enabled && telemetry.submit) {
+        return;
+    }
+    // The first report goes out a week after counting started
+    let metrics = read_metrics();
+    let last = metrics.last_submitted_at.unwrap_or(metrics.since);
+    let due = chrono::DateTime::parse_from_rfc3339(&last)
+        .is_ok_and(|last| chrono::Local::now().signed_duration_since(last).num_days() >= SUBMIT_INTERVAL_DAYS);
+    if due {
+        std::thread::spawn(|| {
+            if let Err(e) = submit() {
+                warn!("{}", e);
+            }
+        });
+    }
+}
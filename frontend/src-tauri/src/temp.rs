@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TempConfig {
+    // Delete a job's temp files as soon as it completes; otherwise they wait for the startup sweep
+    #[serde(default = "default_delete_on_success")]
+    pub delete_on_success: bool,
+    // Files older than this are removed by the scheduled cleanup, including those of failed jobs
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u64,
+}
+
+fn default_delete_on_success() -> bool {
+    true
+}
+
+fn default_max_age_days() -> u64 {
+    7
+}
+
+impl Default for TempConfig {
+    fn default() -> Self {
+        TempConfig { delete_on_success: default_delete_on_success(), max_age_days: default_max_age_days() }
+    }
+}
+
+pub fn temp_config() -> TempConfig {
+    crate::config::load().temp_files.unwrap_or_default()
+}
+
+// Shared system temp folder used by earlier versions; other users' files can end up here too
+fn legacy_dir() -> PathBuf {
+    std::env::temp_dir().join("web-whisper")
+}
+
+// Staged uploads, downloads and other intermediate files all live under here
+pub fn dir() -> PathBuf {
+    crate::config::cache_dir().map(|cache| cache.join("tmp")).unwrap_or_else(|_| legacy_dir())
+}
+
+// Temp files created for each job that is still running
+static TRACKED: Mutex<Option<HashMap<String, Vec<PathBuf>>>> = Mutex::new(None);
+
+// Attach a file to `job_id` so it is cleaned up with the job; files outside dir() are left alone
+pub fn track(job_id: &str, path: &Path) {
+    if !path.starts_with(dir()) {
+        return;
+    }
+    let mut tracked = TRACKED.lock().unwrap();
+    tracked.get_or_insert_with(HashMap::new).entry(job_id.to_string()).or_default().push(path.to_path_buf());
+}
+
+// Forget the job's files, deleting them if it succeeded and retention allows.
+// Files of failed jobs stay so the job can be retried, until the sweep removes them.
+pub fn finish(job_id: &str, succeeded: bool) {
+    let files = TRACKED.lock().unwrap().get_or_insert_with(HashMap::new).remove(job_id).unwrap_or_default();
+    let config = crate::config::load();
+    // Google Drive's automatic export uploads the source audio after the job completes
+    let audio_exported = config.google_drive.is_some_and(|drive| drive.auto_upload && drive.include_audio);
+    if !succeeded || !config.temp_files.unwrap_or_default().delete_on_success || audio_exported {
+        return;
+    }
+    for file in files {
+        match std::fs::remove_file(&file) {
+            Ok(()) => info!("Removed temp file {:?} of job {}", file, job_id),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove temp file {:?}: {}", file, e),
+        }
+    }
+}
+
+pub fn sweep_dir(dir: &Path, cutoff: SystemTime, keep: &HashSet<PathBuf>, removed: &mut usize) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            sweep_dir(&path, cutoff, keep, removed);
+            continue;
+        }
+        if keep.contains(&path) {
+            continue;
+        }
+        let modified = entry.metadata().and_then(|metadata| metadata.modified());
+        if modified.is_ok_and(|modified| modified < cutoff) && std::fs::remove_file(&path).is_ok() {
+            *removed += 1;
+        }
+    }
+}
+
+// Delete files under dir() and the legacy temp folder not modified for `max_age`,
+// except those in `keep`. Returns how many files were removed.
+pub fn sweep(max_age: Duration, keep: &HashSet<PathBuf>) -> usize {
+    let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut removed = 0;
+    sweep_dir(&dir(), cutoff, keep, &mut removed);
+    if legacy_dir() != dir() {
+        sweep_dir(&legacy_dir(), cutoff, keep, &mut removed);
+    }
+    removed
+}
+
+// Remove files older than `max_age` left by crashes, failed jobs and kept files.
+// Inputs of jobs waiting in the queue journal are never touched.
+pub fn sweep_stale(max_age: Duration) -> usize {
+    let keep: HashSet<PathBuf> = crate::journal::pending_files().into_iter().collect();
+    let removed = sweep(max_age, &keep);
+    if removed > 0 {
+        info!("Removed {} stale temp file(s) from {:?}", removed, dir());
+    }
+    removed
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+use crate::{config, disk, engine};
+use crate::error::AppError;
+
+// Largest chunk accepted per append_chunk call; the webview slices files to this size or less
+pub const MAX_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+
+pub struct PendingUpload {
+    path: PathBuf,
+    file: tokio::io::BufWriter<tokio::fs::File>,
+    written: u64,
+    limit: u64,
+}
+
+// In-progress chunked uploads by id; a tokio mutex because writes are awaited while it is held
+pub type UploadRegistry = Arc<tokio::sync::Mutex<HashMap<String, PendingUpload>>>;
+
+pub fn new_registry() -> UploadRegistry {
+    Arc::new(tokio::sync::Mutex::new(HashMap::new()))
+}
+
+// Open a temp file for a new upload; returns the upload id.
+// `size` is the full file size when the caller knows it, checked against limits and free space up front.
+pub async fn begin(registry: &UploadRegistry, file_name: &str, size: Option<u64>) -> Result<String, AppError> {
+    let limit = config::max_upload_bytes();
+    if let Some(size) = size {
+        config::check_upload_size(size, limit)?;
+    }
+    let path = engine::temp_file_path(file_name)?;
+    if let Some(dir) = path.parent() {
+        disk::ensure_space(dir, size.unwrap_or(0))?;
+    }
+    let file = tokio::fs::File::create(&path)
+        .await
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let upload_id = format!("upload-{}", engine::new_job_id().trim_start_matches("job-"));
+    registry.lock().await.insert(
+        upload_id.clone(),
+        PendingUpload { path, file: tokio::io::BufWriter::new(file), written: 0, limit },
+    );
+    Ok(upload_id)
+}
+
+// Append the next chunk; returns the total bytes received so far
+pub async fn append(registry: &UploadRegistry, upload_id: &str, data: &[u8]) -> Result<u64, AppError> {
+    if data.len() > MAX_CHUNK_BYTES {
+        return Err(AppError::limit_exceeded(format!("Chunk of {} bytes exceeds the {} byte limit", data.len(), MAX_CHUNK_BYTES)));
+    }
+    let mut uploads = registry.lock().await;
+    let upload = uploads.get_mut(upload_id).ok_or_else(|| AppError::unknown(format!("Unknown upload: {}", upload_id)))?;
+    if let Err(e) = config::check_upload_size(upload.written + data.len() as u64, upload.limit) {
+        // An oversized upload is dropped entirely rather than left half-written
+        let upload = uploads.remove(upload_id).expect("upload exists");
+        drop(upload.file);
+        let _ = tokio::fs::remove_file(&upload.path).await;
+        return Err(e);
+    }
+    upload.file.write_all(data).await.map_err(|e| format!("Failed to write temp file: {}", e))?;
+    upload.written += data.len() as u64;
+    Ok(upload.written)
+}
+
+// Flush and close the upload; returns the temp file path for transcribe_audio
+pub async fn finish(registry: &UploadRegistry, upload_id: &str) -> Result<String, AppError> {
+    let mut upload = registry
+        .lock()
+        .await
+        .remove(upload_id)
+        .ok_or_else(|| AppError::unknown(format!("Unknown upload: {}", upload_id)))?;
+    upload.file.flush().await.map_err(|e| format!("Failed to write temp file: {}", e))?;
+    info!("Received upload {} ({} bytes) at {:?}", upload_id, upload.written, upload.path);
+    Ok(upload.path.to_string_lossy().to_string())
+}
+
+// Drop a cancelled upload and its partial file
+pub async fn abort(registry: &UploadRegistry, upload_id: &str) -> Result<(), AppError> {
+    let upload = registry.lock().await.remove(upload_id);
+    if let Some(upload) = upload {
+        drop(upload.file);
+        let _ = tokio::fs::remove_file(&upload.path).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::engine::{self, TranscribeOptions};
+use crate::error::AppError;
+use crate::jobs::{self, JobRegistry};
+use crate::stream::StreamHub;
+use crate::{config, history};
+
+// Guards watch_folders.json and keeps two scans from picking up the same file
+static WATCH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+// Recorders keep writing for a while; only pick up files untouched for this long
+const SETTLE_TIME: Duration = Duration::from_secs(30);
+
+// Zoom and Teams nest recordings one or two levels below the base folder
+const MAX_DEPTH: usize = 3;
+
+pub const MEDIA_EXTENSIONS: &[&str] = &["mp3", "m4a", "wav", "flac", "ogg", "opus", "aac", "mp4", "mkv", "mov", "webm"];
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WatchFolder {
+    pub path: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    // Lowercase extensions to pick up; empty means every supported media type
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    // Files (absolute paths) already transcribed or present at enrollment
+    #[serde(default)]
+    pub seen: BTreeSet<String>,
+}
+
+fn watch_path() -> Result<PathBuf, AppError> {
+    Ok(config::data_dir()?.join("watch_folders.json"))
+}
+
+fn read_file() -> Result<Vec<WatchFolder>, AppError> {
+    let _guard = STATE_LOCK.lock().unwrap();
+    let path = watch_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read watch folders: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse watch folders: {}", e).into())
+}
+
+fn write_file(folders: &[WatchFolder]) -> Result<(), AppError> {
+    let _guard = STATE_LOCK.lock().unwrap();
+    let path = watch_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(folders).map_err(|e| format!("Failed to serialize watch folders: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write watch folders: {}", e).into())
+}
+
+// The file is read and written off the async runtime
+async fn load() -> Result<Vec<WatchFolder>, AppError> {
+    tokio::task::spawn_blocking(read_file).await.map_err(|e| format!("Watch folder task failed: {}", e))?
+}
+
+async fn save(folders: Vec<WatchFolder>) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || write_file(&folders)).await.map_err(|e| format!("Watch folder task failed: {}", e))?
+}
+
+pub fn list() -> Result<Vec<WatchFolder>, AppError> {
+    read_file()
+}
+
+fn wanted(folder: &WatchFolder, path: &Path) -> bool {
+    let extension = match path.extension() {
+        Some(extension) => extension.to_string_lossy().to_lowercase(),
+        None => return false,
+    };
+    if folder.extensions.is_empty() {
+        MEDIA_EXTENSIONS.contains(&extension.as_str())
+    } else {
+        folder.extensions.contains(&extension)
+    }
+}
+
+fn scan(folder: &WatchFolder, dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                scan(folder, &path, depth + 1, found);
+            }
+        } else if wanted(folder, &path) {
+            found.push(path);
+        }
+    }
+}
+
+fn media_files(folder: &WatchFolder) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    scan(folder, Path::new(&folder.path), 0, &mut found);
+    found.sort();
+    found
+}
+
+// Enroll a folder; recordings already in it are marked seen and not transcribed
+pub async fn add(path: &str, language: Option<String>, extensions: Vec<String>) -> Result<WatchFolder, AppError> {
+    if !tokio::fs::metadata(path).await.is_ok_and(|metadata| metadata.is_dir()) {
+        return Err(AppError::unknown(format!("Not a folder: {}", path)));
+    }
+    let _guard = WATCH_LOCK.lock().await;
+    let mut folders = load().await?;
+    if folders.iter().any(|folder| folder.path == path) {
+        return Err(format!("Already watching {}", path).into());
+    }
+    let mut folder = WatchFolder {
+        path: path.to_string(),
+        language,
+        extensions: extensions.into_iter().map(|extension| extension.trim_start_matches('.').to_lowercase()).collect(),
+        seen: BTreeSet::new(),
+    };
+    let scanned = folder.clone();
+    folder.seen = tokio::task::spawn_blocking(move || media_files(&scanned))
+        .await
+        .map_err(|e| format!("Folder scan failed: {}", e))?
+        .into_iter()
+        .map(|file| file.to_string_lossy().to_string())
+        .collect();
+    folders.push(folder.clone());
+    save(folders).await?;
+    Ok(folder)
+}
+
+pub async fn remove(path: &str) -> Result<(), AppError> {
+    let _guard = WATCH_LOCK.lock().await;
+    let mut folders = load().await?;
+    folders.retain(|folder| folder.path != path);
+    save(folders).await
+}
+
+fn settled(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age >= SETTLE_TIME)
+}
+
+async fn transcribe_file(hub: &StreamHub, registry: &JobRegistry, folder: &WatchFolder, path: &Path) -> Result<(), AppError> {
+    let job_id = engine::new_job_id();
+    let options = TranscribeOptions {
+        language: folder.language.clone().unwrap_or_else(|| "auto".to_string()),
+        ..Default::default()
+    };
+    let (hub, registry, id, file_path) = (hub.clone(), registry.clone(), job_id.clone(), path.to_string_lossy().to_string());
+    let transcript = tokio::task::spawn_blocking(move || jobs::run_job(&registry, &hub, &id, &file_path, &options))
+        .await
+        .map_err(|e| format!("Transcription task failed: {}", e))??;
+
+    let title = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| job_id.clone());
+    let mut tags = BTreeMap::new();
+    tags.insert("watch_folder".to_string(), folder.path.clone());
+    let source = path.to_string_lossy().to_string();
+    tokio::task::spawn_blocking(move || history::add(&job_id, &title, &source, &transcript, tags))
+        .await
+        .map_err(|e| format!("History task failed: {}", e))??;
+    Ok(())
+}
+
+// Scan every watch folder once and transcribe new, settled recordings.
+// Returns how many files were transcribed.
+pub async fn poll(hub: &StreamHub, registry: &JobRegistry) -> Result<usize, AppError> {
+    let _guard = WATCH_LOCK.lock().await;
+    let mut transcribed = 0;
+
+    for folder in load().await? {
+        let scanned = folder.clone();
+        let new_files: Vec<PathBuf> = tokio::task::spawn_blocking(move || {
+            media_files(&scanned)
+                .into_iter()
+                .filter(|file| !scanned.seen.contains(&*file.to_string_lossy()) && settled(file))
+                .collect()
+        })
+        .await
+        .map_err(|e| format!("Folder scan failed: {}", e))?;
+
+        for file in new_files {
+            info!("New recording in {}: {:?}", folder.path, file);
+            match transcribe_file(hub, registry, &folder, &file).await {
+                Ok(()) => transcribed += 1,
+                // Leave it unseen so the next scan retries
+                Err(e) => {
+                    warn!("Failed to transcribe {:?}: {}", file, e);
+                    continue;
+                }
+            }
+            let mut folders = load().await?;
+            if let Some(stored) = folders.iter_mut().find(|stored| stored.path == folder.path) {
+                stored.seen.insert(file.to_string_lossy().to_string());
+            }
+            save(folders).await?;
+        }
+    }
+    Ok(transcribed)
+}
+
+pub fn start_watching(hub: StreamHub, registry: JobRegistry) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = poll(&hub, &registry).await {
+                warn!("Watch folder scan failed: {}", e);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
@@ -7,6 +7,35 @@ interface ServerInfo {
   status: string;
 }
 
+// Shape of every error returned by a Tauri command (see src-tauri/src/error.rs)
+interface AppError {
+  code: 'backend_unavailable' | 'not_found' | 'invalid_input' | 'not_configured' | 'feature_disabled'
+    | 'cancelled' | 'duplicate' | 'limit_exceeded' | 'insufficient_disk_space' | 'upstream' | 'engine' | 'internal';
+  message: string;
+  path?: string;
+  field?: string;
+  flag?: string;
+  job_id?: string;
+  status?: string;
+}
+
+// Result of get_gpu_info (see src-tauri/src/gpu.rs)
+interface GpuInfo {
+  vendor: string;
+  name: string;
+  vram_bytes: number | null;
+  driver: string | null;
+  source: 'nvml' | 'metal' | 'dxgi' | 'sysfs' | 'python';
+}
+
+function isAppError(error: unknown): error is AppError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+function errorMessage(error: unknown): string {
+  return isAppError(error) ? error.message : String(error);
+}
+
 class WebWhisperApp {
   private fileInput: HTMLInputElement;
   private dropZone: HTMLDivElement;
@@ -122,6 +151,27 @@ class WebWhisperApp {
     this.hideResults();
     this.hideError();
     await this.loadGpuInfo();
+    await this.checkCrashReports();
+  }
+
+  // Offer to save reports from sessions that crashed, then clear them either way
+  private async checkCrashReports() {
+    try {
+      const reports = await invoke<{ id: string; message: string }[]>('list_crash_reports');
+      if (reports.length === 0) return;
+      const save = confirm(
+        `前回アプリが異常終了しました（${reports[0].message}）。\nクラッシュレポートを保存しますか？`
+      );
+      for (const report of reports) {
+        if (save && report === reports[0]) {
+          await invoke('save_crash_report', { id: report.id }).catch(() => undefined);
+        } else {
+          await invoke('dismiss_crash_report', { id: report.id });
+        }
+      }
+    } catch (error) {
+      console.error('Failed to check crash reports:', error);
+    }
   }
 
   private handleFileSelect(event: Event) {
@@ -189,32 +239,68 @@ class WebWhisperApp {
       this.showProgress();
       this.updateProgress(10, 'ファイルを準備中...');
 
-      // Read file data as array buffer
-      const fileData = await this.selectedFile.arrayBuffer();
-      const uint8Array = new Uint8Array(fileData);
-      const fileDataArray = Array.from(uint8Array);
-
-      // Save file temporarily on the backend
-      const tempFilePath = await invoke<string>('save_temp_file', {
-        fileData: fileDataArray,
-        fileName: this.selectedFile.name
-      });
+      // Stream the file to the backend in chunks so large videos never sit in memory whole
+      const tempFilePath = await this.uploadFile(this.selectedFile);
 
       this.updateProgress(30, 'Whisperエンジンを初期化中...');
       this.updateProgress(50, '音声ファイルを処理中...');
       
-      // Start transcription with temp file path (direct script execution)
-      const result = await invoke<string>('transcribe_audio', { 
-        filePath: tempFilePath
-      });
+      const result = await this.transcribe(tempFilePath);
+      if (result === null) {
+        this.hideProgress();
+        return;
+      }
 
       this.updateProgress(100, '転写完了');
       this.showResult(result);
       
     } catch (error) {
       console.error('Transcription error:', error);
+      if (isAppError(error) && error.code === 'backend_unavailable') {
+        this.showError('転写エンジンが起動していません', errorMessage(error));
+        return;
+      }
+      if (isAppError(error) && error.code === 'not_found') {
+        this.showError('ファイルが見つかりません', error.path ?? errorMessage(error));
+        return;
+      }
       this.showError('転写に失敗しました', 
-        `エラー詳細: ${error}`);
+        `エラー詳細: ${errorMessage(error)}`);
+    }
+  }
+
+  // Transcribe the uploaded file; for content already transcribed this session, offer the
+  // earlier result instead. Returns null when the user backs out.
+  private async transcribe(filePath: string): Promise<string | null> {
+    try {
+      return await invoke<string>('transcribe_audio', { filePath });
+    } catch (error) {
+      if (!isAppError(error) || error.code !== 'duplicate' || !error.job_id) throw error;
+      if (error.status === 'completed') {
+        if (confirm('このファイルはすでに転写済みです。結果を表示しますか？\n（キャンセルすると再度転写します）')) {
+          return await invoke<string>('render_transcript', { jobId: error.job_id, format: 'txt' });
+        }
+      } else if (!confirm('このファイルはすでに転写待ちです。もう一度転写しますか？')) {
+        return null;
+      }
+      return await invoke<string>('transcribe_audio', { filePath, force: true });
+    }
+  }
+
+  private async uploadFile(file: File): Promise<string> {
+    const chunkSize = 8 * 1024 * 1024;
+    const uploadId = await invoke<string>('begin_upload', { fileName: file.name, size: file.size });
+    try {
+      for (let offset = 0; offset < file.size; offset += chunkSize) {
+        const chunk = new Uint8Array(await file.slice(offset, offset + chunkSize).arrayBuffer());
+        await invoke('append_chunk', chunk, { headers: { 'x-upload-id': uploadId } });
+        const percent = Math.min(offset + chunkSize, file.size) / file.size;
+        this.updateProgress(10 + Math.round(percent * 20), 'ファイルを転送中...');
+      }
+      return await invoke<string>('finish_upload', { uploadId });
+    } catch (error) {
+      await invoke('abort_upload', { uploadId }).catch(() => {});
+      throw error;
     }
   }
 
@@ -260,12 +346,15 @@ class WebWhisperApp {
         return;
       } catch (firstError) {
         console.log('First save attempt failed:', firstError);
+        if (isAppError(firstError) && firstError.code === 'cancelled') {
+          return;
+        }
         
         // Fallback: Save directly to Downloads
         try {
           const downloadsPath = await invoke<string>('save_to_downloads_direct', {
             content: content,
-            fileName: `${this.selectedFile.name.split('.')[0]}.txt`
+            originalFileName: this.selectedFile.name
           });
           
           this.updateStatus('保存完了', 'success', downloadsPath);
@@ -284,7 +373,7 @@ class WebWhisperApp {
       }
     } catch (error) {
       console.error('Save error:', error);
-      this.showError('保存に失敗しました', `エラー詳細: ${error}`);
+      this.showError('保存に失敗しました', `エラー詳細: ${errorMessage(error)}`);
     }
   }
 
@@ -364,14 +453,26 @@ class WebWhisperApp {
 
   private async loadGpuInfo() {
     try {
-      const gpuInfo = await invoke<string>('get_gpu_info');
-      this.gpuStatus.textContent = gpuInfo;
+      const gpuInfo = await invoke<GpuInfo | null>('get_gpu_info');
+      this.gpuStatus.textContent = this.formatGpuInfo(gpuInfo);
     } catch (error) {
       console.error('Failed to load GPU info:', error);
       this.gpuStatus.textContent = '💻 GPU情報の取得に失敗しました';
     }
   }
 
+  private formatGpuInfo(gpu: GpuInfo | null): string {
+    if (!gpu) return '💻 GPU未検出 (CPU処理)';
+    // The Python fallback already returns a display string
+    if (gpu.source === 'python') return gpu.name;
+    const details = [
+      gpu.vram_bytes ? `VRAM ${this.formatFileSize(gpu.vram_bytes)}` : null,
+      gpu.driver ? `ドライバー ${gpu.driver}` : null,
+    ].filter(Boolean);
+    const name = gpu.name.toLowerCase().includes(gpu.vendor.toLowerCase()) ? gpu.name : `${gpu.vendor} ${gpu.name}`;
+    return `🎮 ${name}${details.length ? ` (${details.join(', ')})` : ''}`;
+  }
+
   private updateStatus(title: string, type: 'success' | 'processing' | 'error', message?: string) {
     this.statusTitle.textContent = title;
     if (message) {
@@ -7,6 +7,14 @@ interface ServerInfo {
   status: string;
 }
 
+// Tauri commands reject with {code, message, ...} (see src-tauri/src/error.rs)
+function errorMessage(error: unknown): string {
+  if (typeof error === 'object' && error !== null && 'message' in error) {
+    return String((error as { message: unknown }).message);
+  }
+  return String(error);
+}
+
 class WebWhisperWebView {
   private loadingScreen: HTMLElement;
   private errorScreen: HTMLElement;
@@ -14,6 +22,8 @@ class WebWhisperWebView {
   private gradioFrame: HTMLIFrameElement;
   private retryCount = 0;
   private maxRetries = 5;
+  private sleeping = false;
+  private degraded = false;
 
   constructor() {
     this.loadingScreen = document.getElementById('loading-screen')!;
@@ -22,6 +32,42 @@ class WebWhisperWebView {
     this.gradioFrame = document.getElementById('gradio-frame') as HTMLIFrameElement;
     
     this.initializeWebView();
+    this.watchBackendStatus();
+  }
+
+  // The backend stops itself after a period without jobs (see src-tauri/src/idle.rs);
+  // show that state and start it again on the next click. When it stops answering,
+  // the heartbeat reconnects or relaunches it and the frame is reloaded.
+  private async watchBackendStatus() {
+    await listen<{ status: string }>('backend-status', async (event) => {
+      if (event.payload.status === 'degraded') {
+        this.degraded = true;
+        this.updateStatus('エンジンとの接続が切れました。再接続しています...');
+        this.gradioFrame.style.display = 'none';
+        this.loadingScreen.style.display = 'flex';
+        return;
+      }
+      if (event.payload.status === 'running' && this.degraded) {
+        this.degraded = false;
+        const serverInfo = await invoke<ServerInfo>('get_server_info');
+        await this.loadGradioInterface(serverInfo.url).catch((error) => this.showError(error as string));
+        return;
+      }
+      if (event.payload.status !== 'sleeping') {
+        return;
+      }
+      this.sleeping = true;
+      this.gradioFrame.style.display = 'none';
+      this.errorScreen.style.display = 'none';
+      this.loadingScreen.style.display = 'flex';
+      this.updateStatus('しばらく使われていないため、エンジンを停止しました。クリックすると再開します');
+    });
+    this.loadingScreen.addEventListener('click', () => {
+      if (this.sleeping) {
+        this.sleeping = false;
+        this.initializeWebView();
+      }
+    });
   }
 
   private async initializeWebView() {
@@ -47,7 +93,7 @@ class WebWhisperWebView {
       await invoke('start_gradio_server');
     } catch (error) {
       console.error('Failed to start Gradio server:', error);
-      throw `サーバーの起動に失敗しました: ${error}`;
+      throw `サーバーの起動に失敗しました: ${errorMessage(error)}`;
     }
   }
 
@@ -85,7 +131,7 @@ class WebWhisperWebView {
           if (Date.now() - startTime < maxWaitTime) {
             setTimeout(checkServer, checkInterval);
           } else {
-            reject(`サーバーの確認に失敗しました: ${error}`);
+            reject(`サーバーの確認に失敗しました: ${errorMessage(error)}`);
           }
         }
       };